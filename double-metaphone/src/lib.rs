@@ -0,0 +1,644 @@
+//! Double Metaphone codes for card names, shared by the Scryfall importer and the
+//! MTGJSON indexer so both build `metaphone:{code}` keys the same way.
+
+// Double Metaphone (Lawrence Philips), following the Apache Commons Codec port. Codes
+// are capped at DOUBLE_METAPHONE_MAX_LENGTH rather than the usual 4, since whole card
+// names are encoded and a 4-letter key would lump every "Lightning ..." together.
+const DOUBLE_METAPHONE_MAX_LENGTH: usize = 12;
+
+/// Primary and alternate Double Metaphone codes of `text`, both indexed under
+/// `metaphone:{code}` by the Scryfall importer and the MTGJSON indexer. The alternate
+/// equals the primary when the name has only one plausible pronunciation. Both are empty
+/// for text without letters.
+pub fn double_metaphone(text: &str) -> (String, String) {
+    let value: Vec<char> = text.chars().flat_map(fold_metaphone_char).collect();
+    let encoder = DoubleMetaphone {
+        slavo_germanic: value.contains(&'W') || value.contains(&'K')
+            || value.windows(2).any(|w| w == ['C', 'Z'])
+            || value.windows(4).any(|w| w == ['W', 'I', 'T', 'Z']),
+        value,
+    };
+    encoder.encode()
+}
+
+// Uppercase, with accents dropped so "Lim-Dûl" encodes like "Lim-Dul"
+fn fold_metaphone_char(c: char) -> Vec<char> {
+    match c.to_uppercase().next().unwrap_or(c) {
+        'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' => vec!['A'],
+        'Æ' => vec!['A', 'E'],
+        'È' | 'É' | 'Ê' | 'Ë' => vec!['E'],
+        'Ì' | 'Í' | 'Î' | 'Ï' => vec!['I'],
+        'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' | 'Ø' => vec!['O'],
+        'Ù' | 'Ú' | 'Û' | 'Ü' => vec!['U'],
+        'Ý' => vec!['Y'],
+        upper => vec![upper],
+    }
+}
+
+struct DoubleMetaphone {
+    value: Vec<char>,
+    slavo_germanic: bool,
+}
+
+#[derive(Default)]
+struct MetaphoneCodes {
+    primary: String,
+    alternate: String,
+}
+
+impl MetaphoneCodes {
+    fn add(&mut self, code: &str) {
+        self.primary.push_str(code);
+        self.alternate.push_str(code);
+    }
+
+    fn add_both(&mut self, primary: &str, alternate: &str) {
+        self.primary.push_str(primary);
+        self.alternate.push_str(alternate);
+    }
+
+    fn is_complete(&self) -> bool {
+        self.primary.len() >= DOUBLE_METAPHONE_MAX_LENGTH && self.alternate.len() >= DOUBLE_METAPHONE_MAX_LENGTH
+    }
+}
+
+impl DoubleMetaphone {
+    fn len(&self) -> isize {
+        self.value.len() as isize
+    }
+
+    fn char_at(&self, index: isize) -> char {
+        if index < 0 {
+            return '\0';
+        }
+        self.value.get(index as usize).copied().unwrap_or('\0')
+    }
+
+    // Whether the `length` letters at `start` spell one of `options`
+    fn contains(&self, start: isize, length: isize, options: &[&str]) -> bool {
+        if start < 0 || start + length > self.len() {
+            return false;
+        }
+        let slice = &self.value[start as usize..(start + length) as usize];
+        options.iter().any(|option| option.chars().eq(slice.iter().copied()))
+    }
+
+    fn is_vowel(c: char) -> bool {
+        matches!(c, 'A' | 'E' | 'I' | 'O' | 'U' | 'Y')
+    }
+
+    fn is_germanic(&self) -> bool {
+        self.contains(0, 4, &["VAN ", "VON "]) || self.contains(0, 3, &["SCH"])
+    }
+
+    fn encode(&self) -> (String, String) {
+        let mut codes = MetaphoneCodes::default();
+        let last = self.len() - 1;
+        let mut index: isize = if self.contains(0, 2, &["GN", "KN", "PN", "WR", "PS"]) { 1 } else { 0 };
+
+        while !codes.is_complete() && index <= last {
+            index = match self.char_at(index) {
+                'A' | 'E' | 'I' | 'O' | 'U' | 'Y' => {
+                    if index == 0 {
+                        codes.add("A");
+                    }
+                    index + 1
+                }
+                'B' => {
+                    codes.add("P");
+                    if self.char_at(index + 1) == 'B' { index + 2 } else { index + 1 }
+                }
+                'Ç' => {
+                    codes.add("S");
+                    index + 1
+                }
+                'C' => self.handle_c(&mut codes, index),
+                'D' => self.handle_d(&mut codes, index),
+                'F' => {
+                    codes.add("F");
+                    if self.char_at(index + 1) == 'F' { index + 2 } else { index + 1 }
+                }
+                'G' => self.handle_g(&mut codes, index),
+                'H' => {
+                    // Only sounded first or between vowels
+                    if (index == 0 || Self::is_vowel(self.char_at(index - 1))) && Self::is_vowel(self.char_at(index + 1)) {
+                        codes.add("H");
+                        index + 2
+                    } else {
+                        index + 1
+                    }
+                }
+                'J' => self.handle_j(&mut codes, index),
+                'K' => {
+                    codes.add("K");
+                    if self.char_at(index + 1) == 'K' { index + 2 } else { index + 1 }
+                }
+                'L' => self.handle_l(&mut codes, index),
+                'M' => {
+                    codes.add("M");
+                    let silent_b = self.contains(index - 1, 3, &["UMB"])
+                        && (index + 1 == last || self.contains(index + 2, 2, &["ER"]));
+                    if self.char_at(index + 1) == 'M' || silent_b { index + 2 } else { index + 1 }
+                }
+                'N' => {
+                    codes.add("N");
+                    if self.char_at(index + 1) == 'N' { index + 2 } else { index + 1 }
+                }
+                'Ñ' => {
+                    codes.add("N");
+                    index + 1
+                }
+                'P' => {
+                    if self.char_at(index + 1) == 'H' {
+                        codes.add("F");
+                        index + 2
+                    } else {
+                        codes.add("P");
+                        if self.contains(index + 1, 1, &["P", "B"]) { index + 2 } else { index + 1 }
+                    }
+                }
+                'Q' => {
+                    codes.add("K");
+                    if self.char_at(index + 1) == 'Q' { index + 2 } else { index + 1 }
+                }
+                'R' => {
+                    // French final -ier: "Rogier"
+                    if index == last && !self.slavo_germanic && self.contains(index - 2, 2, &["IE"])
+                        && !self.contains(index - 4, 2, &["ME", "MA"]) {
+                        codes.add_both("", "R");
+                    } else {
+                        codes.add("R");
+                    }
+                    if self.char_at(index + 1) == 'R' { index + 2 } else { index + 1 }
+                }
+                'S' => self.handle_s(&mut codes, index),
+                'T' => self.handle_t(&mut codes, index),
+                'V' => {
+                    codes.add("F");
+                    if self.char_at(index + 1) == 'V' { index + 2 } else { index + 1 }
+                }
+                'W' => self.handle_w(&mut codes, index),
+                'X' => {
+                    if index == 0 {
+                        codes.add("S");
+                        index + 1
+                    } else {
+                        // French final -aux, -eaux: "Breaux"
+                        let silent = index == last
+                            && (self.contains(index - 3, 3, &["IAU", "EAU"]) || self.contains(index - 2, 2, &["AU", "OU"]));
+                        if !silent {
+                            codes.add("KS");
+                        }
+                        if self.contains(index + 1, 1, &["C", "X"]) { index + 2 } else { index + 1 }
+                    }
+                }
+                'Z' => {
+                    if self.char_at(index + 1) == 'H' {
+                        codes.add("J");
+                        index + 2
+                    } else {
+                        if self.contains(index + 1, 2, &["ZO", "ZI", "ZA"])
+                            || (self.slavo_germanic && index > 0 && self.char_at(index - 1) != 'T') {
+                            codes.add_both("S", "TS");
+                        } else {
+                            codes.add("S");
+                        }
+                        if self.char_at(index + 1) == 'Z' { index + 2 } else { index + 1 }
+                    }
+                }
+                _ => index + 1,
+            };
+        }
+
+        codes.primary.truncate(DOUBLE_METAPHONE_MAX_LENGTH);
+        codes.alternate.truncate(DOUBLE_METAPHONE_MAX_LENGTH);
+        (codes.primary, codes.alternate)
+    }
+
+    fn handle_c(&self, codes: &mut MetaphoneCodes, index: isize) -> isize {
+        if self.is_germanic_ch_k(index) {
+            codes.add("K");
+            index + 2
+        } else if index == 0 && self.contains(index, 6, &["CAESAR"]) {
+            codes.add("S");
+            index + 2
+        } else if self.contains(index, 2, &["CH"]) {
+            self.handle_ch(codes, index)
+        } else if self.contains(index, 2, &["CZ"]) && !self.contains(index - 2, 4, &["WICZ"]) {
+            codes.add_both("S", "X");
+            index + 2
+        } else if self.contains(index + 1, 3, &["CIA"]) {
+            codes.add("X");
+            index + 3
+        } else if self.contains(index, 2, &["CC"]) && !(index == 1 && self.char_at(0) == 'M') {
+            if self.contains(index + 2, 1, &["I", "E", "H"]) && !self.contains(index + 2, 2, &["HU"]) {
+                // "accident", "succeed" vs. "bacci"
+                if (index == 1 && self.char_at(index - 1) == 'A') || self.contains(index - 1, 5, &["UCCEE", "UCCES"]) {
+                    codes.add("KS");
+                } else {
+                    codes.add("X");
+                }
+                index + 3
+            } else {
+                codes.add("K");
+                index + 2
+            }
+        } else if self.contains(index, 2, &["CK", "CG", "CQ"]) {
+            codes.add("K");
+            index + 2
+        } else if self.contains(index, 2, &["CI", "CE", "CY"]) {
+            if self.contains(index, 3, &["CIO", "CIE", "CIA"]) {
+                codes.add_both("S", "X");
+            } else {
+                codes.add("S");
+            }
+            index + 2
+        } else {
+            codes.add("K");
+            if self.contains(index + 1, 2, &[" C", " Q", " G"]) {
+                index + 3
+            } else if self.contains(index + 1, 1, &["C", "K", "Q"]) && !self.contains(index + 1, 2, &["CE", "CI"]) {
+                index + 2
+            } else {
+                index + 1
+            }
+        }
+    }
+
+    // "Bacher", "Macher" and other -ach- spellings where "ch" is a hard K
+    fn is_germanic_ch_k(&self, index: isize) -> bool {
+        if self.contains(index, 4, &["CHIA"]) {
+            return true;
+        }
+        if index <= 1 || Self::is_vowel(self.char_at(index - 2)) || !self.contains(index - 1, 3, &["ACH"]) {
+            return false;
+        }
+        let c = self.char_at(index + 2);
+        (c != 'I' && c != 'E') || self.contains(index - 2, 6, &["BACHER", "MACHER"])
+    }
+
+    fn handle_ch(&self, codes: &mut MetaphoneCodes, index: isize) -> isize {
+        let greek_start = index == 0
+            && (self.contains(index + 1, 5, &["HARAC", "HARIS"]) || self.contains(index + 1, 3, &["HOR", "HYM", "HIA", "HEM"]))
+            && !self.contains(0, 5, &["CHORE"]);
+        let hard = self.is_germanic()
+            || self.contains(index - 2, 6, &["ORCHES", "ARCHIT", "ORCHID"])
+            || self.contains(index + 2, 1, &["T", "S"])
+            || ((self.contains(index - 1, 1, &["A", "O", "U", "E"]) || index == 0)
+                && (self.contains(index + 2, 1, &["L", "R", "N", "M", "B", "H", "F", "V", "W", " "]) || index + 1 == self.len() - 1));
+
+        if index > 0 && self.contains(index, 4, &["CHAE"]) {
+            codes.add_both("K", "X");
+        } else if greek_start || hard {
+            codes.add("K");
+        } else if index > 0 {
+            if self.contains(0, 2, &["MC"]) {
+                codes.add("K");
+            } else {
+                codes.add_both("X", "K");
+            }
+        } else {
+            codes.add("X");
+        }
+        index + 2
+    }
+
+    fn handle_d(&self, codes: &mut MetaphoneCodes, index: isize) -> isize {
+        if self.contains(index, 2, &["DG"]) {
+            if self.contains(index + 2, 1, &["I", "E", "Y"]) {
+                codes.add("J");
+                index + 3
+            } else {
+                codes.add("TK");
+                index + 2
+            }
+        } else if self.contains(index, 2, &["DT", "DD"]) {
+            codes.add("T");
+            index + 2
+        } else {
+            codes.add("T");
+            index + 1
+        }
+    }
+
+    fn handle_g(&self, codes: &mut MetaphoneCodes, index: isize) -> isize {
+        let next = self.char_at(index + 1);
+        if next == 'H' {
+            self.handle_gh(codes, index)
+        } else if next == 'N' {
+            if index == 1 && Self::is_vowel(self.char_at(0)) && !self.slavo_germanic {
+                codes.add_both("KN", "N");
+            } else if !self.contains(index + 2, 2, &["EY"]) && next != 'Y' && !self.slavo_germanic {
+                codes.add_both("N", "KN");
+            } else {
+                codes.add("KN");
+            }
+            index + 2
+        } else if self.contains(index + 1, 2, &["LI"]) && !self.slavo_germanic {
+            codes.add_both("KL", "L");
+            index + 2
+        } else if (index == 0
+            && (next == 'Y' || self.contains(index + 1, 2, &["ES", "EP", "EB", "EL", "EY", "IB", "IL", "IN", "IE", "EI", "ER"])))
+            || ((self.contains(index + 1, 2, &["ER"]) || next == 'Y')
+                && !self.contains(0, 6, &["DANGER", "RANGER", "MANGER"])
+                && !self.contains(index - 1, 1, &["E", "I"])
+                && !self.contains(index - 1, 3, &["RGY", "OGY"])) {
+            codes.add_both("K", "J");
+            index + 2
+        } else if self.contains(index + 1, 1, &["E", "I", "Y"]) || self.contains(index - 1, 4, &["AGGI", "OGGI"]) {
+            if self.is_germanic() || self.contains(index + 1, 2, &["ET"]) {
+                codes.add("K");
+            } else if self.contains(index + 1, 3, &["IER"]) {
+                codes.add("J");
+            } else {
+                codes.add_both("J", "K");
+            }
+            index + 2
+        } else if next == 'G' {
+            codes.add("K");
+            index + 2
+        } else {
+            codes.add("K");
+            index + 1
+        }
+    }
+
+    fn handle_gh(&self, codes: &mut MetaphoneCodes, index: isize) -> isize {
+        if index > 0 && !Self::is_vowel(self.char_at(index - 1)) {
+            codes.add("K");
+        } else if index == 0 {
+            codes.add(if self.char_at(index + 2) == 'I' { "J" } else { "K" });
+        } else if (index > 1 && self.contains(index - 2, 1, &["B", "H", "D"]))
+            || (index > 2 && self.contains(index - 3, 1, &["B", "H", "D"]))
+            || (index > 3 && self.contains(index - 4, 1, &["B", "H"])) {
+            // Silent, as in "hugh"
+        } else if index > 2 && self.char_at(index - 1) == 'U' && self.contains(index - 3, 1, &["C", "G", "L", "R", "T"]) {
+            // "laugh", "cough", "tough"
+            codes.add("F");
+        } else if index > 0 && self.char_at(index - 1) != 'I' {
+            codes.add("K");
+        }
+        index + 2
+    }
+
+    fn handle_j(&self, codes: &mut MetaphoneCodes, index: isize) -> isize {
+        if self.contains(index, 4, &["JOSE"]) || self.contains(0, 4, &["SAN "]) {
+            // Spanish: "Jose", "San Jacinto"
+            if (index == 0 && self.char_at(index + 4) == ' ') || self.len() == 4 || self.contains(0, 4, &["SAN "]) {
+                codes.add("H");
+            } else {
+                codes.add_both("J", "H");
+            }
+            return index + 1;
+        }
+
+        if index == 0 {
+            codes.add_both("J", "A");
+        } else if Self::is_vowel(self.char_at(index - 1)) && !self.slavo_germanic
+            && matches!(self.char_at(index + 1), 'A' | 'O') {
+            codes.add_both("J", "H");
+        } else if index == self.len() - 1 {
+            codes.add_both("J", "");
+        } else if !self.contains(index + 1, 1, &["L", "T", "K", "S", "N", "M", "B", "Z"])
+            && !self.contains(index - 1, 1, &["S", "K", "L"]) {
+            codes.add("J");
+        }
+        if self.char_at(index + 1) == 'J' { index + 2 } else { index + 1 }
+    }
+
+    fn handle_l(&self, codes: &mut MetaphoneCodes, index: isize) -> isize {
+        if self.char_at(index + 1) != 'L' {
+            codes.add("L");
+            return index + 1;
+        }
+        // Spanish -illo, -illa, -alle: "Cabrillo", "Gallegos"
+        let last = self.len() - 1;
+        let spanish = (index == self.len() - 3 && self.contains(index - 1, 4, &["ILLO", "ILLA", "ALLE"]))
+            || ((self.contains(last - 1, 2, &["AS", "OS"]) || self.contains(last, 1, &["A", "O"]))
+                && self.contains(index - 1, 4, &["ALLE"]));
+        if spanish {
+            codes.add_both("L", "");
+        } else {
+            codes.add("L");
+        }
+        index + 2
+    }
+
+    fn handle_s(&self, codes: &mut MetaphoneCodes, index: isize) -> isize {
+        if self.contains(index - 1, 3, &["ISL", "YSL"]) {
+            // "island", "isle"
+            index + 1
+        } else if index == 0 && self.contains(index, 5, &["SUGAR"]) {
+            codes.add_both("X", "S");
+            index + 1
+        } else if self.contains(index, 2, &["SH"]) {
+            if self.contains(index + 1, 4, &["HEIM", "HOEK", "HOLM", "HOLZ"]) {
+                codes.add("S");
+            } else {
+                codes.add("X");
+            }
+            index + 2
+        } else if self.contains(index, 3, &["SIO", "SIA"]) || self.contains(index, 4, &["SIAN"]) {
+            if self.slavo_germanic {
+                codes.add("S");
+            } else {
+                codes.add_both("S", "X");
+            }
+            index + 3
+        } else if (index == 0 && self.contains(index + 1, 1, &["M", "N", "L", "W"])) || self.contains(index + 1, 1, &["Z"]) {
+            // "Smith" matches "Schmidt", "Snider" matches "Schneider"
+            codes.add_both("S", "X");
+            if self.contains(index + 1, 1, &["Z"]) { index + 2 } else { index + 1 }
+        } else if self.contains(index, 2, &["SC"]) {
+            self.handle_sc(codes, index)
+        } else {
+            // French final -ais, -ois: "Artois"
+            if index == self.len() - 1 && self.contains(index - 2, 2, &["AI", "OI"]) {
+                codes.add_both("", "S");
+            } else {
+                codes.add("S");
+            }
+            if self.contains(index + 1, 1, &["S", "Z"]) { index + 2 } else { index + 1 }
+        }
+    }
+
+    fn handle_sc(&self, codes: &mut MetaphoneCodes, index: isize) -> isize {
+        if self.char_at(index + 2) == 'H' {
+            if self.contains(index + 3, 2, &["OO", "ER", "EN", "UY", "ED", "EM"]) {
+                // Dutch: "school", "schenker"
+                if self.contains(index + 3, 2, &["ER", "EN"]) {
+                    codes.add_both("X", "SK");
+                } else {
+                    codes.add("SK");
+                }
+            } else if index == 0 && !Self::is_vowel(self.char_at(3)) && self.char_at(3) != 'W' {
+                codes.add_both("X", "S");
+            } else {
+                codes.add("X");
+            }
+        } else if self.contains(index + 2, 1, &["I", "E", "Y"]) {
+            codes.add("S");
+        } else {
+            codes.add("SK");
+        }
+        index + 3
+    }
+
+    fn handle_t(&self, codes: &mut MetaphoneCodes, index: isize) -> isize {
+        if self.contains(index, 4, &["TION"]) || self.contains(index, 3, &["TIA", "TCH"]) {
+            codes.add("X");
+            index + 3
+        } else if self.contains(index, 2, &["TH"]) || self.contains(index, 3, &["TTH"]) {
+            // "Thomas", "Thames" and Germanic names keep a hard T
+            if self.contains(index + 2, 2, &["OM", "AM"]) || self.is_germanic() {
+                codes.add("T");
+            } else {
+                codes.add_both("0", "T");
+            }
+            index + 2
+        } else {
+            codes.add("T");
+            if self.contains(index + 1, 1, &["T", "D"]) { index + 2 } else { index + 1 }
+        }
+    }
+
+    fn handle_w(&self, codes: &mut MetaphoneCodes, index: isize) -> isize {
+        if self.contains(index, 2, &["WR"]) {
+            codes.add("R");
+            return index + 2;
+        }
+        if index == 0 && (Self::is_vowel(self.char_at(index + 1)) || self.contains(index, 2, &["WH"])) {
+            // "Wasserman" matches "Vasserman"
+            if Self::is_vowel(self.char_at(index + 1)) {
+                codes.add_both("A", "F");
+            } else {
+                codes.add("A");
+            }
+            index + 1
+        } else if (index == self.len() - 1 && Self::is_vowel(self.char_at(index - 1)))
+            || self.contains(index - 1, 5, &["EWSKI", "EWSKY", "OWSKI", "OWSKY"])
+            || self.contains(0, 3, &["SCH"]) {
+            // "Arnow" matches "Arnoff"
+            codes.add_both("", "F");
+            index + 1
+        } else if self.contains(index, 4, &["WICZ", "WITZ"]) {
+            codes.add_both("TS", "FX");
+            index + 4
+        } else {
+            index + 1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(text: &str) -> (&'static str, &'static str) {
+        let (primary, alternate) = double_metaphone(text);
+        (primary.leak(), alternate.leak())
+    }
+
+    #[test]
+    fn silent_initial_letters_are_dropped() {
+        assert_eq!(codes("Knight"), ("NT", "NT"));
+        assert_eq!(codes("Gnome"), ("NM", "NM"));
+        assert_eq!(codes("Wright"), ("RT", "RT"));
+        assert_eq!(codes("Dumb"), ("TM", "TM"));
+    }
+
+    #[test]
+    fn gh_is_silent_or_f_by_position() {
+        assert_eq!(codes("Ghost"), ("KST", "KST"));
+        assert_eq!(codes("Tough"), ("TF", "TF"));
+        assert_eq!(codes("Laugh"), ("LF", "LF"));
+        assert_eq!(codes("Hugh"), ("H", "H"));
+        assert_eq!(codes("Ghislane"), ("JLN", "JLN"));
+    }
+
+    #[test]
+    fn c_and_ch_follow_the_next_letters() {
+        assert_eq!(codes("Caesar"), ("SSR", "SSR"));
+        assert_eq!(codes("Accident"), ("AKSTNT", "AKSTNT"));
+        assert_eq!(codes("Bacchus"), ("PKS", "PKS"));
+        assert_eq!(codes("Campbell"), ("KMPL", "KMPL"));
+        assert_eq!(codes("Michael"), ("MKL", "MXL"));
+    }
+
+    #[test]
+    fn other_pronunciations_go_to_the_alternate() {
+        assert_eq!(codes("Smith"), ("SM0", "XMT"));
+        assert_eq!(codes("Schmidt"), ("XMT", "SMT"));
+        assert_eq!(codes("Sugar"), ("XKR", "SKR"));
+        assert_eq!(codes("Xavier"), ("SF", "SFR"));
+        assert_eq!(codes("Jose"), ("HS", "HS"));
+        assert_eq!(codes("Cabrillo"), ("KPRL", "KPR"));
+        assert_eq!(codes("Gallegos"), ("KLKS", "KKS"));
+        assert_eq!(codes("Arnow"), ("ARN", "ARNF"));
+        assert_eq!(codes("Filipowicz"), ("FLPTS", "FLPFX"));
+        assert_eq!(codes("Jankelowicz"), ("JNKLTS", "ANKLFX"));
+    }
+
+    #[test]
+    fn card_names_encode_whole_and_fold_accents() {
+        assert_eq!(codes("Lightning Bolt"), ("LTNNKPLT", "LTNNKPLT"));
+        assert_eq!(codes("Lim-Dûl"), codes("Lim-Dul"));
+        assert_eq!(codes("Ætherize"), codes("Aetherize"));
+        assert_eq!(codes("Deathrite Shaman"), ("T0RTXMN", "TTRTXMN"));
+    }
+
+    // (card name, primary, alternate)
+    const CARD_NAME_CODES: &[(&str, &str, &str)] = &[
+        ("Counterspell", "KNTRSPL", "KNTRSPL"),
+        ("Llanowar Elves", "LNRLFS", "LNRLFS"),
+        ("Phyrexian Arena", "FRKSNRN", "FRKSNRN"),
+        ("Psychic Spiral", "SXKSPRL", "SKKSPRL"),
+        ("Cyclonic Rift", "SKLNKRFT", "SKLNKRFT"),
+        ("Chandra Nalaar", "XNTRNLR", "XNTRNLR"),
+        ("Chaos Orb", "XSRP", "XSRP"),
+        ("Knight of the White Orchid", "NTF0TRKT", "NTFTTRKT"),
+        ("Gnaw to the Bone", "NT0PN", "NTTPN"),
+        ("Wrath of God", "R0FKT", "RTFKT"),
+        ("Wheel of Fortune", "ALFFRTN", "ALFFRTN"),
+        ("Swords to Plowshares", "SRTSTPLXRS", "XRTSTPLXRS"),
+        ("Thoughtseize", "0TSS", "TTSS"),
+        ("Tough Cookie", "TFKK", "TFKK"),
+        ("Ghost Quarter", "KSTKRTR", "KSTKRTR"),
+        ("Giant Growth", "JNTKR0", "KNTKRT"),
+        ("Gideon Jura", "JTNJR", "KTNJR"),
+        ("Geist of Saint Traft", "KSTFSNTTRFT", "JSTFSNTTRFT"),
+        ("Gyruda, Doom of Depths", "KRTTMFTP0S", "JRTTMFTPTS"),
+        ("Ghalta, Primal Hunger", "KLTPRMLNKR", "KLTPRMLNJR"),
+        ("Danger Close", "TNJRKLS", "TNKRKLS"),
+        ("Sengir Vampire", "SNJRFMPR", "SNKRFMPR"),
+        ("Jace Beleren", "JSPLRN", "ASPLRN"),
+        ("Ajani Goldmane", "AJNKLTMN", "AHNKLTMN"),
+        ("Jitte", "JT", "AT"),
+        ("Jötun Grunt", "JTNKRNT", "ATNKRNT"),
+        ("Xenagos, the Reveler", "SNKS0RFLR", "SNKSTRFLR"),
+        ("Zur the Enchanter", "SR0NXNTR", "SRTNKNTR"),
+        ("Tezzeret the Seeker", "TSRT0SKR", "TTSRTTSKR"),
+        ("Kiki-Jiki, Mirror Breaker", "KKJKMRRPRKR", "KKJKMRRPRKR"),
+        ("Urza's Saga", "ARSSSK", "ARSSSK"),
+        ("Mishra's Factory", "MXRSFKTR", "MXRSFKTR"),
+        ("Scion of the Ur-Dragon", "SNF0RTRKN", "SNFTRTRKN"),
+        ("Sheoldred, the Apocalypse", "XLTRT0PKLPS", "XLTRTTPKLPS"),
+        ("Æther Vial", "A0RFL", "ATRFL"),
+    ];
+
+    #[test]
+    fn card_names_match_their_expected_codes() {
+        for &(name, primary, alternate) in CARD_NAME_CODES {
+            assert_eq!(codes(name), (primary, alternate), "{name}");
+        }
+    }
+
+    #[test]
+    fn codes_are_capped_and_empty_without_letters() {
+        let (primary, alternate) = double_metaphone("Asmoranomardicadaistinaculdacar");
+        assert_eq!(primary.len(), DOUBLE_METAPHONE_MAX_LENGTH);
+        assert_eq!(alternate.len(), DOUBLE_METAPHONE_MAX_LENGTH);
+        assert_eq!(codes("1234"), ("", ""));
+        assert_eq!(codes(""), ("", ""));
+    }
+}
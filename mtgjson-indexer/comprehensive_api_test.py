@@ -209,7 +209,22 @@ class MTGAPITester:
             self.log_success("Health check passed")
         else:
             self.log_error(f"Health check failed: {result.error_message}")
-        
+
+        # Request IDs are echoed back, or generated when absent
+        try:
+            response = self.session.get(urljoin(self.base_url, "health"),
+                                        headers={"x-request-id": "api-test-request-id"},
+                                        timeout=self.timeout)
+            echoed = response.headers.get("x-request-id")
+            generated = self.session.get(urljoin(self.base_url, "health"),
+                                         timeout=self.timeout).headers.get("x-request-id")
+            if echoed == "api-test-request-id" and generated:
+                self.log_success("Request ID header echoed and generated")
+            else:
+                self.log_error(f"Request ID header mismatch: echoed={echoed}, generated={generated}")
+        except Exception as e:
+            self.log_error(f"Request ID check failed: {e}")
+
         # API stats
         result = self.make_request("/stats")
         results.append(result)
@@ -218,6 +233,14 @@ class MTGAPITester:
         else:
             self.log_error(f"API stats failed: {result.error_message}")
         
+        # Card cache metrics
+        result = self.make_request("/metrics")
+        results.append(result)
+        if result.success:
+            self.log_success("Metrics endpoint working")
+        else:
+            self.log_error(f"Metrics failed: {result.error_message}")
+        
         return TestSuite("Health & Status", results, time.time() - start_time)
 
     def test_card_endpoints(self) -> TestSuite:
@@ -235,7 +258,98 @@ class MTGAPITester:
                 self.log_success(f"Card search for '{query}' successful ({result.data_count} results)")
             else:
                 self.log_error(f"Card search for '{query}' failed: {result.error_message}")
-        
+
+        # Fuzzy name plus filters: a misspelled name still honours the rarity filter
+        params = {"q": "Lightnig Bolt", "rarity": "common", "format": "modern", "limit": 10}
+        result = self.make_request("/cards/search/name", params=params)
+        if result.success:
+            cards = self.session.get(f"{self.base_url}/cards/search/name", params=params,
+                                     timeout=self.timeout).json()['data']['results']
+            if not cards or any(card.get('rarity') != 'common' for card in cards):
+                result.success = False
+                result.error_message = "Fuzzy filtered search returned no or non-common cards"
+        results.append(result)
+        if result.success:
+            self.log_success("Fuzzy search with rarity/format filters successful")
+        else:
+            self.log_error(f"Fuzzy filtered search failed: {result.error_message}")
+
+        # Color count: a two-color gold card must land in color_count 2
+        params = {"q": "Lightning Helix", "color_count": 2, "limit": 10}
+        result = self.make_request("/cards/search/name", params=params)
+        if result.success:
+            cards = self.session.get(f"{self.base_url}/cards/search/name", params=params,
+                                     timeout=self.timeout).json()['data']['results']
+            if not any(card.get('name') == "Lightning Helix" for card in cards):
+                result.success = False
+                result.error_message = "Lightning Helix missing from color_count=2 results"
+            elif any(len(card.get('colors') or []) != 2 for card in cards):
+                result.success = False
+                result.error_message = "color_count=2 returned cards without exactly two colors"
+        results.append(result)
+        if result.success:
+            self.log_success("Color count filter returned Lightning Helix among two-color cards")
+        else:
+            self.log_error(f"Color count filter failed: {result.error_message}")
+
+        # Security stamp plus finish: acorn-stamped foils (Unfinity)
+        result = self.make_request("/cards/search/name",
+                                   params={"q": "*", "exact": "true", "security_stamp": "acorn",
+                                           "finish": "foil", "limit": 10})
+        results.append(result)
+        if result.success:
+            self.log_success(f"Security stamp filter successful ({result.data_count} cards)")
+        else:
+            self.log_error(f"Security stamp filter failed: {result.error_message}")
+
+        # Exact color identity: Bant must not include mono-color or four-color cards
+        result = self.make_request("/cards/by-identity/GWU", params={"limit": 25})
+        if result.success:
+            cards = self.session.get(f"{self.base_url}/cards/by-identity/GWU", params={"limit": 25},
+                                     timeout=self.timeout).json()['data']['cards']
+            if any(sorted(card.get('color_identity') or []) != ['G', 'U', 'W'] for card in cards):
+                result.success = False
+                result.error_message = "Non-Bant identity returned for /cards/by-identity/GWU"
+        results.append(result)
+        if result.success:
+            self.log_success("Exact color identity lookup returned only Bant cards")
+        else:
+            self.log_error(f"Exact color identity lookup failed: {result.error_message}")
+
+        # Reprint subsets such as The List
+        result = self.make_request("/cards/by-subset/The List", params={"limit": 10})
+        results.append(result)
+        if result.success:
+            self.log_success(f"Subset lookup successful ({result.data_count} cards)")
+        else:
+            self.log_error(f"Subset lookup failed: {result.error_message}")
+
+        # Curve fill: cheap green 2-drop creatures, and free spells at mana value 0
+        for mv, params in [("2", {"color": "G", "type": "Creature", "limit": 10}), ("0", {"sort": "name", "limit": 10})]:
+            result = self.make_request(f"/cards/by-mv/{mv}", params=params)
+            results.append(result)
+            if result.success:
+                self.log_success(f"Mana value {mv} lookup successful ({result.data_count} cards)")
+            else:
+                self.log_error(f"Mana value {mv} lookup failed: {result.error_message}")
+
+        # Never-reprinted cards, most valuable first
+        result = self.make_request("/cards/unique-printings", params={"limit": 10})
+        results.append(result)
+        if result.success:
+            self.log_success("Unique printings lookup successful")
+        else:
+            self.log_error(f"Unique printings lookup failed: {result.error_message}")
+
+        # Random cards, unfiltered and with intersected filters
+        for params in [{}, {"rarity": "mythic"}, {"rarity": "rare", "color": "g"}]:
+            result = self.make_request("/cards/random", params=params)
+            results.append(result)
+            if result.success:
+                self.log_success(f"Random card with {params or 'no filters'} successful")
+            else:
+                self.log_error(f"Random card with {params} failed: {result.error_message}")
+
         # Autocomplete
         autocomplete_queries = ["light", "black", "counter", "sol"]
         for query in autocomplete_queries:
@@ -255,6 +369,30 @@ class MTGAPITester:
                 self.log_success(f"Fuzzy search for '{query}' successful")
             else:
                 self.log_error(f"Fuzzy search for '{query}' failed: {result.error_message}")
+
+        # Full name -> oracle id -> printings mapping
+        result = self.make_request("/cards/name-mapping", params={"name": "Lightning Bolt"})
+        results.append(result)
+        if result.success:
+            self.log_success("Name mapping for 'Lightning Bolt' successful")
+        else:
+            self.log_error(f"Name mapping for 'Lightning Bolt' failed: {result.error_message}")
+
+        # Substring name search
+        result = self.make_request("/cards/search/contains", params={"q": "dragon", "limit": 5})
+        results.append(result)
+        if result.success:
+            self.log_success(f"Name contains search successful ({result.data_count} results)")
+        else:
+            self.log_error(f"Name contains search failed: {result.error_message}")
+
+        # Oracle text phrase search with Scryfall-style punctuation and highlighted snippets
+        result = self.make_request("/cards/search/text", params={"q": '"create a Treasure token" {T}', "limit": 5})
+        results.append(result)
+        if result.success:
+            self.log_success(f"Oracle text search successful ({result.data_count} results)")
+        else:
+            self.log_error(f"Oracle text search failed: {result.error_message}")
         
         # Get expensive cards
         for min_price in [50, 100, 500]:
@@ -273,7 +411,65 @@ class MTGAPITester:
                 self.log_success(f"Get card by UUID successful")
             else:
                 self.log_error(f"Get card by UUID failed: {result.error_message}")
-        
+
+        # Related cards resolve to summaries (possibly empty lists)
+        for uuid in self.sample_uuids[:2]:
+            result = self.make_request(f"/cards/{uuid}/related")
+            results.append(result)
+            if result.success:
+                self.log_success(f"Related cards lookup successful for {uuid}")
+            else:
+                self.log_error(f"Related cards lookup failed: {result.error_message}")
+
+        # Tokens and emblems a card creates (often an empty list)
+        for uuid in self.sample_uuids[:2]:
+            result = self.make_request(f"/cards/{uuid}/tokens")
+            results.append(result)
+            if result.success:
+                self.log_success(f"Token lookup successful for {uuid}")
+            else:
+                self.log_error(f"Token lookup failed: {result.error_message}")
+
+        # Round-trip: cards found via search must be readable by UUID
+        for uuid in self.sample_uuids[:3]:
+            result = self.make_request(f"/cards/{uuid}")
+            if result.success:
+                card = self.session.get(f"{self.base_url}/cards/{uuid}", timeout=self.timeout).json().get('data') or {}
+                if card.get('uuid') != uuid:
+                    result.success = False
+                    result.error_message = f"Searched card {uuid} read back as {card.get('uuid')}"
+            results.append(result)
+            if result.success:
+                self.log_success(f"Search/lookup round-trip successful for {uuid}")
+            else:
+                self.log_error(f"Search/lookup round-trip failed: {result.error_message}")
+
+        # Batch: results come back in order, a bad operation fails only itself
+        if self.sample_uuids:
+            uuid = self.sample_uuids[0]
+            operations = [
+                {"method": "get_card", "params": {"uuid": uuid}},
+                {"method": "get_card_prices", "params": {"uuid": uuid}},
+                {"method": "search", "params": {"q": "Lightning Bolt", "limit": 3}},
+                {"method": "no_such_method", "params": {}},
+            ]
+            start = time.time()
+            try:
+                response = self.session.post(urljoin(self.base_url, "batch"), json=operations, timeout=self.timeout)
+                batch = response.json().get('data') or []
+                ok = (response.status_code == 200 and len(batch) == 4
+                      and batch[0].get('success') and (batch[0].get('data') or {}).get('uuid') == uuid
+                      and not batch[3].get('success'))
+                error_message = None if ok else f"Unexpected batch response: {response.status_code}"
+            except Exception as e:
+                response, ok, error_message = None, False, str(e)
+            results.append(TestResult("/batch", "POST", response.status_code if response is not None else 0,
+                                      time.time() - start, ok, error_message))
+            if ok:
+                self.log_success("Batch request returned per-operation results in order")
+            else:
+                self.log_error(f"Batch request failed: {error_message}")
+
         return TestSuite("Card Endpoints", results, time.time() - start_time)
 
     def test_deck_endpoints(self) -> TestSuite:
@@ -290,6 +486,15 @@ class MTGAPITester:
         else:
             self.log_error(f"Commander decks failed: {result.error_message}")
         
+        # Commander decks by color identity, exact and within-colors
+        for colors, exact in [("WUBG", "true"), ("UB", "false"), ("C", "true")]:
+            result = self.make_request("/decks/by-color-identity", params={"colors": colors, "exact": exact})
+            results.append(result)
+            if result.success:
+                self.log_success(f"Decks by color identity {colors} (exact={exact}) successful")
+            else:
+                self.log_error(f"Decks by color identity {colors} failed: {result.error_message}")
+        
         # Search decks by name
         deck_queries = ["Commander", "Planeswalker", "Duel"]
         for query in deck_queries:
@@ -318,6 +523,14 @@ class MTGAPITester:
                 self.log_success(f"Expensive decks (>${min_value}) successful ({result.data_count} decks)")
             else:
                 self.log_error(f"Expensive decks (>${min_value}) failed: {result.error_message}")
+
+        # Spell value: deck values without basic lands
+        result = self.make_request("/decks/expensive", params={"min_price": 100, "exclude_basics": "true"})
+        results.append(result)
+        if result.success:
+            self.log_success(f"Expensive decks excluding basics successful ({result.data_count} decks)")
+        else:
+            self.log_error(f"Expensive decks excluding basics failed: {result.error_message}")
         
         # Get specific decks by UUID and their composition
         for uuid in self.sample_deck_uuids[:3]:
@@ -336,6 +549,54 @@ class MTGAPITester:
                 self.log_success(f"Get deck composition successful")
             else:
                 self.log_error(f"Get deck composition failed: {result.error_message}")
+            
+            # Similar precons
+            result = self.make_request(f"/decks/{uuid}/similar", params={"limit": 5})
+            results.append(result)
+            if result.success:
+                self.log_success(f"Similar decks successful")
+            else:
+                self.log_error(f"Similar decks failed: {result.error_message}")
+            
+            # Per-card share of the deck's value
+            result = self.make_request(f"/decks/{uuid}/value-breakdown")
+            results.append(result)
+            if result.success:
+                self.log_success(f"Deck value breakdown successful")
+            else:
+                self.log_error(f"Deck value breakdown failed: {result.error_message}")
+            
+            # Heuristic Commander bracket
+            result = self.make_request(f"/decks/{uuid}/power-level")
+            results.append(result)
+            if result.success:
+                self.log_success(f"Deck power level successful")
+            else:
+                self.log_error(f"Deck power level failed: {result.error_message}")
+            
+            # Market value per index run
+            result = self.make_request(f"/decks/{uuid}/value-history", params={"days": 365})
+            results.append(result)
+            if result.success:
+                self.log_success(f"Deck value history successful")
+            else:
+                self.log_error(f"Deck value history failed: {result.error_message}")
+            
+            # Get proxy print layout
+            result = self.make_request(f"/decks/{uuid}/export/proxy-urls")
+            results.append(result)
+            if result.success:
+                self.log_success(f"Deck proxy export successful")
+            else:
+                self.log_error(f"Deck proxy export failed: {result.error_message}")
+            
+            # Cockatrice .cod XML
+            result = self.make_request(f"/decks/{uuid}/export/cockatrice")
+            results.append(result)
+            if result.success:
+                self.log_success(f"Deck Cockatrice export successful ({result.response_size} bytes)")
+            else:
+                self.log_error(f"Deck Cockatrice export failed: {result.error_message}")
         
         return TestSuite("Deck Endpoints", results, time.time() - start_time)
 
@@ -371,6 +632,31 @@ class MTGAPITester:
                     self.log_success(f"Card price for condition '{condition}' successful")
                 else:
                     self.log_warning(f"Card price for condition '{condition}' not found (expected)")
+            
+            # Every condition in one call
+            result = self.make_request(f"/pricing/card/{uuid}/conditions", params={"finish": "nonfoil"})
+            results.append(result)
+            if result.success:
+                self.log_success(f"All-condition prices successful")
+            else:
+                self.log_error(f"All-condition prices failed: {result.error_message}")
+
+            # Card-level price history (resolves the NM non-foil SKU)
+            result = self.make_request(f"/pricing/card/{uuid}/history",
+                                       params={"finish": "nonfoil", "condition": "NM", "days": 90})
+            results.append(result)
+            if result.success:
+                self.log_success(f"Card price history successful ({result.data_count} points)")
+            else:
+                self.log_error(f"Card price history failed: {result.error_message}")
+            
+            # Latest prices at each marketplace
+            result = self.make_request(f"/pricing/card/{uuid}/vendors")
+            results.append(result)
+            if result.success:
+                self.log_success(f"Cross-vendor prices successful")
+            else:
+                self.log_error(f"Cross-vendor prices failed: {result.error_message}")
         
         return TestSuite("Pricing Endpoints", results, time.time() - start_time)
 
@@ -397,6 +683,32 @@ class MTGAPITester:
             else:
                 self.log_error(f"Get set '{set_code}' failed: {result.error_message}")
         
+        # Full set as CSV
+        if self.sample_set_codes:
+            set_code = self.sample_set_codes[0]
+            result = self.make_request(f"/sets/{set_code}/export/csv")
+            results.append(result)
+            if result.success:
+                self.log_success(f"Set '{set_code}' CSV export successful ({result.response_size} bytes)")
+            else:
+                self.log_error(f"Set '{set_code}' CSV export failed: {result.error_message}")
+            
+            # Full card objects, paginated in collector-number order
+            result = self.make_request(f"/sets/{set_code}/cards", params={"limit": 20, "offset": 0})
+            results.append(result)
+            if result.success:
+                self.log_success(f"Set '{set_code}' cards successful")
+            else:
+                self.log_error(f"Set '{set_code}' cards failed: {result.error_message}")
+        
+        # Legality changes between index runs (empty after the first run)
+        result = self.make_request("/formats/modern/changes", params={"since": "2024-01-01"})
+        results.append(result)
+        if result.success:
+            self.log_success(f"Modern legality changes successful")
+        else:
+            self.log_error(f"Modern legality changes failed: {result.error_message}")
+        
         return TestSuite("Set Endpoints", results, time.time() - start_time)
 
     def test_analytics_endpoints(self) -> TestSuite:
@@ -421,6 +733,23 @@ class MTGAPITester:
         else:
             self.log_error(f"Memory usage failed: {result.error_message}")
         
+        # Cards with the most SKU variations
+        result = self.make_request("/analytics/most-variant", params={"limit": 10})
+        results.append(result)
+        if result.success:
+            self.log_success("Most variant cards successful")
+        else:
+            self.log_error(f"Most variant cards failed: {result.error_message}")
+        
+        # Data-quality gaps: counts plus a sample
+        for data_type in ["no_price", "no_image", "no_oracle_id", "no_tcgplayer_id"]:
+            result = self.make_request(f"/analytics/missing/{data_type}", params={"limit": 5})
+            results.append(result)
+            if result.success:
+                self.log_success(f"Missing data ({data_type}) successful")
+            else:
+                self.log_error(f"Missing data ({data_type}) failed: {result.error_message}")
+        
         return TestSuite("Analytics Endpoints", results, time.time() - start_time)
 
     def test_error_handling(self) -> TestSuite:
@@ -7,7 +7,7 @@ local function log(message)
     return "LOG: " .. message
 end
 
-local function safe_create_index(index_name, schema_args)
+local function safe_create_index(index_name, schema_args, keep_documents)
     -- Check if index already exists
     local exists = pcall(function()
         return redis.call("FT.INFO", index_name)
@@ -15,7 +15,11 @@ local function safe_create_index(index_name, schema_args)
     
     if exists then
         log("Index " .. index_name .. " already exists, dropping and recreating...")
-        redis.call("FT.DROPINDEX", index_name, "DD")
+        if keep_documents then
+            redis.call("FT.DROPINDEX", index_name)
+        else
+            redis.call("FT.DROPINDEX", index_name, "DD")
+        end
     end
     
     -- Create the index
@@ -40,6 +44,35 @@ local results = {}
 
 log("Creating MTGJSON system indexes...")
 
+-- 0. Card index used by the API server (mtg:cards:data:* documents); keep in sync
+-- with create_redisearch_indexes in src/main.rs. Recreated without DD so the card
+-- documents written by the indexer survive.
+table.insert(results, safe_create_index("mtg:cards:idx", {
+    "ON", "JSON",
+    "PREFIX", "1", "mtg:cards:data:",
+    "SCHEMA",
+    "$.uuid", "AS", "uuid", "TEXT", "NOSTEM", "SORTABLE",
+    "$.name", "AS", "name", "TEXT", "PHONETIC", "dm:en", "SORTABLE",
+    "$.face_name", "AS", "face_name", "TEXT", "PHONETIC", "dm:en",
+    "$.set_code", "AS", "set_code", "TAG", "SORTABLE",
+    "$.set_name", "AS", "set_name", "TEXT", "SORTABLE",
+    "$.mana_value", "AS", "mana_value", "NUMERIC", "SORTABLE",
+    "$.types", "AS", "types", "TAG", "SEPARATOR", " ",
+    "$.colors", "AS", "colors", "TAG", "SEPARATOR", ",",
+    "$.color_identity", "AS", "color_identity", "TAG", "SEPARATOR", ",",
+    "$.color_count", "AS", "color_count", "NUMERIC", "SORTABLE",
+    "$.rarity", "AS", "rarity", "TAG", "SORTABLE",
+    "$.border_color", "AS", "border_color", "TAG",
+    "$.is_full_art", "AS", "is_full_art", "TAG",
+    "$.is_textless", "AS", "is_textless", "TAG",
+    "$.security_stamp", "AS", "security_stamp", "TAG",
+    "$.finishes[*]", "AS", "finishes", "TAG",
+    "$.legal_formats[*]", "AS", "legal_formats", "TAG",
+    "$.tcgplayer_product_id", "AS", "tcg_product", "TAG", "SORTABLE",
+    "$.text", "AS", "text", "TEXT",
+    "$.release_date", "AS", "release_date", "TEXT", "SORTABLE"
+}, true))
+
 -- 1. Main MTGJSON Card Index
 table.insert(results, safe_create_index("idx_mtgjson_cards", {
     "ON", "JSON",
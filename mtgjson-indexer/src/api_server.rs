@@ -1,19 +1,21 @@
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
-    response::{IntoResponse, Json},
-    routing::get,
+    extract::{MatchedPath, Path, Query, Request, State},
+    http::{HeaderValue, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Json, Response},
+    routing::{get, post},
     Router,
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::sync::Mutex;
 use tower::ServiceBuilder;
 use tower_http::{cors::CorsLayer, trace::TraceLayer};
-use tracing::{info, error};
+use tracing::{info, error, Instrument};
 use tracing_subscriber;
 
-use mtgjson_indexer::{redis_client::*, api_types::*};
+use mtgjson_indexer::{redis_client::*, api_types::*, color_identity_key};
 
 // =============================================================================
 // STATE AND ERROR HANDLING
@@ -46,6 +48,22 @@ impl<T> ApiResponse<T> {
     }
 }
 
+// Redis command timeouts surface as 504 so clients can tell them from real failures;
+// an index written with an incompatible card schema, or a missing search index, is 503
+// until it is rebuilt; a malformed search filter value is 400
+fn error_status(e: &anyhow::Error) -> StatusCode {
+    if e.downcast_ref::<InvalidSearchFilter>().is_some() {
+        return StatusCode::BAD_REQUEST;
+    }
+    if e.downcast_ref::<ReindexRequired>().is_some() || e.downcast_ref::<SearchIndexMissing>().is_some() {
+        return StatusCode::SERVICE_UNAVAILABLE;
+    }
+    match e.downcast_ref::<redis::RedisError>() {
+        Some(redis_err) if redis_err.is_timeout() => StatusCode::GATEWAY_TIMEOUT,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
 // =============================================================================
 // CARD ENDPOINTS
 // =============================================================================
@@ -56,35 +74,82 @@ async fn get_card(
 ) -> impl IntoResponse {
     let mut client = state.lock().await;
     
-    match client.get_card_by_uuid(&uuid).await {
-        Ok(Some(card)) => Json(ApiResponse::ok(card)).into_response(),
+    match client.get_card_detail(&uuid).await {
+        Ok(Some(detail)) => Json(ApiResponse::ok(detail)).into_response(),
         Ok(None) => (StatusCode::NOT_FOUND, Json(ApiResponse::<()>::error("Card not found".to_string()))).into_response(),
         Err(e) => {
             error!("Error getting card {}: {}", uuid, e);
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
         }
     }
 }
 
+// Filters map for the card search methods from a search request's query parameters
+fn search_filters(params: &SearchQuery) -> std::collections::HashMap<String, String> {
+    let mut filters = std::collections::HashMap::new();
+    if let Some(set_code) = &params.set_code {
+        filters.insert("set".to_string(), set_code.to_string());
+    }
+    if let Some(rarity) = &params.rarity {
+        filters.insert("rarity".to_string(), rarity.to_string());
+    }
+    if let Some(format) = &params.format {
+        filters.insert("format".to_string(), format.to_string());
+    }
+    if let Some(color) = &params.color {
+        filters.insert("color".to_string(), color.to_string());
+    }
+    if let Some(border_color) = &params.border_color {
+        filters.insert("border_color".to_string(), border_color.to_string());
+    }
+    if let Some(full_art) = &params.full_art {
+        filters.insert("full_art".to_string(), full_art.to_string());
+    }
+    if let Some(textless) = &params.textless {
+        filters.insert("textless".to_string(), textless.to_string());
+    }
+    if let Some(security_stamp) = &params.security_stamp {
+        filters.insert("security_stamp".to_string(), security_stamp.to_string());
+    }
+    if let Some(finish) = &params.finish {
+        filters.insert("finish".to_string(), finish.to_string());
+    }
+    if let Some(mana_value_max) = &params.mana_value_max {
+        filters.insert("mana_value_max".to_string(), mana_value_max.to_string());
+    }
+    if let Some(color_count) = &params.color_count {
+        filters.insert("color_count".to_string(), color_count.to_string());
+    }
+    if let Some(not_color) = &params.not_color {
+        filters.insert("not_color".to_string(), not_color.to_string());
+    }
+    if let Some(not_rarity) = &params.not_rarity {
+        filters.insert("not_rarity".to_string(), not_rarity.to_string());
+    }
+    if let Some(not_type) = &params.not_type {
+        filters.insert("not_type".to_string(), not_type.to_string());
+    }
+    if let Some(not_set) = &params.not_set {
+        filters.insert("not_set".to_string(), not_set.to_string());
+    }
+    filters
+}
+
 async fn search_cards(
     Query(params): Query<SearchQuery>,
     State(state): State<AppState>,
 ) -> impl IntoResponse {
     let mut client = state.lock().await;
     
-    // Build filters HashMap
-    let mut filters = std::collections::HashMap::new();
-    if let Some(set_code) = params.set_code {
-        filters.insert("set".to_string(), set_code);
-    }
-    if let Some(rarity) = params.rarity {
-        filters.insert("rarity".to_string(), rarity);
-    }
-    if let Some(color) = params.color {
-        filters.insert("color".to_string(), color);
-    }
+    let filters = search_filters(&params);
+    
+    let result = if params.exact {
+        client.search_cards_by_name(&params.q, params.limit, filters).await
+    } else {
+        client.search_cards_filtered_fuzzy(&params.q, filters, params.limit).await
+    };
     
-    match client.search_cards_by_name(&params.q, params.limit, filters).await {
+    match result {
         Ok(cards) => {
             let response = SearchResponse {
                 query: params.q,
@@ -95,7 +160,341 @@ async fn search_cards(
         }
         Err(e) => {
             error!("Error searching cards: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+async fn get_card_by_tcgplayer_id(
+    Path(product_id): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let mut client = state.lock().await;
+    
+    let card_uuid = match client.get_card_by_tcgplayer_id(&product_id).await {
+        Ok(Some(uuid)) => uuid,
+        Ok(None) => return (StatusCode::NOT_FOUND, Json(ApiResponse::<()>::error("No card mapped to this TCGPlayer product".to_string()))).into_response(),
+        Err(e) => {
+            error!("Error looking up TCGPlayer product {}: {}", product_id, e);
+            return (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response();
+        }
+    };
+    
+    match client.get_card_by_uuid(&card_uuid).await {
+        Ok(Some(card)) => Json(ApiResponse::ok(card)).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, Json(ApiResponse::<()>::error("Card not found".to_string()))).into_response(),
+        Err(e) => {
+            error!("Error getting card {}: {}", card_uuid, e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+async fn get_card_text_history(
+    Path(uuid): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let mut client = state.lock().await;
+    
+    match client.get_card_by_uuid(&uuid).await {
+        Ok(Some(card)) => {
+            // Printed text only differs from oracle text when the card has been errata'd
+            let errata = card.original_text.is_some() && card.original_text != card.text;
+            let response = serde_json::json!({
+                "uuid": card.uuid,
+                "name": card.name,
+                "set_code": card.set_code,
+                "has_errata": errata,
+                "original_text": card.original_text,
+                "current_text": card.text
+            });
+            Json(ApiResponse::ok(response)).into_response()
+        }
+        Ok(None) => (StatusCode::NOT_FOUND, Json(ApiResponse::<()>::error("Card not found".to_string()))).into_response(),
+        Err(e) => {
+            error!("Error getting text history for card {}: {}", uuid, e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+async fn get_card_tokens(
+    Path(uuid): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let mut client = state.lock().await;
+    
+    match client.get_tokens_created_by(&uuid).await {
+        Ok(Some(tokens)) => {
+            let response = serde_json::json!({
+                "uuid": uuid,
+                "count": tokens.len(),
+                "tokens": tokens
+            });
+            Json(ApiResponse::ok(response)).into_response()
+        }
+        Ok(None) => (StatusCode::NOT_FOUND, Json(ApiResponse::<()>::error("Card not found".to_string()))).into_response(),
+        Err(e) => {
+            error!("Error getting tokens for card {}: {}", uuid, e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+async fn get_related_cards(
+    Path(uuid): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let mut client = state.lock().await;
+    
+    match client.get_related_cards(&uuid).await {
+        Ok(Some((card, spellbook, reverse_related))) => {
+            let response = serde_json::json!({
+                "uuid": card.uuid,
+                "name": card.name,
+                "spellbook": spellbook,
+                "reverse_related": reverse_related
+            });
+            Json(ApiResponse::ok(response)).into_response()
+        }
+        Ok(None) => (StatusCode::NOT_FOUND, Json(ApiResponse::<()>::error("Card not found".to_string()))).into_response(),
+        Err(e) => {
+            error!("Error getting related cards for {}: {}", uuid, e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+async fn get_name_mapping(
+    Query(params): Query<NameMappingQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let mut client = state.lock().await;
+    
+    match client.get_name_mapping(&params.name).await {
+        Ok(Some(mapping)) => Json(ApiResponse::ok(mapping)).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, Json(ApiResponse::<()>::error("Card name not found".to_string()))).into_response(),
+        Err(e) => {
+            error!("Error getting name mapping for {}: {}", params.name, e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+async fn get_new_cards(
+    Query(params): Query<NewCardsQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    if chrono::NaiveDate::parse_from_str(&params.since, "%Y-%m-%d").is_err() {
+        return (StatusCode::BAD_REQUEST, Json(ApiResponse::<()>::error("since must be YYYY-MM-DD".to_string()))).into_response();
+    }
+
+    let mut client = state.lock().await;
+    
+    match client.get_new_cards_since(&params.since, params.offset, params.limit).await {
+        Ok((cards, total)) => {
+            let response = serde_json::json!({
+                "since": params.since,
+                "total": total,
+                "offset": params.offset,
+                "count": cards.len(),
+                "cards": cards
+            });
+            Json(ApiResponse::ok(response)).into_response()
+        }
+        Err(e) => {
+            error!("Error getting new cards since {}: {}", params.since, e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+async fn get_random_card(
+    Query(params): Query<RandomCardQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let mut filters = std::collections::HashMap::new();
+    for (name, value) in [("set", params.set), ("rarity", params.rarity), ("color", params.color), ("format", params.format)] {
+        if let Some(value) = value {
+            filters.insert(name.to_string(), value);
+        }
+    }
+    
+    let mut client = state.lock().await;
+    
+    match client.get_random_card(&filters).await {
+        Ok(Some(card)) => Json(ApiResponse::ok(card)).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, Json(ApiResponse::<()>::error("No cards match these filters".to_string()))).into_response(),
+        Err(e) => {
+            error!("Error getting random card: {}", e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+async fn get_cards_by_subtype(
+    Path(subtype): Path<String>,
+    Query(params): Query<SubtypeQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let mut client = state.lock().await;
+    
+    match client.get_cards_by_subtype(&subtype, params.color.as_deref(), params.format.as_deref(), params.offset, params.limit).await {
+        Ok((cards, total)) => {
+            let response = serde_json::json!({
+                "subtype": subtype,
+                "total": total,
+                "offset": params.offset,
+                "count": cards.len(),
+                "cards": cards
+            });
+            Json(ApiResponse::ok(response)).into_response()
+        }
+        Err(e) => {
+            error!("Error getting cards by subtype {}: {}", subtype, e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+async fn get_cards_by_mana_value(
+    Path(mana_value): Path<String>,
+    Query(params): Query<ManaValueQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let mana_value = match mana_value.parse::<f32>() {
+        Ok(mv) if mv.is_finite() && mv >= 0.0 => mv,
+        _ => return (StatusCode::BAD_REQUEST, Json(ApiResponse::<()>::error("Mana value must be a number 0 or greater".to_string()))).into_response(),
+    };
+    if !MANA_VALUE_SORTS.contains(&params.sort.as_str()) {
+        return (StatusCode::BAD_REQUEST, Json(ApiResponse::<()>::error(
+            format!("Unknown sort '{}', expected one of: {}", params.sort, MANA_VALUE_SORTS.join(", "))
+        ))).into_response();
+    }
+    
+    let mut filters = std::collections::HashMap::new();
+    for (name, value) in [("color", &params.color), ("type", &params.card_type), ("format", &params.format), ("rarity", &params.rarity)] {
+        if let Some(value) = value {
+            filters.insert(name.to_string(), value.clone());
+        }
+    }
+    
+    let mut client = state.lock().await;
+    
+    match client.get_cards_by_mana_value(mana_value, filters, &params.sort, params.include_uncastable, params.limit).await {
+        Ok(cards) => {
+            let response = serde_json::json!({
+                "mana_value": mana_value,
+                "sort": params.sort,
+                "count": cards.len(),
+                "cards": cards
+            });
+            Json(ApiResponse::ok(response)).into_response()
+        }
+        Err(e) => {
+            error!("Error getting cards with mana value {}: {}", mana_value, e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+async fn get_cards_by_subset(
+    Path(subset): Path<String>,
+    Query(params): Query<SubsetQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let mut client = state.lock().await;
+    
+    match client.get_cards_by_subset(&subset, params.offset, params.limit).await {
+        Ok((cards, total)) => {
+            let response = serde_json::json!({
+                "subset": subset,
+                "total": total,
+                "offset": params.offset,
+                "count": cards.len(),
+                "cards": cards
+            });
+            Json(ApiResponse::ok(response)).into_response()
+        }
+        Err(e) => {
+            error!("Error getting cards by subset {}: {}", subset, e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+// A string of color symbols in any order (`GWU`, `wub`), or `C`/`colorless`, as colors
+fn parse_color_identity(identity: &str) -> Result<Vec<String>, String> {
+    let colors: Vec<String> = if identity.eq_ignore_ascii_case("c") || identity.eq_ignore_ascii_case("colorless") {
+        Vec::new()
+    } else {
+        identity.chars().map(|c| c.to_ascii_uppercase().to_string()).collect()
+    };
+    match colors.iter().find(|c| !"WUBRG".contains(c.as_str())) {
+        Some(invalid) => Err(format!("Invalid color symbol '{}', expected W, U, B, R, G or C", invalid)),
+        None => Ok(colors),
+    }
+}
+
+/// `identity` is a string of color symbols in any order (`GWU`, `wub`), or `C`/`colorless`.
+async fn get_cards_by_color_identity(
+    Path(identity): Path<String>,
+    Query(params): Query<ColorIdentityQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let colors = match parse_color_identity(&identity) {
+        Ok(colors) => colors,
+        Err(message) => return (StatusCode::BAD_REQUEST, Json(ApiResponse::<()>::error(message))).into_response(),
+    };
+    
+    let mut client = state.lock().await;
+    
+    match client.get_cards_by_exact_color_identity(&colors, params.offset, params.limit).await {
+        Ok((cards, total)) => {
+            let response = serde_json::json!({
+                "identity": color_identity_key(&colors),
+                "total": total,
+                "offset": params.offset,
+                "count": cards.len(),
+                "cards": cards
+            });
+            Json(ApiResponse::ok(response)).into_response()
+        }
+        Err(e) => {
+            error!("Error getting cards by color identity {}: {}", identity, e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+async fn get_unique_printing_cards(
+    Query(params): Query<UniquePrintingsQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let ascending = match params.sort.as_deref() {
+        None | Some("price") => false,
+        Some("price_asc") => true,
+        Some(other) => {
+            return (StatusCode::BAD_REQUEST, Json(ApiResponse::<()>::error(format!("Invalid sort '{}', expected price or price_asc", other)))).into_response();
+        }
+    };
+    
+    let mut client = state.lock().await;
+    
+    match client.get_unique_printing_cards(ascending, params.offset, params.limit).await {
+        Ok((cards, total)) => {
+            let response = serde_json::json!({
+                "sort": if ascending { "price_asc" } else { "price" },
+                "total": total,
+                "offset": params.offset,
+                "count": cards.len(),
+                "cards": cards
+            });
+            Json(ApiResponse::ok(response)).into_response()
+        }
+        Err(e) => {
+            error!("Error getting unique printing cards: {}", e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
         }
     }
 }
@@ -116,7 +515,7 @@ async fn autocomplete_cards(
         }
         Err(e) => {
             error!("Error getting autocomplete: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
         }
     }
 }
@@ -138,7 +537,53 @@ async fn fuzzy_search_cards(
         }
         Err(e) => {
             error!("Error performing fuzzy search: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+async fn search_cards_name_contains(
+    Query(params): Query<SearchQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let mut client = state.lock().await;
+    
+    match client.search_cards_name_contains(&params.q, params.limit).await {
+        Ok(cards) => {
+            let response = SearchResponse {
+                query: params.q,
+                count: cards.len(),
+                results: cards,
+            };
+            Json(ApiResponse::ok(response)).into_response()
+        }
+        Err(e) => {
+            error!("Error performing name contains search: {}", e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+async fn search_cards_by_text(
+    Query(params): Query<SearchQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let mut client = state.lock().await;
+    
+    let filters = search_filters(&params);
+    
+    match client.search_cards_by_text(&params.q, params.limit, filters).await {
+        Ok(cards) => {
+            let response = SearchResponse {
+                query: params.q,
+                count: cards.len(),
+                results: cards,
+            };
+            Json(ApiResponse::ok(response)).into_response()
+        }
+        Err(e) => {
+            error!("Error performing oracle text search: {}", e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
         }
     }
 }
@@ -160,7 +605,7 @@ async fn get_expensive_cards(
         }
         Err(e) => {
             error!("Error getting expensive cards: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
         }
     }
 }
@@ -171,16 +616,58 @@ async fn get_expensive_cards(
 
 async fn get_deck(
     Path(uuid): Path<String>,
+    Query(params): Query<DeckValueQuery>,
     State(state): State<AppState>,
 ) -> impl IntoResponse {
     let mut client = state.lock().await;
     
     match client.get_deck_by_uuid(&uuid).await {
-        Ok(Some(deck)) => Json(ApiResponse::ok(deck)).into_response(),
+        Ok(Some(mut deck)) => {
+            if let (Some(exclude), Some(value)) = (params.exclude_basics, &deck.estimated_value) {
+                deck.estimated_value = Some(value.with_basics_excluded(exclude));
+            }
+            Json(ApiResponse::ok(deck)).into_response()
+        }
         Ok(None) => (StatusCode::NOT_FOUND, Json(ApiResponse::<()>::error("Deck not found".to_string()))).into_response(),
         Err(e) => {
             error!("Error getting deck {}: {}", uuid, e);
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+async fn get_similar_decks(
+    Path(uuid): Path<String>,
+    Query(params): Query<SimilarDecksQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let mut client = state.lock().await;
+    
+    match client.get_similar_decks(&uuid, params.limit).await {
+        Ok(Some(similar)) => {
+            let decks: Vec<_> = similar
+                .into_iter()
+                .map(|(deck, similarity)| serde_json::json!({
+                    "similarity": similarity,
+                    "uuid": deck.uuid,
+                    "name": deck.name,
+                    "code": deck.code,
+                    "deck_type": deck.deck_type,
+                    "release_date": deck.release_date,
+                    "commanders": deck.commanders.iter().map(|c| &c.name).collect::<Vec<_>>()
+                }))
+                .collect();
+            let response = serde_json::json!({
+                "uuid": uuid,
+                "count": decks.len(),
+                "decks": decks
+            });
+            Json(ApiResponse::ok(response)).into_response()
+        }
+        Ok(None) => (StatusCode::NOT_FOUND, Json(ApiResponse::<()>::error("Deck not found".to_string()))).into_response(),
+        Err(e) => {
+            error!("Error finding decks similar to {}: {}", uuid, e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
         }
     }
 }
@@ -192,94 +679,294 @@ async fn get_deck_composition(
     let mut client = state.lock().await;
     
     match client.get_deck_composition(&uuid).await {
-        Ok(composition) => Json(ApiResponse::ok(composition)).into_response(),
+        Ok(Some(composition)) => Json(ApiResponse::ok(composition)).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, Json(ApiResponse::<()>::error("Deck not found".to_string()))).into_response(),
         Err(e) => {
             error!("Error getting deck composition: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+async fn get_deck_power_level(
+    Path(uuid): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let mut client = state.lock().await;
+    
+    match client.estimate_deck_power(&uuid).await {
+        Ok(Some(estimate)) => Json(ApiResponse::ok(estimate)).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, Json(ApiResponse::<()>::error("Deck not found".to_string()))).into_response(),
+        Err(e) => {
+            error!("Error estimating power level for deck {}: {}", uuid, e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+async fn get_deck_value_breakdown(
+    Path(uuid): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let mut client = state.lock().await;
+    
+    match client.get_deck_value_breakdown(&uuid).await {
+        Ok(Some(breakdown)) => {
+            let market_total: f64 = breakdown.iter().map(|(card, unit_price, _)| unit_price * card.count as f64).sum();
+            let cards: Vec<_> = breakdown
+                .into_iter()
+                .map(|(card, unit_price, share)| serde_json::json!({
+                    "uuid": card.uuid,
+                    "name": card.name,
+                    "set_code": card.set_code,
+                    "count": card.count,
+                    "is_basic_land": card.is_basic_land,
+                    "unit_price": unit_price,
+                    "total_price": unit_price * card.count as f64,
+                    "value_share": (share * 10000.0).round() / 10000.0
+                }))
+                .collect();
+            let response = serde_json::json!({
+                "uuid": uuid,
+                "market_total": market_total,
+                "count": cards.len(),
+                "cards": cards
+            });
+            Json(ApiResponse::ok(response)).into_response()
+        }
+        Ok(None) => (StatusCode::NOT_FOUND, Json(ApiResponse::<()>::error("Deck not found".to_string()))).into_response(),
+        Err(e) => {
+            error!("Error getting value breakdown for deck {}: {}", uuid, e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+async fn get_commander_decks(State(state): State<AppState>) -> impl IntoResponse {
+    let mut client = state.lock().await;
+    
+    match client.get_commander_decks().await {
+        Ok(decks) => {
+            let response = serde_json::json!({
+                "count": decks.len(),
+                "decks": decks
+            });
+            Json(ApiResponse::ok(response)).into_response()
+        }
+        Err(e) => {
+            error!("Error getting commander decks: {}", e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+async fn search_decks(
+    Query(params): Query<SearchQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let mut client = state.lock().await;
+    
+    match client.search_decks_by_name(&params.q).await {
+        Ok(decks) => {
+            let response = SearchResponse {
+                query: params.q,
+                count: decks.len(),
+                results: decks,
+            };
+            Json(ApiResponse::ok(response)).into_response()
+        }
+        Err(e) => {
+            error!("Error searching decks: {}", e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+async fn find_decks_with_card(
+    Query(params): Query<SearchQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let mut client = state.lock().await;
+    
+    match client.find_decks_containing_card(&params.q).await {
+        Ok(decks) => {
+            let response = serde_json::json!({
+                "card_name": params.q,
+                "count": decks.len(),
+                "decks": decks
+            });
+            Json(ApiResponse::ok(response)).into_response()
+        }
+        Err(e) => {
+            error!("Error finding decks with card: {}", e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+async fn get_expensive_decks(
+    Query(params): Query<ExpensiveDecksQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let mut client = state.lock().await;
+    
+    match client.get_expensive_decks(params.min_price, params.exclude_basics).await {
+        Ok(decks) => {
+            let response = serde_json::json!({
+                "min_value": params.min_price,
+                "exclude_basics": params.exclude_basics,
+                "count": decks.len(),
+                "decks": decks
+            });
+            Json(ApiResponse::ok(response)).into_response()
+        }
+        Err(e) => {
+            error!("Error getting expensive decks: {}", e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
         }
     }
 }
 
-async fn get_commander_decks(State(state): State<AppState>) -> impl IntoResponse {
+async fn get_decks_by_commander_pair(
+    Query(params): Query<CommanderPairQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
     let mut client = state.lock().await;
     
-    match client.get_commander_decks().await {
+    match client.get_decks_by_commander_pair(&params.a, &params.b).await {
         Ok(decks) => {
             let response = serde_json::json!({
+                "commanders": [params.a, params.b],
                 "count": decks.len(),
                 "decks": decks
             });
             Json(ApiResponse::ok(response)).into_response()
         }
         Err(e) => {
-            error!("Error getting commander decks: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+            error!("Error getting decks by commander pair: {}", e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
         }
     }
 }
 
-async fn search_decks(
-    Query(params): Query<SearchQuery>,
+async fn get_deck_value_history(
+    Path(uuid): Path<String>,
+    Query(params): Query<PriceHistoryQuery>,
     State(state): State<AppState>,
 ) -> impl IntoResponse {
     let mut client = state.lock().await;
     
-    match client.search_decks_by_name(&params.q).await {
-        Ok(decks) => {
-            let response = SearchResponse {
-                query: params.q,
-                count: decks.len(),
-                results: decks,
-            };
+    match client.get_deck_value_history(&uuid, params.days).await {
+        Ok(history) => {
+            let response = serde_json::json!({
+                "uuid": uuid,
+                "days": params.days,
+                "count": history.len(),
+                "history": history.into_iter().map(|(market_total, timestamp)| {
+                    serde_json::json!({
+                        "market_total": market_total,
+                        "timestamp": timestamp
+                    })
+                }).collect::<Vec<_>>()
+            });
             Json(ApiResponse::ok(response)).into_response()
         }
         Err(e) => {
-            error!("Error searching decks: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+            error!("Error getting deck value history for {}: {}", uuid, e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
         }
     }
 }
 
-async fn find_decks_with_card(
-    Query(params): Query<SearchQuery>,
+async fn get_decks_by_color_identity(
+    Query(params): Query<DeckColorIdentityQuery>,
     State(state): State<AppState>,
 ) -> impl IntoResponse {
+    let colors = match parse_color_identity(&params.colors) {
+        Ok(colors) => colors,
+        Err(message) => return (StatusCode::BAD_REQUEST, Json(ApiResponse::<()>::error(message))).into_response(),
+    };
+    
     let mut client = state.lock().await;
     
-    match client.find_decks_containing_card(&params.q).await {
+    match client.find_decks_by_commander_color_identity(&colors, params.exact).await {
         Ok(decks) => {
             let response = serde_json::json!({
-                "card_name": params.q,
+                "identity": color_identity_key(&colors),
+                "exact": params.exact,
                 "count": decks.len(),
                 "decks": decks
             });
             Json(ApiResponse::ok(response)).into_response()
         }
         Err(e) => {
-            error!("Error finding decks with card: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+            error!("Error getting decks by color identity {}: {}", params.colors, e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
         }
     }
 }
 
-async fn get_expensive_decks(
-    Query(params): Query<ExpensiveQuery>,
+async fn get_decks_by_archetype(
+    Path(tag): Path<String>,
     State(state): State<AppState>,
 ) -> impl IntoResponse {
     let mut client = state.lock().await;
     
-    match client.get_expensive_decks(params.min_price).await {
+    match client.get_decks_by_archetype(&tag).await {
         Ok(decks) => {
             let response = serde_json::json!({
-                "min_value": params.min_price,
+                "archetype": tag,
                 "count": decks.len(),
                 "decks": decks
             });
             Json(ApiResponse::ok(response)).into_response()
         }
         Err(e) => {
-            error!("Error getting expensive decks: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+            error!("Error getting decks by archetype {}: {}", tag, e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+const PROXY_CARDS_PER_PAGE: usize = 9; // 3x3 grid on letter / A4
+
+/// Proxy sheet layout: non-basic card images expanded by quantity and split into
+/// pages of 9, with basic lands listed once alongside their counts.
+async fn export_deck_proxy_urls(
+    Path(uuid): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let mut client = state.lock().await;
+    
+    match client.get_deck_proxy_cards(&uuid).await {
+        Ok(Some(proxy_cards)) => {
+            let (basics, spells): (Vec<ProxyCard>, Vec<ProxyCard>) =
+                proxy_cards.into_iter().partition(|card| card.is_basic);
+            
+            let missing: Vec<&str> = spells.iter()
+                .filter(|card| card.image_url.is_none())
+                .map(|card| card.name.as_str())
+                .collect();
+            let images: Vec<&str> = spells.iter()
+                .filter_map(|card| card.image_url.as_deref().map(|url| std::iter::repeat_n(url, card.count as usize)))
+                .flatten()
+                .collect();
+            let pages: Vec<&[&str]> = images.chunks(PROXY_CARDS_PER_PAGE).collect();
+            
+            let response = serde_json::json!({
+                "uuid": uuid,
+                "cards_per_page": PROXY_CARDS_PER_PAGE,
+                "total_images": images.len(),
+                "pages": pages,
+                "basics": basics,
+                "missing_images": missing
+            });
+            Json(ApiResponse::ok(response)).into_response()
+        }
+        Ok(None) => (StatusCode::NOT_FOUND, Json(ApiResponse::<()>::error("Deck not found".to_string()))).into_response(),
+        Err(e) => {
+            error!("Error exporting proxy URLs for deck {}: {}", uuid, e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
         }
     }
 }
@@ -306,7 +993,58 @@ async fn export_deck_csv(
         }
         Err(e) => {
             error!("Error exporting deck CSV: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+async fn export_deck_cockatrice(
+    Path(uuid): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let mut client = state.lock().await;
+    
+    match client.export_deck_cockatrice(&uuid).await {
+        Ok(Some(xml)) => {
+            axum::response::Response::builder()
+                .status(StatusCode::OK)
+                .header("Content-Type", "application/xml")
+                .header("Content-Disposition", format!("attachment; filename=deck_{}.cod", uuid))
+                .body(xml)
+                .unwrap()
+                .into_response()
+        }
+        Ok(None) => (StatusCode::NOT_FOUND, Json(ApiResponse::<()>::error("Deck not found".to_string()))).into_response(),
+        Err(e) => {
+            error!("Error exporting deck {} for Cockatrice: {}", uuid, e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+async fn export_set_csv(
+    Path(set_code): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let mut client = state.lock().await;
+    
+    match client.export_set_csv(&set_code).await {
+        Ok(csv_data) => {
+            if csv_data.is_empty() {
+                return (StatusCode::NOT_FOUND, Json(ApiResponse::<()>::error("Set not found or has no cards".to_string()))).into_response();
+            }
+            
+            axum::response::Response::builder()
+                .status(StatusCode::OK)
+                .header("Content-Type", "text/csv")
+                .header("Content-Disposition", format!("attachment; filename=set_{}.csv", set_code.to_lowercase()))
+                .body(csv_data)
+                .unwrap()
+                .into_response()
+        }
+        Err(e) => {
+            error!("Error exporting set {} as CSV: {}", set_code, e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
         }
     }
 }
@@ -328,7 +1066,7 @@ async fn get_trending_cards(
         }
         Err(e) => {
             error!("Error getting trending cards: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
         }
     }
 }
@@ -351,7 +1089,7 @@ async fn get_arbitrage_opportunities(
         }
         Err(e) => {
             error!("Error getting arbitrage opportunities: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
         }
     }
 }
@@ -372,7 +1110,80 @@ async fn get_card_price(
         Ok(None) => (StatusCode::NOT_FOUND, Json(ApiResponse::<()>::error("Price not found".to_string()))).into_response(),
         Err(e) => {
             error!("Error getting card price: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+async fn get_card_prices_all_conditions(
+    Path(uuid): Path<String>,
+    Query(params): Query<ConditionPricesQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let finish = params.finish.to_lowercase();
+    if finish != "nonfoil" && finish != "foil" {
+        return (StatusCode::BAD_REQUEST, Json(ApiResponse::<()>::error(format!("Invalid finish '{}', expected nonfoil or foil", params.finish)))).into_response();
+    }
+    
+    let mut client = state.lock().await;
+    
+    match client.get_card_prices_all_conditions(&uuid, &finish).await {
+        Ok(Some(prices)) => {
+            let response = serde_json::json!({
+                "uuid": uuid,
+                "finish": finish,
+                "conditions": prices
+            });
+            Json(ApiResponse::ok(response)).into_response()
+        }
+        Ok(None) => (StatusCode::NOT_FOUND, Json(ApiResponse::<()>::error("Card not found".to_string()))).into_response(),
+        Err(e) => {
+            error!("Error getting condition prices for {}: {}", uuid, e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+async fn get_card_price_history(
+    Path(uuid): Path<String>,
+    Query(params): Query<CardPriceHistoryQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let mut client = state.lock().await;
+    
+    match client.get_card_price_history(&uuid, &params.finish, &params.condition, params.days).await {
+        Ok(Some(history)) => {
+            let response = serde_json::json!({
+                "uuid": history.uuid,
+                "finish": history.finish,
+                "condition": history.condition,
+                "sku_id": history.sku_id,
+                "days": params.days,
+                "count": history.history.len(),
+                "history": history.history
+            });
+            Json(ApiResponse::ok(response)).into_response()
+        }
+        Ok(None) => (StatusCode::NOT_FOUND, Json(ApiResponse::<()>::error("Card not found".to_string()))).into_response(),
+        Err(e) => {
+            error!("Error getting price history for {}: {}", uuid, e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+async fn get_card_vendor_prices(
+    Path(uuid): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let mut client = state.lock().await;
+    
+    match client.get_cross_vendor_prices(&uuid).await {
+        Ok(Some(prices)) => Json(ApiResponse::ok(prices)).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, Json(ApiResponse::<()>::error("Card not found".to_string()))).into_response(),
+        Err(e) => {
+            error!("Error getting vendor prices for {}: {}", uuid, e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
         }
     }
 }
@@ -388,7 +1199,7 @@ async fn get_sku_price(
         Ok(None) => (StatusCode::NOT_FOUND, Json(ApiResponse::<()>::error("SKU price not found".to_string()))).into_response(),
         Err(e) => {
             error!("Error getting SKU price: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
         }
     }
 }
@@ -417,7 +1228,7 @@ async fn get_sku_price_history(
         }
         Err(e) => {
             error!("Error getting SKU price history: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
         }
     }
 }
@@ -426,6 +1237,72 @@ async fn get_sku_price_history(
 // SET ENDPOINTS
 // =============================================================================
 
+async fn get_legality_changes(
+    Path(format): Path<String>,
+    Query(params): Query<LegalityChangesQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    if let Some(since) = &params.since {
+        if chrono::NaiveDate::parse_from_str(since, "%Y-%m-%d").is_err() {
+            return (StatusCode::BAD_REQUEST, Json(ApiResponse::<()>::error("since must be YYYY-MM-DD".to_string()))).into_response();
+        }
+    }
+
+    let mut client = state.lock().await;
+    
+    match client.get_legality_changes(&format, params.since.as_deref(), params.offset, params.limit).await {
+        Ok((changes, total)) => {
+            let response = serde_json::json!({
+                "format": format.to_lowercase(),
+                "since": params.since,
+                "total": total,
+                "offset": params.offset,
+                "count": changes.len(),
+                "changes": changes
+            });
+            Json(ApiResponse::ok(response)).into_response()
+        }
+        Err(e) => {
+            error!("Error getting legality changes for {}: {}", format, e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+async fn get_rulings_since(
+    Query(params): Query<RulingsSinceQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    if chrono::NaiveDate::parse_from_str(&params.date, "%Y-%m-%d").is_err() {
+        return (StatusCode::BAD_REQUEST, Json(ApiResponse::<()>::error("date must be YYYY-MM-DD".to_string()))).into_response();
+    }
+
+    let mut client = state.lock().await;
+    
+    match client.get_rulings_since(&params.date).await {
+        Ok(rulings) => {
+            let rulings: Vec<_> = rulings
+                .into_iter()
+                .map(|(card_name, ruling)| serde_json::json!({
+                    "card_name": card_name,
+                    "date": ruling.date,
+                    "text": ruling.text
+                }))
+                .collect();
+            let response = serde_json::json!({
+                "since": params.date,
+                "count": rulings.len(),
+                "rulings": rulings
+            });
+            Json(ApiResponse::ok(response)).into_response()
+        }
+        Err(e) => {
+            error!("Error getting rulings since {}: {}", params.date, e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
 async fn get_set(
     Path(set_code): Path<String>,
     State(state): State<AppState>,
@@ -437,25 +1314,62 @@ async fn get_set(
         Ok(None) => (StatusCode::NOT_FOUND, Json(ApiResponse::<()>::error("Set not found".to_string()))).into_response(),
         Err(e) => {
             error!("Error getting set: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+async fn get_set_cards(
+    Path(set_code): Path<String>,
+    Query(params): Query<SetCardsQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let mut client = state.lock().await;
+    
+    match client.get_set_cards_page(&set_code, params.offset, params.limit).await {
+        Ok(Some((cards, total))) => {
+            let response = serde_json::json!({
+                "set_code": set_code.to_uppercase(),
+                "total": total,
+                "offset": params.offset,
+                "count": cards.len(),
+                "cards": cards
+            });
+            Json(ApiResponse::ok(response)).into_response()
+        }
+        Ok(None) => (StatusCode::NOT_FOUND, Json(ApiResponse::<()>::error("Set not found or has no cards".to_string()))).into_response(),
+        Err(e) => {
+            error!("Error getting cards for set {}: {}", set_code, e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
         }
     }
 }
 
-async fn get_all_sets(State(state): State<AppState>) -> impl IntoResponse {
+async fn get_all_sets(
+    Query(params): Query<SetsQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
     let mut client = state.lock().await;
     
-    match client.get_all_sets().await {
-        Ok(sets) => {
+    match client.get_sets_page(params.offset, params.limit).await {
+        Ok((sets, total)) => {
+            // Plain set codes unless the caller asks for full SetInfo records
+            let sets = if params.detailed {
+                serde_json::json!(sets)
+            } else {
+                serde_json::json!(sets.iter().map(|set| &set.code).collect::<Vec<_>>())
+            };
             let response = serde_json::json!({
-                "count": sets.len(),
+                "total": total,
+                "offset": params.offset,
+                "count": sets.as_array().map(|a| a.len()).unwrap_or(0),
                 "sets": sets
             });
             Json(ApiResponse::ok(response)).into_response()
         }
         Err(e) => {
             error!("Error getting all sets: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
         }
     }
 }
@@ -476,7 +1390,55 @@ async fn get_database_statistics(State(state): State<AppState>) -> impl IntoResp
         }
         Err(e) => {
             error!("Error getting database stats: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+async fn get_most_variant_cards(
+    Query(params): Query<MostVariantQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let mut client = state.lock().await;
+    
+    match client.get_most_variant_cards(params.limit).await {
+        Ok(ranked) => {
+            let cards: Vec<_> = ranked
+                .into_iter()
+                .map(|(card, variations)| serde_json::json!({
+                    "variations": variations,
+                    "card": card
+                }))
+                .collect();
+            let response = serde_json::json!({
+                "count": cards.len(),
+                "cards": cards
+            });
+            Json(ApiResponse::ok(response)).into_response()
+        }
+        Err(e) => {
+            error!("Error getting most variant cards: {}", e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+async fn get_missing_data(
+    Path(data_type): Path<String>,
+    Query(params): Query<MissingDataQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    if !MISSING_DATA_TYPES.contains(&data_type.as_str()) {
+        return (StatusCode::BAD_REQUEST, Json(ApiResponse::<()>::error(format!("Invalid data type '{}', expected one of {}", data_type, MISSING_DATA_TYPES.join(", "))))).into_response();
+    }
+    
+    let mut client = state.lock().await;
+    
+    match client.get_missing_data(&data_type, params.limit).await {
+        Ok(report) => Json(ApiResponse::ok(report)).into_response(),
+        Err(e) => {
+            error!("Error finding cards with {}: {}", data_type, e);
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
         }
     }
 }
@@ -493,7 +1455,7 @@ async fn get_memory_usage(State(state): State<AppState>) -> impl IntoResponse {
         }
         Err(e) => {
             error!("Error getting memory usage: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
         }
     }
 }
@@ -526,7 +1488,7 @@ async fn get_api_stats(State(state): State<AppState>) -> impl IntoResponse {
         return (StatusCode::SERVICE_UNAVAILABLE, Json(ApiResponse::<()>::error("Database unavailable".to_string()))).into_response();
     }
     
-    match (client.get_key_count("*").await, client.get_memory_usage().await, client.get_database_stats().await) {
+    match (client.get_key_count().await, client.get_memory_usage().await, client.get_database_stats().await) {
         (Ok(total_keys), Ok(memory_usage), Ok(database_stats)) => {
             let response = StatsResponse {
                 redis_connection: "ok".to_string(),
@@ -538,9 +1500,138 @@ async fn get_api_stats(State(state): State<AppState>) -> impl IntoResponse {
         }
         (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
             error!("Error getting API stats: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+            (error_status(&e), Json(ApiResponse::<()>::error(e.to_string()))).into_response()
+        }
+    }
+}
+
+async fn get_metrics(State(state): State<AppState>) -> impl IntoResponse {
+    let client = state.lock().await;
+    
+    let response = MetricsResponse {
+        card_cache: client.card_cache_stats(),
+    };
+    Json(ApiResponse::ok(response))
+}
+
+// =============================================================================
+// BATCH
+// =============================================================================
+
+const MAX_BATCH_OPERATIONS: usize = 20;
+const BATCH_METHODS: &[&str] = &["get_card", "get_card_by_oracle_id", "get_deck", "search", "get_card_prices"];
+
+/// Several lookups in one round trip. Operations run concurrently on detached clients,
+/// so the shared client is only locked to create them, and results come back in request
+/// order. Each reports its own success or error, so one failed lookup doesn't fail the batch.
+async fn batch(
+    State(state): State<AppState>,
+    Json(operations): Json<Vec<BatchOperation>>,
+) -> impl IntoResponse {
+    if operations.len() > MAX_BATCH_OPERATIONS {
+        return (StatusCode::BAD_REQUEST, Json(ApiResponse::<()>::error(format!("At most {} operations per batch", MAX_BATCH_OPERATIONS)))).into_response();
+    }
+    
+    let clients: Vec<MTGRedisClient> = {
+        let client = state.lock().await;
+        operations.iter().map(|_| client.detached()).collect()
+    };
+    
+    let runs = operations.into_iter().zip(clients).map(|(operation, mut client)| async move {
+        let method = operation.method.clone();
+        match run_batch_operation(&mut client, operation).await {
+            Ok(data) => BatchResult { success: true, data: Some(data), error: None },
+            Err(e) => {
+                error!("Batch operation {} failed: {}", method, e);
+                BatchResult { success: false, data: None, error: Some(e.to_string()) }
+            }
+        }
+    });
+    let results = futures_util::future::join_all(runs).await;
+    
+    Json(ApiResponse::ok(results)).into_response()
+}
+
+async fn run_batch_operation(client: &mut MTGRedisClient, operation: BatchOperation) -> anyhow::Result<serde_json::Value> {
+    fn params<T: serde::de::DeserializeOwned>(method: &str, params: serde_json::Value) -> anyhow::Result<T> {
+        serde_json::from_value(params).map_err(|e| anyhow::anyhow!("Invalid params for {}: {}", method, e))
+    }
+    
+    let method = operation.method.as_str();
+    let data = match method {
+        "get_card" => {
+            let p: BatchUuidParams = params(method, operation.params)?;
+            let card = client.get_card_detail(&p.uuid).await?
+                .ok_or_else(|| anyhow::anyhow!("Card not found"))?;
+            serde_json::to_value(card)?
+        }
+        "get_card_by_oracle_id" => {
+            let p: BatchOracleIdParams = params(method, operation.params)?;
+            let card = client.get_card_by_oracle_id(&p.oracle_id).await?
+                .ok_or_else(|| anyhow::anyhow!("Card not found"))?;
+            serde_json::to_value(card)?
+        }
+        "get_deck" => {
+            let p: BatchUuidParams = params(method, operation.params)?;
+            let deck = client.get_deck_by_uuid(&p.uuid).await?
+                .ok_or_else(|| anyhow::anyhow!("Deck not found"))?;
+            serde_json::to_value(deck)?
         }
+        "search" => {
+            let p: BatchSearchParams = params(method, operation.params)?;
+            let cards = client.search_cards_filtered_fuzzy(&p.q, std::collections::HashMap::new(), p.limit).await?;
+            serde_json::to_value(cards)?
+        }
+        "get_card_prices" => {
+            let p: BatchPriceParams = params(method, operation.params)?;
+            let finish = p.finish.to_lowercase();
+            if finish != "nonfoil" && finish != "foil" {
+                anyhow::bail!("Invalid finish '{}', expected nonfoil or foil", p.finish);
+            }
+            let prices = client.get_card_prices_all_conditions(&p.uuid, &finish).await?
+                .ok_or_else(|| anyhow::anyhow!("Card not found"))?;
+            serde_json::to_value(prices)?
+        }
+        other => anyhow::bail!("Unknown method '{}', expected one of {}", other, BATCH_METHODS.join(", ")),
+    };
+    Ok(data)
+}
+
+// =============================================================================
+// REQUEST IDS
+// =============================================================================
+
+const REQUEST_ID_HEADER: &str = "x-request-id";
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+// Runs each request inside a span carrying its id, so handler and Redis error logs can be
+// correlated. An incoming x-request-id is reused (e.g. from a proxy), otherwise a UUID is
+// generated; either way it is echoed back in the response header.
+async fn request_id_middleware(request: Request, next: Next) -> Response {
+    let request_id = request.headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| !value.is_empty() && value.len() <= MAX_REQUEST_ID_LEN)
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let route = request.extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+    let span = tracing::info_span!("request", request_id = %request_id, method = %request.method(), route = %route);
+    
+    async move {
+        let started = Instant::now();
+        let mut response = next.run(request).await;
+        info!(status = response.status().as_u16(), latency_ms = started.elapsed().as_millis() as u64, "request finished");
+        
+        if let Ok(value) = HeaderValue::from_str(&request_id) {
+            response.headers_mut().insert(REQUEST_ID_HEADER, value);
+        }
+        response
     }
+    .instrument(span)
+    .await
 }
 
 // =============================================================================
@@ -551,22 +1642,48 @@ fn create_router(state: AppState) -> Router {
     Router::new()
         // Card endpoints
         .route("/cards/:uuid", get(get_card))
+        .route("/cards/:uuid/text-history", get(get_card_text_history))
+        .route("/cards/:uuid/related", get(get_related_cards))
+        .route("/cards/:uuid/tokens", get(get_card_tokens))
         .route("/cards/search/name", get(search_cards))
         .route("/cards/search/fuzzy", get(fuzzy_search_cards))
+        .route("/cards/search/contains", get(search_cards_name_contains))
+        .route("/cards/search/text", get(search_cards_by_text))
         .route("/cards/autocomplete", get(autocomplete_cards))
+        .route("/cards/name-mapping", get(get_name_mapping))
         .route("/cards/expensive", get(get_expensive_cards))
+        .route("/cards/new", get(get_new_cards))
+        .route("/cards/random", get(get_random_card))
+        .route("/cards/unique-printings", get(get_unique_printing_cards))
+        .route("/cards/by-subtype/:subtype", get(get_cards_by_subtype))
+        .route("/cards/by-subset/:subset", get(get_cards_by_subset))
+        .route("/cards/by-mv/:mv", get(get_cards_by_mana_value))
+        .route("/cards/by-tcgplayer/:product_id", get(get_card_by_tcgplayer_id))
+        .route("/cards/by-identity/:identity", get(get_cards_by_color_identity))
         
         // Deck endpoints
         .route("/decks/:uuid", get(get_deck))
         .route("/decks/:uuid/composition", get(get_deck_composition))
+        .route("/decks/:uuid/similar", get(get_similar_decks))
+        .route("/decks/:uuid/value-breakdown", get(get_deck_value_breakdown))
+        .route("/decks/:uuid/power-level", get(get_deck_power_level))
+        .route("/decks/:uuid/value-history", get(get_deck_value_history))
         .route("/decks/commanders", get(get_commander_decks))
         .route("/decks/search/name", get(search_decks))
         .route("/decks/containing-card", get(find_decks_with_card))
         .route("/decks/expensive", get(get_expensive_decks))
+        .route("/decks/by-commander-pair", get(get_decks_by_commander_pair))
+        .route("/decks/by-color-identity", get(get_decks_by_color_identity))
+        .route("/decks/by-archetype/:tag", get(get_decks_by_archetype))
         .route("/decks/:uuid/export/tcg-csv", get(export_deck_csv))
+        .route("/decks/:uuid/export/proxy-urls", get(export_deck_proxy_urls))
+        .route("/decks/:uuid/export/cockatrice", get(export_deck_cockatrice))
         
         // Pricing endpoints
         .route("/pricing/card/:uuid", get(get_card_price))
+        .route("/pricing/card/:uuid/conditions", get(get_card_prices_all_conditions))
+        .route("/pricing/card/:uuid/history", get(get_card_price_history))
+        .route("/pricing/card/:uuid/vendors", get(get_card_vendor_prices))
         .route("/pricing/sku/:sku_id", get(get_sku_price))
         .route("/pricing/sku/:sku_id/history", get(get_sku_price_history))
         .route("/pricing/trending", get(get_trending_cards))
@@ -574,18 +1691,33 @@ fn create_router(state: AppState) -> Router {
         
         // Set endpoints
         .route("/sets/:set_code", get(get_set))
+        .route("/sets/:set_code/export/csv", get(export_set_csv))
+        .route("/sets/:set_code/cards", get(get_set_cards))
         .route("/sets", get(get_all_sets))
         
+        // Rulings endpoints
+        .route("/rulings/since", get(get_rulings_since))
+        
+        // Format endpoints
+        .route("/formats/:format/changes", get(get_legality_changes))
+        
         // Analytics endpoints
         .route("/analytics/database-stats", get(get_database_statistics))
         .route("/analytics/memory-usage", get(get_memory_usage))
+        .route("/analytics/most-variant", get(get_most_variant_cards))
+        .route("/analytics/missing/:data_type", get(get_missing_data))
         
         // Health & status
         .route("/health", get(health_check))
         .route("/stats", get(get_api_stats))
+        .route("/metrics", get(get_metrics))
+        
+        // Batch
+        .route("/batch", post(batch))
         
         .layer(
             ServiceBuilder::new()
+                .layer(middleware::from_fn(request_id_middleware))
                 .layer(TraceLayer::new_for_http())
                 .layer(CorsLayer::permissive())
         )
@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use crate::redis_client::{MemoryUsage, DatabaseStats};
+use crate::redis_client::{CardCacheStats, MemoryUsage, DatabaseStats};
 
 
 // =============================================================================
@@ -21,18 +21,79 @@ pub struct StatsResponse {
     pub database_stats: DatabaseStats,
 }
 
+/// Outcome of one operation in a `POST /batch` request
+#[derive(Debug, Serialize)]
+pub struct BatchResult {
+    pub success: bool,
+    pub data: Option<serde_json::Value>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MetricsResponse {
+    pub card_cache: CardCacheStats,
+}
+
 // =============================================================================
 // QUERY PARAMETERS
 // =============================================================================
 
+/// One operation of a `POST /batch` request, e.g. `{"method": "get_card", "params": {"uuid": "..."}}`
+#[derive(Debug, Deserialize)]
+pub struct BatchOperation {
+    pub method: String,
+    #[serde(default)]
+    pub params: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BatchUuidParams {
+    pub uuid: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BatchOracleIdParams {
+    pub oracle_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BatchSearchParams {
+    pub q: String,
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BatchPriceParams {
+    pub uuid: String,
+    #[serde(default = "default_finish")]
+    pub finish: String,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct SearchQuery {
     pub q: String,
     #[serde(default = "default_limit")]
     pub limit: usize,
+    // Exact RediSearch name match instead of the default fuzzy match
+    #[serde(default)]
+    pub exact: bool,
+    pub format: Option<String>,
     pub set_code: Option<String>,
     pub rarity: Option<String>,
     pub color: Option<String>,
+    pub border_color: Option<String>,
+    pub full_art: Option<bool>,
+    pub textless: Option<bool>,
+    pub security_stamp: Option<String>,
+    pub finish: Option<String>,  // nonfoil, foil or etched
+    pub mana_value_max: Option<f32>,
+    pub color_count: Option<u32>,
+    // Comma-separated exclusions, e.g. not_color=R,B
+    pub not_color: Option<String>,
+    pub not_rarity: Option<String>,
+    pub not_type: Option<String>,
+    pub not_set: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -43,6 +104,21 @@ pub struct ExpensiveQuery {
     pub limit: usize,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ExpensiveDecksQuery {
+    #[serde(default = "default_min_price")]
+    pub min_price: f64,
+    // Value decks without their basic lands ("spell value")
+    #[serde(default)]
+    pub exclude_basics: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeckValueQuery {
+    /// Omitted: the valuation as indexed (see `--exclude-basics-from-value`)
+    pub exclude_basics: Option<bool>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct AutocompleteQuery {
     pub prefix: String,
@@ -50,12 +126,170 @@ pub struct AutocompleteQuery {
     pub limit: usize,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct NameMappingQuery {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommanderPairQuery {
+    pub a: String,
+    pub b: String,
+}
+
+/// `colors` takes the same symbols as `/cards/by-identity/:identity`; `exact=false`
+/// also matches decks whose identity is a subset of it
+#[derive(Debug, Deserialize)]
+pub struct DeckColorIdentityQuery {
+    pub colors: String,
+    #[serde(default = "default_exact")]
+    pub exact: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RandomCardQuery {
+    pub set: Option<String>,
+    pub rarity: Option<String>,
+    pub color: Option<String>,
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubtypeQuery {
+    pub color: Option<String>,
+    pub format: Option<String>,
+    #[serde(default)]
+    pub offset: usize,
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ColorIdentityQuery {
+    #[serde(default)]
+    pub offset: usize,
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SimilarDecksQuery {
+    #[serde(default = "default_similar_limit")]
+    pub limit: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ManaValueQuery {
+    pub color: Option<String>,
+    #[serde(rename = "type")]
+    pub card_type: Option<String>,
+    pub format: Option<String>,
+    pub rarity: Option<String>,
+    #[serde(default = "default_mana_value_sort")]
+    pub sort: String,
+    // With mv=0, also return spells that have no mana cost at all
+    #[serde(default)]
+    pub include_uncastable: bool,
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubsetQuery {
+    #[serde(default)]
+    pub offset: usize,
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MissingDataQuery {
+    #[serde(default = "default_limit")]
+    pub limit: usize,  // Sample size; the count covers every affected card
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MostVariantQuery {
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UniquePrintingsQuery {
+    /// "price" (highest first, the default) or "price_asc"
+    pub sort: Option<String>,
+    #[serde(default)]
+    pub offset: usize,
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewCardsQuery {
+    pub since: String,
+    #[serde(default)]
+    pub offset: usize,
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LegalityChangesQuery {
+    pub since: Option<String>,
+    #[serde(default)]
+    pub offset: usize,
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RulingsSinceQuery {
+    pub date: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetCardsQuery {
+    #[serde(default)]
+    pub offset: usize,
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetsQuery {
+    #[serde(default)]
+    pub offset: usize,
+    #[serde(default = "default_sets_limit")]
+    pub limit: usize,
+    #[serde(default)]
+    pub detailed: bool,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct PriceQuery {
     #[serde(default = "default_condition")]
     pub condition: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ConditionPricesQuery {
+    /// "nonfoil" or "foil"
+    #[serde(default = "default_finish")]
+    pub finish: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CardPriceHistoryQuery {
+    /// "nonfoil" or "foil"
+    #[serde(default = "default_finish")]
+    pub finish: String,
+    /// Full condition name or NM/LP/MP/HP/DMG
+    #[serde(default = "default_condition")]
+    pub condition: String,
+    #[serde(default = "default_days")]
+    pub days: u32,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct PriceHistoryQuery {
     #[serde(default = "default_days")]
@@ -80,8 +314,13 @@ pub struct ArbitrageQuery {
 
 pub fn default_limit() -> usize { 50 }
 pub fn default_autocomplete_limit() -> usize { 10 }
+pub fn default_sets_limit() -> usize { 1000 }
 pub fn default_min_price() -> f64 { 50.0 }
 pub fn default_condition() -> String { "Near Mint".to_string() }
+pub fn default_finish() -> String { "nonfoil".to_string() }
+pub fn default_mana_value_sort() -> String { "price".to_string() }
+pub fn default_similar_limit() -> usize { 10 }
 pub fn default_days() -> u32 { 30 }
 pub fn default_direction() -> String { "up".to_string() }
-pub fn default_min_diff() -> f64 { 5.0 }
\ No newline at end of file
+pub fn default_min_diff() -> f64 { 5.0 }
+pub fn default_exact() -> bool { true }
\ No newline at end of file
@@ -2,17 +2,19 @@ mod types;
 mod sku_pricing;
 mod redis_client;
 mod api_server;
+mod progress;
 
 use anyhow::{Context, Result};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use clap::Parser;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
 use futures_util::StreamExt;
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::ProgressStyle;
 
 use redis::{Client, Commands, Connection};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs::File;
-use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
 use std::path::Path;
 use std::time::{Duration, SystemTime};
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -22,23 +24,280 @@ use memmap2::Mmap;
 use rayon::prelude::*;
 
 use types::*;
+use progress::ProgressMode;
 use sku_pricing::SkuPricingManager;
-use uuid;
 use walkdir;
 use xz2::read::XzDecoder;
 
 const BATCH_SIZE: usize = 2000;           // Larger batches for Redis
 const DECK_BATCH_SIZE: usize = 100;       // Parallel deck processing batches  
+const MAX_REPORTED_DECK_FAILURES: usize = 20; // Failed or flagged deck files listed in the load summary
 const MEMORY_MAP_THRESHOLD: u64 = 50 * 1024 * 1024; // 50MB threshold for memory mapping
 const MAX_PREFIX_LENGTH: usize = 30;      // Max length for autocomplete prefixes
 const NGRAM_SIZE: usize = 3;              // N-gram size for fuzzy matching
-
-// Advanced search indexes structure
+const DECOMPRESS_SAMPLE_EVERY: u64 = 100; // Time one decompression per this many compressed cards
+const INDEX_LOCK_KEY: &str = "mtgjson:index:lock"; // Outside mtg:* so clear_redis_data leaves it alone
+const INDEX_LOCK_TTL_SECS: u64 = 2 * 60 * 60;  // Refreshed per set, so only a crashed run's lock expires
+const DOWNLOAD_RETRY_BASE_SECS: u64 = 2;  // Backoff before the second attempt, doubled per retry, plus up to 1s jitter
+const DOWNLOAD_RETRY_MAX_SECS: u64 = 5 * 60;  // Cap on the doubled backoff, so large --download-attempts can't overflow it
+const DECK_VALUE_HISTORY_DAYS: i64 = 365;  // Older deck value points are trimmed on each store
+
+// Byte counts and timings behind the compression figures in IndexStats
 #[derive(Default)]
-pub struct SearchIndexes {
-    pub ngrams: HashMap<String, HashSet<String>>,
-    pub metaphones: HashMap<String, HashSet<String>>,
-    pub words: HashMap<String, HashSet<String>>,
+struct CardStorageTotals {
+    cards: u64,
+    json_bytes: u64,
+    stored_bytes: u64,
+    lean_saved_bytes: u64,
+    below_price_floor: u64,
+    decompress_samples: u64,
+    decompress_time: Duration,
+}
+
+// One set's share of the IndexStats totals. Kept per set so a --sets run can swap its
+// sets' shares and leave the stats describing the whole index.
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+struct SetIndexStats {
+    cards: usize,
+    cards_with_pricing: usize,
+    total_skus: usize,
+    card_json_bytes: u64,
+    card_stored_bytes: u64,
+    lean_saved_bytes: u64,
+    cards_below_price_floor: u64,
+}
+
+impl SetIndexStats {
+    fn total<'a>(sets: impl IntoIterator<Item = &'a SetIndexStats>) -> SetIndexStats {
+        sets.into_iter().fold(SetIndexStats::default(), |mut total, set| {
+            total.cards += set.cards;
+            total.cards_with_pricing += set.cards_with_pricing;
+            total.total_skus += set.total_skus;
+            total.card_json_bytes += set.card_json_bytes;
+            total.card_stored_bytes += set.card_stored_bytes;
+            total.lean_saved_bytes += set.lean_saved_bytes;
+            total.cards_below_price_floor += set.cards_below_price_floor;
+            total
+        })
+    }
+
+    // Per-set shares after a run. A --sets run replaces the shares of every selected set,
+    // including ones missing from AllPrintings.json (their cards were cleared), and keeps
+    // the stored shares of the rest; a full run has only its own.
+    fn merge(
+        stored: Option<&BTreeMap<String, SetIndexStats>>,
+        set_filter: Option<&BTreeSet<String>>,
+        indexed: BTreeMap<String, SetIndexStats>,
+    ) -> BTreeMap<String, SetIndexStats> {
+        let mut merged: BTreeMap<String, SetIndexStats> = stored
+            .map(|stored| {
+                stored.iter()
+                    .filter(|(set_code, _)| set_filter.is_none_or(|sets| !sets.contains(*set_code)))
+                    .map(|(set_code, stats)| (set_code.clone(), stats.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        merged.extend(indexed);
+        merged
+    }
+}
+
+// Advisory lock held while a run rewrites the index, so overlapping runs (e.g. cron jobs)
+// can't interleave clears and stores. The value identifies the holder; refresh and
+// release only touch the lock while it still holds that value. Released on drop, so
+// error paths free it too.
+struct IndexLock {
+    redis_client: Client,
+    token: String,
+}
+
+impl IndexLock {
+    fn acquire(redis_client: &Client) -> Result<Self> {
+        let mut con = redis_client.get_connection()
+            .context("Failed to connect to Redis")?;
+        let token = format!("pid {} at {} ({})", std::process::id(), Utc::now().to_rfc3339(), uuid::Uuid::new_v4());
+        let acquired: Option<String> = redis::cmd("SET")
+            .arg(INDEX_LOCK_KEY)
+            .arg(&token)
+            .arg("NX")
+            .arg("EX")
+            .arg(INDEX_LOCK_TTL_SECS)
+            .query(&mut con)
+            .context("Failed to acquire index lock")?;
+        
+        if acquired.is_none() {
+            let holder: Option<String> = con.get(INDEX_LOCK_KEY).unwrap_or(None);
+            let ttl: i64 = con.ttl(INDEX_LOCK_KEY).unwrap_or(-1);
+            anyhow::bail!(
+                "Indexing already in progress ({} holds {}, expires in {}s); if that run crashed, rerun with --force-unlock",
+                holder.as_deref().unwrap_or("another run"), INDEX_LOCK_KEY, ttl
+            );
+        }
+        
+        println!("✓ Acquired index lock {}", INDEX_LOCK_KEY);
+        Ok(Self { redis_client: redis_client.clone(), token })
+    }
+
+    // Push the expiry back while a long run is making progress
+    fn refresh(&self, con: &mut Connection) -> Result<()> {
+        let refreshed: i64 = redis::Script::new(
+            "if redis.call('GET', KEYS[1]) == ARGV[1] then return redis.call('EXPIRE', KEYS[1], ARGV[2]) end return 0",
+        )
+            .key(INDEX_LOCK_KEY)
+            .arg(&self.token)
+            .arg(INDEX_LOCK_TTL_SECS)
+            .invoke(con)
+            .context("Failed to refresh index lock")?;
+        if refreshed == 0 {
+            anyhow::bail!("Lost index lock {} (expired or force-unlocked by another run)", INDEX_LOCK_KEY);
+        }
+        Ok(())
+    }
+
+    // Delete a lock left behind by a crashed run; returns its holder if there was one
+    fn force_unlock(redis_client: &Client) -> Result<Option<String>> {
+        let mut con = redis_client.get_connection()
+            .context("Failed to connect to Redis")?;
+        let holder: Option<String> = con.get(INDEX_LOCK_KEY)?;
+        let _: () = con.del(INDEX_LOCK_KEY)?;
+        Ok(holder)
+    }
+}
+
+impl Drop for IndexLock {
+    fn drop(&mut self) {
+        let released: redis::RedisResult<i64> = self.redis_client.get_connection().and_then(|mut con| {
+            redis::Script::new("if redis.call('GET', KEYS[1]) == ARGV[1] then return redis.call('DEL', KEYS[1]) end return 0")
+                .key(INDEX_LOCK_KEY)
+                .arg(&self.token)
+                .invoke(&mut con)
+        });
+        match released {
+            Ok(1) => println!("✓ Released index lock {}", INDEX_LOCK_KEY),
+            Ok(_) => eprintln!("⚠️  Index lock {} was no longer ours to release", INDEX_LOCK_KEY),
+            Err(e) => eprintln!("⚠️  Failed to release index lock {} (it expires in {}s): {}", INDEX_LOCK_KEY, INDEX_LOCK_TTL_SECS, e),
+        }
+    }
+}
+
+// Wall-clock time of each indexing phase, in the order they ran
+#[derive(Default, serde::Serialize)]
+struct PhaseTimings(Vec<PhaseTiming>);
+
+#[derive(serde::Serialize)]
+struct PhaseTiming {
+    phase: &'static str,
+    seconds: f64,
+}
+
+impl PhaseTimings {
+    // Record a phase that began at `started`; returns the start of the next phase
+    fn finish(&mut self, phase: &'static str, started: std::time::Instant) -> std::time::Instant {
+        let now = std::time::Instant::now();
+        self.0.push(PhaseTiming { phase, seconds: (now - started).as_secs_f64() });
+        now
+    }
+}
+
+// Command-line choices for one `index_cards` run
+struct IndexOptions<'a> {
+    tcg_csv_paths: &'a [String],
+    tcg_csv_keep_first: bool,
+    skip_pricing: bool,
+    auto_download_tcg: bool,
+    sku_language: &'a str,
+    sku_condition: &'a str,
+    outlier_filter: Option<&'a PriceOutlierFilter>,
+    exclude_basics_from_value: bool,
+    streaming: bool,
+    set_filter: Option<&'a BTreeSet<String>>,
+}
+
+// What --stats-json writes after a full index run: the stored IndexStats (which carry
+// the MTGJSON build date) plus run timings
+#[derive(serde::Serialize)]
+struct IndexRunReport {
+    #[serde(flatten)]
+    stats: IndexStats,
+    total_seconds: f64,
+    cards_per_sec: f64,
+    legality_changes: usize,
+    vendor_price_records: usize,
+    phases: PhaseTimings,
+}
+
+impl IndexRunReport {
+    fn write(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .context("Failed to serialize indexing stats")?;
+        std::fs::write(path, json)
+            .with_context(|| format!("Failed to write indexing stats to {}", path.display()))?;
+        println!("✓ Wrote indexing stats to {}", path.display());
+        Ok(())
+    }
+}
+
+// Walks an MTGJSON data file (AllPrintings.json, AllPricesToday.json) one entry at a
+// time: each `data` value is deserialized and handed to `on_entry` before the next is
+// read. An `on_entry` error is parked in `error`, since only a message survives the trip
+// through serde's error type.
+struct DataFileStream<T, F> {
+    on_entry: F,
+    error: Option<anyhow::Error>,
+    entry: std::marker::PhantomData<fn() -> T>,
+}
+
+struct DataFileVisitor<'a, T, F>(&'a mut DataFileStream<T, F>);
+
+impl<'de, T: serde::de::DeserializeOwned, F: FnMut(String, T) -> Result<()>> serde::de::Visitor<'de> for DataFileVisitor<'_, T, F> {
+    type Value = Meta;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("an MTGJSON object with meta and data")
+    }
+
+    fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> std::result::Result<Meta, A::Error> {
+        let mut meta = None;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "meta" => meta = Some(map.next_value()?),
+                "data" => map.next_value_seed(EntriesSeed(&mut *self.0))?,
+                _ => {
+                    map.next_value::<serde::de::IgnoredAny>()?;
+                }
+            }
+        }
+        meta.ok_or_else(|| serde::de::Error::missing_field("meta"))
+    }
+}
+
+struct EntriesSeed<'a, T, F>(&'a mut DataFileStream<T, F>);
+
+impl<'de, T: serde::de::DeserializeOwned, F: FnMut(String, T) -> Result<()>> serde::de::DeserializeSeed<'de> for EntriesSeed<'_, T, F> {
+    type Value = ();
+
+    fn deserialize<D: serde::Deserializer<'de>>(self, deserializer: D) -> std::result::Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, T: serde::de::DeserializeOwned, F: FnMut(String, T) -> Result<()>> serde::de::Visitor<'de> for EntriesSeed<'_, T, F> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a map of keys to data entries")
+    }
+
+    fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> std::result::Result<(), A::Error> {
+        while let Some(key) = map.next_key::<String>()? {
+            let entry: T = map.next_value()?;
+            if let Err(e) = (self.0.on_entry)(key, entry) {
+                self.0.error = Some(e);
+                return Err(serde::de::Error::custom("entry processing failed"));
+            }
+        }
+        Ok(())
+    }
 }
 
 #[derive(Parser)]
@@ -57,11 +316,17 @@ struct Cli {
     #[arg(long)]
     index_only: bool,
 
+    #[arg(long, help = "Reload deck files and re-store only deck keys, valuing decks from the prices already in Redis")]
+    decks_only: bool,
+
     #[arg(long, default_value = "data")]
     data_dir: String,
 
-    #[arg(long, help = "Path to TCGPlayer pricing CSV file (obtain from TCGPlayer seller account or API)")]
-    tcg_csv_path: Option<String>,
+    #[arg(long, help = "Path to TCGPlayer pricing CSV file (obtain from TCGPlayer seller account or API); repeat to merge several exports")]
+    tcg_csv_path: Vec<String>,
+
+    #[arg(long, help = "When merging pricing CSVs, keep the first file's prices for a product id seen in several files (default: last file wins)")]
+    tcg_csv_keep_first: bool,
 
     #[arg(long, help = "Skip pricing data processing even if CSV is provided")]
     skip_pricing: bool,
@@ -72,27 +337,242 @@ struct Cli {
     #[arg(long, help = "Force download even if files are fresh (default: skip if files are less than 24 hours old)")]
     force_download: bool,
 
+    #[arg(long, default_value = "3", help = "Attempts per MTGJSON file download before giving up (retries back off exponentially)")]
+    download_attempts: u32,
+
     #[arg(long, default_value = "24", help = "Maximum age in hours before files are considered stale")]
     max_age_hours: u64,
 
+    #[arg(long, help = "Maximum age in hours for card data (AllPrintings, AllDeckFiles); overrides --max-age-hours")]
+    max_age_cards: Option<u64>,
+
+    #[arg(long, help = "Maximum age in hours for price data (TcgplayerSkus); overrides --max-age-hours")]
+    max_age_prices: Option<u64>,
+
     #[arg(long, default_value = "english", help = "Language filter for TCGPlayer SKUs (english, spanish, etc.)")]
     sku_language: String,
 
     #[arg(long, default_value = "near mint", help = "Condition filter for TCGPlayer SKUs (near mint, lightly played, etc.)")]
     sku_condition: String,
 
+    #[arg(long, help = "Cap or drop TCGPlayer prices that are far from the product's median across conditions")]
+    filter_price_outliers: bool,
+
+    #[arg(long, default_value = "3.0", help = "Standard deviations from the median before a price counts as an outlier")]
+    price_outlier_stddevs: f64,
+
+    #[arg(long, help = "Absolute price cap applied by --filter-price-outliers")]
+    max_price: Option<f64>,
+
+    #[arg(long, help = "Drop outlier prices instead of capping them (with --filter-price-outliers)")]
+    drop_price_outliers: bool,
+
+    #[arg(long, help = "Leave basic lands out of stored deck values (they are still subtotaled separately)")]
+    exclude_basics_from_value: bool,
+
     #[arg(long, help = "Show data freshness status and exit")]
     status: bool,
+
+    #[arg(long, help = "Compare the indexed MTGJSON build date with the remote Meta.json and exit, without downloading data")]
+    check_update: bool,
+
+    #[arg(long, default_value = "8", help = "Skip deck files larger than this many megabytes")]
+    max_deck_file_mb: u64,
+
+    #[arg(long, alias = "quiet", help = "Disable progress output (bars are replaced by periodic log lines when stderr is not a terminal)")]
+    no_progress: bool,
+
+    #[arg(long, help = "Parse AllPrintings.json one set at a time instead of loading it whole (lower peak memory)")]
+    streaming: bool,
+
+    #[arg(long, value_delimiter = ',', help = "Affiliate codes to add to stored purchase URLs, as vendor=code (only tcgplayer: other vendors are linked through mtgjson.com redirects); comma-separate or repeat")]
+    affiliate_tags: Vec<String>,
+
+    #[arg(long, help = "Gzip full card JSON into mtg:cards:gz:{uuid} and keep only searchable fields in the RediSearch documents")]
+    compress_cards: bool,
+
+    #[arg(long, help = "Leave purchase URLs and TCGPlayer SKU arrays out of stored cards (for search-only setups)")]
+    lean_cards: bool,
+
+    #[arg(long, default_value = "0.5", help = "Cards whose near-mint market price is below this stay out of the price_range:* indexes (their prices are still stored)")]
+    price_floor: f64,
+
+    #[arg(long, value_delimiter = ',', help = "Index only these set codes (e.g. DOM,WAR,ELD); only their cards are cleared first, other sets stay as they are")]
+    sets: Vec<String>,
+
+    #[arg(long, help = "Remove the indexing lock left by a crashed run before indexing (make sure no other run is active)")]
+    force_unlock: bool,
+
+    #[arg(long, value_name = "PATH", help = "After a full index run, write its stats, per-phase timings and the MTGJSON version/date to this JSON file")]
+    stats_json: Option<String>,
+}
+
+// Enhanced fuzzy search script optimized for MTGJSON data. Callers pass the query's
+// `double_metaphone` codes as ARGV[4] and ARGV[5], so the phonetic keys always come
+// from the same Rust encoder that built the metaphone:{code} index.
+const FUZZY_SEARCH_SCRIPT: &str = r#"
+local query = ARGV[1]
+local max_distance = tonumber(ARGV[2]) or 2
+local max_results = tonumber(ARGV[3]) or 20
+local metaphone_codes = { ARGV[4], ARGV[5] }
+
+local candidates = {}
+local results = {}
+
+-- Function to calculate simple edit distance for small strings
+local function edit_distance(s1, s2)
+    if #s1 == 0 then return #s2 end
+    if #s2 == 0 then return #s1 end
+    
+    local matrix = {}
+    for i = 0, #s1 do
+        matrix[i] = {[0] = i}
+    end
+    for j = 0, #s2 do
+        matrix[0][j] = j
+    end
+    
+    for i = 1, #s1 do
+        for j = 1, #s2 do
+            local cost = (s1:sub(i,i) == s2:sub(j,j)) and 0 or 1
+            matrix[i][j] = math.min(
+                matrix[i-1][j] + 1,
+                matrix[i][j-1] + 1,
+                matrix[i-1][j-1] + cost
+            )
+        end
+    end
+    
+    return matrix[#s1][#s2]
+end
+
+-- First try exact prefix matches (highest priority)
+local query_lower = query:lower()
+local prefix_key = 'auto:prefix:' .. query_lower
+local prefix_matches = redis.call('SMEMBERS', prefix_key)
+
+for _, uuid in ipairs(prefix_matches) do
+    candidates[uuid] = (candidates[uuid] or 0) + 10  -- High score for prefix matches
+    if #results < max_results then
+        table.insert(results, uuid)
+    end
+end
+
+-- If we have enough exact prefix matches, return early
+if #results >= max_results then
+    return results
+end
+
+-- Try word-based matching for multi-word queries
+local words = {}
+for word in query_lower:gmatch('%S+') do
+    if #word >= 2 then
+        table.insert(words, word)
+    end
+end
+
+for _, word in ipairs(words) do
+    local word_key = 'word:' .. word
+    local word_matches = redis.call('SMEMBERS', word_key)
+    
+    for _, uuid in ipairs(word_matches) do
+        candidates[uuid] = (candidates[uuid] or 0) + 5  -- Good score for word matches
+    end
+end
+
+-- Try n-gram fuzzy matching for partial matches
+if #query_lower >= 3 then
+    local ngram_scores = {}
+    
+    for i = 1, #query_lower - 2 do
+        local ngram = query_lower:sub(i, i + 2)
+        local ngram_key = 'ngram:' .. ngram
+        local ngram_matches = redis.call('SMEMBERS', ngram_key)
+        
+        for _, uuid in ipairs(ngram_matches) do
+            ngram_scores[uuid] = (ngram_scores[uuid] or 0) + 1
+        end
+    end
+    
+    -- Only add n-gram matches that have sufficient overlap
+    local min_ngram_score = math.max(1, math.floor((#query_lower - 2) * 0.3))
+    for uuid, score in pairs(ngram_scores) do
+        if score >= min_ngram_score then
+            candidates[uuid] = (candidates[uuid] or 0) + score
+        end
+    end
+end
+
+-- Try metaphone matching for phonetic similarity, on the primary and alternate codes
+local phonetic_matches = {}
+for _, metaphone in pairs(metaphone_codes) do
+    if #metaphone > 0 then
+        local metaphone_matches = redis.call('SMEMBERS', 'metaphone:' .. metaphone)
+        for _, uuid in ipairs(metaphone_matches) do
+            phonetic_matches[uuid] = true
+        end
+    end
+end
+for uuid in pairs(phonetic_matches) do
+    candidates[uuid] = (candidates[uuid] or 0) + 3  -- Moderate score for phonetic matches
+end
+
+-- Convert candidates to sorted array
+local candidate_array = {}
+for uuid, score in pairs(candidates) do
+    table.insert(candidate_array, {uuid = uuid, score = score})
+end
+
+-- Sort by score (higher is better)
+table.sort(candidate_array, function(a, b) return a.score > b.score end)
+
+-- Build final results list
+local final_results = {}
+for i = 1, math.min(#candidate_array, max_results) do
+    table.insert(final_results, candidate_array[i].uuid)
+end
+
+return final_results
+"#;
+
+/// SKU index and pricing by TCGPlayer product id, as `calculate_deck_value` takes them
+type DeckPricing = (HashMap<String, Vec<TcgplayerSku>>, HashMap<String, Vec<TcgPrice>>);
+
+// Indexer-wide settings fixed at startup
+struct IndexerOptions {
+    data_dir: String,
+    max_deck_file_mb: u64,
+    progress: ProgressMode,
+    compress_cards: bool,
+    lean_cards: bool,
+    price_floor: f64,
+    affiliate_tags: AffiliateTags,
 }
 
 struct MTGJSONIndexer {
     redis_client: Client,
     data_dir: String,
     sku_pricing: SkuPricingManager,
+    max_deck_file_bytes: u64,
+    progress: ProgressMode,
+    compress_cards: bool,
+    lean_cards: bool,
+    price_floor: f64,
+    affiliate_tags: AffiliateTags,
 }
 
 impl MTGJSONIndexer {
-    fn new(redis_host: &str, redis_port: u16, data_dir: String) -> Result<Self> {
+    fn new(redis_host: &str, redis_port: u16, options: IndexerOptions) -> Result<Self> {
+        let IndexerOptions {
+            data_dir,
+            max_deck_file_mb,
+            progress,
+            compress_cards,
+            lean_cards,
+            price_floor,
+            affiliate_tags,
+        } = options;
+
         let redis_url = format!("redis://{}:{}", redis_host, redis_port);
         let redis_client = Client::open(redis_url)
             .context("Failed to create Redis client")?;
@@ -114,6 +594,12 @@ impl MTGJSONIndexer {
             redis_client: redis_client.clone(),
             data_dir,
             sku_pricing: SkuPricingManager::new(redis_client),
+            max_deck_file_bytes: max_deck_file_mb * 1024 * 1024,
+            progress,
+            compress_cards,
+            lean_cards,
+            price_floor,
+            affiliate_tags,
         })
     }
 
@@ -151,47 +637,49 @@ impl MTGJSONIndexer {
         }
     }
 
-    // === ADVANCED SEARCH FUNCTIONS (ported from Scryfall indexer) ===
+    /// Parse AllPrintings.json from a buffered reader, calling `on_set` for each set as
+    /// soon as it is read, so only one set is in memory at a time. Returns the file's meta.
+    fn stream_all_printings<F>(&self, file_path: &Path, on_set: F) -> Result<Meta>
+    where
+        F: FnMut(String, Set) -> Result<()>,
+    {
+        self.stream_data_file(file_path, on_set)
+    }
 
-    fn generate_metaphone(&self, text: &str) -> String {
-        let text = text.to_lowercase();
-        let mut result = String::new();
-        let mut prev_char: Option<char> = None;
-        
-        for c in text.chars() {
-            let code = match c {
-                'b' | 'p' | 'f' | 'v' => "B",
-                'c' | 'k' | 'q' => "K",
-                'd' | 't' => "T",
-                'g' | 'j' => "J",
-                'l' => "L",
-                'm' | 'n' => "M",
-                'r' => "R",
-                's' | 'z' => "S",
-                'x' => "KS",
-                'a' | 'e' | 'i' | 'o' | 'u' | 'y' | 'w' | 'h' => "",
-                _ => "",
-            };
-            
-            if !code.is_empty() {
-                let code_chars: Vec<char> = code.chars().collect();
-                
-                if code_chars.len() == 1 {
-                    let code_char = code_chars[0];
-                    if prev_char != Some(code_char) {
-                        result.push(code_char);
-                        prev_char = Some(code_char);
-                    }
-                } else {
-                    result.push_str(code);
-                    if let Some(first_char) = code_chars.first() {
-                        prev_char = Some(*first_char);
-                    }
-                }
-            }
+    /// Parse an MTGJSON file's `data` map from a buffered reader, calling `on_entry` with
+    /// each key and value as soon as it is read. Returns the file's meta.
+    fn stream_data_file<T, F>(&self, file_path: &Path, on_entry: F) -> Result<Meta>
+    where
+        T: serde::de::DeserializeOwned,
+        F: FnMut(String, T) -> Result<()>,
+    {
+        let file = File::open(file_path)
+            .context("Failed to open JSON file")?;
+        let mut deserializer = serde_json::Deserializer::from_reader(BufReader::with_capacity(1024 * 1024, file));
+        
+        let mut stream = DataFileStream { on_entry, error: None, entry: std::marker::PhantomData };
+        let meta = serde::Deserializer::deserialize_map(&mut deserializer, DataFileVisitor(&mut stream));
+        if let Some(e) = stream.error.take() {
+            return Err(e);
         }
+        let meta = meta.context("Failed to stream-parse JSON")?;
+        deserializer.end()
+            .context("Trailing data after JSON")?;
         
-        result
+        Ok(meta)
+    }
+
+    // === ADVANCED SEARCH FUNCTIONS (ported from Scryfall indexer) ===
+
+    // Distinct non-empty Double Metaphone codes (primary, then alternate)
+    fn generate_metaphones(&self, text: &str) -> Vec<String> {
+        let (primary, alternate) = double_metaphone(text);
+        let mut codes = vec![primary];
+        if alternate != codes[0] {
+            codes.push(alternate);
+        }
+        codes.retain(|code| !code.is_empty());
+        codes
     }
 
     fn generate_ngrams(&self, text: &str, n: usize) -> Vec<String> {
@@ -240,66 +728,36 @@ impl MTGJSONIndexer {
         Ok(())
     }
 
-    fn is_data_fresh(&self, max_age_hours: u64) -> bool {
-        let timestamp_file = self.get_timestamp_file_path();
-        
-        if !timestamp_file.exists() {
-            println!("📝 No download timestamp found - will download files");
+    /// Whether `filename` in the data directory exists and was written less than
+    /// `max_age_hours` ago
+    fn is_file_fresh(&self, filename: &str, max_age_hours: u64) -> bool {
+        let file_path = Path::new(&self.data_dir).join(filename);
+        let Some(age_hours) = Self::file_age_hours(&file_path) else {
+            println!("📁 Required file missing: {} - will download", filename);
             return false;
-        }
-
-        // Check if required files exist
-        let required_files = vec![
-            "AllPrintings.json",
-            "TcgplayerSkus.json", 
-            "AllDeckFiles.tar"
-        ];
+        };
 
-        for filename in &required_files {
-            let file_path = Path::new(&self.data_dir).join(filename);
-            if !file_path.exists() {
-                println!("📁 Required file missing: {} - will download", filename);
-                return false;
-            }
+        if age_hours < max_age_hours as f64 {
+            println!("✅ {} is fresh ({:.1} hours old, max: {} hours)", filename, age_hours, max_age_hours);
+            true
+        } else {
+            println!("⏰ {} is stale ({:.1} hours old, max: {} hours)", filename, age_hours, max_age_hours);
+            false
         }
+    }
 
-        // Read timestamp
-        match std::fs::read_to_string(&timestamp_file) {
-            Ok(content) => {
-                match content.trim().parse::<u64>() {
-                    Ok(timestamp) => {
-                        let current_time = SystemTime::now()
-                            .duration_since(SystemTime::UNIX_EPOCH)
-                            .unwrap()
-                            .as_secs();
-                        
-                        let age_seconds = current_time.saturating_sub(timestamp);
-                        let max_age_seconds = max_age_hours * 3600;
-                        
-                        if age_seconds < max_age_seconds {
-                            println!("✅ Data is fresh ({:.1} hours old, max: {} hours)", 
-                                   age_seconds as f64 / 3600.0, max_age_hours);
-                            true
-                        } else {
-                            println!("⏰ Data is stale ({:.1} hours old, max: {} hours)", 
-                                   age_seconds as f64 / 3600.0, max_age_hours);
-                            false
-                        }
-                    }
-                    Err(_) => {
-                        println!("⚠️  Invalid timestamp format - will download");
-                        false
-                    }
-                }
-            }
-            Err(_) => {
-                println!("⚠️  Cannot read timestamp file - will download");
-                false
-            }
-        }
+    // Hours since the file was last written, None when it doesn't exist
+    fn file_age_hours(path: &Path) -> Option<f64> {
+        let modified = path.metadata().ok()?.modified().ok()?;
+        let age = SystemTime::now().duration_since(modified).unwrap_or_default();
+        Some(age.as_secs_f64() / 3600.0)
     }
 
-    async fn download_file(&self, url: &str, filename: &str, force_download: bool) -> Result<()> {
+    /// Download and decompress `url` into the data directory, retrying up to `max_attempts`
+    /// times with exponential backoff. A download only counts when every byte announced
+    /// by Content-Length arrived and it decompresses cleanly. The output is written to a
+    /// `.partial` file and renamed into place, so a failed run keeps the previous file.
+    async fn download_file(&self, url: &str, filename: &str, force_download: bool, max_attempts: u32) -> Result<()> {
         let file_path = Path::new(&self.data_dir).join(filename);
         
         if file_path.exists() && force_download {
@@ -311,14 +769,74 @@ impl MTGJSONIndexer {
         std::fs::create_dir_all(&self.data_dir)
             .context("Failed to create data directory")?;
 
-        println!("Downloading {}...", url);
+        let max_attempts = max_attempts.max(1);
+        let mut attempt = 1;
+        let decompressed_data = loop {
+            println!("Downloading {} (attempt {}/{})...", url, attempt, max_attempts);
+            
+            let mut received = 0;
+            let result = match self.fetch_download(url, filename, &mut received).await {
+                Ok(compressed_data) => {
+                    println!("Decompressing {} ({} bytes)...", filename, compressed_data.len());
+                    Self::decompress_stream(&compressed_data, filename)
+                }
+                Err(e) => Err(e),
+            };
+            
+            match result {
+                Ok(decompressed_data) => break decompressed_data,
+                Err(e) if attempt < max_attempts => {
+                    let backoff = Duration::from_secs(Self::download_backoff_secs(attempt))
+                        + Duration::from_millis((uuid::Uuid::new_v4().as_u128() % 1000) as u64);
+                    println!("⚠️  Attempt {}/{} for {} failed after {} bytes: {:#}", attempt, max_attempts, filename, received, e);
+                    println!("   Retrying in {:.1}s...", backoff.as_secs_f64());
+                    tokio::time::sleep(backoff).await;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    println!("❌ Giving up on {} after {} attempts (last reached {} bytes); any existing copy is kept", filename, max_attempts, received);
+                    return Err(e.context(format!("Failed to download {}", filename)));
+                }
+            }
+        };
+
+        // Write decompressed JSON next to the old file, then swap it in
+        let json_filename = filename.replace(".xz", "");
+        let json_path = Path::new(&self.data_dir).join(&json_filename);
+        let partial_path = Path::new(&self.data_dir).join(format!("{}.partial", json_filename));
+        
+        let mut file = BufWriter::new(File::create(&partial_path)
+            .context("Failed to create output file")?);
+        file.write_all(&decompressed_data)
+            .context("Failed to write decompressed data")?;
+        file.flush()
+            .context("Failed to flush file")?;
+        drop(file);
+        std::fs::rename(&partial_path, &json_path)
+            .with_context(|| format!("Failed to move {} into place", json_filename))?;
+
+        println!("✓ Downloaded and saved {} ({} bytes)", json_filename, decompressed_data.len());
         
+        Ok(())
+    }
+
+    // Seconds to wait after failed attempt `attempt` (1-based), before jitter
+    fn download_backoff_secs(attempt: u32) -> u64 {
+        1u64.checked_shl(attempt.saturating_sub(1))
+            .map_or(u64::MAX, |factor| DOWNLOAD_RETRY_BASE_SECS.saturating_mul(factor))
+            .min(DOWNLOAD_RETRY_MAX_SECS)
+    }
+
+    // One download attempt; `received` tracks the bytes read so far for retry messages
+    async fn fetch_download(&self, url: &str, filename: &str, received: &mut u64) -> Result<Vec<u8>> {
         let response = reqwest::get(url).await
+            .context("Failed to download file")?
+            .error_for_status()
             .context("Failed to download file")?;
         
-        let total_size = response.content_length().unwrap_or(0);
+        let total_size = response.content_length();
         
-        let pb = ProgressBar::new(total_size);
+        let pb = self.progress.bar(total_size.unwrap_or(0), &format!("Downloading {}", filename));
         pb.set_style(ProgressStyle::default_bar()
             .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta})")?
             .progress_chars("#>-"));
@@ -329,63 +847,82 @@ impl MTGJSONIndexer {
         while let Some(chunk) = stream.next().await {
             let chunk = chunk.context("Failed to read chunk")?;
             compressed_data.extend_from_slice(&chunk);
+            *received += chunk.len() as u64;
             pb.inc(chunk.len() as u64);
         }
         
-        pb.finish_with_message("Download complete");
-
-        println!("Decompressing {} ({} bytes)...", filename, compressed_data.len());
-        
-        // Decompress XZ data
-        let mut decoder = XzDecoder::new(&compressed_data[..]);
-        let mut decompressed_data = Vec::new();
-        std::io::copy(&mut decoder, &mut decompressed_data)
-            .context("Failed to decompress XZ data")?;
-
-        // Write decompressed JSON to file
-        let json_filename = filename.replace(".xz", "");
-        let json_path = Path::new(&self.data_dir).join(&json_filename);
+        if let Some(total_size) = total_size {
+            if *received != total_size {
+                pb.abandon();
+                anyhow::bail!("Incomplete download: received {} of {} bytes", received, total_size);
+            }
+        }
         
-        let mut file = BufWriter::new(File::create(&json_path)
-            .context("Failed to create output file")?);
-        file.write_all(&decompressed_data)
-            .context("Failed to write decompressed data")?;
-        file.flush()
-            .context("Failed to flush file")?;
+        pb.finish_with_message("Download complete");
+        Ok(compressed_data)
+    }
 
-        println!("✓ Downloaded and saved {} ({} bytes)", json_filename, decompressed_data.len());
+    /// Decompress a download by its magic bytes rather than its extension: mirrors serve
+    /// `.json.gz`, and proxies may recompress or hand back plain JSON or an uncompressed
+    /// `AllDeckFiles.tar`
+    fn decompress_stream(bytes: &[u8], filename: &str) -> Result<Vec<u8>> {
+        let mut decompressed = Vec::new();
+        
+        if bytes.starts_with(&[0xFD, b'7', b'z', b'X', b'Z', 0x00]) {
+            std::io::copy(&mut XzDecoder::new(bytes), &mut decompressed)
+                .with_context(|| format!("Failed to decompress XZ data in {}", filename))?;
+        } else if bytes.starts_with(&[0x1F, 0x8B]) {
+            std::io::copy(&mut GzDecoder::new(bytes), &mut decompressed)
+                .with_context(|| format!("Failed to decompress gzip data in {}", filename))?;
+        } else if matches!(bytes.iter().find(|b| !b.is_ascii_whitespace()), Some(b'{') | Some(b'['))
+            || bytes.get(257..262) == Some(b"ustar".as_slice())
+        {
+            // The tar header's magic sits at offset 257
+            println!("   {} arrived uncompressed", filename);
+            decompressed.extend_from_slice(bytes);
+        } else {
+            let head: Vec<String> = bytes.iter().take(8).map(|b| format!("{:02x}", b)).collect();
+            anyhow::bail!("{} is not xz, gzip, tar or JSON (starts with {})", filename, head.join(" "));
+        }
         
-        Ok(())
+        Ok(decompressed)
     }
 
-    async fn download_data_files(&self, force_download: bool, max_age_hours: u64) -> Result<()> {
+    /// Download each MTGJSON file that is missing or older than its limit: card data
+    /// (AllPrintings, AllDeckFiles) only changes on set releases, so it uses
+    /// `max_age_cards`, while the SKU list and daily prices (TcgplayerSkus,
+    /// AllPricesToday) use the shorter `max_age_prices`. `force_download` fetches all four.
+    async fn download_data_files(&self, force_download: bool, max_age_cards: u64, max_age_prices: u64, max_attempts: u32) -> Result<()> {
         println!("=== Checking MTGJSON Data Files ===");
         
-        // Check freshness first (unless force download is requested)
-        if !force_download && self.is_data_fresh(max_age_hours) {
-            println!("🎯 All required files are fresh - skipping download");
-            return Ok(());
-        }
-        
         if force_download {
             println!("🔄 Force download enabled - will download all files");
-        } else {
-            println!("📅 Data is stale or missing - downloading fresh files");
         }
         
         let downloads = vec![
-            ("https://mtgjson.com/api/v5/AllPrintings.json.xz", "AllPrintings.json.xz"),
-            ("https://mtgjson.com/api/v5/TcgplayerSkus.json.xz", "TcgplayerSkus.json.xz"),
-            ("https://mtgjson.com/api/v5/AllDeckFiles.tar.xz", "AllDeckFiles.tar.xz"),
+            ("https://mtgjson.com/api/v5/AllPrintings.json.xz", "AllPrintings.json.xz", max_age_cards),
+            ("https://mtgjson.com/api/v5/TcgplayerSkus.json.xz", "TcgplayerSkus.json.xz", max_age_prices),
+            ("https://mtgjson.com/api/v5/AllPricesToday.json.xz", "AllPricesToday.json.xz", max_age_prices),
+            ("https://mtgjson.com/api/v5/AllDeckFiles.tar.xz", "AllDeckFiles.tar.xz", max_age_cards),
         ];
 
-        for (url, filename) in downloads {
-            self.download_file(url, filename, force_download).await?;
+        let mut downloaded = 0;
+        for (url, filename, max_age_hours) in downloads {
+            if !force_download && self.is_file_fresh(&filename.replace(".xz", ""), max_age_hours) {
+                continue;
+            }
+            self.download_file(url, filename, force_download, max_attempts).await?;
+            downloaded += 1;
+        }
+
+        if downloaded == 0 {
+            println!("🎯 All required files are fresh - skipping download");
+            return Ok(());
         }
 
         // Write timestamp after successful downloads
         self.write_download_timestamp()?;
-        println!("✅ All MTGJSON files downloaded and timestamp updated");
+        println!("✅ Downloaded {} MTGJSON files and updated timestamp", downloaded);
 
         Ok(())
     }
@@ -443,7 +980,7 @@ impl MTGJSONIndexer {
             .len();
         
         // Create loading progress bar for file reading/parsing
-        let load_pb = ProgressBar::new_spinner();
+        let load_pb = self.progress.spinner("TCGPlayer SKUs");
         load_pb.set_style(ProgressStyle::default_spinner()
             .template("{spinner:.green} {msg}")?);
         load_pb.set_message(format!("Loading TCGPlayer SKUs ({:.1} MB) - filtering for {} {}", 
@@ -462,7 +999,7 @@ impl MTGJSONIndexer {
 
         // Create progress bar for SKU filtering
         let total_cards = skus_file.data.len();
-        let sku_pb = ProgressBar::new(total_cards as u64);
+        let sku_pb = self.progress.bar(total_cards as u64, "Filtering SKUs");
         sku_pb.set_style(ProgressStyle::default_bar()
             .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} products ({eta}) - {msg}")?
             .progress_chars("#>-"));
@@ -508,7 +1045,60 @@ impl MTGJSONIndexer {
         Ok(sku_index)
     }
 
-    fn show_data_status(&self, max_age_hours: u64) -> Result<()> {
+    /// Report whether MTGJSON has published data newer than what was last indexed, by
+    /// comparing `meta.date` from the remote Meta.json with the one in `mtgjson:stats`.
+    /// Only the few-hundred-byte Meta.json is fetched.
+    async fn check_for_update(&self) -> Result<()> {
+        println!("=== Checking for MTGJSON Updates ===");
+
+        let remote: MetaFile = reqwest::get("https://mtgjson.com/api/v5/Meta.json").await
+            .context("Failed to fetch Meta.json")?
+            .error_for_status()
+            .context("Failed to fetch Meta.json")?
+            .json().await
+            .context("Failed to parse Meta.json")?;
+        println!("🌐 Remote MTGJSON: {} (version {})", remote.data.date, remote.data.version);
+
+        let indexed_stats: Option<IndexStats> = match self.redis_client.get_connection() {
+            Ok(mut con) => {
+                let stats_json: Option<String> = con.get("mtgjson:stats")
+                    .context("Failed to read index stats")?;
+                stats_json.and_then(|json| serde_json::from_str(&json).ok())
+            }
+            Err(e) => {
+                println!("⚠️  Could not connect to Redis ({}), can't compare with the indexed data", e);
+                return Ok(());
+            }
+        };
+
+        let Some(stats) = indexed_stats else {
+            println!("❌ Nothing indexed yet - run the indexer to load {}", remote.data.date);
+            return Ok(());
+        };
+        let Some(indexed_date) = stats.mtgjson_date else {
+            println!("⚠️  Indexed data (version {}) predates build date tracking - reindex to record it", stats.version);
+            return Ok(());
+        };
+        println!("💾 Indexed MTGJSON: {} (version {})", indexed_date, stats.version);
+
+        // Compare as dates; if either doesn't parse, any difference counts as an update
+        let newer = match (
+            chrono::NaiveDate::parse_from_str(&remote.data.date, "%Y-%m-%d"),
+            chrono::NaiveDate::parse_from_str(&indexed_date, "%Y-%m-%d"),
+        ) {
+            (Ok(remote_date), Ok(indexed_date)) => remote_date > indexed_date,
+            _ => remote.data.date != indexed_date,
+        };
+        if newer {
+            println!("🆕 Newer data is available - run the indexer (with --force-download) to update");
+        } else {
+            println!("✅ Indexed data is up to date");
+        }
+
+        Ok(())
+    }
+
+    fn show_data_status(&self, max_age_cards: u64, max_age_prices: u64) -> Result<()> {
         println!("=== MTGJSON Data Status ===");
         
         let timestamp_file = self.get_timestamp_file_path();
@@ -537,13 +1127,7 @@ impl MTGJSONIndexer {
                         
                         println!("📅 Last download: {} UTC", datetime.format("%Y-%m-%d %H:%M:%S"));
                         println!("⏱️  Data age: {:.1} hours", age_hours);
-                        println!("🎯 Max age setting: {} hours", max_age_hours);
-                        
-                        if age_hours < max_age_hours as f64 {
-                            println!("✅ Data status: FRESH");
-                        } else {
-                            println!("⚠️  Data status: STALE (download recommended)");
-                        }
+                        println!("🎯 Max age: {} hours for card data, {} hours for price data", max_age_cards, max_age_prices);
                     }
                     Err(_) => {
                         println!("⚠️  Invalid timestamp format in file");
@@ -558,17 +1142,20 @@ impl MTGJSONIndexer {
         // Check if files exist
         println!("\n📁 File status:");
         let required_files = vec![
-            ("AllPrintings.json", "Card data"),
-            ("TcgplayerSkus.json", "TCGPlayer SKU mapping"), 
-            ("AllDeckFiles.tar", "Preconstructed deck data")
+            ("AllPrintings.json", "Card data", max_age_cards),
+            ("TcgplayerSkus.json", "TCGPlayer SKU mapping", max_age_prices), 
+            ("AllPricesToday.json", "Multi-vendor prices", max_age_prices),
+            ("AllDeckFiles.tar", "Preconstructed deck data", max_age_cards)
         ];
 
-        for (filename, description) in required_files {
+        for (filename, description, max_age_hours) in required_files {
             let file_path = Path::new(&self.data_dir).join(filename);
             if file_path.exists() {
-                if let Ok(metadata) = file_path.metadata() {
+                if let (Ok(metadata), Some(age_hours)) = (file_path.metadata(), Self::file_age_hours(&file_path)) {
                     let size_mb = metadata.len() / 1_024 / 1_024;
-                    println!("   ✅ {} ({}) - {} MB", filename, description, size_mb);
+                    let status = if age_hours < max_age_hours as f64 { "FRESH" } else { "STALE" };
+                    println!("   ✅ {} ({}) - {} MB, {:.1} hours old, {} (max {} hours)",
+                             filename, description, size_mb, age_hours, status, max_age_hours);
                 } else {
                     println!("   ⚠️  {} ({}) - exists but can't read size", filename, description);
                 }
@@ -638,7 +1225,7 @@ impl MTGJSONIndexer {
         // Skip header line
         lines.next();
         
-        let pb = ProgressBar::new(total_lines as u64);
+        let pb = self.progress.bar(total_lines as u64, "Loading pricing records");
         pb.set_style(ProgressStyle::default_bar()
             .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} pricing records ({eta})")?
             .progress_chars("#>-"));
@@ -722,6 +1309,7 @@ impl MTGJSONIndexer {
                 total_quantity: parse_int(total_quantity_col),
                 add_to_quantity: parse_int(add_to_quantity_col),
                 tcg_marketplace_price: parse_price(tcg_marketplace_price_col),
+                price_confidence: None,
             };
             
             // Index by TCGPlayer product ID for reliable matching with MTGJSON cards
@@ -738,6 +1326,56 @@ impl MTGJSONIndexer {
         Ok(pricing_data)
     }
 
+    /// Load and merge several TCGPlayer pricing exports. A product id present in more than
+    /// one file takes the later file's prices unless `keep_first` is set.
+    fn load_tcgplayer_pricing_files(&self, csv_paths: &[String], keep_first: bool) -> Result<HashMap<String, Vec<TcgPrice>>> {
+        let mut merged: HashMap<String, Vec<TcgPrice>> = HashMap::new();
+        let mut loaded_files = 0;
+
+        for csv_path in csv_paths {
+            if !Path::new(csv_path).exists() {
+                println!("❌ TCGPlayer CSV file not found, skipping: {}", csv_path);
+                continue;
+            }
+
+            let file_pricing = self.load_tcgplayer_pricing(csv_path)?;
+            loaded_files += 1;
+
+            let mut duplicates = Vec::new();
+            for (tcgplayer_id, prices) in file_pricing {
+                if merged.contains_key(&tcgplayer_id) {
+                    duplicates.push(tcgplayer_id.clone());
+                    if keep_first {
+                        continue;
+                    }
+                }
+                merged.insert(tcgplayer_id, prices);
+            }
+
+            if !duplicates.is_empty() {
+                duplicates.sort();
+                let examples = duplicates.iter().take(5).cloned().collect::<Vec<_>>().join(", ");
+                println!("⚠️  {} product ids in {} were already loaded from an earlier file ({} kept): {}{}",
+                         duplicates.len(), csv_path,
+                         if keep_first { "earlier prices" } else { "these prices" },
+                         examples,
+                         if duplicates.len() > 5 { ", ..." } else { "" });
+            }
+        }
+
+        if csv_paths.len() > 1 {
+            println!("✓ Merged pricing for {} product variants from {} of {} CSV files", merged.len(), loaded_files, csv_paths.len());
+        }
+        if loaded_files == 0 {
+            println!("   To obtain TCGPlayer pricing data:");
+            println!("   • Export from your TCGPlayer seller account");
+            println!("   • Use TCGPlayer API with authentication");
+            println!("   • Use --auto-download-tcg flag with tcgcsv_clean.py");
+            println!("   ℹ️  Continuing without pricing data...");
+        }
+        Ok(merged)
+    }
+
     fn load_deck_files(&self) -> Result<HashMap<String, IndexedDeck>> {
         // First check if AllDeckFiles directory exists
         let deck_files_path = Path::new(&self.data_dir).join("AllDeckFiles");
@@ -762,11 +1400,32 @@ impl MTGJSONIndexer {
             })
             .collect();
 
-        let pb = ProgressBar::new(deck_files.len() as u64);
-        pb.set_style(ProgressStyle::default_bar()
-            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} deck files ({eta})")?
-            .progress_chars("#>-"));
-
+        // Size guard: a corrupt or runaway file shouldn't stall the parallel loader
+        let mut failures: Vec<(std::path::PathBuf, String)> = Vec::new();
+        let deck_files: Vec<_> = deck_files
+            .into_iter()
+            .filter(|path| {
+                let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+                if size > self.max_deck_file_bytes {
+                    let reason = format!(
+                        "file is {:.1} MB, above --max-deck-file-mb limit of {} MB",
+                        size as f64 / (1024.0 * 1024.0),
+                        self.max_deck_file_bytes / (1024 * 1024)
+                    );
+                    println!("⚠️  Skipping deck file {}: {}", path.display(), reason);
+                    failures.push((path.clone(), reason));
+                    false
+                } else {
+                    true
+                }
+            })
+            .collect();
+
+        let pb = self.progress.bar(deck_files.len() as u64, "Loading deck files");
+        pb.set_style(ProgressStyle::default_bar()
+            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} deck files ({eta})")?
+            .progress_chars("#>-"));
+
         let mut decks = HashMap::with_capacity(deck_files.len()); // Pre-allocate capacity
         let total_processed = Arc::new(AtomicUsize::new(0));
         
@@ -788,21 +1447,17 @@ impl MTGJSONIndexer {
                 .collect();
 
             // Collect successful results from this batch
-            let mut batch_successes = 0;
             for (path, result) in batch_results {
                 match result {
                     Ok(Some(deck)) => {
                         decks.insert(deck.uuid.clone(), deck);
-                        batch_successes += 1;
                     }
                     Ok(None) => {
                         // Deck was skipped (invalid data, etc.)
                     }
                     Err(e) => {
-                        // Only show first few errors to avoid spam
-                        if batch_idx == 0 && batch_successes < 3 {
-                            println!("⚠️  Error processing deck file {}: {}", path.display(), e);
-                        }
+                        // Keep the full context chain; reported in the summary below
+                        failures.push((path.clone(), format!("{:#}", e)));
                     }
                 }
             }
@@ -817,6 +1472,30 @@ impl MTGJSONIndexer {
         pb.finish_with_message("Deck files loaded");
 
         println!("✓ Loaded {} preconstructed decks", decks.len());
+
+        let mut flagged: Vec<&IndexedDeck> = decks.values()
+            .filter(|deck| !deck.validation_issues.is_empty())
+            .collect();
+        if !flagged.is_empty() {
+            flagged.sort_by(|a, b| a.name.cmp(&b.name));
+            println!("⚠️  {} decks indexed with validation issues:", flagged.len());
+            for deck in flagged.iter().take(MAX_REPORTED_DECK_FAILURES) {
+                println!("   - {}: {}", deck.name, deck.validation_issues.join("; "));
+            }
+            if flagged.len() > MAX_REPORTED_DECK_FAILURES {
+                println!("   ... and {} more", flagged.len() - MAX_REPORTED_DECK_FAILURES);
+            }
+        }
+
+        if !failures.is_empty() {
+            println!("⚠️  {} deck files failed to load:", failures.len());
+            for (path, reason) in failures.iter().take(MAX_REPORTED_DECK_FAILURES) {
+                println!("   - {}: {}", path.display(), reason);
+            }
+            if failures.len() > MAX_REPORTED_DECK_FAILURES {
+                println!("   ... and {} more", failures.len() - MAX_REPORTED_DECK_FAILURES);
+            }
+        }
         Ok(decks)
     }
 
@@ -825,23 +1504,44 @@ impl MTGJSONIndexer {
         let deck_file: DeckFile = self.load_json_file(deck_path)
             .context("Failed to parse deck file")?;
 
-        let deck_data = deck_file.data;
+        Ok(Some(self.index_deck(deck_file.data)))
+    }
 
-        // Generate a UUID for the deck based on its code and name
-        let deck_uuid = format!("deck_{}", uuid::Uuid::new_v5(
-            &uuid::Uuid::NAMESPACE_DNS,
-            format!("{}_{}", deck_data.code, deck_data.name).as_bytes()
-        ));
+    fn index_deck(&self, deck_data: DeckData) -> IndexedDeck {
+        let deck_uuid = deck_uuid(&deck_data.code, &deck_data.name);
 
         let is_commander = !deck_data.commander.is_empty() || !deck_data.display_commander.is_empty();
         
-        // Process all card sections (use display_commander first if available, otherwise commander)
-        let commanders_cards = if !deck_data.display_commander.is_empty() {
+        // `commander` lists every commander; `displayCommander` only the one on the box,
+        // so a Partner or Background deck has one of its two there
+        let commanders_cards = if !deck_data.commander.is_empty() {
+            &deck_data.commander
+        } else {
             &deck_data.display_commander
+        };
+
+        // Partner / Background decks have exactly two commanders
+        let commander_pair = if commanders_cards.len() == 2 {
+            Some((commanders_cards[0].name.clone(), commanders_cards[1].name.clone()))
         } else {
-            &deck_data.commander
+            None
         };
-        
+
+        // Problems are stored with the deck and summarized after loading; the deck is
+        // still indexed, since it is a real product either way
+        let validation_issues = self.validate_deck(commanders_cards, &deck_data.main_board);
+
+        let archetypes = infer_deck_archetype(&deck_data);
+
+        // Partner / Background pairs share the union of both commanders' identities
+        let commander_identity: Vec<String> = commanders_cards.iter()
+            .flat_map(|card| card.color_identity.iter().cloned())
+            .collect();
+        let commander_color_identity: Vec<String> = color_identity_key(&commander_identity)
+            .chars()
+            .map(|symbol| symbol.to_string())
+            .collect();
+
         let commanders = self.process_cardset_as_deck_cards(commanders_cards);
         let main_board = self.process_cardset_as_deck_cards(&deck_data.main_board);
         let side_board = self.process_cardset_as_deck_cards(&deck_data.side_board);
@@ -860,7 +1560,7 @@ impl MTGJSONIndexer {
             .collect::<std::collections::HashSet<_>>()
             .len() as u32;
 
-        Ok(Some(IndexedDeck {
+        IndexedDeck {
             uuid: deck_uuid,
             name: deck_data.name,
             code: deck_data.code,
@@ -870,10 +1570,45 @@ impl MTGJSONIndexer {
             total_cards,
             unique_cards,
             commanders,
+            commander_pair,
+            archetypes,
+            commander_color_identity,
             main_board,
             side_board,
+            validation_issues,
             estimated_value: None, // Will be calculated later with pricing data
-        }))
+        }
+    }
+
+    // Rule problems in a deck's list: main board cards outside the commanders' combined
+    // color identity
+    fn validate_deck(&self, commanders: &[CardSet], main_board: &[CardSet]) -> Vec<String> {
+        if commanders.is_empty() {
+            return Vec::new();
+        }
+
+        // Partner pairs share the combined color identity of both commanders
+        let commander_identity: HashSet<&str> = commanders.iter()
+            .flat_map(|c| c.color_identity.iter().map(|color| color.as_str()))
+            .collect();
+
+        let off_identity: Vec<&str> = main_board.iter()
+            .filter(|card| card.color_identity.iter().any(|color| !commander_identity.contains(color.as_str())))
+            .map(|card| card.name.as_str())
+            .collect();
+
+        if off_identity.is_empty() {
+            return Vec::new();
+        }
+
+        let mut identity: Vec<&str> = commander_identity.into_iter().collect();
+        identity.sort();
+        vec![format!(
+            "{} card(s) outside commander color identity [{}]: {}",
+            off_identity.len(),
+            identity.join(","),
+            off_identity.join(", ")
+        )]
     }
 
     fn process_deck_cards(&self, cards: &[DeckCard]) -> Vec<DeckCardInfo> {
@@ -885,6 +1620,7 @@ impl MTGJSONIndexer {
                 is_foil: card.is_foil,
                 set_code: card.set_code.clone(),
                 tcgplayer_product_id: card.identifiers.tcgplayer_product_id.clone(),
+                is_basic_land: is_basic_land(&card.supertypes, &card.types),
             }
         }).collect()
     }
@@ -898,21 +1634,33 @@ impl MTGJSONIndexer {
                 is_foil: card.finishes.contains(&"foil".to_string()),
                 set_code: card.set_code.clone(),
                 tcgplayer_product_id: card.identifiers.tcgplayer_product_id.clone(),
+                is_basic_land: is_basic_land(&card.supertypes, &card.types),
             }
         }).collect()
     }
 
+    /// Totals over every deck card with a TCGPlayer price. Basic lands are always
+    /// subtotaled into the `basic_land_*` fields; with `exclude_basics` they are left
+    /// out of the main totals, pricing counts and confidence (the "spell value").
     fn calculate_deck_value(
         &self,
         deck: &mut IndexedDeck,
         pricing_data: &HashMap<String, Vec<TcgPrice>>,
         sku_index: &HashMap<String, Vec<TcgplayerSku>>,
+        sets: &HashMap<String, SetInfo>,
+        exclude_basics: bool,
     ) {
         let mut market_total = 0.0;
         let mut direct_total = 0.0;
         let mut low_total = 0.0;
         let mut cards_with_pricing = 0;
         let mut cards_without_pricing = 0;
+        let mut confidence_total = 0.0;
+        let mut basic_land_count = 0;
+        let mut basic_lands_with_pricing = 0;
+        let mut basic_land_market_total = 0.0;
+        let mut basic_land_direct_total = 0.0;
+        let mut basic_land_low_total = 0.0;
 
         let all_cards: Vec<&DeckCardInfo> = deck.commanders.iter()
             .chain(deck.main_board.iter())
@@ -920,94 +1668,103 @@ impl MTGJSONIndexer {
             .collect();
 
         for card in &all_cards {
-            let mut card_priced = false;
-            
-            // Use SKU-based pricing flow via product_id
-            if let Some(product_id) = &card.tcgplayer_product_id {
+            let foil = sets.get(&card.set_code).map_or(card.is_foil, |set| set.prices_as_foil(card.is_foil));
+            let price = card.tcgplayer_product_id.as_ref()
                 // Look up pricing data by product_id (not sku_id)
-                if let Some(prices) = pricing_data.get(product_id) {
-                    // If we have SKU information, try to find the best match
-                    if let Some(skus) = sku_index.get(product_id) {
-                        // Find the best SKU match (prefer Near Mint, English)
-                        let mut best_sku: Option<&TcgplayerSku> = None;
-                        
-                        for sku in skus {
-                            let is_near_mint = sku.condition.as_ref()
-                                .map(|c| c.eq_ignore_ascii_case("near mint") || c.eq_ignore_ascii_case("nm") || c == "1")
-                                .unwrap_or(false);
-                            let is_english = sku.language.as_ref()
-                                .map(|l| l.eq_ignore_ascii_case("english") || l == "1")
-                                .unwrap_or(false);
-                            
-                            if is_near_mint && is_english {
-                                best_sku = Some(sku);
-                                break;
-                            } else if best_sku.is_none() {
-                                best_sku = Some(sku); // Fallback to any SKU
-                            }
-                        }
-                        
-                        // Find pricing record that matches the chosen SKU's condition
-                        if let Some(sku) = best_sku {
-                            let target_condition = sku.condition.as_deref().unwrap_or("Near Mint");
-                            
-                            let matching_price = prices.iter()
-                                .find(|p| p.condition.eq_ignore_ascii_case(target_condition))
-                                .or_else(|| prices.first()); // Fallback to any price
-                                
-                            if let Some(price) = matching_price {
-                                let card_count = card.count as f64;
-                                
-                                if let Some(market_price) = price.tcg_market_price {
-                                    market_total += market_price * card_count;
-                                }
-                                if let Some(direct_price) = price.tcg_direct_low {
-                                    direct_total += direct_price * card_count;
-                                }
-                                if let Some(low_price) = price.tcg_low_price {
-                                    low_total += low_price * card_count;
-                                }
-                                
-                                cards_with_pricing += card.count;
-                                card_priced = true;
-                            }
-                        }
-                    } else {
-                        // No SKU data available, use any price record
-                        if let Some(price) = prices.first() {
-                            let card_count = card.count as f64;
-                            
-                            if let Some(market_price) = price.tcg_market_price {
-                                market_total += market_price * card_count;
-                            }
-                            if let Some(direct_price) = price.tcg_direct_low {
-                                direct_total += direct_price * card_count;
-                            }
-                            if let Some(low_price) = price.tcg_low_price {
-                                low_total += low_price * card_count;
-                            }
-                            
-                            cards_with_pricing += card.count;
-                            card_priced = true;
-                        }
-                    }
+                .and_then(|product_id| pricing_data.get(product_id).map(|prices| (product_id, prices)))
+                .and_then(|(product_id, prices)| match sku_index.get(product_id) {
+                    // If we have SKU information, price the best match
+                    Some(skus) => Self::best_sku(skus, foil).and_then(|sku| {
+                        // Find pricing record that matches the chosen SKU's condition and
+                        // finish; TCGplayer labels foil rows "<condition> Foil"
+                        let condition = sku.condition.as_deref().unwrap_or("Near Mint");
+                        let target_condition = if foil { format!("{} Foil", condition) } else { condition.to_string() };
+                        prices.iter()
+                            .find(|p| p.condition.eq_ignore_ascii_case(&target_condition))
+                            .or_else(|| prices.first()) // Fallback to any price
+                    }),
+                    // No SKU data available, use any price record
+                    None => prices.first(),
+                });
+            let card_count = card.count as f64;
+
+            if card.is_basic_land {
+                basic_land_count += card.count;
+                if let Some(price) = price {
+                    basic_lands_with_pricing += card.count;
+                    basic_land_market_total += price.tcg_market_price.unwrap_or(0.0) * card_count;
+                    basic_land_direct_total += price.tcg_direct_low.unwrap_or(0.0) * card_count;
+                    basic_land_low_total += price.tcg_low_price.unwrap_or(0.0) * card_count;
+                }
+                if exclude_basics {
+                    continue;
                 }
             }
-            
-            if !card_priced {
-                cards_without_pricing += card.count;
+
+            match price {
+                Some(price) => {
+                    if let Some(market_price) = price.tcg_market_price {
+                        market_total += market_price * card_count;
+                    }
+                    if let Some(direct_price) = price.tcg_direct_low {
+                        direct_total += direct_price * card_count;
+                    }
+                    if let Some(low_price) = price.tcg_low_price {
+                        low_total += low_price * card_count;
+                    }
+                    // Prices read back from Redis carry the stored confidence instead of quantities
+                    let confidence = price.price_confidence
+                        .unwrap_or_else(|| price_confidence(price.total_quantity, price.add_to_quantity));
+                    confidence_total += confidence * card_count;
+                    cards_with_pricing += card.count;
+                }
+                None => cards_without_pricing += card.count,
             }
         }
 
+        // Copy-weighted mean confidence over the priced cards
+        let price_confidence = if cards_with_pricing > 0 {
+            confidence_total / cards_with_pricing as f64
+        } else {
+            0.0
+        };
+
         deck.estimated_value = Some(DeckValue {
             market_total,
             direct_total,
             low_total,
             cards_with_pricing,
             cards_without_pricing,
+            price_confidence,
+            basics_excluded: exclude_basics,
+            basic_land_count,
+            basic_lands_with_pricing,
+            basic_land_market_total,
+            basic_land_direct_total,
+            basic_land_low_total,
         });
     }
 
+    /// The NM English SKU in the wanted finish, else any SKU in that finish, else any SKU
+    fn best_sku(skus: &[TcgplayerSku], foil: bool) -> Option<&TcgplayerSku> {
+        let in_finish: Vec<&TcgplayerSku> = skus.iter()
+            .filter(|sku| sku.printing.as_deref().is_some_and(|p| p.eq_ignore_ascii_case("foil")) == foil)
+            .collect();
+        in_finish.iter()
+            .copied()
+            .find(|sku| {
+                let is_near_mint = sku.condition.as_ref()
+                    .map(|c| c.eq_ignore_ascii_case("near mint") || c.eq_ignore_ascii_case("nm") || c == "1")
+                    .unwrap_or(false);
+                let is_english = sku.language.as_ref()
+                    .map(|l| l.eq_ignore_ascii_case("english") || l == "1")
+                    .unwrap_or(false);
+                is_near_mint && is_english
+            })
+            .or_else(|| in_finish.first().copied())
+            .or_else(|| skus.first())
+    }
+
     fn process_card(
         &self,
         card: &CardSet,
@@ -1026,9 +1783,17 @@ impl MTGJSONIndexer {
             Vec::new()
         };
 
+        let mut purchase_urls = card.purchase_urls.clone();
+        if !self.affiliate_tags.is_empty() {
+            self.affiliate_tags.apply(&mut purchase_urls, &card.identifiers);
+        }
+
         IndexedCard {
             uuid: card.uuid.clone(),
             name: card.name.clone(),
+            face_name: card.face_name.clone(),
+            side: card.side.clone(),
+            other_face_ids: card.other_face_ids.clone().unwrap_or_default(),
             set_code: set_code.to_string(),
             set_name: set_name.to_string(),
             collector_number: card.number.clone(),
@@ -1037,6 +1802,7 @@ impl MTGJSONIndexer {
             mana_cost: card.mana_cost.clone(),
             colors: card.colors.clone(),
             color_identity: card.color_identity.clone(),
+            color_count: card.colors.len() as u32,
             types: card.types.clone(),
             subtypes: card.subtypes.clone(),
             supertypes: card.supertypes.clone(),
@@ -1044,7 +1810,12 @@ impl MTGJSONIndexer {
             toughness: card.toughness.clone(),
             loyalty: card.loyalty.clone(),
             defense: card.defense.clone(),
+            attraction_lights: card.attraction_lights.clone(),
+            hand: card.hand.clone(),
+            life: card.life.clone(),
             text: card.text.clone(),
+            original_text: card.original_text.clone(),
+            related_cards: card.related_cards.clone().unwrap_or_default(),
             flavor_text: card.flavor_text.clone(),
             layout: card.layout.clone(),
             availability: card.availability.clone(),
@@ -1053,17 +1824,40 @@ impl MTGJSONIndexer {
             has_non_foil: card.has_non_foil,
             is_reserved: card.is_reserved.unwrap_or(false),
             is_promo: card.is_promo.unwrap_or(false),
+            border_color: card.border_color.clone(),
+            is_full_art: card.is_full_art.unwrap_or(false),
+            is_textless: card.is_textless.unwrap_or(false),
+            security_stamp: card.security_stamp.clone(),
+            subsets: card.subsets.clone().unwrap_or_default(),
+            legal_formats: card.legalities.legal_formats(),
+            edhrec_rank: card.edhrec_rank,
             release_date: release_date.to_string(),
             scryfall_oracle_id: card.identifiers.scryfall_oracle_id.clone(),
             scryfall_id: card.identifiers.scryfall_id.clone(),
             tcgplayer_product_id,
+            tcgplayer_etched_product_id: card.identifiers.tcgplayer_etched_product_id.clone(),
             tcgplayer_skus,
-            purchase_urls: card.purchase_urls.clone(),
+            purchase_urls,
+            schema_version: CARD_SCHEMA_VERSION,
         }
     }
 
-    fn index_cards(&self, tcg_csv_path: Option<&str>, skip_pricing: bool, auto_download_tcg: bool, sku_language: &str, sku_condition: &str) -> Result<()> {
+    fn index_cards(&self, options: IndexOptions) -> Result<IndexRunReport> {
+        let IndexOptions {
+            tcg_csv_paths,
+            tcg_csv_keep_first,
+            skip_pricing,
+            auto_download_tcg,
+            sku_language,
+            sku_condition,
+            outlier_filter,
+            exclude_basics_from_value,
+            streaming,
+            set_filter,
+        } = options;
         println!("=== Starting MTGJSON Card Indexing ===");
+        let run_start = std::time::Instant::now();
+        let mut phases = PhaseTimings::default();
 
         // Connect to Redis
         let mut con = self.redis_client.get_connection()
@@ -1075,25 +1869,26 @@ impl MTGJSONIndexer {
         
         println!("✓ Connected to Redis");
 
+        // Held until the end of this function; a concurrent run stops here
+        let lock = IndexLock::acquire(&self.redis_client)?;
+
+        // A --sets run adds to the stored index, so its stats merge into the stored ones
+        let previous_index = match set_filter {
+            Some(_) => self.previous_index_for_partial_run(&mut con)?,
+            None => None,
+        };
+        let mut phase_start = phases.finish("connect", run_start);
+
         // Load TCGPlayer SKUs
         let sku_index = self.load_tcgplayer_skus(sku_language, sku_condition)?;
+        phase_start = phases.finish("load_skus", phase_start);
 
         // Load TCGPlayer pricing if provided or auto-download if requested
-        let pricing_data = if !skip_pricing {
-            if let Some(csv_path) = tcg_csv_path {
-                // User provided explicit CSV path
-                if Path::new(csv_path).exists() {
-                    println!("Loading TCGPlayer pricing data from: {}", csv_path);
-                    self.load_tcgplayer_pricing(csv_path)?
-                } else {
-                    println!("❌ TCGPlayer CSV file not found: {}", csv_path);
-                    println!("   To obtain TCGPlayer pricing data:");
-                    println!("   • Export from your TCGPlayer seller account");
-                    println!("   • Use TCGPlayer API with authentication");
-                    println!("   • Use --auto-download-tcg flag with tcgcsv_clean.py");
-                    println!("   ℹ️  Continuing without pricing data...");
-                    HashMap::new()
-                }
+        let mut pricing_data = if !skip_pricing {
+            if !tcg_csv_paths.is_empty() {
+                // User provided explicit CSV path(s)
+                println!("Loading TCGPlayer pricing data from: {}", tcg_csv_paths.join(", "));
+                self.load_tcgplayer_pricing_files(tcg_csv_paths, tcg_csv_keep_first)?
             } else if auto_download_tcg {
                 // Auto-download using Python script
                 match self.download_tcgplayer_csv() {
@@ -1120,46 +1915,100 @@ impl MTGJSONIndexer {
             HashMap::new()
         };
 
+        if let Some(filter) = outlier_filter {
+            let flagged = filter.apply(&mut pricing_data);
+            let action = if filter.drop { "Dropped" } else { "Capped" };
+            println!("✓ {} {} outlier prices (>{} std devs from median{})", action, flagged, filter.max_stddevs,
+                     filter.max_price.map(|cap| format!(" or above ${:.2}", cap)).unwrap_or_default());
+        }
+
+        phase_start = phases.finish("load_pricing", phase_start);
+
         // Load deck files
         let mut decks = self.load_deck_files()?;
+        phase_start = phases.finish("load_decks", phase_start);
 
-        // Load AllPrintings.json with high-performance memory mapping
+        let include_set = |set_code: &str| set_filter.is_none_or(|sets| sets.contains(set_code));
+
+        // Load AllPrintings.json with high-performance memory mapping, or stream it set by
+        // set. Streaming keeps one set in memory instead of the multi-GB file; the file is
+        // parsed once up front so a malformed download fails before the old index is cleared.
         let all_printings_path = Path::new(&self.data_dir).join("AllPrintings.json");
         let file_size = std::fs::metadata(&all_printings_path)?.len();
         
-        println!("📖 Loading AllPrintings.json ({:.2} MB) with memory mapping...", 
-                file_size as f64 / 1024.0 / 1024.0);
-        
-        let all_printings: AllPrintingsFile = self.load_json_file(&all_printings_path)
-            .context("Failed to parse AllPrintings.json")?;
+        let (all_printings, total_cards) = if streaming {
+            println!("📖 Checking AllPrintings.json ({:.2} MB) one set at a time before streaming it...", 
+                    file_size as f64 / 1024.0 / 1024.0);
+            let mut streamed_sets = 0;
+            let mut total_cards = 0;
+            self.stream_all_printings(&all_printings_path, |set_code, set_data| {
+                streamed_sets += 1;
+                if include_set(&set_code) {
+                    total_cards += set_data.cards.len();
+                }
+                Ok(())
+            }).context("Failed to parse AllPrintings.json")?;
+            println!("✓ Parsed {} sets", streamed_sets);
+            (None, total_cards)
+        } else {
+            println!("📖 Loading AllPrintings.json ({:.2} MB) with memory mapping...", 
+                    file_size as f64 / 1024.0 / 1024.0);
+            
+            let all_printings: AllPrintingsFile = self.load_json_file(&all_printings_path)
+                .context("Failed to parse AllPrintings.json")?;
+            println!("✓ Loaded {} sets", all_printings.data.len());
+            let total_cards = all_printings.data.iter()
+                .filter(|(set_code, _)| include_set(set_code))
+                .map(|(_, set)| set.cards.len())
+                .sum::<usize>();
+            (Some(all_printings), total_cards)
+        };
 
-        let sets_data = all_printings.data;
-        println!("✓ Loaded {} sets", sets_data.len());
+        phase_start = phases.finish("load_all_printings", phase_start);
 
-        // Clear existing data
-        self.clear_redis_data(&mut con)?;
+        // Clear existing data, or only the selected sets' cards and the decks
+        match set_filter {
+            Some(sets) => {
+                self.clear_set_data(&mut con, sets)?;
+                self.clear_deck_data(&mut con)?;
+            }
+            None => self.clear_redis_data(&mut con)?,
+        }
+        phase_start = phases.finish("clear", phase_start);
 
         // Process all cards
-        let total_cards: usize = sets_data.values()
-            .map(|set| set.cards.len())
-            .sum();
-        
         println!("Processing {} total cards...", total_cards);
-
-        let pb = ProgressBar::new(total_cards as u64);
+        let pb = self.progress.bar(total_cards as u64, "Indexing cards");
         pb.set_style(ProgressStyle::default_bar()
             .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} cards ({eta})")?
             .progress_chars("#>-"));
 
         let mut processed_cards = 0;
-        let mut all_indexed_cards = Vec::with_capacity(total_cards); // Collect all cards for search indexing
+        let mut cards_with_pricing = 0; // A card counts as priced when any of its SKUs has a price record
+        let mut total_skus = 0;
+        let mut seen_ruling_cards = HashSet::new(); // Rulings repeat on every printing
+        let mut seen_legality_cards = HashSet::new(); // So do legalities
+        let mut legality_changes = 0;
+        let mut tokens_stored = 0;
+        let run_started = Utc::now();
+        let mut storage_totals = CardStorageTotals::default();
         
         // Process sets with performance monitoring
         let start_time = std::time::Instant::now();
         let mut sets_processed = 0;
-        
-        for (set_code, set_data) in sets_data {
+        let mut included_sets = Vec::new();
+        let mut sets_skipped = 0;
+        let mut indexed_set_stats = BTreeMap::new();
+        
+        let mut index_set = |set_code: String, set_data: Set| -> Result<()> {
+            if !include_set(&set_code) {
+                sets_skipped += 1;
+                return Ok(());
+            }
+            lock.refresh(&mut con)?;
             sets_processed += 1;
+            included_sets.push(set_code.clone());
+            pb.set_message(set_code.clone());
             // Store set metadata
             let set_info = SetInfo {
                 code: set_code.clone(),
@@ -1168,11 +2017,20 @@ impl MTGJSONIndexer {
                 set_type: set_data.set_type.clone(),
                 total_cards: set_data.cards.len(),
                 base_set_size: set_data.base_set_size,
+                is_foil_only: set_data.is_foil_only,
+                is_non_foil_only: set_data.is_non_foil_only.unwrap_or(false),
+                is_online_only: set_data.is_online_only,
+                is_foreign_only: set_data.is_foreign_only.unwrap_or(false),
+                booster_types: set_data.booster.as_ref().map(|booster| booster.keys().cloned().collect()).unwrap_or_default(),
             };
 
             let set_json = serde_json::to_string(&set_info)?;
             let _: () = con.set(format!("set:{}", set_code), set_json)?;
 
+            let mut set_stats = SetIndexStats { cards: set_data.cards.len(), ..SetIndexStats::default() };
+            let storage_before = (storage_totals.json_bytes, storage_totals.stored_bytes,
+                                  storage_totals.lean_saved_bytes, storage_totals.below_price_floor);
+
             // Process cards in batches
             for card_batch in set_data.cards.chunks(BATCH_SIZE) {
                 let mut cards = Vec::new();
@@ -1186,61 +2044,152 @@ impl MTGJSONIndexer {
                         &sku_index,
                         &pricing_data,
                     );
-                    all_indexed_cards.push(indexed_card.clone());
+                    if indexed_card.tcgplayer_skus.iter().any(|sku| pricing_data.contains_key(&sku.sku_id.to_string())) {
+                        cards_with_pricing += 1;
+                        set_stats.cards_with_pricing += 1;
+                    }
+                    total_skus += indexed_card.tcgplayer_skus.len();
+                    set_stats.total_skus += indexed_card.tcgplayer_skus.len();
                     cards.push(indexed_card);
                 }
 
-                self.store_cards_batch(&mut con, cards, &pricing_data, &sku_index)?;
+                self.store_cards_batch(&mut con, cards, &pricing_data, &sku_index, &mut storage_totals)?;
+                self.store_card_rulings(&mut con, card_batch, &mut seen_ruling_cards)?;
+                self.store_unique_printings(&mut con, card_batch, &pricing_data)?;
+                legality_changes += self.store_legality_changes(&mut con, card_batch, &mut seen_legality_cards, run_started)?;
                 processed_cards += card_batch.len();
                 pb.set_position(processed_cards as u64);
             }
-        }
+            tokens_stored += self.store_set_tokens(&mut con, &set_data.tokens)?;
+
+            set_stats.card_json_bytes = storage_totals.json_bytes - storage_before.0;
+            set_stats.card_stored_bytes = storage_totals.stored_bytes - storage_before.1;
+            set_stats.lean_saved_bytes = storage_totals.lean_saved_bytes - storage_before.2;
+            set_stats.cards_below_price_floor = storage_totals.below_price_floor - storage_before.3;
+            indexed_set_stats.insert(set_code, set_stats);
+            Ok(())
+        };
+
+        let meta = match all_printings {
+            Some(all_printings) => {
+                for (set_code, set_data) in all_printings.data {
+                    index_set(set_code, set_data)?;
+                }
+                all_printings.meta
+            }
+            None => {
+                self.stream_all_printings(&all_printings_path, &mut index_set)
+                    .context("Failed to parse AllPrintings.json")?
+            }
+        };
 
         pb.finish_with_message("Card storage complete");
+        if let Some(sets) = set_filter {
+            included_sets.sort();
+            println!("✓ Indexed sets: {} ({} skipped)", included_sets.join(", "), sets_skipped);
+            let missing: Vec<&str> = sets.iter()
+                .filter(|code| !included_sets.contains(code))
+                .map(String::as_str)
+                .collect();
+            if !missing.is_empty() {
+                println!("⚠️  Set codes not found in AllPrintings.json: {}", missing.join(", "));
+            }
+        }
+        println!("✓ Detected {} legality changes since the previous run", legality_changes);
+        println!("✓ Stored {} tokens and emblems", tokens_stored);
+        phase_start = phases.finish("store_cards", phase_start);
+
+        let mut vendor_price_records = 0;
+        if !skip_pricing {
+            vendor_price_records = self.store_vendor_prices(&mut con)?;
+            println!("✓ Stored {} cross-vendor price records", vendor_price_records);
+        }
+        phase_start = phases.finish("vendor_prices", phase_start);
         
-        // Create RediSearch indexes for fast search and autocomplete
+        // Create RediSearch indexes for fast search and autocomplete, and load the fuzzy
+        // search script over the keys stored with each card batch
         self.create_redisearch_indexes(&mut con)?;
+        self.store_fuzzy_search_script(&mut con)?;
+        phase_start = phases.finish("search_indexes", phase_start);
 
         // Process decks with or without pricing information
         if !decks.is_empty() {
-            let pricing_status = if !pricing_data.is_empty() { "with pricing" } else { "without pricing" };
-            println!("Processing {} deck files {}...", decks.len(), pricing_status);
-            
-            let deck_pb = ProgressBar::new(decks.len() as u64);
-            deck_pb.set_style(ProgressStyle::default_bar()
-                .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} decks ({eta})")?
-                .progress_chars("#>-"));
+            let processed_decks = self.value_and_store_decks(&mut con, &mut decks, &pricing_data, &sku_index, exclude_basics_from_value, run_started)?;
+            println!("✓ Processed {} decks", processed_decks);
+        }
+        phase_start = phases.finish("store_decks", phase_start);
 
-            let mut processed_decks = 0;
-            
-            // Calculate deck values and store in batches
-            for deck_batch in decks.values_mut().collect::<Vec<_>>().chunks_mut(BATCH_SIZE) {
-                for deck in deck_batch.iter_mut() {
-                    self.calculate_deck_value(deck, &pricing_data, &sku_index);
-                }
+        let price_coverage_pct = if processed_cards > 0 {
+            cards_with_pricing as f64 / processed_cards as f64 * 100.0
+        } else {
+            0.0
+        };
 
-                let deck_batch_vec: Vec<IndexedDeck> = deck_batch.iter().map(|d| (*d).clone()).collect();
-                self.store_decks_batch(&mut con, deck_batch_vec)?;
-                
-                processed_decks += deck_batch.len();
-                deck_pb.set_position(processed_decks as u64);
-            }
+        let compression_savings_pct = if storage_totals.json_bytes > 0 {
+            (1.0 - storage_totals.stored_bytes as f64 / storage_totals.json_bytes as f64) * 100.0
+        } else {
+            0.0
+        };
+        let avg_decompress_us = if storage_totals.decompress_samples > 0 {
+            storage_totals.decompress_time.as_secs_f64() * 1_000_000.0 / storage_totals.decompress_samples as f64
+        } else {
+            0.0
+        };
 
-            deck_pb.finish_with_message("Deck processing complete");
-            println!("✓ Processed {} decks", processed_decks);
-        }
+        // Tells the API client whether full card data lives in mtg:cards:gz:{uuid}. A --sets
+        // run was checked to match the stored setting, so this never flips it for other sets.
+        let _: () = con.set("mtgjson:cards_compressed", if self.compress_cards { "true" } else { "false" })
+            .context("Failed to store card compression flag")?;
+
+        // Totals over the whole index: this run's sets plus, on a --sets run, the stored
+        // shares of the sets it left alone
+        let (previous_stats, previous_set_stats) = previous_index.unzip();
+        let all_set_stats = SetIndexStats::merge(previous_set_stats.as_ref(), set_filter, indexed_set_stats);
+        self.store_set_stats(&mut con, &all_set_stats)?;
+        let index_totals = SetIndexStats::total(all_set_stats.values());
+
+        // The stored build date only moves on full runs; sets a --sets run skipped are
+        // still from the earlier build, so --check-update must keep comparing against it
+        let (version, mtgjson_date) = match previous_stats {
+            Some(stats) => (stats.version, stats.mtgjson_date),
+            None => (meta.version, Some(meta.date)),
+        };
 
         // Store metadata
         let index_stats = IndexStats {
-            total_sets: 0, // Will be updated
-            total_cards,
-            processed_cards,
-            last_update: Utc::now().to_rfc3339(),
+            total_sets: all_set_stats.len(),
+            total_cards: index_totals.cards,
+            processed_cards: index_totals.cards,
+            deck_count: decks.len(),
+            cards_with_pricing: index_totals.cards_with_pricing,
+            total_skus: index_totals.total_skus,
+            price_coverage_pct: if index_totals.cards > 0 {
+                index_totals.cards_with_pricing as f64 / index_totals.cards as f64 * 100.0
+            } else {
+                0.0
+            },
+            cards_compressed: self.compress_cards,
+            lean_cards: self.lean_cards,
+            lean_saved_bytes: index_totals.lean_saved_bytes,
+            price_floor: self.price_floor,
+            cards_below_price_floor: index_totals.cards_below_price_floor,
+            card_json_bytes: index_totals.card_json_bytes,
+            card_stored_bytes: index_totals.card_stored_bytes,
+            compression_savings_pct: if index_totals.card_json_bytes > 0 {
+                (1.0 - index_totals.card_stored_bytes as f64 / index_totals.card_json_bytes as f64) * 100.0
+            } else {
+                0.0
+            },
+            avg_decompress_us,
+            last_update: run_started.to_rfc3339(),  // Also the deck value history timestamp
             source: "mtgjson".to_string(),
-            version: all_printings.meta.version,
+            version,
+            mtgjson_date,
+            card_schema_version: CARD_SCHEMA_VERSION,
         };
 
-        self.store_index_stats(&mut con, index_stats)?;
+        self.store_index_stats(&mut con, index_stats.clone())?;
+        phases.finish("store_stats", phase_start);
 
         pb.finish_with_message("Indexing complete");
         
@@ -1253,12 +2202,242 @@ impl MTGJSONIndexer {
                 processed_cards, total_time.as_secs_f64(), cards_per_sec);
         println!("   • Indexed {} sets across {} threads", sets_processed, rayon::current_num_threads());
         println!("   • Indexed {} preconstructed decks", decks.len());
+        println!("   • Price coverage: {}/{} cards ({:.1}%), {} SKUs", 
+                cards_with_pricing, processed_cards, price_coverage_pct, total_skus);
         if !pricing_data.is_empty() {
             println!("   • Integrated pricing for {} product variants", pricing_data.len());
         }
+        if self.compress_cards {
+            println!("   • Card storage: {:.1} MB -> {:.1} MB ({:.1}% saved, ~{:.0}µs per decompression)",
+                    storage_totals.json_bytes as f64 / 1_048_576.0, storage_totals.stored_bytes as f64 / 1_048_576.0,
+                    compression_savings_pct, avg_decompress_us);
+        }
+        if self.lean_cards {
+            let full_bytes = storage_totals.json_bytes + storage_totals.lean_saved_bytes;
+            let lean_savings_pct = if full_bytes > 0 {
+                storage_totals.lean_saved_bytes as f64 / full_bytes as f64 * 100.0
+            } else {
+                0.0
+            };
+            println!("   • Lean cards: dropped {:.1} MB of purchase URLs and SKUs ({:.1}% of card JSON)",
+                    storage_totals.lean_saved_bytes as f64 / 1_048_576.0, lean_savings_pct);
+        }
+        if storage_totals.below_price_floor > 0 {
+            println!("   • {} priced cards below ${:.2} left out of the price range indexes",
+                    storage_totals.below_price_floor, self.price_floor);
+        }
         println!("   • Batch size: {} cards/batch", BATCH_SIZE);
-        println!("   • Memory optimization: {}", if file_size > MEMORY_MAP_THRESHOLD { "Memory-mapped JSON" } else { "Buffered reading" });
+        println!("   • Memory optimization: {}", if streaming {
+            "Streaming JSON, one set at a time"
+        } else if file_size > MEMORY_MAP_THRESHOLD {
+            "Memory-mapped JSON"
+        } else {
+            "Buffered reading"
+        });
+        
+        let total_seconds = run_start.elapsed().as_secs_f64();
+        Ok(IndexRunReport {
+            stats: index_stats,
+            total_seconds,
+            cards_per_sec,
+            legality_changes,
+            vendor_price_records,
+            phases,
+        })
+    }
+
+    /// `--decks-only`: reload the deck files and replace just the deck keys. Deck values
+    /// come from the SKU metadata and prices already in Redis, so cards, prices and the
+    /// search indexes are left untouched.
+    fn reindex_decks(&self, exclude_basics_from_value: bool) -> Result<()> {
+        println!("=== Reindexing Decks Only ===");
+
+        let mut con = self.redis_client.get_connection()
+            .context("Failed to connect to Redis")?;
+        let _: String = redis::cmd("PING").query(&mut con)
+            .context("Redis connection test failed")?;
+        println!("✓ Connected to Redis");
+
+        let _lock = IndexLock::acquire(&self.redis_client)?;
+
+        let mut decks = self.load_deck_files()?;
+        if decks.is_empty() {
+            println!("⚠️  No deck files loaded, leaving stored decks as they are");
+            return Ok(());
+        }
+
+        let (sku_index, pricing_data) = self.load_stored_deck_pricing(&mut con, &decks)?;
+        println!("✓ Loaded stored prices for {} of {} deck products", pricing_data.len(), sku_index.len());
+
+        self.clear_deck_data(&mut con)?;
+        let refreshed = self.value_and_store_decks(&mut con, &mut decks, &pricing_data, &sku_index, exclude_basics_from_value, Utc::now())?;
+
+        let stats_json: Option<String> = con.get("mtgjson:stats")
+            .context("Failed to read index stats")?;
+        if let Some(mut stats) = stats_json.and_then(|json| serde_json::from_str::<IndexStats>(&json).ok()) {
+            stats.deck_count = refreshed;
+            self.store_index_stats(&mut con, stats)?;
+        }
+
+        println!("✓ Refreshed {} decks", refreshed);
+        Ok(())
+    }
+
+    // Value each deck and store it in batches; returns the number of decks stored
+    fn value_and_store_decks(
+        &self,
+        con: &mut Connection,
+        decks: &mut HashMap<String, IndexedDeck>,
+        pricing_data: &HashMap<String, Vec<TcgPrice>>,
+        sku_index: &HashMap<String, Vec<TcgplayerSku>>,
+        exclude_basics_from_value: bool,
+        run_time: DateTime<Utc>,
+    ) -> Result<usize> {
+        let pricing_status = if !pricing_data.is_empty() { "with pricing" } else { "without pricing" };
+        println!("Processing {} deck files {}...", decks.len(), pricing_status);
+        
+        let deck_pb = self.progress.bar(decks.len() as u64, "Indexing decks");
+        deck_pb.set_style(ProgressStyle::default_bar()
+            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} decks ({eta})")?
+            .progress_chars("#>-"));
+
+        let mut processed_decks = 0;
+        
+        // Set finish flags decide whether a copy is priced as foil
+        let set_codes: BTreeSet<&str> = decks.values()
+            .flat_map(|deck| deck.commanders.iter().chain(&deck.main_board).chain(&deck.side_board))
+            .map(|card| card.set_code.as_str())
+            .collect();
+        let set_keys: Vec<String> = set_codes.iter().map(|code| format!("set:{}", code)).collect();
+        let set_docs: Vec<Option<String>> = if set_keys.is_empty() {
+            Vec::new()
+        } else {
+            redis::cmd("MGET").arg(&set_keys).query(con)?
+        };
+        let sets: HashMap<String, SetInfo> = set_docs.into_iter()
+            .flatten()
+            .filter_map(|json| serde_json::from_str::<SetInfo>(&json).ok())
+            .map(|set| (set.code.clone(), set))
+            .collect();
         
+        // Calculate deck values and store in batches
+        for deck_batch in decks.values_mut().collect::<Vec<_>>().chunks_mut(BATCH_SIZE) {
+            for deck in deck_batch.iter_mut() {
+                self.calculate_deck_value(deck, pricing_data, sku_index, &sets, exclude_basics_from_value);
+            }
+
+            let deck_batch_vec: Vec<IndexedDeck> = deck_batch.iter().map(|d| (*d).clone()).collect();
+            self.store_decks_batch(con, deck_batch_vec, run_time)?;
+            
+            processed_decks += deck_batch.len();
+            deck_pb.set_position(processed_decks as u64);
+        }
+
+        deck_pb.finish_with_message("Deck processing complete");
+        Ok(processed_decks)
+    }
+
+    /// SKU index and pricing for the products in `decks`, rebuilt from the stored
+    /// `mtg:tcg:product_skus` / `sku_meta` / `sku_price` keys in the shapes
+    /// `calculate_deck_value` takes, so no CSV has to be parsed again.
+    fn load_stored_deck_pricing(
+        &self,
+        con: &mut Connection,
+        decks: &HashMap<String, IndexedDeck>,
+    ) -> Result<DeckPricing> {
+        let product_ids: Vec<String> = decks.values()
+            .flat_map(|deck| deck.commanders.iter().chain(&deck.main_board).chain(&deck.side_board))
+            .filter_map(|card| card.tcgplayer_product_id.clone())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        let mut sku_index: HashMap<String, Vec<TcgplayerSku>> = HashMap::new();
+        let mut pricing_data: HashMap<String, Vec<TcgPrice>> = HashMap::new();
+        for product_batch in product_ids.chunks(BATCH_SIZE) {
+            let mut pipe = redis::pipe();
+            for product_id in product_batch {
+                pipe.cmd("SMEMBERS").arg(format!("mtg:tcg:product_skus:{}", product_id));
+            }
+            let sku_sets: Vec<Vec<String>> = pipe.query(con)
+                .context("Failed to read product SKUs")?;
+
+            // Lowest SKU id first, so best_sku's fallback is stable between runs
+            let skus: Vec<(&String, u64)> = product_batch.iter()
+                .zip(sku_sets)
+                .flat_map(|(product_id, sku_ids)| {
+                    let mut sku_ids: Vec<u64> = sku_ids.iter().filter_map(|id| id.parse().ok()).collect();
+                    sku_ids.sort_unstable();
+                    sku_ids.into_iter().map(move |sku_id| (product_id, sku_id))
+                })
+                .collect();
+            if skus.is_empty() {
+                continue;
+            }
+
+            let mut pipe = redis::pipe();
+            for (_, sku_id) in &skus {
+                pipe.cmd("JSON.GET").arg(format!("mtg:tcg:sku_meta:{}", sku_id));
+                pipe.cmd("JSON.GET").arg(format!("mtg:tcg:sku_price:{}", sku_id));
+            }
+            let docs: Vec<Option<String>> = pipe.query(con)
+                .context("Failed to read SKU metadata and prices")?;
+
+            for ((product_id, sku_id), pair) in skus.into_iter().zip(docs.chunks(2)) {
+                let parse = |doc: &Option<String>| doc.as_deref().and_then(|json| serde_json::from_str::<serde_json::Value>(json).ok());
+                let Some(meta) = parse(&pair[0]) else { continue };
+                let condition = meta["condition"].as_str().unwrap_or("Near Mint").to_string();
+
+                sku_index.entry(product_id.clone()).or_default().push(TcgplayerSku {
+                    condition: Some(condition.clone()),
+                    language: meta["language"].as_str().map(str::to_string),
+                    printing: meta["foil"].as_bool().unwrap_or(false).then(|| "Foil".to_string()),
+                    product_id: product_id.parse().unwrap_or_default(),
+                    sku_id,
+                });
+
+                if let Some(price) = parse(&pair[1]) {
+                    pricing_data.entry(product_id.clone()).or_default().push(TcgPrice {
+                        tcgplayer_id: product_id.clone(),
+                        product_line: "Magic".to_string(),
+                        set_name: String::new(),
+                        product_name: String::new(),
+                        title: String::new(),
+                        number: String::new(),
+                        rarity: String::new(),
+                        condition: price["condition"].as_str().map(str::to_string).unwrap_or(condition),
+                        tcg_market_price: price["tcg_market_price"].as_f64(),
+                        tcg_direct_low: price["tcg_direct_low"].as_f64(),
+                        tcg_low_price_with_shipping: None,
+                        tcg_low_price: price["tcg_low_price"].as_f64(),
+                        total_quantity: None,
+                        add_to_quantity: None,
+                        tcg_marketplace_price: None,
+                        price_confidence: price["price_confidence"].as_f64(),
+                    });
+                }
+            }
+        }
+
+        Ok((sku_index, pricing_data))
+    }
+
+    // Deck documents and the card/commander reverse lookups written by store_decks_batch.
+    // mtg:decks:idx follows its documents, so it is kept.
+    fn clear_deck_data(&self, con: &mut Connection) -> Result<()> {
+        println!("Clearing existing deck data...");
+
+        for pattern in ["mtg:decks:*", "mtg:cards:decks:*", "mtg:commanders:decks:*"] {
+            let keys: Vec<String> = con.keys(pattern)
+                .context("Failed to get keys")?;
+            
+            if !keys.is_empty() {
+                let _: () = con.del(&keys)
+                    .context("Failed to delete keys")?;
+                println!("  ✓ Cleared {} keys matching {}", keys.len(), pattern);
+            }
+        }
+
         Ok(())
     }
 
@@ -1308,36 +2487,124 @@ impl MTGJSONIndexer {
         Ok(())
     }
 
+    /// Remove the cards of `sets` ahead of a `--sets` run: each stored card's document,
+    /// its per-card keys, its entries in the attribute indexes (`card_index_keys`) and
+    /// the fuzzy search sets (`enhanced_search_index_keys`), plus the set record. A
+    /// `mtg:cards:unique_printings` entry goes once its last printing is cleared. Keys
+    /// shared across sets (oracle name lookups, SKU prices) are left in place and
+    /// overwritten as the sets are stored again.
+    fn clear_set_data(&self, con: &mut Connection, sets: &BTreeSet<String>) -> Result<()> {
+        println!("Clearing existing data for sets: {}", sets.iter().cloned().collect::<Vec<_>>().join(", "));
+
+        for set_code in sets {
+            let uuids: Vec<String> = con.smembers(format!("mtg:cards:set_cards:{}", set_code))
+                .context("Failed to read set cards")?;
+
+            for uuid_batch in uuids.chunks(BATCH_SIZE) {
+                let mut mget = redis::cmd("JSON.MGET");
+                for uuid in uuid_batch {
+                    mget.arg(format!("mtg:cards:data:{}", uuid));
+                }
+                let docs: Vec<Option<String>> = mget.arg("$").query(con)
+                    .context("Failed to read stored cards")?;
+
+                let mut pipe = redis::pipe();
+                let mut oracle_ids = BTreeSet::new();
+                for (uuid, doc) in uuid_batch.iter().zip(docs) {
+                    let card = doc
+                        .and_then(|json| serde_json::from_str::<Vec<IndexedCard>>(&json).ok())
+                        .and_then(|cards| cards.into_iter().next());
+                    if let Some(card) = &card {
+                        for key in Self::card_index_keys(card) {
+                            pipe.cmd("SREM").arg(key).arg(uuid);
+                        }
+                        for key in self.enhanced_search_index_keys(&card.name) {
+                            pipe.cmd("SREM").arg(key).arg(uuid);
+                        }
+                    }
+                    pipe.cmd("ZREM").arg("mtg:cards:variation_count").arg(uuid);
+                    for (bucket, _) in PRICE_BUCKETS {
+                        pipe.cmd("ZREM").arg(format!("price_range:{}", bucket)).arg(uuid);
+                    }
+                    if let Some(oracle_id) = card.and_then(|card| card.scryfall_oracle_id) {
+                        oracle_ids.insert(oracle_id);
+                    }
+
+                    let del = pipe.cmd("DEL")
+                        .arg(format!("mtg:cards:data:{}", uuid))
+                        .arg(format!("mtg:cards:gz:{}", uuid))
+                        .arg(format!("mtg:cards:other_faces:{}", uuid))
+                        .arg(format!("mtg:tcg:uuid_to_product:{}", uuid))
+                        .arg(format!("mtg:tcg:uuid_skus:{}", uuid));
+                    for vendor in PRICE_VENDORS {
+                        del.arg(format!("price:vendor:{}:{}", vendor, uuid));
+                    }
+                }
+                let _: () = pipe.query(con)
+                    .context("Failed to clear set cards")?;
+
+                // A unique printing's entry goes once none of its variants are left
+                let oracle_ids: Vec<String> = oracle_ids.into_iter().collect();
+                let mut scard = redis::pipe();
+                for oracle_id in &oracle_ids {
+                    scard.cmd("SCARD").arg(format!("mtg:cards:oracle_to_printings:{}", oracle_id));
+                }
+                let remaining: Vec<usize> = scard.query(con)
+                    .context("Failed to count remaining printings")?;
+                let orphaned: Vec<&String> = oracle_ids.iter()
+                    .zip(remaining)
+                    .filter(|&(_, count)| count == 0)
+                    .map(|(oracle_id, _)| oracle_id)
+                    .collect();
+                if !orphaned.is_empty() {
+                    let _: () = con.zrem("mtg:cards:unique_printings", orphaned)
+                        .context("Failed to clear unique printings")?;
+                }
+            }
+
+            let _: () = con.del(vec![format!("set:{}", set_code), format!("mtg:cards:set_cards:{}", set_code)])
+                .context("Failed to delete set keys")?;
+            println!("  ✓ Cleared {} cards of {}", uuids.len(), set_code);
+        }
+
+        Ok(())
+    }
+
     fn create_redisearch_indexes(&self, con: &mut Connection) -> Result<()> {
         println!("Creating RediSearch indexes...");
         
         // Create main card index
-        let card_index_result: Result<String, redis::RedisError> = redis::cmd("FT.CREATE")
+        let mut card_index = redis::cmd("FT.CREATE");
+        card_index
             .arg("mtg:cards:idx")
             .arg("ON").arg("JSON")
             .arg("PREFIX").arg(1).arg("mtg:cards:data:")
             .arg("SCHEMA")
             .arg("$.uuid").arg("AS").arg("uuid").arg("TEXT").arg("NOSTEM").arg("SORTABLE")
             .arg("$.name").arg("AS").arg("name").arg("TEXT").arg("PHONETIC").arg("dm:en").arg("SORTABLE")
+            .arg("$.face_name").arg("AS").arg("face_name").arg("TEXT").arg("PHONETIC").arg("dm:en")
             .arg("$.set_code").arg("AS").arg("set_code").arg("TAG").arg("SORTABLE")
             .arg("$.set_name").arg("AS").arg("set_name").arg("TEXT").arg("SORTABLE")
             .arg("$.mana_value").arg("AS").arg("mana_value").arg("NUMERIC").arg("SORTABLE")
             .arg("$.types").arg("AS").arg("types").arg("TAG").arg("SEPARATOR").arg(" ")
             .arg("$.colors").arg("AS").arg("colors").arg("TAG").arg("SEPARATOR").arg(",")
             .arg("$.color_identity").arg("AS").arg("color_identity").arg("TAG").arg("SEPARATOR").arg(",")
+            .arg("$.color_count").arg("AS").arg("color_count").arg("NUMERIC").arg("SORTABLE")
             .arg("$.rarity").arg("AS").arg("rarity").arg("TAG").arg("SORTABLE")
-            .arg("$.tcgplayer_product_id").arg("AS").arg("tcg_product").arg("SORTABLE")
-            .arg("$.text").arg("AS").arg("oracle_text").arg("TEXT")
-            .arg("$.release_date").arg("AS").arg("release_date").arg("TEXT").arg("SORTABLE")
-            .query(con);
-            
-        match card_index_result {
-            Ok(_) => println!("  ✓ Created mtg:cards:idx"),
-            Err(e) => println!("  ⚠ Card index creation failed: {}", e),
-        }
+            .arg("$.border_color").arg("AS").arg("border_color").arg("TAG")
+            .arg("$.is_full_art").arg("AS").arg("is_full_art").arg("TAG")
+            .arg("$.is_textless").arg("AS").arg("is_textless").arg("TAG")
+            .arg("$.security_stamp").arg("AS").arg("security_stamp").arg("TAG")
+            .arg("$.finishes[*]").arg("AS").arg("finishes").arg("TAG")
+            .arg("$.legal_formats[*]").arg("AS").arg("legal_formats").arg("TAG")
+            .arg("$.tcgplayer_product_id").arg("AS").arg("tcg_product").arg("TAG").arg("SORTABLE")
+            .arg("$.text").arg("AS").arg("text").arg("TEXT")
+            .arg("$.release_date").arg("AS").arg("release_date").arg("TEXT").arg("SORTABLE");
+        Self::create_search_index(con, "mtg:cards:idx", &card_index);
         
         // Create deck index
-        let deck_index_result: Result<String, redis::RedisError> = redis::cmd("FT.CREATE")
+        let mut deck_index = redis::cmd("FT.CREATE");
+        deck_index
             .arg("mtg:decks:idx")
             .arg("ON").arg("JSON")
             .arg("PREFIX").arg(1).arg("mtg:decks:data:")
@@ -1348,16 +2615,12 @@ impl MTGJSONIndexer {
             .arg("$.estimated_value.market_total").arg("AS").arg("market_value").arg("NUMERIC").arg("SORTABLE")
             .arg("$.total_cards").arg("AS").arg("total_cards").arg("NUMERIC").arg("SORTABLE")
             .arg("$.is_commander").arg("AS").arg("is_commander").arg("TAG")
-            .arg("$.release_date").arg("AS").arg("release_date").arg("TEXT").arg("SORTABLE")
-            .query(con);
-            
-        match deck_index_result {
-            Ok(_) => println!("  ✓ Created mtg:decks:idx"),
-            Err(e) => println!("  ⚠ Deck index creation failed: {}", e),
-        }
+            .arg("$.release_date").arg("AS").arg("release_date").arg("TEXT").arg("SORTABLE");
+        Self::create_search_index(con, "mtg:decks:idx", &deck_index);
         
         // Create set index  
-        let set_index_result: Result<String, redis::RedisError> = redis::cmd("FT.CREATE")
+        let mut set_index = redis::cmd("FT.CREATE");
+        set_index
             .arg("mtg:sets:idx")
             .arg("ON").arg("JSON")
             .arg("PREFIX").arg(1).arg("mtg:sets:data:")
@@ -1366,33 +2629,398 @@ impl MTGJSONIndexer {
             .arg("$.name").arg("AS").arg("name").arg("TEXT").arg("SORTABLE")
             .arg("$.set_type").arg("AS").arg("set_type").arg("TAG").arg("SORTABLE")
             .arg("$.release_date").arg("AS").arg("release_date").arg("TEXT").arg("SORTABLE")
-            .arg("$.total_cards").arg("AS").arg("total_cards").arg("NUMERIC").arg("SORTABLE")
-            .query(con);
-            
-        match set_index_result {
-            Ok(_) => println!("  ✓ Created mtg:sets:idx"),
-            Err(e) => println!("  ⚠ Set index creation failed: {}", e),
-        }
+            .arg("$.total_cards").arg("AS").arg("total_cards").arg("NUMERIC").arg("SORTABLE");
+        Self::create_search_index(con, "mtg:sets:idx", &set_index);
         
         println!("✅ RediSearch indexes created");
         Ok(())
     }
 
-        fn store_cards_batch(
+    // Run an FT.CREATE unless `index` already exists. --sets runs keep the indexes (and
+    // their documents), so an existing index is expected there, not a failure.
+    fn create_search_index(con: &mut Connection, index: &str, create: &redis::Cmd) {
+        let exists: redis::RedisResult<redis::Value> = redis::cmd("FT.INFO").arg(index).query(con);
+        if exists.is_ok() {
+            println!("  ✓ {} already exists", index);
+            return;
+        }
+        let created: redis::RedisResult<String> = create.query(con);
+        match created {
+            Ok(_) => println!("  ✓ Created {}", index),
+            Err(e) => println!("  ⚠ {} creation failed: {}", index, e),
+        }
+    }
+
+        fn store_card_rulings(
         &self,
         con: &mut Connection,
-        cards: Vec<IndexedCard>,
-        pricing_data: &HashMap<String, Vec<TcgPrice>>,
-        sku_index: &HashMap<String, Vec<TcgplayerSku>>,
+        cards: &[CardSet],
+        seen_cards: &mut HashSet<String>,
     ) -> Result<()> {
         let mut pipe = redis::pipe();
-        pipe.atomic();
-        
-        let timestamp = chrono::Utc::now().timestamp();
 
-        for card in &cards {
-            let card_json = serde_json::to_string(card)
-                .context("Failed to serialize card")?;
+        for card in cards {
+            let rulings = match &card.rulings {
+                Some(rulings) if !rulings.is_empty() => rulings,
+                _ => continue,
+            };
+
+            // Store each oracle card's rulings once, not once per printing
+            let card_key = card.identifiers.scryfall_oracle_id.clone()
+                .unwrap_or_else(|| card.name.to_lowercase());
+            if !seen_cards.insert(card_key.clone()) {
+                continue;
+            }
+
+            pipe.cmd("SET")
+                .arg(format!("mtg:rulings:card:{}", card_key))
+                .arg(serde_json::to_string(rulings)?);
+
+            // Date index so recent clarifications can be range-queried
+            for ruling in rulings {
+                let date = match chrono::NaiveDate::parse_from_str(&ruling.date, "%Y-%m-%d") {
+                    Ok(date) => date,
+                    Err(_) => continue,
+                };
+                let timestamp = date.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp();
+                let entry = serde_json::json!({
+                    "card_name": card.name,
+                    "date": ruling.date,
+                    "text": ruling.text
+                });
+
+                pipe.cmd("ZADD")
+                    .arg("mtg:rulings:by_date")
+                    .arg(timestamp)
+                    .arg(entry.to_string());
+            }
+        }
+
+        let _: () = pipe.query(con)
+            .context("Failed to store card rulings")?;
+
+        Ok(())
+    }
+
+    /// Oracle ids of paper cards whose MTGJSON `printings` list a single set (never reprinted),
+    /// scored by Near Mint market price (0 when unpriced) so the API can sort them by value.
+    /// Variants within that set share the entry, which keeps the highest price (`ZADD GT`).
+    fn store_unique_printings(
+        &self,
+        con: &mut Connection,
+        cards: &[CardSet],
+        pricing_data: &HashMap<String, Vec<TcgPrice>>,
+    ) -> Result<()> {
+        let mut pipe = redis::pipe();
+
+        for card in cards {
+            let single_set = card.printings.as_ref().is_some_and(|printings| printings.len() == 1);
+            if !single_set || !card.availability.iter().any(|a| a == "paper") {
+                continue;
+            }
+            let oracle_id = match &card.identifiers.scryfall_oracle_id {
+                Some(oracle_id) => oracle_id,
+                None => continue,
+            };
+
+            let prices = card.identifiers.tcgplayer_product_id.as_ref()
+                .and_then(|product_id| pricing_data.get(product_id));
+            let market_price = prices
+                .and_then(|prices| {
+                    prices.iter()
+                        .find(|price| price.condition == "Near Mint" && price.tcg_market_price.is_some())
+                        .or_else(|| prices.iter().find(|price| price.tcg_market_price.is_some()))
+                })
+                .and_then(|price| price.tcg_market_price)
+                .unwrap_or(0.0);
+
+            pipe.cmd("ZADD").arg("mtg:cards:unique_printings").arg("GT").arg(market_price).arg(oracle_id);
+        }
+
+        let _: () = pipe.query(con)
+            .context("Failed to store unique printings")?;
+
+        Ok(())
+    }
+
+    /// Store a set's tokens and emblems as `mtg:tokens:data:{uuid}`, and link each to the
+    /// cards that create it (its `reverse_related` names) through
+    /// `mtg:tokens:created_by:{name key}`. Links from an earlier store that `reverse_related`
+    /// no longer names are removed first, using the name keys kept in
+    /// `mtg:tokens:creators:{uuid}`. Returns the number of tokens stored.
+    fn store_set_tokens(&self, con: &mut Connection, tokens: &[CardToken]) -> Result<usize> {
+        if tokens.is_empty() {
+            return Ok(0);
+        }
+        
+        let mut pipe = redis::pipe();
+        for token in tokens {
+            pipe.cmd("SMEMBERS").arg(format!("mtg:tokens:creators:{}", token.uuid));
+        }
+        let previous_creators: Vec<Vec<String>> = pipe.query(con)
+            .context("Failed to read token creators")?;
+        
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        for (token, previous) in tokens.iter().zip(previous_creators) {
+            let creators_key = format!("mtg:tokens:creators:{}", token.uuid);
+            for name_key in previous {
+                pipe.cmd("SREM").arg(format!("mtg:tokens:created_by:{}", name_key)).arg(&token.uuid);
+            }
+            pipe.cmd("DEL").arg(&creators_key);
+            
+            pipe.cmd("JSON.SET")
+                .arg(format!("mtg:tokens:data:{}", token.uuid))
+                .arg("$")
+                .arg(serde_json::to_string(&TokenSummary::from(token))?);
+            let creators = token.related_cards.as_ref().and_then(|related| related.reverse_related.as_ref());
+            for creator in creators.into_iter().flatten() {
+                let name_key = name_index_key(creator);
+                pipe.cmd("SADD")
+                    .arg(format!("mtg:tokens:created_by:{}", name_key))
+                    .arg(&token.uuid);
+                pipe.cmd("SADD").arg(&creators_key).arg(&name_key);
+            }
+        }
+        let _: () = pipe.query(con)
+            .context("Failed to store tokens")?;
+        
+        Ok(tokens.len())
+    }
+
+    /// Store each card's latest prices per marketplace from AllPricesToday.json as
+    /// `price:vendor:{vendor}:{uuid}`, for the vendors in `PRICE_VENDORS`. The file is
+    /// streamed one card at a time and written in pipelines of `BATCH_SIZE` cards, so
+    /// memory stays bounded however large it grows. A missing file is skipped with a
+    /// notice. Returns the number of records stored.
+    fn store_vendor_prices(&self, con: &mut Connection) -> Result<usize> {
+        let prices_path = Path::new(&self.data_dir).join("AllPricesToday.json");
+        if !prices_path.exists() {
+            println!("ℹ️  AllPricesToday.json not found, skipping cross-vendor prices");
+            return Ok(0);
+        }
+        
+        let mut stored = 0;
+        let mut pipe = redis::pipe();
+        let mut pending_cards = 0;
+        self.stream_data_file(&prices_path, |uuid, formats: CardPriceFormats| {
+            for vendor in PRICE_VENDORS {
+                let Some(price) = formats.paper.get(*vendor).and_then(|list| VendorPrice::from_price_list(vendor, list)) else {
+                    continue;
+                };
+                pipe.cmd("JSON.SET")
+                    .arg(format!("price:vendor:{}:{}", vendor, uuid))
+                    .arg("$")
+                    .arg(serde_json::to_string(&price)?);
+                stored += 1;
+            }
+            
+            pending_cards += 1;
+            if pending_cards == BATCH_SIZE {
+                let _: () = pipe.query(con)
+                    .context("Failed to store vendor prices")?;
+                pipe.clear();
+                pending_cards = 0;
+            }
+            Ok(())
+        }).context("Failed to parse AllPricesToday.json")?;
+        
+        if pending_cards > 0 {
+            let _: () = pipe.query(con)
+                .context("Failed to store vendor prices")?;
+        }
+        
+        Ok(stored)
+    }
+
+    /// Diff each oracle card's format statuses against the snapshot left by the previous
+    /// run, record what changed, then replace the snapshot. Both live under mtgjson: so
+    /// they survive the wipe at the start of each run. Returns the number of changes.
+    fn store_legality_changes(
+        &self,
+        con: &mut Connection,
+        cards: &[CardSet],
+        seen_cards: &mut HashSet<String>,
+        run_started: DateTime<Utc>,
+    ) -> Result<usize> {
+        let mut current = Vec::new();
+        for card in cards {
+            let oracle_id = match &card.identifiers.scryfall_oracle_id {
+                Some(oracle_id) => oracle_id.clone(),
+                None => continue,
+            };
+            if !seen_cards.insert(oracle_id.clone()) {
+                continue;
+            }
+
+            let statuses: BTreeMap<String, String> = card.legalities.statuses()
+                .into_iter()
+                .map(|(format, status)| (format.to_string(), status.to_string()))
+                .collect();
+            current.push((oracle_id, card, statuses));
+        }
+        if current.is_empty() {
+            return Ok(0);
+        }
+
+        let oracle_ids: Vec<&str> = current.iter().map(|(oracle_id, _, _)| oracle_id.as_str()).collect();
+        let snapshots: Vec<Option<String>> = redis::cmd("HMGET")
+            .arg("mtgjson:legalities:snapshot")
+            .arg(&oracle_ids)
+            .query(con)
+            .context("Failed to read legality snapshot")?;
+
+        let mut pipe = redis::pipe();
+        let mut changes = 0;
+
+        for ((oracle_id, card, statuses), snapshot) in current.iter().zip(snapshots) {
+            // No snapshot means the card is new this run, which isn't a legality change
+            if let Some(snapshot) = snapshot {
+                let previous: BTreeMap<String, String> = serde_json::from_str(&snapshot).unwrap_or_default();
+                let formats: BTreeSet<&String> = previous.keys().chain(statuses.keys()).collect();
+
+                for format in formats {
+                    let (before, after) = (previous.get(format), statuses.get(format));
+                    if before == after {
+                        continue;
+                    }
+
+                    let change = LegalityChange {
+                        format: format.clone(),
+                        oracle_id: oracle_id.clone(),
+                        name: card.name.clone(),
+                        uuid: card.uuid.clone(),
+                        previous: before.cloned(),
+                        current: after.cloned(),
+                        detected_at: run_started.to_rfc3339(),
+                    };
+                    pipe.cmd("ZADD")
+                        .arg(format!("mtgjson:legalities:changes:{}", format))
+                        .arg(run_started.timestamp())
+                        .arg(serde_json::to_string(&change)?);
+                    changes += 1;
+                }
+            }
+
+            pipe.cmd("HSET")
+                .arg("mtgjson:legalities:snapshot")
+                .arg(oracle_id)
+                .arg(serde_json::to_string(statuses)?);
+        }
+
+        let _: () = pipe.query(con)
+            .context("Failed to store legality changes")?;
+
+        Ok(changes)
+    }
+
+    /// Membership sets that hold `card.uuid`, written by `store_cards_batch` and undone by
+    /// `clear_set_data`. Keys are built from the stored card alone so both sides agree.
+    fn card_index_keys(card: &IndexedCard) -> Vec<String> {
+        let mut keys = vec![format!("mtg:cards:set_cards:{}", card.set_code)];
+        if let Some(oracle_id) = &card.scryfall_oracle_id {
+            keys.push(format!("mtg:cards:oracle_to_printings:{}", oracle_id));
+        }
+
+        // Frame treatment indexes for collector filters
+        keys.push(format!("mtg:cards:border:{}", card.border_color.to_lowercase()));
+        if card.is_full_art {
+            keys.push("mtg:cards:full_art:true".to_string());
+        }
+        if card.is_textless {
+            keys.push("mtg:cards:textless:true".to_string());
+        }
+        // Acorn-stamped cards aren't tournament legal, even in black-border-looking sets
+        if let Some(stamp) = &card.security_stamp {
+            keys.push(format!("mtg:cards:security_stamp:{}", stamp.to_lowercase()));
+        }
+
+        // Reprint vehicles like The List, keyed like card names ("the_list")
+        for subset in &card.subsets {
+            keys.push(format!("mtg:cards:subset:{}", name_index_key(subset)));
+        }
+
+        // Mana value and card type indexes for curve-fill queries. f32 Display drops
+        // the ".0", so keys read mana_value:2 and mana_value:0.5 (Un-cards).
+        keys.push(format!("mtg:cards:mana_value:{}", card.mana_value));
+        for card_type in &card.types {
+            keys.push(format!("mtg:cards:type:{}", card_type.to_lowercase()));
+        }
+        // Spells with no mana cost at all (suspend-only, Living End) also have mana value 0
+        // but can't be cast for free, unlike {0} spells and lands
+        if card.mana_cost.is_none() && !card.types.iter().any(|card_type| card_type == "Land") {
+            keys.push("mtg:cards:no_mana_cost".to_string());
+        }
+
+        // Subtype, color and format indexes for tribal deckbuilding queries
+        for subtype in &card.subtypes {
+            keys.push(format!("mtg:cards:subtype:{}", subtype.to_lowercase()));
+        }
+        for color in &card.colors {
+            keys.push(format!("mtg:cards:color:{}", color.to_lowercase()));
+        }
+        // Exact identity, so "Bant" doesn't also return mono-green cards
+        keys.push(format!("mtg:cards:color_identity:{}", color_identity_key(&card.color_identity)));
+        keys.push(format!("mtg:cards:rarity:{}", card.rarity.to_lowercase()));
+        // 0 = colorless, 1 = mono, 2 = two-color gold, ...
+        keys.push(format!("mtg:cards:color_count:{}", card.color_count));
+        for format in &card.legal_formats {
+            keys.push(format!("mtg:cards:legal:{}", format));
+        }
+
+        // Etched foils are listed as a separate TCGPlayer product
+        if let Some(etched_product_id) = &card.tcgplayer_etched_product_id {
+            keys.push(format!("mtg:tcg:product_cards:{}", etched_product_id));
+        }
+        if let Some(product_id) = &card.tcgplayer_product_id {
+            keys.push(format!("mtg:tcg:product_cards:{}", product_id));
+        }
+
+        keys
+    }
+
+    fn store_cards_batch(
+        &self,
+        con: &mut Connection,
+        mut cards: Vec<IndexedCard>,
+        pricing_data: &HashMap<String, Vec<TcgPrice>>,
+        sku_index: &HashMap<String, Vec<TcgplayerSku>>,
+        totals: &mut CardStorageTotals,
+    ) -> Result<()> {
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        
+        let timestamp = chrono::Utc::now().timestamp();
+
+        for card in cards.iter_mut() {
+            let mut card_json = if self.lean_cards {
+                totals.lean_saved_bytes += Self::lean_saved_bytes(card)?;
+                Self::lean_card_json(card)
+            } else {
+                serde_json::to_string(&*card)
+            }
+            .context("Failed to serialize card")?;
+            totals.cards += 1;
+            totals.json_bytes += card_json.len() as u64;
+
+            if self.compress_cards {
+                let compressed = self.compress_card_json(&card_json, totals)?;
+                totals.stored_bytes += compressed.len() as u64;
+                pipe.cmd("SET").arg(format!("mtg:cards:gz:{}", card.uuid)).arg(compressed);
+
+                // RediSearch needs plain JSON, so keep a slim document with the indexed fields
+                let slim_card = IndexedCard {
+                    flavor_text: None,
+                    original_text: None,
+                    related_cards: RelatedCards::default(),
+                    tcgplayer_skus: Vec::new(),
+                    purchase_urls: PurchaseUrls::default(),
+                    ..card.clone()
+                };
+                card_json = serde_json::to_string(&slim_card)
+                    .context("Failed to serialize card")?;
+            }
+            totals.stored_bytes += card_json.len() as u64;
 
             // Store as RediSearch JSON document - this replaces ALL manual indexing
             pipe.cmd("JSON.SET")
@@ -1400,11 +3028,59 @@ impl MTGJSONIndexer {
                 .arg("$")
                 .arg(&card_json);
 
+            // Attribute indexes for lookups outside RediSearch
+            for key in Self::card_index_keys(card) {
+                pipe.cmd("SADD").arg(key).arg(&card.uuid);
+            }
+            // Prefix, word, n-gram and metaphone keys for the fuzzy search script,
+            // built batch by batch so no index needs every card in memory
+            self.add_enhanced_search_indexes(&mut pipe, &card.name, &card.uuid);
+            if let Some(oracle_id) = &card.scryfall_oracle_id {
+                pipe.cmd("SADD").arg(format!("mtg:cards:name_to_oracle:{}", name_index_key(&card.name))).arg(oracle_id);
+                if let Some(face_name) = &card.face_name {
+                    pipe.cmd("SADD").arg(format!("mtg:cards:name_to_oracle:{}", name_index_key(face_name))).arg(oracle_id);
+                }
+            }
+
+            // First-seen time lives outside mtg:* so it survives the wipe at the start of each
+            // run; NX keeps the earliest score
+            pipe.cmd("ZADD").arg("mtgjson:cards:first_seen").arg("NX").arg(timestamp).arg(&card.uuid);
+
+            // Link the faces of multi-face cards; each face is its own printing uuid
+            for other_face_id in &card.other_face_ids {
+                pipe.cmd("SADD").arg(format!("mtg:cards:other_faces:{}", card.uuid)).arg(other_face_id);
+            }
+
+            // Distinct SKUs (finish / condition / language combinations) per printing
+            let mut variation_skus: HashSet<u64> = card.tcgplayer_skus.iter().map(|sku| sku.sku_id).collect();
+            if let Some(skus) = card.tcgplayer_product_id.as_ref().and_then(|product_id| sku_index.get(product_id)) {
+                variation_skus.extend(skus.iter().map(|sku| sku.sku_id));
+            }
+            if !variation_skus.is_empty() {
+                pipe.cmd("ZADD").arg("mtg:cards:variation_count").arg(variation_skus.len()).arg(&card.uuid);
+            }
+
+            // Price-range indexes by the cheapest near-mint market price. Bulk below the
+            // floor is left out so range scans only see cards people trade.
+            let nm_market_price = variation_skus.iter()
+                .filter_map(|sku_id| pricing_data.get(&sku_id.to_string()))
+                .flatten()
+                .filter(|price| price.condition == "Near Mint")
+                .filter_map(|price| price.tcg_market_price)
+                .min_by(|a, b| a.total_cmp(b));
+            if let Some(price) = nm_market_price {
+                match self.price_range_bucket(price) {
+                    Some(bucket) => {
+                        pipe.cmd("ZADD").arg(format!("price_range:{}", bucket)).arg(price).arg(&card.uuid);
+                    }
+                    None => totals.below_price_floor += 1,
+                }
+            }
+
             // TCGPlayer pricing chain - optimized for search performance
             if let Some(product_id) = &card.tcgplayer_product_id {
                 // Direct UUID -> ProductID mapping (step 1 of pricing chain)
                 pipe.cmd("SET").arg(format!("mtg:tcg:uuid_to_product:{}", card.uuid)).arg(product_id);
-                pipe.cmd("SADD").arg(format!("mtg:tcg:product_cards:{}", product_id)).arg(&card.uuid);
                 
                 // Store SKU-based pricing (steps 2-3 of pricing chain)
                 if let Some(skus) = sku_index.get(product_id) {
@@ -1437,6 +3113,7 @@ impl MTGJSONIndexer {
                                     "tcg_direct_low": price.tcg_direct_low,
                                     "tcg_low_price": price.tcg_low_price,
                                     "condition": price.condition,
+                                    "price_confidence": price_confidence(price.total_quantity, price.add_to_quantity),
                                     "timestamp": timestamp
                                 });
                                 
@@ -1493,6 +3170,25 @@ impl MTGJSONIndexer {
         Ok(())
     }
     
+    // A card's JSON without its SKUs and purchase URLs (--lean-cards). The SKU indexes
+    // still need them, so they are set aside while serializing and put back, not cloned.
+    fn lean_card_json(card: &mut IndexedCard) -> serde_json::Result<String> {
+        let skus = std::mem::take(&mut card.tcgplayer_skus);
+        let purchase_urls = std::mem::take(&mut card.purchase_urls);
+        let json = serde_json::to_string(&*card);
+        card.tcgplayer_skus = skus;
+        card.purchase_urls = purchase_urls;
+        json
+    }
+
+    // JSON bytes lean_card_json leaves out: the SKUs and purchase URLs, less the empty
+    // values written in their place
+    fn lean_saved_bytes(card: &IndexedCard) -> serde_json::Result<u64> {
+        let full = serde_json::to_string(&card.tcgplayer_skus)?.len() + serde_json::to_string(&card.purchase_urls)?.len();
+        let empty = "[]".len() + serde_json::to_string(&PurchaseUrls::default())?.len();
+        Ok(full.saturating_sub(empty) as u64)
+    }
+
     fn build_autocomplete_suggestions(&self, con: &mut Connection, cards: &[IndexedCard]) -> Result<()> {
         for card in cards {
             // Add card name to suggestions with score based on popularity/rarity
@@ -1512,18 +3208,37 @@ impl MTGJSONIndexer {
                 .arg("PAYLOAD")
                 .arg(&card.uuid)
                 .query(con);
+
+            // Faces are suggested by their own name as well, pointing at that face
+            if let Some(face_name) = card.face_name.as_ref().filter(|face_name| **face_name != card.name) {
+                let _: Result<i64, redis::RedisError> = redis::cmd("FT.SUGADD")
+                    .arg("mtg:autocomplete:names")
+                    .arg(face_name)
+                    .arg(score)
+                    .arg("PAYLOAD")
+                    .arg(&card.uuid)
+                    .query(con);
+            }
         }
         
         Ok(())
     }
 
+    /// Store deck documents and lookups, and append each valued deck's market total to
+    /// `mtgjson:decks:value_history:{uuid}` at `run_time`. The history lives outside
+    /// mtg:* so it survives reindexes; points older than `DECK_VALUE_HISTORY_DAYS` are
+    /// trimmed as new ones are added.
     fn store_decks_batch(
         &self,
         con: &mut Connection,
         decks: Vec<IndexedDeck>,
+        run_time: DateTime<Utc>,
     ) -> Result<()> {
         let mut pipe = redis::pipe();
         pipe.atomic();
+        
+        let timestamp = run_time.timestamp();
+        let history_cutoff = timestamp - DECK_VALUE_HISTORY_DAYS * 86400;
 
         for deck in &decks {
             let deck_json = serde_json::to_string(deck)
@@ -1535,6 +3250,14 @@ impl MTGJSONIndexer {
                 .arg("$")
                 .arg(&deck_json);
 
+            // Forward ids from the pre-normalization scheme so bookmarked decks still resolve
+            let legacy_uuid = legacy_deck_uuid(&deck.code, &deck.name);
+            if legacy_uuid != deck.uuid {
+                pipe.cmd("SET")
+                    .arg(format!("mtg:decks:legacy_uuid:{}", legacy_uuid))
+                    .arg(&deck.uuid);
+            }
+
             // Store deck composition with card quantities
             let all_cards: Vec<&DeckCardInfo> = deck.commanders.iter()
                 .chain(deck.main_board.iter())
@@ -1565,6 +3288,38 @@ impl MTGJSONIndexer {
                     .arg(format!("mtg:commanders:decks:{}", commander.uuid))
                     .arg(&deck.uuid);
             }
+
+            // Index Partner / Background pairs for pair lookups
+            if let Some((first, second)) = &deck.commander_pair {
+                pipe.cmd("SADD")
+                    .arg(format!("mtg:decks:commander_pair:{}", commander_pair_key(first, second)))
+                    .arg(&deck.uuid);
+            }
+
+            for tag in &deck.archetypes {
+                pipe.cmd("SADD")
+                    .arg(format!("mtg:decks:archetype:{}", tag))
+                    .arg(&deck.uuid);
+            }
+
+            if deck.is_commander {
+                pipe.cmd("SADD")
+                    .arg(format!("mtg:decks:color_identity:{}", color_identity_key(&deck.commander_color_identity)))
+                    .arg(&deck.uuid);
+            }
+
+            // Members carry the timestamp so runs with an unchanged value stay separate points
+            if let Some(value) = &deck.estimated_value {
+                let history_key = format!("mtgjson:decks:value_history:{}", deck.uuid);
+                pipe.cmd("ZADD")
+                    .arg(&history_key)
+                    .arg(timestamp)
+                    .arg(format!("{}:{:.2}", timestamp, value.market_total));
+                pipe.cmd("ZREMRANGEBYSCORE")
+                    .arg(&history_key)
+                    .arg("-inf")
+                    .arg(format!("({}", history_cutoff));
+            }
         }
 
         let _: () = pipe.query(con)
@@ -1595,25 +3350,26 @@ impl MTGJSONIndexer {
         }
     }
 
-    fn get_price_bucket(price: f64) -> String {
-        match price {
-            p if p < 1.0 => "under_1".to_string(),
-            p if p < 5.0 => "1_to_5".to_string(),
-            p if p < 10.0 => "5_to_10".to_string(),
-            p if p < 25.0 => "10_to_25".to_string(),
-            p if p < 50.0 => "25_to_50".to_string(),
-            p if p < 100.0 => "50_to_100".to_string(),
-            p if p < 500.0 => "100_to_500".to_string(),
-            _ => "over_500".to_string(),
-        }
+    // The price_range bucket for a near-mint market price, or None below --price-floor
+    fn price_range_bucket(&self, price: f64) -> Option<&'static str> {
+        (price >= self.price_floor).then(|| price_bucket(price))
     }
 
     fn add_enhanced_search_indexes(&self, pipe: &mut redis::Pipeline, name: &str, uuid: &str) {
+        for key in self.enhanced_search_index_keys(name) {
+            pipe.cmd("SADD").arg(key).arg(uuid);
+        }
+    }
+
+    // Word, autocomplete prefix, n-gram and metaphone sets a card name's uuid goes into.
+    // Shared by `add_enhanced_search_indexes` and `clear_set_data` so both sides agree.
+    fn enhanced_search_index_keys(&self, name: &str) -> BTreeSet<String> {
         let name_lower = name.to_lowercase();
+        let mut keys = BTreeSet::new();
         
         // Add word-based indexes with improved autocomplete
         for word in self.tokenize_words(&name_lower) {
-            pipe.cmd("SADD").arg(format!("word:{}", word)).arg(uuid);
+            keys.insert(format!("word:{}", word));
             
             // Enhanced autocomplete with word-level and character-level prefixes
             let chars: Vec<char> = word.chars().collect();
@@ -1621,7 +3377,7 @@ impl MTGJSONIndexer {
             
             for i in 1..=prefix_limit {
                 let prefix: String = chars[..i].iter().collect();
-                pipe.cmd("SADD").arg(format!("auto:prefix:{}", prefix)).arg(uuid);
+                keys.insert(format!("auto:prefix:{}", prefix));
             }
         }
 
@@ -1631,301 +3387,134 @@ impl MTGJSONIndexer {
         
         for i in 1..=name_prefix_limit {
             let prefix: String = name_chars[..i].iter().collect();
-            pipe.cmd("SADD").arg(format!("auto:prefix:{}", prefix)).arg(uuid);
+            keys.insert(format!("auto:prefix:{}", prefix));
         }
 
         // Add n-grams for fuzzy matching 
         for ngram in self.generate_ngrams(&name_lower, NGRAM_SIZE) {
-            pipe.cmd("SADD").arg(format!("ngram:{}", ngram)).arg(uuid);
+            keys.insert(format!("ngram:{}", ngram));
         }
 
-        // Add metaphone for phonetic matching
-        let metaphone = self.generate_metaphone(&name_lower);
-        if !metaphone.is_empty() {
-            pipe.cmd("SADD").arg(format!("metaphone:{}", metaphone)).arg(uuid);
+        // Add both Double Metaphone codes for phonetic matching
+        for metaphone in self.generate_metaphones(&name_lower) {
+            keys.insert(format!("metaphone:{}", metaphone));
         }
-    }
-
-    fn build_and_store_search_indexes(&self, con: &mut Connection, indexed_cards: &[IndexedCard]) -> Result<()> {
-        println!("Building comprehensive search indexes for {} cards...", indexed_cards.len());
         
-        let start_time = std::time::Instant::now();
-        let pb = ProgressBar::new(indexed_cards.len() as u64);
-        pb.set_style(ProgressStyle::default_bar()
-            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} cards processed ({eta})")?
-            .progress_chars("#>-"));
+        keys
+    }
 
-        // Process in smaller batches to avoid memory issues with large datasets
-        const SEARCH_BATCH_SIZE: usize = 1000;
-        let mut search_indexes = SearchIndexes::default();
+    fn store_fuzzy_search_script(&self, con: &mut Connection) -> Result<()> {
+        println!("📜 Loading enhanced fuzzy search Lua script...");
         
-        for card_batch in indexed_cards.chunks(SEARCH_BATCH_SIZE) {
-            for card in card_batch {
-                let name_lower = card.name.to_lowercase();
-                
-                // Build n-grams
-                for ngram in self.generate_ngrams(&name_lower, NGRAM_SIZE) {
-                    search_indexes.ngrams.entry(ngram)
-                        .or_insert_with(HashSet::new)
-                        .insert(card.uuid.clone());
-                }
-                
-                // Build metaphones
-                let metaphone = self.generate_metaphone(&name_lower);
-                if !metaphone.is_empty() {
-                    search_indexes.metaphones.entry(metaphone)
-                        .or_insert_with(HashSet::new)
-                        .insert(card.uuid.clone());
-                }
-                
-                // Build word indexes
-                for word in self.tokenize_words(&name_lower) {
-                    search_indexes.words.entry(word)
-                        .or_insert_with(HashSet::new)
-                        .insert(card.uuid.clone());
-                }
-                
-                pb.inc(1);
+        // Unchanged and still cached (no SCRIPT FLUSH since the last run): nothing to load
+        let script_sha = redis::Script::new(FUZZY_SEARCH_SCRIPT).get_hash().to_string();
+        let stored_sha: Option<String> = con.get("mtgjson:script:fuzzy_search")?;
+        if stored_sha.as_deref() == Some(script_sha.as_str()) {
+            let loaded: Vec<i32> = redis::cmd("SCRIPT")
+                .arg("EXISTS")
+                .arg(&script_sha)
+                .query(con)
+                .context("Failed to check fuzzy search script")?;
+            if loaded.first() == Some(&1) {
+                println!("✅ Enhanced fuzzy search script already loaded ({})", script_sha);
+                return Ok(());
             }
         }
-
-        pb.finish_with_message("Search index building complete");
-        
-        // Store the search indexes in Redis
-        self.store_search_indexes(con, search_indexes)?;
         
-        // Store the advanced fuzzy search Lua script
-        self.store_fuzzy_search_script(con)?;
+        let script_sha: String = redis::cmd("SCRIPT")
+            .arg("LOAD")
+            .arg(FUZZY_SEARCH_SCRIPT)
+            .query(con)
+            .context("Failed to load fuzzy search script")?;
         
-        let elapsed = start_time.elapsed();
-        println!("✅ Advanced search indexes built and stored in {:.2}s", elapsed.as_secs_f32());
+        let _: () = con.set("mtgjson:script:fuzzy_search", script_sha)
+            .context("Failed to store script SHA")?;
+            
+        println!("✅ Enhanced fuzzy search script loaded and ready");
         
         Ok(())
     }
 
-    fn store_search_indexes(&self, con: &mut Connection, search_indexes: SearchIndexes) -> Result<()> {
-        println!("Storing search indexes in Redis...");
-        
-        // Store n-grams
-        println!("  📝 Storing {} n-gram indexes...", search_indexes.ngrams.len());
-        let ngram_pb = ProgressBar::new(search_indexes.ngrams.len() as u64);
-        ngram_pb.set_style(ProgressStyle::default_bar()
-            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} n-grams")?
-            .progress_chars("#>-"));
-
-        for (ngram, card_uuids) in search_indexes.ngrams {
-            let uuids_vec: Vec<String> = card_uuids.into_iter().collect();
-            
-            // Store in chunks to avoid Redis memory limits
-            const CHUNK_SIZE: usize = 1000;
-            for chunk in uuids_vec.chunks(CHUNK_SIZE) {
-                let _: () = con.sadd(format!("ngram:{}", ngram), chunk)
-                    .context("Failed to store n-gram index")?;
-            }
-            ngram_pb.inc(1);
+    /// Gzip one card's JSON, timing a sampled round-trip decompression for the stats
+    fn compress_card_json(&self, card_json: &str, totals: &mut CardStorageTotals) -> Result<Vec<u8>> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(card_json.as_bytes())
+            .context("Failed to compress card")?;
+        let compressed = encoder.finish()
+            .context("Failed to compress card")?;
+
+        if totals.cards % DECOMPRESS_SAMPLE_EVERY == 1 {
+            let start = std::time::Instant::now();
+            let mut decompressed = String::with_capacity(card_json.len());
+            GzDecoder::new(compressed.as_slice()).read_to_string(&mut decompressed)
+                .context("Failed to decompress card")?;
+            totals.decompress_time += start.elapsed();
+            totals.decompress_samples += 1;
         }
-        ngram_pb.finish_with_message("N-gram indexes stored");
 
-        // Store metaphones
-        println!("  🔊 Storing {} metaphone indexes...", search_indexes.metaphones.len());
-        let metaphone_pb = ProgressBar::new(search_indexes.metaphones.len() as u64);
-        metaphone_pb.set_style(ProgressStyle::default_bar()
-            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} metaphones")?
-            .progress_chars("#>-"));
+        Ok(compressed)
+    }
 
-        for (metaphone, card_uuids) in search_indexes.metaphones {
-            let uuids_vec: Vec<String> = card_uuids.into_iter().collect();
-            
-            const CHUNK_SIZE: usize = 1000;
-            for chunk in uuids_vec.chunks(CHUNK_SIZE) {
-                let _: () = con.sadd(format!("metaphone:{}", metaphone), chunk)
-                    .context("Failed to store metaphone index")?;
-            }
-            metaphone_pb.inc(1);
-        }
-        metaphone_pb.finish_with_message("Metaphone indexes stored");
+    /// The stored stats and per-set shares a `--sets` run merges into, or None when nothing
+    /// is indexed yet. Fails when the index was stored with other card storage settings, or
+    /// before per-set shares were recorded, since the merged stats would then be wrong.
+    fn previous_index_for_partial_run(&self, con: &mut Connection) -> Result<Option<(IndexStats, BTreeMap<String, SetIndexStats>)>> {
+        let stats_json: Option<String> = con.get("mtgjson:stats")
+            .context("Failed to read index stats")?;
+        let stats: IndexStats = match stats_json {
+            Some(json) => serde_json::from_str(&json).context("Failed to parse stored index stats")?,
+            None => return Ok(None),
+        };
 
-        // Store words
-        println!("  📚 Storing {} word indexes...", search_indexes.words.len());
-        let word_pb = ProgressBar::new(search_indexes.words.len() as u64);
-        word_pb.set_style(ProgressStyle::default_bar()
-            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} words")?
-            .progress_chars("#>-"));
+        let mismatches = self.storage_setting_mismatches(&stats);
+        if !mismatches.is_empty() {
+            anyhow::bail!(
+                "--sets has to store cards the way the existing index does, but these settings differ: {}. \
+                 Rerun with matching settings, or without --sets to rebuild the whole index",
+                mismatches.join(", ")
+            );
+        }
 
-        for (word, card_uuids) in search_indexes.words {
-            let uuids_vec: Vec<String> = card_uuids.into_iter().collect();
-            
-            const CHUNK_SIZE: usize = 1000;
-            for chunk in uuids_vec.chunks(CHUNK_SIZE) {
-                let _: () = con.sadd(format!("word:{}", word), chunk)
-                    .context("Failed to store word index")?;
-            }
-            word_pb.inc(1);
+        let stored: HashMap<String, String> = con.hgetall(SET_STATS_KEY)
+            .context("Failed to read per-set index stats")?;
+        if stored.is_empty() {
+            anyhow::bail!("The existing index has no per-set stats ({}); run once without --sets before using it", SET_STATS_KEY);
         }
-        word_pb.finish_with_message("Word indexes stored");
+        let set_stats = stored.into_iter()
+            .map(|(set_code, json)| Ok((set_code, serde_json::from_str(&json).context("Failed to parse per-set index stats")?)))
+            .collect::<Result<BTreeMap<_, _>>>()?;
 
-        Ok(())
+        Ok(Some((stats, set_stats)))
     }
 
-    fn store_fuzzy_search_script(&self, con: &mut Connection) -> Result<()> {
-        println!("📜 Loading enhanced fuzzy search Lua script...");
-        
-        // Enhanced fuzzy search script optimized for MTGJSON data
-        let fuzzy_search_script = r#"
-        local query = ARGV[1]
-        local max_distance = tonumber(ARGV[2]) or 2
-        local max_results = tonumber(ARGV[3]) or 20
-        
-        local candidates = {}
-        local results = {}
-        
-        -- Function to calculate simple edit distance for small strings
-        local function edit_distance(s1, s2)
-            if #s1 == 0 then return #s2 end
-            if #s2 == 0 then return #s1 end
-            
-            local matrix = {}
-            for i = 0, #s1 do
-                matrix[i] = {[0] = i}
-            end
-            for j = 0, #s2 do
-                matrix[0][j] = j
-            end
-            
-            for i = 1, #s1 do
-                for j = 1, #s2 do
-                    local cost = (s1:sub(i,i) == s2:sub(j,j)) and 0 or 1
-                    matrix[i][j] = math.min(
-                        matrix[i-1][j] + 1,
-                        matrix[i][j-1] + 1,
-                        matrix[i-1][j-1] + cost
-                    )
-                end
-            end
-            
-            return matrix[#s1][#s2]
-        end
-        
-        -- First try exact prefix matches (highest priority)
-        local query_lower = query:lower()
-        local prefix_key = 'auto:prefix:' .. query_lower
-        local prefix_matches = redis.call('SMEMBERS', prefix_key)
-        
-        for _, uuid in ipairs(prefix_matches) do
-            candidates[uuid] = (candidates[uuid] or 0) + 10  -- High score for prefix matches
-            if #results < max_results then
-                table.insert(results, uuid)
-            end
-        end
-        
-        -- If we have enough exact prefix matches, return early
-        if #results >= max_results then
-            return results
-        end
-        
-        -- Try word-based matching for multi-word queries
-        local words = {}
-        for word in query_lower:gmatch('%S+') do
-            if #word >= 2 then
-                table.insert(words, word)
-            end
-        end
-        
-        for _, word in ipairs(words) do
-            local word_key = 'word:' .. word
-            local word_matches = redis.call('SMEMBERS', word_key)
-            
-            for _, uuid in ipairs(word_matches) do
-                candidates[uuid] = (candidates[uuid] or 0) + 5  -- Good score for word matches
-            end
-        end
-        
-        -- Try n-gram fuzzy matching for partial matches
-        if #query_lower >= 3 then
-            local ngram_scores = {}
-            
-            for i = 1, #query_lower - 2 do
-                local ngram = query_lower:sub(i, i + 2)
-                local ngram_key = 'ngram:' .. ngram
-                local ngram_matches = redis.call('SMEMBERS', ngram_key)
-                
-                for _, uuid in ipairs(ngram_matches) do
-                    ngram_scores[uuid] = (ngram_scores[uuid] or 0) + 1
-                end
-            end
-            
-            -- Only add n-gram matches that have sufficient overlap
-            local min_ngram_score = math.max(1, math.floor((#query_lower - 2) * 0.3))
-            for uuid, score in pairs(ngram_scores) do
-                if score >= min_ngram_score then
-                    candidates[uuid] = (candidates[uuid] or 0) + score
-                end
-            end
-        end
-        
-        -- Try metaphone matching for phonetic similarity
-        local function simple_metaphone(text)
-            local result = ""
-            local map = {
-                ['b'] = 'B', ['p'] = 'B', ['f'] = 'B', ['v'] = 'B',
-                ['c'] = 'K', ['k'] = 'K', ['q'] = 'K',
-                ['d'] = 'T', ['t'] = 'T',
-                ['g'] = 'J', ['j'] = 'J',
-                ['l'] = 'L',
-                ['m'] = 'M', ['n'] = 'M',
-                ['r'] = 'R',
-                ['s'] = 'S', ['z'] = 'S',
-                ['x'] = 'KS'
-            }
-            
-            for i = 1, #text do
-                local char = text:sub(i, i):lower()
-                local code = map[char] or ""
-                result = result .. code
-            end
-            
-            return result
-        end
-        
-        local metaphone = simple_metaphone(query_lower)
-        if #metaphone > 0 then
-            local metaphone_key = 'metaphone:' .. metaphone
-            local metaphone_matches = redis.call('SMEMBERS', metaphone_key)
-            
-            for _, uuid in ipairs(metaphone_matches) do
-                candidates[uuid] = (candidates[uuid] or 0) + 3  -- Moderate score for phonetic matches
-            end
-        end
-        
-        -- Convert candidates to sorted array
-        local candidate_array = {}
-        for uuid, score in pairs(candidates) do
-            table.insert(candidate_array, {uuid = uuid, score = score})
-        end
-        
-        -- Sort by score (higher is better)
-        table.sort(candidate_array, function(a, b) return a.score > b.score end)
-        
-        -- Build final results list
-        local final_results = {}
-        for i = 1, math.min(#candidate_array, max_results) do
-            table.insert(final_results, candidate_array[i].uuid)
-        end
-        
-        return final_results
-        "#;
-        
-        let script_sha: String = redis::cmd("SCRIPT")
-            .arg("LOAD")
-            .arg(fuzzy_search_script)
-            .query(con)
-            .context("Failed to load fuzzy search script")?;
-        
-        let _: () = con.set("mtgjson:script:fuzzy_search", script_sha)
-            .context("Failed to store script SHA")?;
-            
-        println!("✅ Enhanced fuzzy search script loaded and ready");
-        
+    // Settings that shape stored card documents and differ between this run and `stored`
+    fn storage_setting_mismatches(&self, stored: &IndexStats) -> Vec<String> {
+        let mut mismatches = Vec::new();
+        if stored.cards_compressed != self.compress_cards {
+            mismatches.push(format!("--compress-cards (index: {}, this run: {})", stored.cards_compressed, self.compress_cards));
+        }
+        if stored.lean_cards != self.lean_cards {
+            mismatches.push(format!("--lean-cards (index: {}, this run: {})", stored.lean_cards, self.lean_cards));
+        }
+        if stored.price_floor != self.price_floor {
+            mismatches.push(format!("--price-floor (index: {}, this run: {})", stored.price_floor, self.price_floor));
+        }
+        if stored.card_schema_version != CARD_SCHEMA_VERSION {
+            mismatches.push(format!("card schema (index: v{}, this build: v{})", stored.card_schema_version, CARD_SCHEMA_VERSION));
+        }
+        mismatches
+    }
+
+    // Replace the per-set shares with the merged ones, in one transaction
+    fn store_set_stats(&self, con: &mut Connection, set_stats: &BTreeMap<String, SetIndexStats>) -> Result<()> {
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        pipe.cmd("DEL").arg(SET_STATS_KEY);
+        for (set_code, stats) in set_stats {
+            pipe.cmd("HSET").arg(SET_STATS_KEY).arg(set_code).arg(serde_json::to_string(stats)?);
+        }
+        let _: () = pipe.query(con)
+            .context("Failed to store per-set index stats")?;
         Ok(())
     }
 
@@ -1944,22 +3533,505 @@ impl MTGJSONIndexer {
 async fn main() -> Result<()> {
     let cli = Cli::parse();
 
-    let indexer = MTGJSONIndexer::new(&cli.redis_host, cli.redis_port, cli.data_dir)?;
+    let indexer = MTGJSONIndexer::new(
+        &cli.redis_host,
+        cli.redis_port,
+        IndexerOptions {
+            data_dir: cli.data_dir,
+            max_deck_file_mb: cli.max_deck_file_mb,
+            progress: ProgressMode::detect(cli.no_progress),
+            compress_cards: cli.compress_cards,
+            lean_cards: cli.lean_cards,
+            price_floor: cli.price_floor,
+            affiliate_tags: AffiliateTags::parse(&cli.affiliate_tags).map_err(|e| anyhow::anyhow!(e))?,
+        },
+    )?;
+
+    let max_age_cards = cli.max_age_cards.unwrap_or(cli.max_age_hours);
+    let max_age_prices = cli.max_age_prices.unwrap_or(cli.max_age_hours);
 
     // Handle status command
     if cli.status {
-        indexer.show_data_status(cli.max_age_hours)?;
+        indexer.show_data_status(max_age_cards, max_age_prices)?;
         return Ok(());
     }
 
+    if cli.check_update {
+        indexer.check_for_update().await?;
+        return Ok(());
+    }
+
+    if cli.force_unlock {
+        match IndexLock::force_unlock(&indexer.redis_client)? {
+            Some(holder) => println!("⚠️  Removed index lock held by {}", holder),
+            None => println!("ℹ️  No index lock to remove"),
+        }
+    }
+
     if !cli.index_only {
-        indexer.download_data_files(cli.force_download, cli.max_age_hours).await?;
+        indexer.download_data_files(cli.force_download, max_age_cards, max_age_prices, cli.download_attempts).await?;
     }
 
-    if !cli.download_only {
-        indexer.index_cards(cli.tcg_csv_path.as_deref(), cli.skip_pricing, cli.auto_download_tcg, &cli.sku_language, &cli.sku_condition)?;
+    if cli.decks_only {
+        indexer.reindex_decks(cli.exclude_basics_from_value)?;
+    } else if !cli.download_only {
+        let set_filter: Option<BTreeSet<String>> = (!cli.sets.is_empty())
+            .then(|| cli.sets.iter().map(|code| code.trim().to_uppercase()).collect());
+        let outlier_filter = cli.filter_price_outliers.then_some(PriceOutlierFilter {
+            max_stddevs: cli.price_outlier_stddevs,
+            max_price: cli.max_price,
+            drop: cli.drop_price_outliers,
+        });
+        let report = indexer.index_cards(IndexOptions {
+            tcg_csv_paths: &cli.tcg_csv_path,
+            tcg_csv_keep_first: cli.tcg_csv_keep_first,
+            skip_pricing: cli.skip_pricing,
+            auto_download_tcg: cli.auto_download_tcg,
+            sku_language: &cli.sku_language,
+            sku_condition: &cli.sku_condition,
+            outlier_filter: outlier_filter.as_ref(),
+            exclude_basics_from_value: cli.exclude_basics_from_value,
+            streaming: cli.streaming,
+            set_filter: set_filter.as_ref(),
+        })?;
+        if let Some(path) = &cli.stats_json {
+            report.write(Path::new(path))?;
+        }
     }
 
     println!("✓ All operations completed successfully!");
     Ok(())
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INDEXED_NAME: &str = "lightning bolt";
+    const MISSPELLED_QUERY: &str = "litening bolt";
+
+    fn redis_url() -> String {
+        std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string())
+    }
+
+    const SAMPLE_JSON: &[u8] = br#"{"meta": {"version": "5.2.2"}}"#;
+
+    // A two-card MTGJSON set (Lightning Helix, Llanowar Elves) under the unused code ZZRT
+    const FIXTURE_SET: &str = include_str!("../tests/fixtures/round_trip_set.json");
+    const FIXTURE_PRODUCT_ID: &str = "999000101";  // Lightning Helix
+    const FIXTURE_SKU_ID: u64 = 999_000_102;
+
+    // An indexer with default settings; building one doesn't connect to Redis
+    fn test_indexer() -> MTGJSONIndexer {
+        let redis_client = Client::open(redis_url()).expect("REDIS_URL is a valid Redis URL");
+        MTGJSONIndexer {
+            sku_pricing: SkuPricingManager::new(redis_client.clone()),
+            redis_client,
+            data_dir: String::new(),
+            max_deck_file_bytes: 0,
+            progress: ProgressMode::Hidden,
+            compress_cards: false,
+            lean_cards: false,
+            price_floor: 0.5,
+            affiliate_tags: AffiliateTags::default(),
+        }
+    }
+
+    // Lightning Helix's near-mint SKU and its price, keyed the way load_tcgplayer_skus and
+    // the pricing CSV loader key them
+    fn fixture_pricing() -> (HashMap<String, Vec<TcgplayerSku>>, HashMap<String, Vec<TcgPrice>>) {
+        let sku = TcgplayerSku {
+            condition: Some("Near Mint".to_string()),
+            language: Some("English".to_string()),
+            printing: Some("Non Foil".to_string()),
+            product_id: FIXTURE_PRODUCT_ID.parse().unwrap(),
+            sku_id: FIXTURE_SKU_ID,
+        };
+        let price = TcgPrice {
+            tcgplayer_id: FIXTURE_PRODUCT_ID.to_string(),
+            product_line: "Magic".to_string(),
+            set_name: "Round Trip Test Set".to_string(),
+            product_name: "Lightning Helix".to_string(),
+            title: String::new(),
+            number: "1".to_string(),
+            rarity: "U".to_string(),
+            condition: "Near Mint".to_string(),
+            tcg_market_price: Some(1.5),
+            tcg_direct_low: None,
+            tcg_low_price_with_shipping: None,
+            tcg_low_price: Some(1.25),
+            total_quantity: Some(10),
+            add_to_quantity: Some(2),
+            tcg_marketplace_price: None,
+            price_confidence: None,
+        };
+        (
+            HashMap::from([(FIXTURE_PRODUCT_ID.to_string(), vec![sku])]),
+            HashMap::from([(FIXTURE_SKU_ID.to_string(), vec![price])]),
+        )
+    }
+
+    // Store the fixture set through process_card and store_cards_batch, as index_cards does
+    fn store_fixture_set(indexer: &MTGJSONIndexer, con: &mut Connection) -> Result<Vec<IndexedCard>> {
+        let set: Set = serde_json::from_str(FIXTURE_SET)?;
+        let (sku_index, pricing_data) = fixture_pricing();
+        let cards: Vec<IndexedCard> = set.cards.iter()
+            .map(|card| indexer.process_card(card, &set.code, &set.name, &set.release_date, &sku_index, &pricing_data))
+            .collect();
+        indexer.store_cards_batch(con, cards.clone(), &pricing_data, &sku_index, &mut CardStorageTotals::default())?;
+        Ok(cards)
+    }
+
+    fn fixture_set_codes() -> BTreeSet<String> {
+        BTreeSet::from(["ZZRT".to_string()])
+    }
+
+    #[test]
+    fn decompress_stream_reads_xz() -> Result<()> {
+        let mut encoder = xz2::write::XzEncoder::new(Vec::new(), 6);
+        encoder.write_all(SAMPLE_JSON)?;
+        let xz = encoder.finish()?;
+        assert_eq!(MTGJSONIndexer::decompress_stream(&xz, "AllPrintings.json.xz")?, SAMPLE_JSON);
+        Ok(())
+    }
+
+    #[test]
+    fn decompress_stream_reads_gzip() -> Result<()> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(SAMPLE_JSON)?;
+        let gzip = encoder.finish()?;
+        assert_eq!(MTGJSONIndexer::decompress_stream(&gzip, "AllPrintings.json.gz")?, SAMPLE_JSON);
+        Ok(())
+    }
+
+    #[test]
+    fn decompress_stream_passes_plain_json_through() -> Result<()> {
+        let plain = [b"\n  ".as_slice(), SAMPLE_JSON].concat();
+        assert_eq!(MTGJSONIndexer::decompress_stream(&plain, "AllPrintings.json.xz")?, plain);
+        Ok(())
+    }
+
+    #[test]
+    fn decompress_stream_passes_uncompressed_tar_through() -> Result<()> {
+        let mut tar = vec![0u8; 1024];
+        tar[..14].copy_from_slice(b"AllDeckFiles/a");
+        tar[257..263].copy_from_slice(b"ustar\0");
+        assert_eq!(MTGJSONIndexer::decompress_stream(&tar, "AllDeckFiles.tar.xz")?, tar);
+        Ok(())
+    }
+
+    #[test]
+    fn decompress_stream_rejects_bz2() {
+        let bz2 = b"BZh91AY&SY\x00\x00";
+        let error = MTGJSONIndexer::decompress_stream(bz2, "AllPrintings.json.bz2").unwrap_err();
+        assert!(error.to_string().contains("not xz, gzip, tar or JSON"));
+    }
+
+    #[test]
+    fn decompress_stream_rejects_zip() {
+        let zip = b"PK\x03\x04\x14\x00\x00\x00";
+        let error = MTGJSONIndexer::decompress_stream(zip, "AllPrintings.json.zip").unwrap_err();
+        assert!(error.to_string().contains("not xz, gzip, tar or JSON"));
+    }
+
+    #[test]
+    fn download_backoff_doubles_up_to_the_cap() {
+        assert_eq!(MTGJSONIndexer::download_backoff_secs(1), 2);
+        assert_eq!(MTGJSONIndexer::download_backoff_secs(2), 4);
+        assert_eq!(MTGJSONIndexer::download_backoff_secs(8), 256);
+        assert_eq!(MTGJSONIndexer::download_backoff_secs(9), DOWNLOAD_RETRY_MAX_SECS);
+        for attempt in [64, 65, 100, u32::MAX] {
+            assert_eq!(MTGJSONIndexer::download_backoff_secs(attempt), DOWNLOAD_RETRY_MAX_SECS);
+        }
+    }
+
+    #[test]
+    fn prices_below_the_floor_stay_out_of_the_price_ranges() {
+        let indexer = MTGJSONIndexer { price_floor: 2.0, ..test_indexer() };
+        assert_eq!(indexer.price_range_bucket(1.99), None);
+        assert_eq!(indexer.price_range_bucket(2.0), Some("1_to_5"));
+        assert_eq!(indexer.price_range_bucket(750.0), Some("over_500"));
+
+        let no_floor = MTGJSONIndexer { price_floor: 0.0, ..test_indexer() };
+        assert_eq!(no_floor.price_range_bucket(0.1), Some("under_1"));
+    }
+
+    #[test]
+    fn partial_run_replaces_only_the_selected_sets_stats() {
+        let stats = |cards| SetIndexStats { cards, cards_with_pricing: cards / 2, ..SetIndexStats::default() };
+        let stored = BTreeMap::from([
+            ("DOM".to_string(), stats(10)),
+            ("WAR".to_string(), stats(6)),
+            ("OLD".to_string(), stats(4)),
+        ]);
+        // OLD was selected but is gone from AllPrintings.json, so its cards were cleared
+        let selected = BTreeSet::from(["WAR".to_string(), "OLD".to_string(), "ELD".to_string()]);
+        let indexed = BTreeMap::from([("WAR".to_string(), stats(8)), ("ELD".to_string(), stats(2))]);
+
+        let merged = SetIndexStats::merge(Some(&stored), Some(&selected), indexed.clone());
+        assert_eq!(merged.keys().collect::<Vec<_>>(), ["DOM", "ELD", "WAR"]);
+        assert_eq!(merged["WAR"], stats(8));
+        let total = SetIndexStats::total(merged.values());
+        assert_eq!((total.cards, total.cards_with_pricing), (20, 10));
+
+        // A full run starts over from its own sets
+        assert_eq!(SetIndexStats::merge(None, None, indexed.clone()), indexed);
+    }
+
+    #[test]
+    fn partial_run_must_match_stored_card_settings() {
+        let indexer = test_indexer();
+        let stored: IndexStats = serde_json::from_value(serde_json::json!({
+            "total_sets": 1, "total_cards": 2, "processed_cards": 2,
+            "cards_compressed": true, "lean_cards": false, "price_floor": 0.5,
+            "last_update": "2026-01-01T00:00:00Z", "source": "mtgjson", "version": "5.2.2",
+            "card_schema_version": CARD_SCHEMA_VERSION,
+        })).unwrap();
+        let mismatches = indexer.storage_setting_mismatches(&stored);
+        assert_eq!(mismatches.len(), 1);
+        assert!(mismatches[0].starts_with("--compress-cards"), "{:?}", mismatches);
+
+        let compressed = MTGJSONIndexer { compress_cards: true, ..test_indexer() };
+        assert!(compressed.storage_setting_mismatches(&stored).is_empty());
+    }
+
+    #[test]
+    fn lean_cards_leave_out_commerce_fields_without_losing_them() -> Result<()> {
+        let indexer = test_indexer();
+        let set: Set = serde_json::from_str(FIXTURE_SET)?;
+        let (sku_index, pricing_data) = fixture_pricing();
+        let mut helix = indexer.process_card(&set.cards[0], &set.code, &set.name, &set.release_date, &sku_index, &pricing_data);
+        assert!(!helix.tcgplayer_skus.is_empty() && helix.purchase_urls.tcgplayer.is_some());
+        
+        let full_json = serde_json::to_string(&helix)?;
+        let lean_json = MTGJSONIndexer::lean_card_json(&mut helix)?;
+        let lean: IndexedCard = serde_json::from_str(&lean_json)?;
+        assert!(lean.tcgplayer_skus.is_empty() && lean.purchase_urls.tcgplayer.is_none());
+        assert_eq!(MTGJSONIndexer::lean_saved_bytes(&helix)?, (full_json.len() - lean_json.len()) as u64);
+        // Put back for the SKU indexes
+        assert_eq!(serde_json::to_string(&helix)?, full_json);
+        Ok(())
+    }
+
+    #[test]
+    #[ignore = "needs Redis Stack with RedisJSON at REDIS_URL"]
+    fn clearing_a_set_leaves_no_index_memberships_behind() -> Result<()> {
+        let indexer = test_indexer();
+        let mut con = indexer.redis_client.get_connection()?;
+        let cards = store_fixture_set(&indexer, &mut con)?;
+        let helix = &cards[0];
+        let stored: bool = con.sismember(format!("mtg:cards:set_cards:{}", helix.set_code), &helix.uuid)?;
+        let bucketed: Option<f64> = con.zscore("price_range:1_to_5", &helix.uuid)?;
+        assert!(stored && bucketed.is_some(), "fixture set was not stored");
+
+        indexer.clear_set_data(&mut con, &fixture_set_codes())?;
+
+        for card in &cards {
+            let membership_keys = MTGJSONIndexer::card_index_keys(card).into_iter()
+                .chain(indexer.enhanced_search_index_keys(&card.name));
+            for key in membership_keys {
+                let member: bool = con.sismember(&key, &card.uuid)?;
+                assert!(!member, "{} still in {}", card.uuid, key);
+            }
+            for key in PRICE_BUCKETS.iter().map(|(bucket, _)| format!("price_range:{}", bucket))
+                .chain(["mtg:cards:variation_count".to_string()]) {
+                let score: Option<f64> = con.zscore(&key, &card.uuid)?;
+                assert!(score.is_none(), "{} still in {}", card.uuid, key);
+            }
+            let document: bool = con.exists(format!("mtg:cards:data:{}", card.uuid))?;
+            assert!(!document, "{} document still stored", card.uuid);
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[ignore = "needs Redis Stack with RedisJSON at REDIS_URL"]
+    fn unique_printings_outlive_clears_while_variants_remain() -> Result<()> {
+        let indexer = test_indexer();
+        let mut con = indexer.redis_client.get_connection()?;
+        let oracle_id = store_fixture_set(&indexer, &mut con)?[0].scryfall_oracle_id.clone()
+            .expect("fixture cards have oracle ids");
+        let printings_key = format!("mtg:cards:oracle_to_printings:{}", oracle_id);
+        let _: () = con.zadd("mtg:cards:unique_printings", &oracle_id, 3.5)?;
+        let _: () = con.sadd(&printings_key, "other-variant")?;
+
+        indexer.clear_set_data(&mut con, &fixture_set_codes())?;
+        let kept: Option<f64> = con.zscore("mtg:cards:unique_printings", &oracle_id)?;
+        let _: () = con.srem(&printings_key, "other-variant")?;
+
+        store_fixture_set(&indexer, &mut con)?;
+        indexer.clear_set_data(&mut con, &fixture_set_codes())?;
+        let dropped: Option<f64> = con.zscore("mtg:cards:unique_printings", &oracle_id)?;
+
+        assert_eq!(kept, Some(3.5), "entry removed while a variant was still stored");
+        assert_eq!(dropped, None, "entry kept after its last printing was cleared");
+        Ok(())
+    }
+
+    #[test]
+    #[ignore = "needs Redis Stack with RedisJSON at REDIS_URL"]
+    fn gold_cards_are_indexed_by_their_color_count() -> Result<()> {
+        let indexer = test_indexer();
+        let mut con = indexer.redis_client.get_connection()?;
+        let cards = store_fixture_set(&indexer, &mut con)?;
+        let (helix, elves) = (&cards[0], &cards[1]);
+        let helix_in_two: bool = con.sismember("mtg:cards:color_count:2", &helix.uuid)?;
+        let helix_in_one: bool = con.sismember("mtg:cards:color_count:1", &helix.uuid)?;
+        let elves_in_one: bool = con.sismember("mtg:cards:color_count:1", &elves.uuid)?;
+
+        indexer.clear_set_data(&mut con, &fixture_set_codes())?;
+
+        assert_eq!(helix.color_count, 2);
+        assert!(helix_in_two && !helix_in_one, "Lightning Helix should only be in color_count:2");
+        assert!(elves_in_one, "Llanowar Elves should be in color_count:1");
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[ignore = "needs Redis Stack with RedisJSON at REDIS_URL"]
+    async fn stored_cards_read_back_through_the_api_client() -> Result<()> {
+        let indexer = test_indexer();
+        let mut con = indexer.redis_client.get_connection()?;
+        let cards = store_fixture_set(&indexer, &mut con)?;
+        let helix = &cards[0];
+        let oracle_id = helix.scryfall_oracle_id.clone().expect("fixture cards have oracle ids");
+
+        let mut api = redis_client::MTGRedisClient::new(&redis_url()).await?;
+        let stored = api.get_card_by_uuid(&helix.uuid).await;
+        let by_oracle = api.get_card_by_oracle_id(&oracle_id).await;
+        let skus = api.get_card_skus(&helix.uuid).await;
+        let sku_card = api.get_card_by_sku_id(&FIXTURE_SKU_ID.to_string()).await;
+
+        indexer.clear_set_data(&mut con, &fixture_set_codes())?;
+        redis::cmd("DEL")
+            .arg(format!("mtg:tcg:product_skus:{}", FIXTURE_PRODUCT_ID))
+            .arg(format!("mtg:tcg:sku_meta:{}", FIXTURE_SKU_ID))
+            .arg(format!("mtg:tcg:sku_price:{}", FIXTURE_SKU_ID))
+            .arg(format!("mtg:tcg:price_history:{}", FIXTURE_SKU_ID))
+            .query::<()>(&mut con)?;
+
+        let stored = stored?.expect("card document was not stored");
+        assert_eq!(stored.name, "Lightning Helix");
+        assert_eq!(stored.colors, ["R", "W"]);
+        assert_eq!(stored.tcgplayer_skus.iter().map(|sku| sku.sku_id).collect::<Vec<_>>(), [FIXTURE_SKU_ID]);
+        assert_eq!(by_oracle?.map(|card| card.uuid).as_deref(), Some(helix.uuid.as_str()));
+        assert!(skus?.contains(&FIXTURE_SKU_ID.to_string()));
+        assert_eq!(sku_card?.as_deref(), Some(helix.uuid.as_str()));
+        Ok(())
+    }
+
+    #[test]
+    #[ignore = "needs Redis Stack with RedisJSON at REDIS_URL"]
+    fn restored_tokens_drop_creators_no_longer_listed() -> Result<()> {
+        let indexer = test_indexer();
+        let mut con = indexer.redis_client.get_connection()?;
+        let token = |creators: &[&str]| CardToken {
+            uuid: "zzrt-token-elf-warrior".to_string(),
+            name: "Elf Warrior".to_string(),
+            face_name: None,
+            number: "1".to_string(),
+            set_code: "TZZRT".to_string(),
+            colors: vec!["G".to_string()],
+            types: vec!["Creature".to_string()],
+            subtypes: vec!["Elf".to_string(), "Warrior".to_string()],
+            supertypes: vec!["Token".to_string()],
+            power: Some("1".to_string()),
+            toughness: Some("1".to_string()),
+            text: None,
+            related_cards: Some(RelatedCards {
+                reverse_related: Some(creators.iter().map(|name| name.to_string()).collect()),
+                spellbook: None,
+            }),
+        };
+        let created_by = |name: &str| format!("mtg:tokens:created_by:{}", name_index_key(name));
+        
+        indexer.store_set_tokens(&mut con, &[token(&["Llanowar Elves", "Lightning Helix"])])?;
+        indexer.store_set_tokens(&mut con, &[token(&["Lightning Helix"])])?;
+        let by_elves: bool = con.sismember(created_by("Llanowar Elves"), "zzrt-token-elf-warrior")?;
+        let by_helix: bool = con.sismember(created_by("Lightning Helix"), "zzrt-token-elf-warrior")?;
+        
+        redis::cmd("DEL")
+            .arg(created_by("Llanowar Elves"))
+            .arg(created_by("Lightning Helix"))
+            .arg("mtg:tokens:creators:zzrt-token-elf-warrior")
+            .arg("mtg:tokens:data:zzrt-token-elf-warrior")
+            .query::<()>(&mut con)?;
+        
+        assert!(!by_elves, "token still listed for a creator it no longer names");
+        assert!(by_helix);
+        Ok(())
+    }
+
+    // A commander deck built from the fixture cards: Lightning Helix and Llanowar Elves
+    fn fixture_deck(commander: &[&str], display_commander: &[&str], main_board: &[&str]) -> DeckData {
+        let set: Set = serde_json::from_str(FIXTURE_SET).unwrap();
+        let cards = |names: &[&str]| -> Vec<CardSet> {
+            names.iter()
+                .map(|name| set.cards.iter().find(|card| card.name == *name).unwrap().clone())
+                .collect()
+        };
+        DeckData {
+            name: "Fixture Deck".to_string(),
+            code: "ZZRT".to_string(),
+            deck_type: Some("Commander Deck".to_string()),
+            release_date: None,
+            commander: cards(commander),
+            display_commander: cards(display_commander),
+            main_board: cards(main_board),
+            side_board: Vec::new(),
+            planes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn partner_decks_use_every_commander() {
+        // The box shows one partner; the other is only in `commander`
+        let deck = test_indexer().index_deck(fixture_deck(
+            &["Lightning Helix", "Llanowar Elves"],
+            &["Lightning Helix"],
+            &["Llanowar Elves"],
+        ));
+        assert!(deck.validation_issues.is_empty(), "{:?}", deck.validation_issues);
+        assert_eq!(deck.commander_pair, Some(("Lightning Helix".to_string(), "Llanowar Elves".to_string())));
+        assert_eq!(deck.commander_color_identity, ["W", "R", "G"]);
+        assert_eq!(deck.commanders.len(), 2);
+    }
+
+    #[test]
+    fn off_identity_decks_are_kept_with_the_problem_recorded() {
+        let deck = test_indexer().index_deck(fixture_deck(&[], &["Llanowar Elves"], &["Lightning Helix"]));
+        assert_eq!(deck.commanders.len(), 1);
+        assert_eq!(deck.validation_issues.len(), 1);
+        assert!(deck.validation_issues[0].contains("Lightning Helix"), "{:?}", deck.validation_issues);
+    }
+
+    #[test]
+    fn misspelled_query_encodes_to_the_indexed_metaphone_key() {
+        let (indexed, _) = double_metaphone(INDEXED_NAME);
+        let (query, _) = double_metaphone(MISSPELLED_QUERY);
+        assert!(!indexed.is_empty());
+        assert_eq!(query, indexed);
+    }
+
+    #[test]
+    #[ignore = "needs Redis at REDIS_URL"]
+    fn fuzzy_script_finds_card_by_query_metaphone_codes() -> Result<()> {
+        let uuid = "fuzzy-script-test-card";
+        let (primary, _) = double_metaphone(INDEXED_NAME);
+        let mut con = redis::Client::open(redis_url())?.get_connection()?;
+        let _: () = con.sadd(format!("metaphone:{}", primary), uuid)?;
+
+        // Called the way search clients call it: query, max distance, max results, codes
+        let (query_primary, query_alternate) = double_metaphone(MISSPELLED_QUERY);
+        let found: Vec<String> = redis::Script::new(FUZZY_SEARCH_SCRIPT)
+            .arg(MISSPELLED_QUERY)
+            .arg(2)
+            .arg(20)
+            .arg(&query_primary)
+            .arg(&query_alternate)
+            .invoke(&mut con)?;
+
+        let _: () = con.srem(format!("metaphone:{}", primary), uuid)?;
+        assert!(found.iter().any(|found| found == uuid));
+        Ok(())
+    }
+}
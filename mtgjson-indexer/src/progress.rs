@@ -0,0 +1,76 @@
+use indicatif::{ProgressBar, ProgressDrawTarget};
+use std::io::IsTerminal;
+use std::time::Duration;
+
+const PROGRESS_LOG_INTERVAL: Duration = Duration::from_secs(10); // Plain-text update cadence off-TTY
+
+/// How long-running steps report progress.
+///
+/// Live bars redraw with carriage returns, which turns log files and CI output
+/// into noise, so they are only used when stderr is a terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressMode {
+    /// Interactive `indicatif` bars on stderr
+    Live,
+    /// Hidden bars plus a periodic plain-text line on stdout
+    Log,
+    /// No progress output at all (`--no-progress`)
+    Hidden,
+}
+
+impl ProgressMode {
+    pub fn detect(no_progress: bool) -> Self {
+        if no_progress {
+            ProgressMode::Hidden
+        } else if std::io::stderr().is_terminal() {
+            ProgressMode::Live
+        } else {
+            ProgressMode::Log
+        }
+    }
+
+    /// Progress bar for `len` items; `label` prefixes the plain-text lines in log mode.
+    pub fn bar(&self, len: u64, label: &str) -> ProgressBar {
+        let pb = match self {
+            ProgressMode::Live => ProgressBar::new(len),
+            _ => ProgressBar::with_draw_target(Some(len), ProgressDrawTarget::hidden()),
+        };
+        self.attach_logger(&pb, label);
+        pb
+    }
+
+    /// Spinner for work of unknown length; log mode reports its current message.
+    pub fn spinner(&self, label: &str) -> ProgressBar {
+        let pb = match self {
+            ProgressMode::Live => ProgressBar::new_spinner(),
+            _ => ProgressBar::with_draw_target(None, ProgressDrawTarget::hidden()),
+        };
+        self.attach_logger(&pb, label);
+        pb
+    }
+
+    fn attach_logger(&self, pb: &ProgressBar, label: &str) {
+        if *self != ProgressMode::Log {
+            return;
+        }
+
+        // Hold a weak handle so a dropped bar never keeps the thread alive
+        let weak = pb.downgrade();
+        let label = label.to_string();
+        std::thread::spawn(move || loop {
+            std::thread::sleep(PROGRESS_LOG_INTERVAL);
+            let Some(pb) = weak.upgrade() else { break };
+            if pb.is_finished() {
+                break;
+            }
+
+            match pb.length() {
+                Some(len) if len > 0 => {
+                    let pct = pb.position() as f64 / len as f64 * 100.0;
+                    println!("{}: {}/{} ({:.1}%)", label, pb.position(), len, pct);
+                }
+                _ => println!("{}: {}", label, pb.message()),
+            }
+        });
+    }
+}
@@ -1,146 +1,283 @@
-use redis::{Client, AsyncCommands, Script};
+use redis::{Client, AsyncCommands, AsyncConnectionConfig, Script};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use anyhow::{Result, Context};
 use chrono::{DateTime, Utc};
 use std::env;
+use std::io::Read;
+use std::num::NonZeroUsize;
 use std::path::Path;
+use std::time::{Duration, Instant};
+use redis::aio::MultiplexedConnection;
+use lru::LruCache;
 use tokio::fs;
+use flate2::read::GzDecoder;
+use tracing::{info, warn};
+
+use crate::types::{color_identity_key, commander_pair_key, legacy_card_schema_version, name_index_key, price_buckets_from, price_confidence, rules_text_snippet, score_deck_power, LegalityChange, PowerLevelLists, CARD_SCHEMA_VERSION, MIN_READABLE_CARD_SCHEMA_VERSION, SET_STATS_KEY};
+
+// Stored document types are shared with the indexer; re-exported so API code can
+// keep importing them from here
+pub use crate::types::{
+    DeckCardInfo, DeckValue, IndexStats, IndexedCard, IndexedDeck, PowerEstimate, SetInfo, TcgPrice,
+    TokenSummary, VendorPrice,
+};
+
+/// A stored card document whose layout this build can't read. The index was written
+/// by an incompatible indexer version and has to be rebuilt.
+#[derive(Debug)]
+pub struct ReindexRequired {
+    pub stored_version: u32,
+}
 
-// API-specific type definitions
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct IndexedCard {
-    pub uuid: String,
+impl std::fmt::Display for ReindexRequired {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Reindex required: stored cards use schema version {}, this build reads versions {} to {}",
+            self.stored_version, MIN_READABLE_CARD_SCHEMA_VERSION, CARD_SCHEMA_VERSION
+        )
+    }
+}
+
+impl std::error::Error for ReindexRequired {}
+
+/// The RediSearch card index `mtg:cards:idx` (or the RediSearch module itself) is
+/// missing, so a search that has no fallback can't run until it is created.
+#[derive(Debug)]
+pub struct SearchIndexMissing;
+
+impl std::fmt::Display for SearchIndexMissing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RediSearch index mtg:cards:idx not found; run create_search_indexes to create it")
+    }
+}
+
+impl std::error::Error for SearchIndexMissing {}
+
+/// A numeric search filter whose value isn't a number, e.g. `mana_value_max=abc`
+#[derive(Debug)]
+pub struct InvalidSearchFilter {
+    pub filter: String,
+    pub value: String,
+}
+
+impl std::fmt::Display for InvalidSearchFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid {} '{}', expected a number", self.filter, self.value)
+    }
+}
+
+impl std::error::Error for InvalidSearchFilter {}
+
+/// One distinct card in a deck's proxy sheet. `image_url` is None when the card has
+/// no Scryfall id to build an image link from.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProxyCard {
     pub name: String,
-    pub set_code: String,
-    pub set_name: String,
-    pub collector_number: String,
-    pub rarity: String,
-    pub mana_value: f32,
+    pub image_url: Option<String>,
+    pub count: u32,
+    pub is_basic: bool,
+}
+
+/// A card plus the other faces of a DFC / split / adventure card, in side order
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CardDetail {
+    #[serde(flatten)]
+    pub card: IndexedCard,
+    pub other_faces: Vec<IndexedCard>,
+}
+
+/// A related card name resolved through the name index. Names with no indexed
+/// card (e.g. most tokens) keep only `name`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RelatedCardSummary {
+    pub name: String,
+    pub uuid: Option<String>,
+    pub set_code: Option<String>,
     pub mana_cost: Option<String>,
-    pub colors: Vec<String>,
-    pub color_identity: Vec<String>,
     pub types: Vec<String>,
-    pub subtypes: Vec<String>,
-    pub supertypes: Vec<String>,
-    pub power: Option<String>,
-    pub toughness: Option<String>,
-    pub loyalty: Option<String>,
-    pub defense: Option<String>,
-    pub text: Option<String>,
-    pub flavor_text: Option<String>,
-    pub layout: String,
-    pub availability: Vec<String>,
-    pub finishes: Vec<String>,
-    pub has_foil: bool,
-    pub has_non_foil: bool,
-    pub is_reserved: bool,
-    pub is_promo: bool,
-    pub release_date: String,
-    pub scryfall_oracle_id: Option<String>,
-    pub scryfall_id: Option<String>,
-    pub tcgplayer_product_id: Option<String>,
-    pub tcgplayer_skus: Vec<TcgplayerSku>,
-    pub purchase_urls: PurchaseUrls,
+    pub rarity: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Ruling {
+    pub date: String,
+    pub text: String,
 }
 
+/// Name and printing of a card listed in a data-quality report
 #[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct IndexedDeck {
+pub struct CardSummary {
     pub uuid: String,
     pub name: String,
-    pub code: String,
-    pub deck_type: String,
-    pub release_date: String,
-    pub is_commander: bool,
-    pub total_cards: u32,
-    pub unique_cards: u32,
-    pub commanders: Vec<DeckCardInfo>,
-    pub main_board: Vec<DeckCardInfo>,
-    pub side_board: Vec<DeckCardInfo>,
-    pub estimated_value: Option<DeckValue>,
+    pub set_code: String,
 }
 
+/// One printing in a `NameMapping`
 #[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct DeckCardInfo {
+pub struct PrintingSummary {
     pub uuid: String,
     pub name: String,
-    pub count: u32,
-    pub is_foil: bool,
+    pub face_name: Option<String>,
+    pub side: Option<String>,
     pub set_code: String,
-    pub tcgplayer_product_id: Option<String>,
+    pub collector_number: String,
+    pub rarity: String,
 }
 
+/// Every oracle id a normalized card name resolves to, with the printings of each
 #[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct DeckValue {
-    pub market_total: f64,
-    pub direct_total: f64,
-    pub low_total: f64,
-    pub cards_with_pricing: u32,
-    pub cards_without_pricing: u32,
+pub struct NameMapping {
+    pub name: String,
+    pub oracle_ids: Vec<String>,
+    pub printings_by_oracle: HashMap<String, Vec<PrintingSummary>>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct TcgplayerSku {
-    #[serde(default)]
-    pub condition: Option<String>,
-    #[serde(default)]
-    pub language: Option<String>,
-    #[serde(default)]
-    pub printing: Option<String>,
-    pub product_id: u64,
-    pub sku_id: u64,
+/// How many indexed cards lack one kind of data, with a sample of them
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MissingDataReport {
+    pub data_type: String,
+    pub total_cards: usize,
+    pub missing_count: usize,
+    pub sample: Vec<CardSummary>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct PurchaseUrls {
-    #[serde(default)]
-    pub card_kingdom: Option<String>,
-    #[serde(default)]
-    pub card_kingdom_etched: Option<String>,
-    #[serde(default)]
-    pub card_kingdom_foil: Option<String>,
-    #[serde(default)]
-    pub cardmarket: Option<String>,
-    #[serde(default)]
-    pub tcgplayer: Option<String>,
-    #[serde(default)]
-    pub tcgplayer_etched: Option<String>,
-}
+/// Data types accepted by `get_missing_data`
+pub const MISSING_DATA_TYPES: &[&str] = &["no_price", "no_image", "no_oracle_id", "no_tcgplayer_id"];
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct SetInfo {
-    pub code: String,
-    pub name: String,
-    pub release_date: String,
-    pub set_type: String,
-    pub total_cards: usize,
-    pub base_set_size: u32,
+/// Orders accepted by `get_cards_by_mana_value`
+pub const MANA_VALUE_SORTS: &[&str] = &["price", "edhrec", "name"];
+
+/// One market price observation from `mtg:tcg:price_history:{sku}`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PricePoint {
+    pub price: f64,
+    pub timestamp: i64,
 }
 
+/// Price history of the SKU that represents a card in one finish and condition.
+/// `sku_id` is None (and `history` empty) when the card has no such SKU.
 #[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct TcgPrice {
-    pub tcgplayer_id: String,
-    pub product_line: String,
-    pub set_name: String,
-    pub product_name: String,
-    pub title: String,
-    pub number: String,
-    pub rarity: String,
+pub struct CardPriceHistory {
+    pub uuid: String,
+    pub finish: String,
     pub condition: String,
-    pub tcg_market_price: Option<f64>,
-    pub tcg_direct_low: Option<f64>,
-    pub tcg_low_price_with_shipping: Option<f64>,
-    pub tcg_low_price: Option<f64>,
-    pub total_quantity: Option<i32>,
-    pub add_to_quantity: Option<i32>,
-    pub tcg_marketplace_price: Option<f64>,
+    pub sku_id: Option<String>,
+    pub history: Vec<PricePoint>,
 }
 
+/// A card's latest prices at each marketplace from MTGJSON AllPrices. A vendor is None
+/// when it has no price for the card.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VendorPrices {
+    pub uuid: String,
+    pub tcgplayer: Option<VendorPrice>,
+    pub cardmarket: Option<VendorPrice>,
+    pub cardkingdom: Option<VendorPrice>,
+}
+
+/// Counters of the in-process card cache, reported by `/metrics`
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CardCacheStats {
+    pub enabled: bool,
+    pub capacity: usize,
+    pub entries: usize,
+    pub ttl_secs: u64,
+    pub hits: u64,
+    pub misses: u64,
+    pub hit_rate: f64,
+}
+
+// A printing's grouping and sort fields, projected from its card document
+struct PrintingSortFields {
+    uuid: String,
+    name: String,
+    is_promo: bool,
+    release_date: String,
+    set_code: String,
+    edhrec_rank: Option<u32>,
+}
+
+// LRU of recently read cards, keyed by "uuid:{uuid}" or "oracle:{oracle_id}". Only
+// found cards are stored, and entries older than the TTL count as misses.
+struct CardCache {
+    entries: LruCache<String, (Instant, IndexedCard)>,
+    ttl: Duration,
+    hits: u64,
+    misses: u64,
+}
+
+impl CardCache {
+    fn get(&mut self, key: &str) -> Option<IndexedCard> {
+        if let Some((stored_at, card)) = self.entries.get(key) {
+            if stored_at.elapsed() < self.ttl {
+                self.hits += 1;
+                return Some(card.clone());
+            }
+        }
+        self.entries.pop(key);
+        self.misses += 1;
+        None
+    }
+
+    fn put(&mut self, key: String, card: &IndexedCard) {
+        self.entries.put(key, (Instant::now(), card.clone()));
+    }
+}
+
+const DEFAULT_COMMAND_TIMEOUT_MS: u64 = 5000;
+const DEFAULT_ANALYTICS_TIMEOUT_MS: u64 = 60000;
+const DEFAULT_CARD_CACHE_TTL_SECS: u64 = 60;  // Card documents only change on reindex
+const FUZZY_MIN_WORD_LENGTH: usize = 4;     // Shorter words match exactly; %ab% matches almost anything
+const FUZZY_CANDIDATE_LIMIT: usize = 1000;  // Name keys scanned by the non-RediSearch fallback
+const FALLBACK_SCAN_COUNT: usize = 1000;    // SCAN batch size for name-key searches without RediSearch
+const SIMILAR_DECK_SIGNATURE_CARDS: usize = 15;  // Rarest cards of a deck used to find candidates
+const SIMILAR_DECK_CANDIDATES: usize = 200;      // Candidates scored after ranking by shared signature cards
+const RULES_TEXT_SNIPPET_RADIUS: usize = 60;     // Context kept on each side of the first rules-text match
+const MISSING_DATA_BATCH_SIZE: usize = 1000;     // Keys per JSON.MGET / pipeline in missing-data scans
+const CARD_FETCH_BATCH_SIZE: usize = 1000;       // Card documents per JSON.MGET when loading a whole index set
+const REDISEARCH_RECHECK_SECS: u64 = 30;         // How long a missing mtg:cards:idx is remembered before FT.INFO runs again
+
+// Layouts whose "Front // Back" names Cockatrice's card database knows by the front face
+const FRONT_FACE_NAME_LAYOUTS: &[&str] = &["transform", "modal_dfc", "flip", "adventure"];
+
+// Lua scripts that scan large parts of the keyspace and get the longer analytics timeout
+const LONG_RUNNING_SCRIPTS: &[&str] = &[
+    "card_stats",
+    "set_analysis",
+    "find_missing_data",
+    "find_duplicates",
+    "cleanup_indexes",
+    "create_redis_indexes",
+    "export_tcg_csv",
+    "sealed_arbitrage",
+    "unique_printings",
+];
+
 pub struct MTGRedisClient {
     client: Client,
     lua_scripts: HashMap<String, Script>,
+    command_timeout: Duration,
+    analytics_timeout: Duration,
+    redisearch: Option<(bool, Option<Instant>)>, // Last availability check and, for a missing index, when to recheck it
+    card_cache: Option<CardCache>, // Enabled by a non-zero CARD_CACHE_SIZE
+}
+
+// Escape text for an XML attribute or element body
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
 }
 
 impl MTGRedisClient {
@@ -154,9 +291,104 @@ impl MTGRedisClient {
         Ok(Self {
             client,
             lua_scripts,
+            command_timeout: Self::timeout_from_env("REDIS_TIMEOUT_MS", DEFAULT_COMMAND_TIMEOUT_MS),
+            analytics_timeout: Self::timeout_from_env("REDIS_ANALYTICS_TIMEOUT_MS", DEFAULT_ANALYTICS_TIMEOUT_MS),
+            redisearch: None,
+            card_cache: Self::card_cache_from_env(),
         })
     }
 
+    /// A client on the same Redis server, Lua scripts and timeouts, without a card cache.
+    /// Lookups on it don't need the shared client, so several can run at once.
+    pub fn detached(&self) -> Self {
+        Self {
+            client: self.client.clone(),
+            lua_scripts: self.lua_scripts.clone(),
+            command_timeout: self.command_timeout,
+            analytics_timeout: self.analytics_timeout,
+            redisearch: self.redisearch,
+            card_cache: None,
+        }
+    }
+
+    // CARD_CACHE_SIZE entries (unset or 0 disables the cache), each kept for
+    // CARD_CACHE_TTL_SECS
+    fn card_cache_from_env() -> Option<CardCache> {
+        let capacity = env::var("CARD_CACHE_SIZE")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .and_then(NonZeroUsize::new)?;
+        let ttl_secs = env::var("CARD_CACHE_TTL_SECS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_CARD_CACHE_TTL_SECS);
+        Some(CardCache {
+            entries: LruCache::new(capacity),
+            ttl: Duration::from_secs(ttl_secs),
+            hits: 0,
+            misses: 0,
+        })
+    }
+
+    pub fn card_cache_stats(&self) -> CardCacheStats {
+        match &self.card_cache {
+            Some(cache) => {
+                let lookups = cache.hits + cache.misses;
+                CardCacheStats {
+                    enabled: true,
+                    capacity: cache.entries.cap().get(),
+                    entries: cache.entries.len(),
+                    ttl_secs: cache.ttl.as_secs(),
+                    hits: cache.hits,
+                    misses: cache.misses,
+                    hit_rate: if lookups > 0 { cache.hits as f64 / lookups as f64 } else { 0.0 },
+                }
+            }
+            None => CardCacheStats::default(),
+        }
+    }
+
+    fn cached_card(&mut self, key: &str) -> Option<IndexedCard> {
+        self.card_cache.as_mut().and_then(|cache| cache.get(key))
+    }
+
+    fn cache_card(&mut self, key: String, card: &Option<IndexedCard>) {
+        if let (Some(cache), Some(card)) = (self.card_cache.as_mut(), card) {
+            cache.put(key, card);
+        }
+    }
+
+    fn timeout_from_env(var: &str, default_ms: u64) -> Duration {
+        let ms = env::var(var)
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(default_ms);
+        Duration::from_millis(ms)
+    }
+
+    // Multiplexed connection whose commands fail with a timeout error instead of hanging
+    async fn connection(&self) -> Result<MultiplexedConnection> {
+        self.connection_with_timeout(self.command_timeout).await
+    }
+
+    async fn connection_with_timeout(&self, timeout: Duration) -> Result<MultiplexedConnection> {
+        let config = AsyncConnectionConfig::new()
+            .set_response_timeout(timeout)
+            .set_connection_timeout(self.command_timeout);
+        let con = self.client
+            .get_multiplexed_async_connection_with_config(&config)
+            .await?;
+        Ok(con)
+    }
+
+    fn script_timeout(&self, script_name: &str) -> Duration {
+        if LONG_RUNNING_SCRIPTS.contains(&script_name) {
+            self.analytics_timeout
+        } else {
+            self.command_timeout
+        }
+    }
+
     async fn load_lua_scripts() -> Result<HashMap<String, Script>> {
         let mut scripts = HashMap::new();
         
@@ -178,7 +410,6 @@ impl MTGRedisClient {
             ("search_cards", "search_cards.lua"),
             ("deck_search", "deck_search.lua"),
             ("card_stats", "card_stats.lua"),
-            ("find_expensive_cards", "find_expensive_cards.lua"),
             ("price_comparison", "price_comparison.lua"),
             ("pricing_trends", "pricing_trends.lua"),
             ("sku_price_analysis", "sku_price_analysis.lua"),
@@ -222,7 +453,7 @@ impl MTGRedisClient {
         let script = self.lua_scripts.get(script_name)
             .ok_or_else(|| anyhow::anyhow!("Lua script '{}' not loaded", script_name))?;
 
-        let mut con = self.client.get_multiplexed_async_connection().await?;
+        let mut con = self.connection_with_timeout(self.script_timeout(script_name)).await?;
         
         // Convert args to the format expected by Redis
         let mut cmd = script.prepare_invoke();
@@ -238,160 +469,1225 @@ impl MTGRedisClient {
         let script = self.lua_scripts.get(script_name)
             .ok_or_else(|| anyhow::anyhow!("Lua script '{}' not loaded", script_name))?;
 
-        let mut con = self.client.get_multiplexed_async_connection().await?;
+        let mut con = self.connection_with_timeout(self.script_timeout(script_name)).await?;
         
         // Convert args to the format expected by Redis
         let mut cmd = script.prepare_invoke();
         for arg in args {
             cmd.arg(arg);
         }
-        
-        let result: redis::Value = cmd.invoke_async(&mut con).await?;
-        Ok(result)
+        
+        let result: redis::Value = cmd.invoke_async(&mut con).await?;
+        Ok(result)
+    }
+
+    pub async fn from_env() -> Result<Self> {
+        let redis_url = env::var("REDIS_URL")
+            .unwrap_or_else(|_| "redis://127.0.0.1:9999".to_string());
+        Self::new(&redis_url).await
+    }
+
+    // =============================================================================
+    // CARD OPERATIONS
+    // =============================================================================
+
+    /// Card document by printing uuid, served from the card cache when it is enabled
+    pub async fn get_card_by_uuid(&mut self, uuid: &str) -> Result<Option<IndexedCard>> {
+        let cache_key = format!("uuid:{}", uuid);
+        if let Some(card) = self.cached_card(&cache_key) {
+            return Ok(Some(card));
+        }
+        let card = self.fetch_card_by_uuid(uuid).await?;
+        self.cache_card(cache_key, &card);
+        Ok(card)
+    }
+
+    async fn fetch_card_by_uuid(&mut self, uuid: &str) -> Result<Option<IndexedCard>> {
+        let mut con = self.connection().await?;
+        let key = format!("mtg:cards:data:{}", uuid);
+        
+        // Use JSON.GET to retrieve the RediSearch JSON document
+        let data: Option<String> = redis::cmd("JSON.GET")
+            .arg(&key)
+            .arg("$")
+            .query_async(&mut con)
+            .await
+            .unwrap_or(None);
+        
+        match data {
+            Some(json_str) => {
+                // JSON.GET returns a JSON array, extract the first element
+                let parsed: Vec<serde_json::Value> = serde_json::from_str(&json_str)?;
+                if let Some(card_data) = parsed.into_iter().next() {
+                    let card = Self::decode_card(card_data)?;
+                    Ok(self.expand_compressed_cards(vec![card]).await?.pop())
+                } else {
+                    Ok(None)
+                }
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// `get_card_by_uuid` plus the card's other faces from `mtg:cards:other_faces:{uuid}`
+    pub async fn get_card_detail(&mut self, uuid: &str) -> Result<Option<CardDetail>> {
+        let card = match self.get_card_by_uuid(uuid).await? {
+            Some(card) => card,
+            None => return Ok(None),
+        };
+
+        let mut con = self.connection().await?;
+        let face_ids: Vec<String> = con.smembers(format!("mtg:cards:other_faces:{}", uuid)).await?;
+        let mut other_faces = self.get_cards_by_uuids(&face_ids).await?;
+        other_faces.sort_by(|a, b| a.side.cmp(&b.side));
+
+        Ok(Some(CardDetail { card, other_faces }))
+    }
+
+    /// With `--compress-cards` the JSON documents only hold searchable fields; swap in
+    /// the full cards from the gzip blobs at `mtg:cards:gz:{uuid}`. No-op otherwise.
+    async fn expand_compressed_cards(&mut self, cards: Vec<IndexedCard>) -> Result<Vec<IndexedCard>> {
+        if cards.is_empty() {
+            return Ok(cards);
+        }
+
+        let mut con = self.connection().await?;
+        let compressed: Option<String> = con.get("mtgjson:cards_compressed").await?;
+        if compressed.as_deref() != Some("true") {
+            return Ok(cards);
+        }
+
+        let keys: Vec<String> = cards.iter().map(|card| format!("mtg:cards:gz:{}", card.uuid)).collect();
+        let blobs: Vec<Option<Vec<u8>>> = redis::cmd("MGET").arg(&keys).query_async(&mut con).await?;
+
+        let mut expanded = Vec::with_capacity(cards.len());
+        for (card, blob) in cards.into_iter().zip(blobs) {
+            match blob {
+                Some(blob) => {
+                    let mut card_json = String::new();
+                    GzDecoder::new(blob.as_slice()).read_to_string(&mut card_json)
+                        .context("Failed to decompress card")?;
+                    expanded.push(Self::decode_card(serde_json::from_str(&card_json)?)?);
+                }
+                // Keep the slim document rather than dropping the card
+                None => expanded.push(card),
+            }
+        }
+
+        Ok(expanded)
+    }
+
+    /// Batch counterpart to `get_card_by_uuid`. Reads the RediSearch JSON documents at
+    /// `mtg:cards:data:{uuid}` with a single JSON.MGET, not the plain `card:{uuid}` strings.
+    /// Missing UUIDs are skipped, so the result may be shorter than the input.
+    pub async fn get_cards_by_uuids(&mut self, uuids: &[String]) -> Result<Vec<IndexedCard>> {
+        if uuids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut con = self.connection().await?;
+
+        let mut cmd = redis::cmd("JSON.MGET");
+        for uuid in uuids {
+            cmd.arg(format!("mtg:cards:data:{}", uuid));
+        }
+        cmd.arg("$");
+
+        // One entry per key: a JSON array string for hits, nil for misses
+        let data: Vec<Option<String>> = cmd.query_async(&mut con).await?;
+
+        let mut cards = Vec::with_capacity(data.len());
+        for json_str in data.into_iter().flatten() {
+            let parsed: Vec<serde_json::Value> = serde_json::from_str(&json_str)?;
+            if let Some(card_data) = parsed.into_iter().next() {
+                cards.push(Self::decode_card(card_data)?);
+            }
+        }
+
+        self.expand_compressed_cards(cards).await
+    }
+
+    // Check the stored schema version before deserializing, so an incompatible index
+    // reports `ReindexRequired` instead of a serde error about some missing field
+    fn decode_card(card_data: serde_json::Value) -> Result<IndexedCard> {
+        let stored_version = card_data.get("schema_version")
+            .and_then(|version| version.as_u64())
+            .map_or(legacy_card_schema_version(), |version| version as u32);
+        if !(MIN_READABLE_CARD_SCHEMA_VERSION..=CARD_SCHEMA_VERSION).contains(&stored_version) {
+            return Err(ReindexRequired { stored_version }.into());
+        }
+
+        serde_json::from_value(card_data).context("Failed to parse stored card")
+    }
+
+    /// Page through cards with a given subtype (e.g. "Goblin"), optionally narrowed to a
+    /// color and/or a format the card is legal in. Returns the page plus the total match count.
+    pub async fn get_cards_by_subtype(
+        &mut self,
+        subtype: &str,
+        color: Option<&str>,
+        format: Option<&str>,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(Vec<IndexedCard>, usize)> {
+        let mut con = self.connection().await?;
+
+        let mut keys = vec![format!("mtg:cards:subtype:{}", subtype.to_lowercase())];
+        if let Some(color) = color {
+            keys.push(format!("mtg:cards:color:{}", color.to_lowercase()));
+        }
+        if let Some(format) = format {
+            keys.push(format!("mtg:cards:legal:{}", format.to_lowercase()));
+        }
+
+        let mut uuids: Vec<String> = con.sinter(&keys).await?;
+        uuids.sort();
+        let total = uuids.len();
+
+        let page: Vec<String> = uuids.into_iter().skip(offset).take(limit).collect();
+        let cards = self.get_cards_by_uuids(&page).await?;
+
+        Ok((cards, total))
+    }
+
+    /// A random printing matching every filter in `filters` (`set`, `rarity`, `color`,
+    /// `format`), or None when nothing matches. Redis picks the member: a single filter
+    /// set is sampled directly; several are intersected server-side, smallest first, into
+    /// a short-lived key that is sampled and dropped in one transaction. Without filters
+    /// a `color_count` bucket is chosen in proportion to its size, so every printing is
+    /// equally likely.
+    pub async fn get_random_card(&mut self, filters: &HashMap<String, String>) -> Result<Option<IndexedCard>> {
+        let mut keys = Vec::new();
+        for (name, value) in filters {
+            match Self::filter_set_key(name, value) {
+                Some(key) => keys.push(key),
+                None => anyhow::bail!("Unknown random card filter '{}'", name),
+            }
+        }
+        if keys.is_empty() {
+            keys = (0..=5).map(|n| format!("mtg:cards:color_count:{}", n)).collect();
+        }
+        
+        let mut con = self.connection().await?;
+        let mut pipe = redis::pipe();
+        for key in &keys {
+            pipe.cmd("SCARD").arg(key);
+        }
+        let sizes: Vec<u64> = pipe.query_async(&mut con).await?;
+        let mut sized: Vec<(u64, String)> = sizes.into_iter().zip(keys).collect();
+        
+        let uuid: Option<String> = if filters.is_empty() {
+            let total: u64 = sized.iter().map(|(size, _)| size).sum();
+            if total == 0 {
+                return Ok(None);
+            }
+            let mut pick = (uuid::Uuid::new_v4().as_u128() % total as u128) as u64;
+            let bucket = sized.iter()
+                .find(|(size, _)| {
+                    let hit = pick < *size;
+                    if !hit {
+                        pick -= size;
+                    }
+                    hit
+                })
+                .map(|(_, key)| key.clone())
+                .unwrap_or_default();
+            con.srandmember(&bucket).await?
+        } else if sized.iter().any(|(size, _)| *size == 0) {
+            return Ok(None);
+        } else if sized.len() == 1 {
+            con.srandmember(&sized[0].1).await?
+        } else {
+            sized.sort();
+            let scratch_key = format!("mtg:cards:random:{}", uuid::Uuid::new_v4());
+            let mut intersect = redis::cmd("SINTERSTORE");
+            intersect.arg(&scratch_key);
+            for (_, key) in &sized {
+                intersect.arg(key);
+            }
+            let (_, uuid, _): (u64, Option<String>, u64) = redis::pipe()
+                .atomic()
+                .add_command(intersect)
+                .cmd("SRANDMEMBER").arg(&scratch_key)
+                .cmd("DEL").arg(&scratch_key)
+                .query_async(&mut con)
+                .await?;
+            uuid
+        };
+        
+        match uuid {
+            Some(uuid) => self.get_card_by_uuid(&uuid).await,
+            None => Ok(None),
+        }
+    }
+
+    /// One page of the printings in a subset such as "The List" or "Special Guests",
+    /// ordered by uuid, plus the subset's total size. The name is matched like a card
+    /// name, so case and punctuation don't matter.
+    pub async fn get_cards_by_subset(&mut self, subset: &str, offset: usize, limit: usize) -> Result<(Vec<IndexedCard>, usize)> {
+        let mut con = self.connection().await?;
+        
+        let mut uuids: Vec<String> = con.smembers(format!("mtg:cards:subset:{}", name_index_key(subset))).await?;
+        uuids.sort();
+        let total = uuids.len();
+        
+        let page: Vec<String> = uuids.into_iter().skip(offset).take(limit).collect();
+        let cards = self.get_cards_by_uuids(&page).await?;
+        
+        Ok((cards, total))
+    }
+
+    /// Distinct cards with exactly `mana_value`, for filling a curve slot. `color`, `type`,
+    /// `format` and `rarity` filters narrow the `mtg:cards:mana_value:` set by a server-side
+    /// SINTER. Each card appears once, as its newest non-promo printing, ordered by `sort_by`:
+    /// `price` (cheapest first, unpriced last), `edhrec` (most played first) or `name`.
+    /// Printings are grouped on a few projected fields; only the returned page's card
+    /// documents are loaded.
+    ///
+    /// Mana value 0 covers lands and free spells. Spells with no mana cost at all
+    /// (suspend-only cards, Living End) also have mana value 0 but can't be cast for free,
+    /// so they are left out unless `include_uncastable` is set.
+    pub async fn get_cards_by_mana_value(
+        &mut self,
+        mana_value: f32,
+        filters: HashMap<String, String>,
+        sort_by: &str,
+        include_uncastable: bool,
+        limit: usize,
+    ) -> Result<Vec<serde_json::Value>> {
+        if !MANA_VALUE_SORTS.contains(&sort_by) {
+            anyhow::bail!("Unknown sort '{}', expected one of: {}", sort_by, MANA_VALUE_SORTS.join(", "));
+        }
+        let mut con = self.connection().await?;
+        
+        let mut keys = vec![format!("mtg:cards:mana_value:{}", mana_value)];
+        for (name, value) in &filters {
+            let key = match name.as_str() {
+                "color" | "colors" => format!("mtg:cards:color:{}", value.to_lowercase()),
+                "type" | "types" => format!("mtg:cards:type:{}", value.to_lowercase()),
+                "format" => format!("mtg:cards:legal:{}", value.to_lowercase()),
+                "rarity" => format!("mtg:cards:rarity:{}", value.to_lowercase()),
+                _ => continue,
+            };
+            keys.push(key);
+        }
+        
+        let mut uuids: Vec<String> = con.sinter(&keys).await?;
+        if mana_value == 0.0 && !include_uncastable {
+            let uncastable: HashSet<String> = con.smembers("mtg:cards:no_mana_cost").await?;
+            uuids.retain(|uuid| !uncastable.contains(uuid));
+        }
+        uuids.sort();
+        
+        let mut by_name: HashMap<String, PrintingSortFields> = HashMap::new();
+        for chunk in uuids.chunks(CARD_FETCH_BATCH_SIZE) {
+            for printing in Self::printing_sort_fields(&mut con, chunk).await? {
+                let newer = by_name.get(&printing.name).is_none_or(|current| {
+                    (!printing.is_promo, &printing.release_date) > (!current.is_promo, &current.release_date)
+                });
+                if newer {
+                    by_name.insert(printing.name.clone(), printing);
+                }
+            }
+        }
+        let mut printings: Vec<PrintingSortFields> = by_name.into_values().collect();
+        printings.sort_by(|a, b| a.name.cmp(&b.name));
+        
+        // Pricing every candidate is only needed to sort by price; otherwise price the page
+        if sort_by != "price" {
+            if sort_by == "edhrec" {
+                printings.sort_by_key(|printing| printing.edhrec_rank.unwrap_or(u32::MAX));
+            }
+            printings.truncate(limit);
+        }
+        let set_codes: Vec<String> = printings.iter().map(|printing| printing.set_code.clone()).collect();
+        let sets = Self::set_infos(&mut con, &set_codes).await?;
+        let wanted: Vec<(String, bool)> = printings.iter()
+            .map(|printing| (printing.uuid.clone(), sets.get(&printing.set_code).is_some_and(|set| set.prices_as_foil(false))))
+            .collect();
+        let prices = Self::preferred_market_prices(&mut con, &wanted).await?;
+        let mut priced: Vec<(PrintingSortFields, Option<f64>)> = printings.into_iter().zip(prices).collect();
+        if sort_by == "price" {
+            priced.sort_by(|a, b| match (a.1, b.1) {
+                (Some(a), Some(b)) => a.total_cmp(&b),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            });
+            priced.truncate(limit);
+        }
+        
+        let page: Vec<String> = priced.iter().map(|(printing, _)| printing.uuid.clone()).collect();
+        let mut cards: HashMap<String, IndexedCard> = self.get_cards_by_uuids(&page).await?
+            .into_iter()
+            .map(|card| (card.uuid.clone(), card))
+            .collect();
+        Ok(priced.into_iter()
+            .filter_map(|(printing, price)| {
+                let card = cards.remove(&printing.uuid)?;
+                let mut summary = Self::card_summary(&serde_json::to_value(&card).ok()?);
+                summary["edhrec_rank"] = serde_json::json!(card.edhrec_rank);
+                summary["market_price"] = serde_json::json!(price);
+                Some(summary)
+            })
+            .collect())
+    }
+
+    // The fields `get_cards_by_mana_value` groups, sorts and prices printings by, read
+    // with one JSON.MGET per field instead of whole card documents. Printings without a
+    // stored document are skipped.
+    async fn printing_sort_fields(con: &mut MultiplexedConnection, uuids: &[String]) -> Result<Vec<PrintingSortFields>> {
+        let mut pipe = redis::pipe();
+        for path in ["$.name", "$.is_promo", "$.release_date", "$.set_code", "$.edhrec_rank"] {
+            let mget = pipe.cmd("JSON.MGET");
+            for uuid in uuids {
+                mget.arg(format!("mtg:cards:data:{}", uuid));
+            }
+            mget.arg(path);
+        }
+        let fields: Vec<Vec<Option<String>>> = pipe.query_async(con).await?;
+        
+        // Each reply is a JSON array of the path's matches, or nil for a missing document
+        fn first<T: serde::de::DeserializeOwned>(reply: &Option<String>) -> Option<T> {
+            serde_json::from_str::<Vec<T>>(reply.as_deref()?).ok()?.into_iter().next()
+        }
+        Ok(uuids.iter()
+            .enumerate()
+            .filter_map(|(i, uuid)| Some(PrintingSortFields {
+                uuid: uuid.clone(),
+                name: first(&fields[0][i])?,
+                is_promo: first(&fields[1][i]).unwrap_or(false),
+                release_date: first(&fields[2][i]).unwrap_or_default(),
+                set_code: first(&fields[3][i]).unwrap_or_default(),
+                edhrec_rank: first::<Option<u32>>(&fields[4][i]).flatten(),
+            }))
+            .collect())
+    }
+
+    /// Oracle card for an exact card name via `mtg:cards:name_to_oracle:`
+    pub async fn get_card_by_exact_name(&mut self, name: &str) -> Result<Option<IndexedCard>> {
+        let mut con = self.connection().await?;
+        let key = format!("mtg:cards:name_to_oracle:{}", name_index_key(name));
+        let oracle_ids: Vec<String> = con.smembers(&key).await?;
+
+        match oracle_ids.into_iter().min() {
+            Some(oracle_id) => self.get_card_by_oracle_id(&oracle_id).await,
+            None => Ok(None),
+        }
+    }
+
+    /// The full `mtg:cards:name_to_oracle:` → `mtg:cards:oracle_to_printings:` tree for a
+    /// name, for clients doing their own disambiguation. `None` if the name is not indexed.
+    pub async fn get_name_mapping(&mut self, name: &str) -> Result<Option<NameMapping>> {
+        let mut con = self.connection().await?;
+        let key = format!("mtg:cards:name_to_oracle:{}", name_index_key(name));
+        let mut oracle_ids: Vec<String> = con.smembers(&key).await?;
+        if oracle_ids.is_empty() {
+            return Ok(None);
+        }
+        oracle_ids.sort();
+        
+        let mut printings_by_oracle = HashMap::new();
+        for oracle_id in &oracle_ids {
+            let uuids: Vec<String> = con.smembers(format!("mtg:cards:oracle_to_printings:{}", oracle_id)).await?;
+            let mut printings: Vec<PrintingSummary> = self.get_cards_by_uuids(&uuids).await?
+                .into_iter()
+                .map(|card| PrintingSummary {
+                    uuid: card.uuid,
+                    name: card.name,
+                    face_name: card.face_name,
+                    side: card.side,
+                    set_code: card.set_code,
+                    collector_number: card.collector_number,
+                    rarity: card.rarity,
+                })
+                .collect();
+            printings.sort_by(|a, b| a.set_code.cmp(&b.set_code)
+                .then_with(|| collector_number_sort_key(&a.collector_number).cmp(&collector_number_sort_key(&b.collector_number)))
+                .then_with(|| a.uuid.cmp(&b.uuid)));
+            printings_by_oracle.insert(oracle_id.clone(), printings);
+        }
+        
+        Ok(Some(NameMapping {
+            name: name.to_string(),
+            oracle_ids,
+            printings_by_oracle,
+        }))
+    }
+
+    /// Tokens and emblems a card creates, found through the tokens' `reverse_related`
+    /// names (the card's name or one of its face names). Each distinct token is listed
+    /// once, preferring the printing from the card's own token set (`T` + set code).
+    /// None when the card doesn't exist.
+    pub async fn get_tokens_created_by(&mut self, card_uuid: &str) -> Result<Option<Vec<TokenSummary>>> {
+        let card = match self.get_card_by_uuid(card_uuid).await? {
+            Some(card) => card,
+            None => return Ok(None),
+        };
+        
+        let mut name_keys: Vec<String> = std::iter::once(card.name.as_str())
+            .chain(card.name.split(" // "))
+            .chain(card.face_name.as_deref())
+            .map(|name| format!("mtg:tokens:created_by:{}", name_index_key(name)))
+            .collect();
+        name_keys.sort();
+        name_keys.dedup();
+        
+        let mut con = self.connection().await?;
+        let token_uuids: Vec<String> = con.sunion(&name_keys).await?;
+        let keys: Vec<String> = token_uuids.iter().map(|uuid| format!("mtg:tokens:data:{}", uuid)).collect();
+        let mut tokens: Vec<TokenSummary> = Self::json_mget(&mut con, &keys).await?
+            .into_iter()
+            .flatten()
+            .filter_map(|doc| serde_json::from_value(doc).ok())
+            .collect();
+        
+        let own_token_set = format!("T{}", card.set_code);
+        tokens.sort_by(|a, b| (a.set_code != own_token_set).cmp(&(b.set_code != own_token_set))
+            .then_with(|| a.set_code.cmp(&b.set_code))
+            .then_with(|| collector_number_sort_key(&a.collector_number).cmp(&collector_number_sort_key(&b.collector_number))));
+        let mut seen = HashSet::new();
+        tokens.retain(|token| seen.insert((
+            token.name.clone(),
+            token.colors.clone(),
+            token.types.clone(),
+            token.subtypes.clone(),
+            token.power.clone(),
+            token.toughness.clone(),
+            token.text.clone(),
+        )));
+        tokens.sort_by(|a, b| a.name.cmp(&b.name));
+        
+        Ok(Some(tokens))
+    }
+
+    /// Resolve a card's `spellbook` and `reverse_related` names into summaries.
+    /// Returns the source card alongside `(spellbook, reverse_related)`.
+    pub async fn get_related_cards(
+        &mut self,
+        uuid: &str,
+    ) -> Result<Option<(IndexedCard, Vec<RelatedCardSummary>, Vec<RelatedCardSummary>)>> {
+        let card = match self.get_card_by_uuid(uuid).await? {
+            Some(card) => card,
+            None => return Ok(None),
+        };
+
+        let mut resolved = Vec::new();
+        for names in [&card.related_cards.spellbook, &card.related_cards.reverse_related] {
+            let mut summaries = Vec::new();
+            for name in names.iter().flatten() {
+                let summary = match self.get_card_by_exact_name(name).await? {
+                    Some(related) => RelatedCardSummary {
+                        name: related.name,
+                        uuid: Some(related.uuid),
+                        set_code: Some(related.set_code),
+                        mana_cost: related.mana_cost,
+                        types: related.types,
+                        rarity: Some(related.rarity),
+                    },
+                    None => RelatedCardSummary {
+                        name: name.clone(),
+                        uuid: None,
+                        set_code: None,
+                        mana_cost: None,
+                        types: Vec::new(),
+                        rarity: None,
+                    },
+                };
+                summaries.push(summary);
+            }
+            resolved.push(summaries);
+        }
+
+        let reverse_related = resolved.pop().unwrap_or_default();
+        let spellbook = resolved.pop().unwrap_or_default();
+        Ok(Some((card, spellbook, reverse_related)))
+    }
+
+    /// Cards whose color identity is exactly `colors` (any order); an empty slice means colorless.
+    pub async fn get_cards_by_exact_color_identity(
+        &mut self,
+        colors: &[String],
+        offset: usize,
+        limit: usize,
+    ) -> Result<(Vec<IndexedCard>, usize)> {
+        let mut con = self.connection().await?;
+        let key = format!("mtg:cards:color_identity:{}", color_identity_key(colors));
+
+        let mut uuids: Vec<String> = con.smembers(&key).await?;
+        uuids.sort();
+        let total = uuids.len();
+
+        let page: Vec<String> = uuids.into_iter().skip(offset).take(limit).collect();
+        let cards = self.get_cards_by_uuids(&page).await?;
+
+        Ok((cards, total))
+    }
+
+    /// Printings with the most TCGPlayer SKUs, paired with their SKU count
+    pub async fn get_most_variant_cards(&mut self, limit: usize) -> Result<Vec<(IndexedCard, u64)>> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut con = self.connection().await?;
+        let ranked: Vec<(String, u64)> = con
+            .zrevrange_withscores("mtg:cards:variation_count", 0, limit as isize - 1)
+            .await?;
+
+        let uuids: Vec<String> = ranked.iter().map(|(uuid, _)| uuid.clone()).collect();
+        let counts: HashMap<String, u64> = ranked.into_iter().collect();
+        let cards = self.get_cards_by_uuids(&uuids).await?;
+
+        Ok(cards
+            .into_iter()
+            .map(|card| {
+                let count = counts.get(&card.uuid).copied().unwrap_or(0);
+                (card, count)
+            })
+            .collect())
+    }
+
+    /// Cards printed in exactly one set, most valuable first (or cheapest first with
+    /// `ascending`). Unpriced cards sort as $0.
+    pub async fn get_unique_printing_cards(
+        &mut self,
+        ascending: bool,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(Vec<IndexedCard>, usize)> {
+        let mut con = self.connection().await?;
+        let key = "mtg:cards:unique_printings";
+
+        let total: usize = con.zcard(key).await?;
+        if limit == 0 {
+            return Ok((Vec::new(), total));
+        }
+        let start = offset.min(isize::MAX as usize) as isize;
+        let stop = offset.saturating_add(limit - 1).min(isize::MAX as usize) as isize;
+        let oracle_ids: Vec<String> = if ascending {
+            con.zrange(key, start, stop).await?
+        } else {
+            con.zrevrange(key, start, stop).await?
+        };
+
+        // One printing per oracle id, the same one get_card_by_oracle_id picks
+        let cards = self.primary_printings(&oracle_ids).await?;
+
+        Ok((cards, total))
+    }
+
+    pub async fn get_card_by_oracle_id(&mut self, oracle_id: &str) -> Result<Option<IndexedCard>> {
+        let cache_key = format!("oracle:{}", oracle_id);
+        if let Some(card) = self.cached_card(&cache_key) {
+            return Ok(Some(card));
+        }
+        
+        // Not get_card_by_uuid: one lookup should count as one cache miss, not two
+        let card = self.primary_printings(&[oracle_id.to_string()]).await?.pop();
+        self.cache_card(cache_key, &card);
+        Ok(card)
+    }
+
+    // The primary printing of each oracle id that has any, in `oracle_ids` order. All
+    // printings are loaded in one JSON.MGET so the choice can look at the cards.
+    async fn primary_printings(&mut self, oracle_ids: &[String]) -> Result<Vec<IndexedCard>> {
+        if oracle_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        
+        let mut con = self.connection().await?;
+        let mut pipe = redis::pipe();
+        for oracle_id in oracle_ids {
+            pipe.cmd("SMEMBERS").arg(format!("mtg:cards:oracle_to_printings:{}", oracle_id));
+        }
+        let printings: Vec<Vec<String>> = pipe.query_async(&mut con).await?;
+        let uuids: Vec<String> = printings.into_iter().flatten().collect();
+        
+        let mut by_oracle: HashMap<String, Vec<IndexedCard>> = HashMap::new();
+        for card in self.get_cards_by_uuids(&uuids).await? {
+            if let Some(oracle_id) = card.scryfall_oracle_id.clone() {
+                by_oracle.entry(oracle_id).or_default().push(card);
+            }
+        }
+        Ok(oracle_ids.iter()
+            .filter_map(|oracle_id| by_oracle.remove(oracle_id))
+            .filter_map(Self::primary_printing)
+            .collect())
+    }
+
+    // The printing that stands in for an oracle card, as the Scryfall importer's default
+    // "latest" strategy picks it: regular paper printings (not promo, sold in paper)
+    // first, then the most recent release, with ties going to the lowest uuid
+    fn primary_printing(printings: Vec<IndexedCard>) -> Option<IndexedCard> {
+        let is_regular = |card: &IndexedCard| !card.is_promo && card.availability.iter().any(|a| a == "paper");
+        printings.into_iter().max_by(|a, b| {
+            is_regular(a).cmp(&is_regular(b))
+                .then_with(|| a.release_date.cmp(&b.release_date))
+                .then_with(|| b.uuid.cmp(&a.uuid))
+        })
+    }
+
+    /// Name search with tag filters. Besides the `filters` map, the query itself may carry
+    /// `field:value` terms and negated `-field:value` terms, where field is one of
+    /// `color`, `rarity`, `type` or `set`; e.g. `"goblin type:Creature -color:R -color:B"`.
+    /// Negated filters can also be passed as `not_color`/`not_rarity`/`not_type`/`not_set`
+    /// entries holding comma-separated values.
+    pub async fn search_cards_by_name(&mut self, query: &str, max_results: usize, filters: HashMap<String, String>) -> Result<Vec<serde_json::Value>> {
+        let (name_query, query_filters) = Self::split_query_filters(query);
+        
+        if !self.redisearch_available().await {
+            let set_keys = Self::fallback_filter_set_keys(&query_filters, &filters)?;
+            return self.fallback_name_search(&name_query, false, &set_keys, max_results).await;
+        }
+        
+        let mut con = self.connection().await?;
+        
+        let mut search_query = if name_query.is_empty() {
+            "*".to_string()
+        } else {
+            // For multi-word queries, wrap in quotes or use phrase search
+            // Match face names too, so back faces are found by their own name
+            if name_query.contains(' ') {
+                format!("@name|face_name:\"{}\"", name_query)
+            } else {
+                format!("@name|face_name:{}", name_query)
+            }
+        };
+        
+        Self::append_filter_clauses(&mut search_query, query_filters, filters)?;
+        
+        // Execute FT.SEARCH
+        let search_result: redis::RedisResult<Vec<redis::Value>> = redis::cmd("FT.SEARCH")
+            .arg("mtg:cards:idx")
+            .arg(&search_query)
+            .arg("LIMIT")
+            .arg(0)
+            .arg(max_results)
+            .arg("SORTBY")
+            .arg("name")
+            .arg("ASC")
+            .query_async(&mut con)
+            .await;
+
+        let results = search_result.map_err(Self::search_error)?;
+        Ok(Self::search_result_summaries(&results))
+    }
+
+    /// Oracle text search over the `text` field of `mtg:cards:idx`, e.g. `create a Treasure
+    /// token`. Double-quoted parts match as exact phrases. Punctuation is folded to the
+    /// token boundaries RediSearch indexes, so Scryfall-style text such as `{T}`, `+1/+1`
+    /// or `opponent's` matches instead of being read as query syntax. Filters work as in
+    /// `search_cards_filtered_fuzzy`. Each result is a card summary plus a `snippet` of its
+    /// rules text around the matched words, highlighted with `**`. Fails with
+    /// `SearchIndexMissing` when the index doesn't exist.
+    pub async fn search_cards_by_text(&mut self, query: &str, max_results: usize, filters: HashMap<String, String>) -> Result<Vec<serde_json::Value>> {
+        let (text_query, query_filters) = Self::split_query_filters(query);
+        let clauses = Self::text_query_clauses(&text_query);
+        if clauses.is_empty() {
+            return Ok(Vec::new());
+        }
+        
+        let mut con = self.connection().await?;
+        let mut search_query = format!("@text:({})", clauses.join(" "));
+        
+        // Every filter is a clause of the query, so LIMIT applies after filtering
+        Self::append_filter_clauses(&mut search_query, query_filters, filters)?;
+        
+        let search_result: redis::RedisResult<Vec<redis::Value>> = redis::cmd("FT.SEARCH")
+            .arg("mtg:cards:idx")
+            .arg(&search_query)
+            .arg("LIMIT")
+            .arg(0)
+            .arg(max_results)
+            .query_async(&mut con)
+            .await;
+        
+        let results = search_result.map_err(Self::search_error)?;
+        
+        let terms: Vec<String> = text_query
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(|word| word.to_lowercase())
+            .collect();
+        let mut cards = Self::search_result_summaries(&results);
+        for card in &mut cards {
+            // RediSearch stems terms, so a stemmed match may not prefix-match and gets no snippet
+            let snippet = card.get("text")
+                .and_then(|text| text.as_str())
+                .and_then(|text| rules_text_snippet(text, &terms, RULES_TEXT_SNIPPET_RADIUS));
+            card["snippet"] = serde_json::json!(snippet);
+        }
+        
+        Ok(cards)
+    }
+
+    // RediSearch clauses for free text: a quoted phrase per double-quoted part or per
+    // word that punctuation splits into several tokens, a bare term otherwise. Only
+    // alphanumerics survive, so no query syntax can get through.
+    fn text_query_clauses(text: &str) -> Vec<String> {
+        let mut clauses = Vec::new();
+        for (i, part) in text.split('"').enumerate() {
+            let in_quotes = i % 2 == 1;
+            let words: Vec<&str> = if in_quotes { vec![part] } else { part.split_whitespace().collect() };
+            for word in words {
+                let tokens: Vec<String> = word
+                    .split(|c: char| !c.is_alphanumeric())
+                    .filter(|token| !token.is_empty())
+                    .map(|token| token.to_lowercase())
+                    .collect();
+                match tokens.len() {
+                    0 => {}
+                    1 if !in_quotes => clauses.push(tokens[0].clone()),
+                    _ => clauses.push(format!("\"{}\"", tokens.join(" "))),
+                }
+            }
+        }
+        clauses
+    }
+
+    // Name terms for the fuzzy search: the query is split into alphanumeric tokens the way
+    // RediSearch tokenizes names, so punctuation ("Will-o'-the-Wisp", "Fire // Ice") and
+    // query syntax (`|`, `@`, `-`) never reach FT.SEARCH. Tokens of
+    // `FUZZY_MIN_WORD_LENGTH`+ characters match within one edit.
+    fn fuzzy_name_terms(name_query: &str) -> Vec<String> {
+        name_query
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|token| !token.is_empty())
+            .map(|token| {
+                let token = token.to_lowercase();
+                if token.chars().count() >= FUZZY_MIN_WORD_LENGTH { format!("%{}%", token) } else { token }
+            })
+            .collect()
+    }
+
+    /// Fuzzy name search combined with filters. Each name word of 4+ characters matches
+    /// within one edit (`%word%`), against card and face names. Filters, including
+    /// `format` (the `legal_formats` tag) and inline `field:value` terms, are clauses of
+    /// the same RediSearch query, so every match that passes them is a candidate. Without
+    /// RediSearch, `format`, `color`, `rarity`, `set` and `security_stamp` are applied as
+    /// intersections with the `mtg:cards:legal/color/rarity/set_cards/security_stamp` sets;
+    /// any other filter, or a negated one, fails with `SearchIndexMissing`.
+    pub async fn search_cards_filtered_fuzzy(&mut self, query: &str, filters: HashMap<String, String>, max_results: usize) -> Result<Vec<serde_json::Value>> {
+        let (name_query, query_filters) = Self::split_query_filters(query);
+        
+        if !self.redisearch_available().await {
+            let set_keys = Self::fallback_filter_set_keys(&query_filters, &filters)?;
+            return self.fallback_name_search(&name_query, false, &set_keys, max_results).await;
+        }
+        
+        let mut con = self.connection().await?;
+        let name_terms = Self::fuzzy_name_terms(&name_query);
+        
+        let mut search_query = if name_terms.is_empty() {
+            "*".to_string()
+        } else {
+            format!("@name|face_name:({})", name_terms.join(" "))
+        };
+        
+        // Every filter is a clause of the query, so LIMIT applies after filtering
+        Self::append_filter_clauses(&mut search_query, query_filters, filters)?;
+        
+        let search_result: redis::RedisResult<Vec<redis::Value>> = redis::cmd("FT.SEARCH")
+            .arg("mtg:cards:idx")
+            .arg(&search_query)
+            .arg("LIMIT")
+            .arg(0)
+            .arg(max_results)
+            .query_async(&mut con)
+            .await;
+        
+        let results = search_result.map_err(Self::search_error)?;
+        Ok(Self::search_result_summaries(&results))
+    }
+
+    // A failed FT.SEARCH as an API error: `SearchIndexMissing` when the index or module is
+    // missing, otherwise the Redis error itself (so timeouts still map to 504)
+    fn search_error(e: redis::RedisError) -> anyhow::Error {
+        let message = e.to_string().to_lowercase();
+        if message.contains("no such index") || message.contains("unknown index") || message.contains("unknown command") {
+            return SearchIndexMissing.into();
+        }
+        e.into()
+    }
+
+    /// Whether RediSearch and the `mtg:cards:idx` index are present. Plain Redis servers
+    /// lack FT.* commands, so card name searches then fall back to the
+    /// `mtg:cards:name_to_oracle:*` sets written by the indexer. A found index or missing
+    /// module is remembered; a missing index is checked again after
+    /// `REDISEARCH_RECHECK_SECS`, since the indexer may still create it.
+    async fn redisearch_available(&mut self) -> bool {
+        if let Some((available, recheck_at)) = self.redisearch {
+            if recheck_at.is_none_or(|recheck_at| Instant::now() < recheck_at) {
+                return available;
+            }
+        }
+        let index_was_missing = self.redisearch.is_some();
+        
+        // Connection failures are not cached; the search itself will report them
+        let mut con = match self.connection().await {
+            Ok(con) => con,
+            Err(_) => return false,
+        };
+        let info: redis::RedisResult<redis::Value> = redis::cmd("FT.INFO")
+            .arg("mtg:cards:idx")
+            .query_async(&mut con)
+            .await;
+        
+        match info {
+            Ok(_) => {
+                info!("RediSearch index mtg:cards:idx found, card search uses FT.SEARCH");
+                self.redisearch = Some((true, None));
+                true
+            }
+            Err(e) if e.to_string().to_lowercase().contains("unknown command") => {
+                warn!("RediSearch module not loaded, card search falls back to name-key lookups");
+                self.redisearch = Some((false, None));
+                false
+            }
+            Err(_) => {
+                // Warned once, not on every recheck while the index is still missing
+                if !index_was_missing {
+                    warn!("RediSearch index mtg:cards:idx unavailable, card search falls back to name-key lookups");
+                }
+                self.redisearch = Some((false, Some(Instant::now() + Duration::from_secs(REDISEARCH_RECHECK_SECS))));
+                false
+            }
+        }
     }
 
-    pub async fn from_env() -> Result<Self> {
-        let redis_url = env::var("REDIS_URL")
-            .unwrap_or_else(|_| "redis://127.0.0.1:9999".to_string());
-        Self::new(&redis_url).await
+    // Membership sets for the filters of a search without RediSearch. A negated filter
+    // or one with no `mtg:cards:*` set (type, mana value, color count, border, finish,
+    // ...) can't be applied, so the search fails instead of returning unfiltered cards.
+    fn fallback_filter_set_keys(
+        query_filters: &[(&'static str, String, bool)],
+        filters: &HashMap<String, String>,
+    ) -> Result<Vec<String>> {
+        let query_keys = query_filters.iter()
+            .map(|(field, value, negated)| if *negated { None } else { Self::filter_set_key(field, value) });
+        let filter_keys = filters.iter()
+            .map(|(key, value)| Self::filter_set_key(key, value));
+        query_keys.chain(filter_keys)
+            .collect::<Option<Vec<String>>>()
+            .ok_or_else(|| SearchIndexMissing.into())
     }
 
-    // =============================================================================
-    // CARD OPERATIONS
-    // =============================================================================
-
-    pub async fn get_card_by_uuid(&mut self, uuid: &str) -> Result<Option<IndexedCard>> {
-        let mut con = self.client.get_multiplexed_async_connection().await?;
-        let key = format!("mtg:cards:data:{}", uuid);
+    /// Card search without RediSearch: exact normalized name first, then a SCAN for name
+    /// keys containing (or, with `prefix_only`, starting with) the query. One printing
+    /// per oracle card, narrowed by `set_keys` membership. Only the set-backed filters
+    /// (format, color, rarity, set, security stamp) apply here; callers reject the rest
+    /// with `fallback_filter_set_keys`.
+    async fn fallback_name_search(
+        &mut self,
+        name_query: &str,
+        prefix_only: bool,
+        set_keys: &[String],
+        max_results: usize,
+    ) -> Result<Vec<serde_json::Value>> {
+        let mut con = self.connection().await?;
+        let name_key = name_index_key(name_query);
         
-        // Use JSON.GET to retrieve the RediSearch JSON document
-        let data: Option<String> = redis::cmd("JSON.GET")
-            .arg(&key)
-            .arg("$")
-            .query_async(&mut con)
-            .await
-            .unwrap_or(None);
+        let mut oracle_ids: Vec<String> = if name_key.is_empty() || prefix_only {
+            Vec::new()
+        } else {
+            con.smembers(format!("mtg:cards:name_to_oracle:{}", name_key)).await?
+        };
         
-        match data {
-            Some(json_str) => {
-                // JSON.GET returns a JSON array, extract the first element
-                let parsed: Vec<serde_json::Value> = serde_json::from_str(&json_str)?;
-                if let Some(card_data) = parsed.first() {
-                    let card: IndexedCard = serde_json::from_value(card_data.clone())?;
-                    Ok(Some(card))
-                } else {
-                    Ok(None)
+        if oracle_ids.is_empty() {
+            let pattern = if prefix_only {
+                format!("mtg:cards:name_to_oracle:{}*", name_key)
+            } else {
+                format!("mtg:cards:name_to_oracle:*{}*", name_key)
+            };
+            let mut name_keys: Vec<String> = Vec::new();
+            let mut cursor: u64 = 0;
+            loop {
+                let (next, batch): (u64, Vec<String>) = redis::cmd("SCAN")
+                    .arg(cursor)
+                    .arg("MATCH")
+                    .arg(&pattern)
+                    .arg("COUNT")
+                    .arg(FALLBACK_SCAN_COUNT)
+                    .query_async(&mut con)
+                    .await?;
+                name_keys.extend(batch);
+                cursor = next;
+                if cursor == 0 || name_keys.len() >= FUZZY_CANDIDATE_LIMIT {
+                    break;
                 }
             }
-            None => Ok(None),
+            name_keys.sort();
+            
+            for key in name_keys {
+                let ids: Vec<String> = con.smembers(&key).await?;
+                oracle_ids.extend(ids);
+            }
         }
+        oracle_ids.sort();
+        oracle_ids.dedup();
+        
+        oracle_ids.truncate(FUZZY_CANDIDATE_LIMIT);
+        
+        let mut cards: Vec<serde_json::Value> = self.primary_printings(&oracle_ids).await?
+            .iter()
+            .filter_map(|card| serde_json::to_value(card).ok())
+            .map(|card_data| Self::card_summary(&card_data))
+            .collect();
+        Self::retain_set_members(&mut con, &mut cards, set_keys).await?;
+        
+        cards.sort_by(|a, b| a["name"].as_str().cmp(&b["name"].as_str()));
+        cards.truncate(max_results);
+        Ok(cards)
     }
 
-    pub async fn get_card_by_oracle_id(&mut self, oracle_id: &str) -> Result<Option<IndexedCard>> {
-        let mut con = self.client.get_multiplexed_async_connection().await?;
-        let key = format!("card:oracle:{}", oracle_id);
-        let data: Option<String> = con.get(&key).await?;
+    /// Cards whose name contains `substring` anywhere, matched on normalized name keys
+    /// (`mtg:cards:name_to_oracle:*{key}*`) so punctuation and case are ignored. Unlike the
+    /// fallback search every matching name key is collected and sorted before the first
+    /// `max_results` are looked up, one printing per oracle card, so results are stable.
+    pub async fn search_cards_name_contains(&mut self, substring: &str, max_results: usize) -> Result<Vec<serde_json::Value>> {
+        let name_key = name_index_key(substring);
+        if name_key.is_empty() {
+            return Ok(Vec::new());
+        }
         
-        match data {
-            Some(json_str) => {
-                let card = serde_json::from_str(&json_str)?;
-                Ok(Some(card))
-            }
-            None => Ok(None),
+        let mut name_keys = self.scan_keys(&format!("mtg:cards:name_to_oracle:*{}*", name_key)).await?;
+        name_keys.sort();
+        name_keys.truncate(max_results);
+        if name_keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        
+        let mut con = self.connection().await?;
+        let mut pipe = redis::pipe();
+        for key in &name_keys {
+            pipe.cmd("SMEMBERS").arg(key);
+        }
+        let id_sets: Vec<Vec<String>> = pipe.query_async(&mut con).await?;
+        let mut oracle_ids: Vec<String> = id_sets.into_iter().flatten().collect();
+        oracle_ids.sort();
+        oracle_ids.dedup();
+        
+        let mut pipe = redis::pipe();
+        for oracle_id in &oracle_ids {
+            pipe.cmd("SMEMBERS").arg(format!("mtg:cards:oracle_to_printings:{}", oracle_id));
         }
+        let printings: Vec<Vec<String>> = pipe.query_async(&mut con).await?;
+        let uuids: Vec<String> = printings
+            .into_iter()
+            .filter_map(|uuids| uuids.into_iter().min())
+            .collect();
+        
+        let mut cards: Vec<serde_json::Value> = self.get_cards_by_uuids(&uuids).await?
+            .iter()
+            .filter_map(|card| serde_json::to_value(card).ok())
+            .map(|card_data| Self::card_summary(&card_data))
+            .collect();
+        cards.sort_by(|a, b| a["name"].as_str().cmp(&b["name"].as_str()));
+        cards.truncate(max_results);
+        Ok(cards)
     }
 
-    pub async fn search_cards_by_name(&mut self, query: &str, max_results: usize, filters: HashMap<String, String>) -> Result<Vec<serde_json::Value>> {
-        let mut con = self.client.get_multiplexed_async_connection().await?;
-        
-        let mut search_query = if query.is_empty() {
-            "*".to_string()
-        } else {
-            // For multi-word queries, wrap in quotes or use phrase search
-            if query.contains(' ') {
-                format!("@name:\"{}\"", query)
-            } else {
-                format!("@name:{}", query)
+    // Membership set backing a filter, for filters the indexer keeps as plain sets
+    fn filter_set_key(name: &str, value: &str) -> Option<String> {
+        match name {
+            "format" => Some(format!("mtg:cards:legal:{}", value.to_lowercase())),
+            "colors" | "color" => Some(format!("mtg:cards:color:{}", value.to_lowercase())),
+            "rarity" => Some(format!("mtg:cards:rarity:{}", value.to_lowercase())),
+            "set_code" | "set" => Some(format!("mtg:cards:set_cards:{}", value.to_uppercase())),
+            "security_stamp" => Some(format!("mtg:cards:security_stamp:{}", value.to_lowercase())),
+            _ => None,
+        }
+    }
+
+    // Drop card summaries whose uuid is missing from any of `set_keys`
+    async fn retain_set_members(
+        con: &mut MultiplexedConnection,
+        cards: &mut Vec<serde_json::Value>,
+        set_keys: &[String],
+    ) -> Result<()> {
+        for key in set_keys {
+            if cards.is_empty() {
+                break;
             }
-        };
+            let uuids: Vec<&str> = cards.iter().map(|card| card["uuid"].as_str().unwrap_or_default()).collect();
+            let members: Vec<bool> = redis::cmd("SMISMEMBER")
+                .arg(key)
+                .arg(&uuids)
+                .query_async(con)
+                .await?;
+            let mut members = members.into_iter();
+            cards.retain(|_| members.next().unwrap_or(false));
+        }
+        Ok(())
+    }
+
+    // Append inline `field:value` terms and API filters to a RediSearch query. Tag values
+    // are escaped and numeric ones parsed, so no filter value can change the query syntax.
+    fn append_filter_clauses(
+        search_query: &mut String,
+        query_filters: Vec<(&'static str, String, bool)>,
+        filters: HashMap<String, String>,
+    ) -> Result<()> {
+        // Filters written inline in the query string
+        for (field, value, negated) in query_filters {
+            let prefix = if negated { "-" } else { "" };
+            search_query.push_str(&format!(" {}@{}:{{{}}}", prefix, field, Self::escape_tag_value(&value)));
+        }
         
         // Add filters to the query
         for (key, value) in filters {
-            match key.as_str() {
-                "set_code" => search_query.push_str(&format!(" @set_code:{{{}}}", value)),
-                "rarity" => search_query.push_str(&format!(" @rarity:{{{}}}", value)),
-                "colors" => search_query.push_str(&format!(" @colors:{{{}}}", value)),
-                "types" => search_query.push_str(&format!(" @types:{{{}}}", value)),
-                "mana_value" => search_query.push_str(&format!(" @mana_value:[{} {}]", value, value)),
-                _ => {} // Ignore unknown filters
+            if let Some(field) = key.strip_prefix("not_").and_then(Self::tag_filter_field) {
+                for excluded in value.split(',').map(str::trim).filter(|v| !v.is_empty()) {
+                    search_query.push_str(&format!(" -@{}:{{{}}}", field, Self::escape_tag_value(excluded)));
+                }
+                continue;
             }
+        
+            let tag = |field: &str, value: &str| format!(" @{}:{{{}}}", field, Self::escape_tag_value(value));
+            let clause = match key.as_str() {
+                "set_code" | "set" => tag("set_code", &value),
+                "rarity" => tag("rarity", &value),
+                "format" => tag("legal_formats", &value.to_lowercase()),
+                "colors" | "color" => tag("colors", &value),
+                "types" | "type" => tag("types", &value),
+                "mana_value" => {
+                    let mana_value = Self::numeric_filter_value(&key, &value)?;
+                    format!(" @mana_value:[{} {}]", mana_value, mana_value)
+                }
+                "mana_value_max" => format!(" @mana_value:[-inf {}]", Self::numeric_filter_value(&key, &value)?),
+                "color_count" => {
+                    let color_count = Self::numeric_filter_value(&key, &value)?;
+                    format!(" @color_count:[{} {}]", color_count, color_count)
+                }
+                "border_color" => tag("border_color", &value),
+                "full_art" => tag("is_full_art", &value),
+                "textless" => tag("is_textless", &value),
+                "security_stamp" => tag("security_stamp", &value.to_lowercase()),
+                "finish" => tag("finishes", &value.to_lowercase()),
+                _ => continue, // Ignore unknown filters
+            };
+            search_query.push_str(&clause);
         }
         
-        // Execute FT.SEARCH
-        let search_result: redis::RedisResult<Vec<redis::Value>> = redis::cmd("FT.SEARCH")
-            .arg("mtg:cards:idx")
-            .arg(&search_query)
-            .arg("LIMIT")
-            .arg(0)
-            .arg(max_results)
-            .arg("SORTBY")
-            .arg("name")
-            .arg("ASC")
-            .query_async(&mut con)
-            .await;
-            
+        Ok(())
+    }
+
+    // Backslash-escape everything but letters, digits and `_` in a TAG value; RediSearch
+    // otherwise reads `}`, `|`, `-`, spaces and other punctuation as query syntax
+    fn escape_tag_value(value: &str) -> String {
+        let mut escaped = String::with_capacity(value.len());
+        for c in value.chars() {
+            if !c.is_alphanumeric() && c != '_' {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+        escaped
+    }
+
+    // A NUMERIC filter value, or `InvalidSearchFilter` for anything but a finite number
+    fn numeric_filter_value(filter: &str, value: &str) -> Result<f64> {
+        value.trim().parse::<f64>().ok()
+            .filter(|number| number.is_finite())
+            .ok_or_else(|| InvalidSearchFilter { filter: filter.to_string(), value: value.to_string() }.into())
+    }
+
+    // Summaries of the JSON documents in an FT.SEARCH reply
+    fn search_result_summaries(results: &[redis::Value]) -> Vec<serde_json::Value> {
         let mut cards = Vec::new();
         
-        if let Ok(results) = search_result {
-            // RediSearch returns [count, key1, doc1, key2, doc2, ...]
-            if results.len() > 1 {
-                let mut i = 1; // Skip count
-                while i + 1 < results.len() {
-                    // Skip the key (i), process the document (i + 1)
-                    if let redis::Value::Array(doc_array) = &results[i + 1] {
-                        // RediSearch JSON document format: ["$", "JSON_STRING"]
-                        if doc_array.len() >= 2 {
-                            if let redis::Value::BulkString(json_bytes) = &doc_array[1] {
-                                if let Ok(json_str) = String::from_utf8(json_bytes.clone()) {
-                                    if let Ok(card_data) = serde_json::from_str::<serde_json::Value>(&json_str) {
-                                        // Format the response with key fields for API compatibility
-                                        let card_json = serde_json::json!({
-                                            "uuid": card_data.get("uuid"),
-                                            "name": card_data.get("name"),
-                                            "set_code": card_data.get("set_code"),
-                                            "set_name": card_data.get("set_name"),
-                                            "mana_cost": card_data.get("mana_cost"),
-                                            "mana_value": card_data.get("mana_value"),
-                                            "rarity": card_data.get("rarity"),
-                                            "types": card_data.get("types"),
-                                            "colors": card_data.get("colors"),
-                                            "text": card_data.get("text"),
-                                            "collector_number": card_data.get("collector_number"),
-                                            "release_date": card_data.get("release_date")
-                                        });
-                                        cards.push(card_json);
-                                    }
+        // RediSearch returns [count, key1, doc1, key2, doc2, ...]
+        if results.len() > 1 {
+            let mut i = 1; // Skip count
+            while i + 1 < results.len() {
+                // Skip the key (i), process the document (i + 1)
+                if let redis::Value::Array(doc_array) = &results[i + 1] {
+                    // RediSearch JSON document format: ["$", "JSON_STRING"]
+                    if doc_array.len() >= 2 {
+                        if let redis::Value::BulkString(json_bytes) = &doc_array[1] {
+                            if let Ok(json_str) = String::from_utf8(json_bytes.clone()) {
+                                if let Ok(card_data) = serde_json::from_str::<serde_json::Value>(&json_str) {
+                                    cards.push(Self::card_summary(&card_data));
                                 }
                             }
                         }
                     }
-                    i += 2; // Skip to next key-value pair
                 }
+                i += 2; // Skip to next key-value pair
             }
         }
         
-        Ok(cards)
+        cards
+    }
+
+    // Key fields of a card document, the shape search endpoints return
+    fn card_summary(card_data: &serde_json::Value) -> serde_json::Value {
+        serde_json::json!({
+            "uuid": card_data.get("uuid"),
+            "name": card_data.get("name"),
+            "set_code": card_data.get("set_code"),
+            "set_name": card_data.get("set_name"),
+            "mana_cost": card_data.get("mana_cost"),
+            "mana_value": card_data.get("mana_value"),
+            "rarity": card_data.get("rarity"),
+            "types": card_data.get("types"),
+            "colors": card_data.get("colors"),
+            "text": card_data.get("text"),
+            "collector_number": card_data.get("collector_number"),
+            "release_date": card_data.get("release_date")
+        })
+    }
+
+    // RediSearch tag field for a user-facing filter name
+    fn tag_filter_field(name: &str) -> Option<&'static str> {
+        match name {
+            "color" | "colors" => Some("colors"),
+            "rarity" => Some("rarity"),
+            "type" | "types" => Some("types"),
+            "set" | "set_code" => Some("set_code"),
+            _ => None,
+        }
+    }
+
+    // Pull `field:value` / `-field:value` terms out of a search string, returning the
+    // remaining name text and (tag field, value, negated) triples
+    fn split_query_filters(query: &str) -> (String, Vec<(&'static str, String, bool)>) {
+        let mut name_terms = Vec::new();
+        let mut filters = Vec::new();
+        
+        for term in query.split_whitespace() {
+            let (negated, body) = match term.strip_prefix('-') {
+                Some(rest) => (true, rest),
+                None => (false, term),
+            };
+            
+            let parsed = body.split_once(':')
+                .filter(|(_, value)| !value.is_empty())
+                .and_then(|(name, value)| Self::tag_filter_field(&name.to_lowercase()).map(|field| (field, value)));
+            
+            match parsed {
+                Some((field, value)) => filters.push((field, value.to_string(), negated)),
+                None => name_terms.push(term),
+            }
+        }
+        
+        (name_terms.join(" "), filters)
     }
 
     pub async fn get_cards_in_set(&mut self, set_code: &str) -> Result<HashSet<String>> {
-        let mut con = self.client.get_multiplexed_async_connection().await?;
-        let key = format!("set:{}:cards", set_code);
+        let mut con = self.connection().await?;
+        let key = format!("mtg:cards:set_cards:{}", set_code);
         let card_uuids = con.smembers(&key).await?;
         Ok(card_uuids)
     }
 
     pub async fn autocomplete_card_names(&mut self, prefix: &str, limit: usize) -> Result<Vec<String>> {
-        let mut con = self.client.get_multiplexed_async_connection().await?;
+        if !self.redisearch_available().await {
+            let cards = self.fallback_name_search(prefix, true, &[], limit).await?;
+            return Ok(cards.iter().filter_map(|card| card["name"].as_str().map(str::to_string)).collect());
+        }
+        
+        let mut con = self.connection().await?;
         
         // First try FT.SUGGET autocomplete
         let suggestions_result: redis::RedisResult<Vec<String>> = redis::cmd("FT.SUGGET")
@@ -443,7 +1739,11 @@ impl MTGRedisClient {
     }
 
     pub async fn fuzzy_search_cards(&mut self, query: &str, limit: usize) -> Result<Vec<serde_json::Value>> {
-        let mut con = self.client.get_multiplexed_async_connection().await?;
+        if !self.redisearch_available().await {
+            return self.fallback_name_search(query, false, &[], limit).await;
+        }
+        
+        let mut con = self.connection().await?;
         
         // Use RediSearch fuzzy matching with % prefix and suffix for fuzzy search
         let fuzzy_query = format!("%{}%", query.replace(' ', ""));  // Remove spaces for fuzzy matching
@@ -509,17 +1809,252 @@ impl MTGRedisClient {
     // DECK OPERATIONS
     // =============================================================================
 
-    pub async fn get_deck_composition(&mut self, deck_uuid: &str) -> Result<serde_json::Value> {
+    /// Deck summary, its cards per board, and deckbuilding stats joined from the card
+    /// documents: `land_count` / `nonland_count` (copies in the commander zone and main
+    /// board), `land_ratio`, and `avg_mana_value` over nonland copies only. Cards
+    /// missing from the index are listed but left out of the stats.
+    pub async fn get_deck_composition(&mut self, deck_uuid: &str) -> Result<Option<serde_json::Value>> {
         let formatted_uuid = if deck_uuid.starts_with("deck_") {
             deck_uuid.to_string()
         } else {
             format!("deck_{}", deck_uuid)
         };
+        let deck = match self.get_deck_by_uuid(&formatted_uuid).await? {
+            Some(deck) => deck,
+            None => return Ok(None),
+        };
         
-        let args = vec!["composition".to_string(), formatted_uuid];
-        let result: String = self.execute_lua_script("deck_search", args).await?;
-        let composition: serde_json::Value = serde_json::from_str(&result)?;
-        Ok(composition)
+        let boards = [
+            ("commander", &deck.commanders),
+            ("main", &deck.main_board),
+            ("side", &deck.side_board),
+        ];
+        let mut uuids: Vec<String> = boards.iter()
+            .flat_map(|(_, cards)| cards.iter().map(|card| card.uuid.clone()))
+            .collect();
+        uuids.sort();
+        uuids.dedup();
+        let indexed: HashMap<String, IndexedCard> = self.get_cards_by_uuids(&uuids).await?
+            .into_iter()
+            .map(|card| (card.uuid.clone(), card))
+            .collect();
+        
+        let mut land_count = 0u32;
+        let mut nonland_count = 0u32;
+        let mut nonland_mana_value = 0.0f64;
+        let mut cards = Vec::new();
+        for (board, board_cards) in boards {
+            for card in board_cards.iter() {
+                cards.push(serde_json::json!({
+                    "uuid": card.uuid,
+                    "name": card.name,
+                    "set_code": card.set_code,
+                    "quantity": card.count,
+                    "board": board,
+                }));
+                
+                let Some(indexed_card) = indexed.get(&card.uuid) else { continue };
+                if board == "side" {
+                    continue;
+                }
+                if indexed_card.types.iter().any(|t| t == "Land") {
+                    land_count += card.count;
+                } else {
+                    nonland_count += card.count;
+                    nonland_mana_value += indexed_card.mana_value as f64 * card.count as f64;
+                }
+            }
+        }
+        
+        let counted = land_count + nonland_count;
+        let avg_mana_value = if nonland_count > 0 { nonland_mana_value / nonland_count as f64 } else { 0.0 };
+        let land_ratio = if counted > 0 { land_count as f64 / counted as f64 } else { 0.0 };
+        
+        Ok(Some(serde_json::json!({
+            "deck_info": {
+                "uuid": deck.uuid,
+                "name": deck.name,
+                "code": deck.code,
+                "deck_type": deck.deck_type,
+                "total_cards": deck.total_cards,
+                "unique_cards": deck.unique_cards,
+            },
+            "cards": cards,
+            "avg_mana_value": (avg_mana_value * 100.0).round() / 100.0,
+            "land_count": land_count,
+            "nonland_count": nonland_count,
+            "land_ratio": (land_ratio * 1000.0).round() / 1000.0,
+        })))
+    }
+
+    /// Every card in a deck with its unit market price and its share of the deck's total
+    /// market value, largest share first: the cards that carry a precon's value. Each
+    /// copy is priced from a Near Mint English SKU in its finish (foil copies and cards
+    /// from foil-only sets as foil) when there is one, else from a SKU in the other
+    /// finish. Unpriced cards are kept with zeros. None when the deck doesn't exist.
+    pub async fn get_deck_value_breakdown(&mut self, uuid: &str) -> Result<Option<Vec<(DeckCardInfo, f64, f64)>>> {
+        let deck = match self.get_deck_by_uuid(uuid).await? {
+            Some(deck) => deck,
+            None => return Ok(None),
+        };
+        let entries: Vec<DeckCardInfo> = deck.commanders.into_iter()
+            .chain(deck.main_board)
+            .chain(deck.side_board)
+            .collect();
+        
+        let mut con = self.connection().await?;
+        let set_codes: Vec<String> = entries.iter().map(|entry| entry.set_code.clone()).collect();
+        let sets = Self::set_infos(&mut con, &set_codes).await?;
+        let prices_as_foil = |entry: &DeckCardInfo| sets.get(&entry.set_code)
+            .map_or(entry.is_foil, |set| set.prices_as_foil(entry.is_foil));
+        
+        let printings: Vec<(String, bool)> = entries.iter()
+            .map(|entry| (entry.uuid.clone(), prices_as_foil(entry)))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let prices = Self::preferred_market_prices(&mut con, &printings).await?;
+        let unit_prices: HashMap<(String, bool), f64> = printings.into_iter()
+            .zip(prices)
+            .map(|(printing, price)| (printing, price.unwrap_or(0.0)))
+            .collect();
+        
+        let market_total: f64 = entries.iter()
+            .map(|entry| unit_prices[&(entry.uuid.clone(), prices_as_foil(entry))] * entry.count as f64)
+            .sum();
+        let mut breakdown: Vec<(DeckCardInfo, f64, f64)> = entries.into_iter()
+            .map(|entry| {
+                let unit_price = unit_prices[&(entry.uuid.clone(), prices_as_foil(&entry))];
+                let share = if market_total > 0.0 { unit_price * entry.count as f64 / market_total } else { 0.0 };
+                (entry, unit_price, share)
+            })
+            .collect();
+        breakdown.sort_by(|a, b| b.2.total_cmp(&a.2).then_with(|| b.1.total_cmp(&a.1)));
+        
+        Ok(Some(breakdown))
+    }
+
+    /// Estimated Commander bracket of a deck from `score_deck_power` over its commander
+    /// zone and main board, using the lists in `data/power_level.json`. Cards missing
+    /// from the index are skipped. None when the deck doesn't exist.
+    pub async fn estimate_deck_power(&mut self, uuid: &str) -> Result<Option<PowerEstimate>> {
+        let formatted_uuid = if uuid.starts_with("deck_") {
+            uuid.to_string()
+        } else {
+            format!("deck_{}", uuid)
+        };
+        let deck = match self.get_deck_by_uuid(&formatted_uuid).await? {
+            Some(deck) => deck,
+            None => return Ok(None),
+        };
+        
+        let mut copies: HashMap<String, u32> = HashMap::new();
+        for card in deck.commanders.iter().chain(&deck.main_board) {
+            *copies.entry(card.uuid.clone()).or_insert(0) += card.count;
+        }
+        let uuids: Vec<String> = copies.keys().cloned().collect();
+        let indexed = self.get_cards_by_uuids(&uuids).await?;
+        let cards: Vec<(&IndexedCard, u32)> = indexed.iter()
+            .map(|card| (card, copies[&card.uuid]))
+            .collect();
+        
+        Ok(Some(score_deck_power(&cards, &PowerLevelLists::builtin()?)))
+    }
+
+    // Stored set metadata for each distinct code, by code; unknown codes are left out
+    async fn set_infos(con: &mut MultiplexedConnection, set_codes: &[String]) -> Result<HashMap<String, SetInfo>> {
+        let codes: HashSet<&String> = set_codes.iter().collect();
+        if codes.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let keys: Vec<String> = codes.iter().map(|code| format!("set:{}", code)).collect();
+        let data: Vec<Option<String>> = redis::cmd("MGET").arg(&keys).query_async(con).await?;
+        Ok(data.into_iter()
+            .flatten()
+            .filter_map(|json_str| serde_json::from_str::<SetInfo>(&json_str).ok())
+            .map(|set| (set.code.clone(), set))
+            .collect())
+    }
+
+    // Market price of each (uuid, foil) printing's NM English SKU in the wanted finish,
+    // else of a SKU in the other finish
+    async fn preferred_market_prices(con: &mut MultiplexedConnection, printings: &[(String, bool)]) -> Result<Vec<Option<f64>>> {
+        let uuids: Vec<String> = printings.iter().map(|(uuid, _)| uuid.clone()).collect();
+        let priced = Self::priced_skus_batch(con, &uuids).await?;
+        Ok(printings.iter()
+            .zip(priced)
+            .map(|((_, foil), priced)| {
+                Self::preferred_sku_price(&priced, *foil)
+                    .or_else(|| Self::preferred_sku_price(&priced, !*foil))
+            })
+            .collect())
+    }
+
+    // (sku meta, market price) for every SKU with a market price of each printing, in
+    // `uuids` order, with each lookup step pipelined or MGET'd per batch
+    async fn priced_skus_batch(con: &mut MultiplexedConnection, uuids: &[String]) -> Result<Vec<Vec<(serde_json::Value, f64)>>> {
+        let mut priced_skus = Vec::with_capacity(uuids.len());
+        for batch in uuids.chunks(CARD_FETCH_BATCH_SIZE) {
+            let mut pipe = redis::pipe();
+            for uuid in batch {
+                pipe.cmd("SMEMBERS").arg(format!("mtg:tcg:uuid_skus:{}", uuid));
+                pipe.cmd("GET").arg(format!("mtg:tcg:uuid_to_product:{}", uuid));
+            }
+            let lookups: Vec<(Vec<String>, Option<String>)> = pipe.query_async(con).await?;
+            
+            let mut pipe = redis::pipe();
+            for (_, product_id) in &lookups {
+                // Printings without a product still get a slot, so replies line up
+                pipe.cmd("SMEMBERS").arg(format!("mtg:tcg:product_skus:{}", product_id.as_deref().unwrap_or_default()));
+            }
+            let product_skus: Vec<Vec<String>> = pipe.query_async(con).await?;
+            
+            let sku_ids: Vec<Vec<String>> = lookups.into_iter()
+                .zip(product_skus)
+                .map(|((mut sku_ids, _), product_skus)| {
+                    sku_ids.extend(product_skus);
+                    sku_ids.sort();
+                    sku_ids.dedup();
+                    sku_ids
+                })
+                .collect();
+            let all_skus: Vec<&String> = sku_ids.iter().flatten().collect::<HashSet<_>>().into_iter().collect();
+            let mut priced_by_sku: HashMap<&str, (serde_json::Value, f64)> = HashMap::new();
+            for sku_batch in all_skus.chunks(CARD_FETCH_BATCH_SIZE) {
+                let meta_keys: Vec<String> = sku_batch.iter().map(|sku_id| format!("mtg:tcg:sku_meta:{}", sku_id)).collect();
+                let price_keys: Vec<String> = sku_batch.iter().map(|sku_id| format!("mtg:tcg:sku_price:{}", sku_id)).collect();
+                let metas = Self::json_mget(con, &meta_keys).await?;
+                let sku_prices = Self::json_mget(con, &price_keys).await?;
+                for ((sku_id, meta), price) in sku_batch.iter().zip(metas).zip(sku_prices) {
+                    if let (Some(meta), Some(price)) = (meta, price.and_then(|price| price["tcg_market_price"].as_f64())) {
+                        priced_by_sku.insert(sku_id.as_str(), (meta, price));
+                    }
+                }
+            }
+            
+            for printing_skus in &sku_ids {
+                priced_skus.push(printing_skus.iter()
+                    .filter_map(|sku_id| priced_by_sku.get(sku_id.as_str()).cloned())
+                    .collect());
+            }
+        }
+        Ok(priced_skus)
+    }
+
+    // Price of the NM English SKU in one finish, else of any SKU in that finish
+    fn preferred_sku_price(priced: &[(serde_json::Value, f64)], foil: bool) -> Option<f64> {
+        let in_finish: Vec<&(serde_json::Value, f64)> = priced.iter()
+            .filter(|(meta, _)| meta["foil"].as_bool().unwrap_or(false) == foil)
+            .collect();
+        let is_preferred = |meta: &serde_json::Value| {
+            let is_near_mint = meta["condition"].as_str().is_none_or(|c| c.eq_ignore_ascii_case("near mint"));
+            let is_english = meta["language"].as_str().is_none_or(|l| l.eq_ignore_ascii_case("english"));
+            is_near_mint && is_english
+        };
+        in_finish.iter()
+            .find(|(meta, _)| is_preferred(meta))
+            .or_else(|| in_finish.first())
+            .map(|(_, price)| *price)
     }
 
     pub async fn get_deck_statistics(&mut self) -> Result<serde_json::Value> {
@@ -530,7 +2065,7 @@ impl MTGRedisClient {
     }
 
     pub async fn get_commander_decks(&mut self) -> Result<Vec<serde_json::Value>> {
-        let mut con = self.client.get_multiplexed_async_connection().await?;
+        let mut con = self.connection().await?;
         
         // Use RediSearch to find commander decks
         let search_result: redis::RedisResult<Vec<redis::Value>> = redis::cmd("FT.SEARCH")
@@ -574,8 +2109,11 @@ impl MTGRedisClient {
         Ok(Vec::new())
     }
 
-    pub async fn get_expensive_decks(&mut self, min_value: f64) -> Result<Vec<serde_json::Value>> {
-        let mut con = self.client.get_multiplexed_async_connection().await?;
+    /// Decks worth at least `min_value` at market price, most valuable first. With
+    /// `exclude_basics` the values (and the threshold) leave out basic lands; the
+    /// candidates still come from the indexed value, which is never lower.
+    pub async fn get_expensive_decks(&mut self, min_value: f64, exclude_basics: bool) -> Result<Vec<serde_json::Value>> {
+        let mut con = self.connection().await?;
         
         // Use RediSearch to find expensive decks
         let search_query = format!("@market_value:[{} +inf]", min_value);
@@ -612,11 +2150,24 @@ impl MTGRedisClient {
             }
         }
         
+        if exclude_basics {
+            for deck in &mut decks {
+                let value = deck.get("estimated_value")
+                    .and_then(|value| serde_json::from_value::<DeckValue>(value.clone()).ok());
+                if let Some(value) = value {
+                    deck["estimated_value"] = serde_json::to_value(value.with_basics_excluded(true))?;
+                }
+            }
+            let market_total = |deck: &serde_json::Value| deck["estimated_value"]["market_total"].as_f64().unwrap_or(0.0);
+            decks.retain(|deck| market_total(deck) >= min_value);
+            decks.sort_by(|a, b| market_total(b).total_cmp(&market_total(a)));
+        }
+        
         Ok(decks)
     }
 
     pub async fn search_decks_by_name(&mut self, deck_name: &str) -> Result<Vec<serde_json::Value>> {
-        let mut con = self.client.get_multiplexed_async_connection().await?;
+        let mut con = self.connection().await?;
         
         // Use RediSearch to search decks by name
         let search_query = if deck_name.is_empty() {
@@ -664,8 +2215,17 @@ impl MTGRedisClient {
     // DECK OPERATIONS - Direct Redis Access
     // =============================================================================
 
+    /// Map a deck id from the old un-normalized scheme to its current id.
+    /// Ids with no mapping are returned unchanged.
+    pub async fn resolve_deck_uuid(&mut self, uuid: &str) -> Result<String> {
+        let mut con = self.connection().await?;
+        let current: Option<String> = con.get(format!("mtg:decks:legacy_uuid:{}", uuid)).await?;
+        Ok(current.unwrap_or_else(|| uuid.to_string()))
+    }
+
     pub async fn get_deck_by_uuid(&mut self, uuid: &str) -> Result<Option<IndexedDeck>> {
-        let mut con = self.client.get_multiplexed_async_connection().await?;
+        let uuid = self.resolve_deck_uuid(uuid).await?;
+        let mut con = self.connection().await?;
         
         let key = format!("mtg:decks:data:{}", uuid);
         
@@ -688,22 +2248,168 @@ impl MTGRedisClient {
                     Ok(None)
                 }
             }
-            None => Ok(None),
+            None => Ok(None),
+        }
+    }
+
+    /// Decks most similar to `uuid`, by Jaccard similarity of their commander + main board
+    /// card names (names, so reprints of the same card in another precon still match).
+    /// Candidates are decks sharing at least one of the deck's rarest cards, limited to the
+    /// same commander-ness and, for non-commander decks, the same deck type. None when the
+    /// deck doesn't exist.
+    pub async fn get_similar_decks(&mut self, uuid: &str, limit: usize) -> Result<Option<Vec<(IndexedDeck, f64)>>> {
+        let deck = match self.get_deck_by_uuid(uuid).await? {
+            Some(deck) => deck,
+            None => return Ok(None),
+        };
+        let mut con = self.connection().await?;
+        
+        // Rarest cards first: the fewer decks a printing appears in, the more it says about this one
+        let deck_cards: Vec<&DeckCardInfo> = deck.commanders.iter().chain(deck.main_board.iter()).collect();
+        let mut pipe = redis::pipe();
+        for card in &deck_cards {
+            pipe.cmd("SCARD").arg(format!("mtg:cards:decks:{}", card.uuid));
+        }
+        let deck_counts: Vec<usize> = pipe.query_async(&mut con).await?;
+        let mut by_rarity: Vec<(&DeckCardInfo, usize)> = deck_cards.iter().copied().zip(deck_counts).collect();
+        by_rarity.sort_by_key(|(card, count)| (*count, card.name.clone()));
+        
+        // Follow each signature card through every printing of it, so other sets' precons count
+        let mut shared_signatures: HashMap<String, usize> = HashMap::new();
+        let mut seen_names = HashSet::new();
+        for (card, _) in by_rarity {
+            if seen_names.len() >= SIMILAR_DECK_SIGNATURE_CARDS {
+                break;
+            }
+            let name_key = name_index_key(&card.name);
+            if !seen_names.insert(name_key.clone()) {
+                continue;
+            }
+            
+            let oracle_ids: Vec<String> = con.smembers(format!("mtg:cards:name_to_oracle:{}", name_key)).await?;
+            let mut printing_keys: Vec<String> = vec![format!("mtg:cards:decks:{}", card.uuid)];
+            for oracle_id in oracle_ids {
+                let printings: Vec<String> = con.smembers(format!("mtg:cards:oracle_to_printings:{}", oracle_id)).await?;
+                printing_keys.extend(printings.iter().map(|printing| format!("mtg:cards:decks:{}", printing)));
+            }
+            let decks: Vec<String> = con.sunion(&printing_keys).await?;
+            for other in decks.into_iter().filter(|other| *other != deck.uuid) {
+                *shared_signatures.entry(other).or_insert(0) += 1;
+            }
+        }
+        
+        let mut candidates: Vec<(String, usize)> = shared_signatures.into_iter().collect();
+        candidates.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        candidates.truncate(SIMILAR_DECK_CANDIDATES);
+        
+        let keys: Vec<String> = candidates.iter().map(|(other, _)| format!("mtg:decks:data:{}", other)).collect();
+        let docs = Self::json_mget(&mut con, &keys).await?;
+        
+        let names_of = |deck: &IndexedDeck| -> HashSet<String> {
+            deck.commanders.iter()
+                .chain(deck.main_board.iter())
+                .map(|card| name_index_key(&card.name))
+                .collect()
+        };
+        let deck_names = names_of(&deck);
+        
+        let mut similar: Vec<(IndexedDeck, f64)> = docs
+            .into_iter()
+            .flatten()
+            .filter_map(|doc| serde_json::from_value::<IndexedDeck>(doc).ok())
+            .filter(|other| other.is_commander == deck.is_commander && (deck.is_commander || other.deck_type == deck.deck_type))
+            .map(|other| {
+                let other_names = names_of(&other);
+                let union = deck_names.union(&other_names).count();
+                let jaccard = if union == 0 { 0.0 } else { deck_names.intersection(&other_names).count() as f64 / union as f64 };
+                (other, jaccard)
+            })
+            .collect();
+        
+        similar.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        similar.truncate(limit);
+        Ok(Some(similar))
+    }
+
+    pub async fn get_decks_by_type(&mut self, deck_type: &str) -> Result<HashSet<String>> {
+        let mut con = self.connection().await?;
+        let key = format!("deck:type:{}", deck_type);
+        let deck_uuids = con.smembers(&key).await?;
+        Ok(deck_uuids)
+    }
+
+    pub async fn get_decks_in_set(&mut self, set_code: &str) -> Result<HashSet<String>> {
+        let mut con = self.connection().await?;
+        let key = format!("deck:set:{}", set_code);
+        let deck_uuids = con.smembers(&key).await?;
+        Ok(deck_uuids)
+    }
+
+    pub async fn get_decks_by_commander_pair(&mut self, first: &str, second: &str) -> Result<Vec<IndexedDeck>> {
+        let mut con = self.connection().await?;
+        let key = format!("mtg:decks:commander_pair:{}", commander_pair_key(first, second));
+        let deck_uuids: HashSet<String> = con.smembers(&key).await?;
+
+        let mut decks = Vec::new();
+        for deck_uuid in deck_uuids {
+            if let Some(deck) = self.get_deck_by_uuid(&deck_uuid).await? {
+                decks.push(deck);
+            }
+        }
+
+        Ok(decks)
+    }
+
+    /// Commander decks by their commanders' combined color identity. With `exact` the
+    /// identity must equal `colors`; otherwise any deck whose identity fits within
+    /// `colors` matches (every subset, including colorless). An empty `colors` means
+    /// colorless. Decks are sorted by name.
+    pub async fn find_decks_by_commander_color_identity(&mut self, colors: &[String], exact: bool) -> Result<Vec<IndexedDeck>> {
+        let identity: Vec<char> = color_identity_key(colors).chars().collect();
+        let keys: Vec<String> = if exact {
+            vec![format!("mtg:decks:color_identity:{}", identity.iter().collect::<String>())]
+        } else {
+            (0..1u32 << identity.len())
+                .map(|mask| {
+                    let subset: String = identity.iter()
+                        .enumerate()
+                        .filter(|(i, _)| mask & (1 << i) != 0)
+                        .map(|(_, symbol)| *symbol)
+                        .collect();
+                    format!("mtg:decks:color_identity:{}", subset)
+                })
+                .collect()
+        };
+        
+        let mut con = self.connection().await?;
+        let deck_uuids: HashSet<String> = con.sunion(&keys).await?;
+        
+        // Identity sets hold current deck uuids, so the documents load directly
+        let deck_keys: Vec<String> = deck_uuids.iter().map(|uuid| format!("mtg:decks:data:{}", uuid)).collect();
+        let mut decks = Vec::with_capacity(deck_keys.len());
+        for batch in deck_keys.chunks(CARD_FETCH_BATCH_SIZE) {
+            for doc in Self::json_mget(&mut con, batch).await?.into_iter().flatten() {
+                decks.push(serde_json::from_value::<IndexedDeck>(doc)?);
+            }
         }
+        decks.sort_by(|a, b| a.name.cmp(&b.name));
+        
+        Ok(decks)
     }
 
-    pub async fn get_decks_by_type(&mut self, deck_type: &str) -> Result<HashSet<String>> {
-        let mut con = self.client.get_multiplexed_async_connection().await?;
-        let key = format!("deck:type:{}", deck_type);
-        let deck_uuids = con.smembers(&key).await?;
-        Ok(deck_uuids)
-    }
+    pub async fn get_decks_by_archetype(&mut self, tag: &str) -> Result<Vec<IndexedDeck>> {
+        let mut con = self.connection().await?;
+        let key = format!("mtg:decks:archetype:{}", tag.to_lowercase());
+        let deck_uuids: HashSet<String> = con.smembers(&key).await?;
 
-    pub async fn get_decks_in_set(&mut self, set_code: &str) -> Result<HashSet<String>> {
-        let mut con = self.client.get_multiplexed_async_connection().await?;
-        let key = format!("deck:set:{}", set_code);
-        let deck_uuids = con.smembers(&key).await?;
-        Ok(deck_uuids)
+        let mut decks = Vec::new();
+        for deck_uuid in deck_uuids {
+            if let Some(deck) = self.get_deck_by_uuid(&deck_uuid).await? {
+                decks.push(deck);
+            }
+        }
+
+        Ok(decks)
     }
 
     // =============================================================================
@@ -711,27 +2417,219 @@ impl MTGRedisClient {
     // =============================================================================
 
     pub async fn get_card_price(&mut self, uuid: &str, condition: &str) -> Result<Option<TcgPrice>> {
-        let mut con = self.client.get_multiplexed_async_connection().await?;
+        let mut con = self.connection().await?;
         let key = format!("price:{}:{}", uuid, condition);
         let data: Option<String> = con.get(&key).await?;
         
         match data {
             Some(json_str) => {
-                let price = serde_json::from_str(&json_str)?;
+                let mut price: TcgPrice = serde_json::from_str(&json_str)?;
+                // Records written before confidence was stored carry the raw quantities
+                price.price_confidence.get_or_insert_with(|| price_confidence(price.total_quantity, price.add_to_quantity));
                 Ok(Some(price))
             }
             None => Ok(None),
         }
     }
 
+    /// Latest price of a printing in every condition for one finish ("nonfoil" or "foil"),
+    /// keyed by condition. Follows uuid -> product -> SKUs and reads each SKU's metadata
+    /// and price documents; English SKUs only, and conditions without a price are left out.
+    /// None when the card doesn't exist.
+    pub async fn get_card_prices_all_conditions(&mut self, uuid: &str, finish: &str) -> Result<Option<HashMap<String, TcgPrice>>> {
+        let card = match self.get_card_by_uuid(uuid).await? {
+            Some(card) => card,
+            None => return Ok(None),
+        };
+        let want_foil = finish.eq_ignore_ascii_case("foil");
+        
+        let mut con = self.connection().await?;
+        let (sku_ids, product_id) = Self::card_sku_ids(&mut con, uuid).await?;
+        
+        let meta_keys: Vec<String> = sku_ids.iter().map(|sku_id| format!("mtg:tcg:sku_meta:{}", sku_id)).collect();
+        let price_keys: Vec<String> = sku_ids.iter().map(|sku_id| format!("mtg:tcg:sku_price:{}", sku_id)).collect();
+        let metas = Self::json_mget(&mut con, &meta_keys).await?;
+        let prices = Self::json_mget(&mut con, &price_keys).await?;
+        
+        let mut by_condition = HashMap::new();
+        for (meta, price) in metas.into_iter().zip(prices) {
+            let (meta, price) = match (meta, price) {
+                (Some(meta), Some(price)) => (meta, price),
+                _ => continue,
+            };
+            let is_foil = meta["foil"].as_bool().unwrap_or(false);
+            let is_english = meta["language"].as_str().is_none_or(|language| language.eq_ignore_ascii_case("english"));
+            if is_foil != want_foil || !is_english {
+                continue;
+            }
+            
+            let tcg_market_price = price["tcg_market_price"].as_f64();
+            let tcg_direct_low = price["tcg_direct_low"].as_f64();
+            let tcg_low_price = price["tcg_low_price"].as_f64();
+            if tcg_market_price.is_none() && tcg_direct_low.is_none() && tcg_low_price.is_none() {
+                continue;
+            }
+            
+            let condition = meta["condition"].as_str().unwrap_or("Near Mint").to_string();
+            by_condition.entry(condition.clone()).or_insert_with(|| TcgPrice {
+                tcgplayer_id: meta["product_id"].as_str().map(str::to_string)
+                    .or_else(|| meta["product_id"].as_u64().map(|id| id.to_string()))
+                    .or_else(|| product_id.clone())
+                    .unwrap_or_default(),
+                product_line: "Magic".to_string(),
+                set_name: card.set_name.clone(),
+                product_name: card.name.clone(),
+                title: String::new(),
+                number: card.collector_number.clone(),
+                rarity: card.rarity.clone(),
+                condition,
+                tcg_market_price,
+                tcg_direct_low,
+                tcg_low_price_with_shipping: None,
+                tcg_low_price,
+                total_quantity: None,
+                add_to_quantity: None,
+                tcg_marketplace_price: None,
+                price_confidence: price["price_confidence"].as_f64(),
+            });
+        }
+        
+        Ok(Some(by_condition))
+    }
+
+    /// Market price history for a card in one finish ("nonfoil"/"foil") and condition
+    /// (full name or NM/LP/MP/HP/DMG), over the last `days` days. The card's English SKU
+    /// with that finish and condition is used; if several match, the lowest SKU id wins.
+    pub async fn get_card_price_history(&mut self, uuid: &str, finish: &str, condition: &str, days: u32) -> Result<Option<CardPriceHistory>> {
+        if self.get_card_by_uuid(uuid).await?.is_none() {
+            return Ok(None);
+        }
+        let want_foil = finish.eq_ignore_ascii_case("foil");
+        let condition = Self::condition_name(condition);
+        
+        let mut con = self.connection().await?;
+        let (sku_ids, _) = Self::card_sku_ids(&mut con, uuid).await?;
+        let meta_keys: Vec<String> = sku_ids.iter().map(|sku_id| format!("mtg:tcg:sku_meta:{}", sku_id)).collect();
+        let metas = Self::json_mget(&mut con, &meta_keys).await?;
+        
+        let sku_id = sku_ids.into_iter()
+            .zip(metas)
+            .filter_map(|(sku_id, meta)| {
+                let meta = meta?;
+                let is_foil = meta["foil"].as_bool().unwrap_or(false);
+                let is_english = meta["language"].as_str().is_none_or(|language| language.eq_ignore_ascii_case("english"));
+                let sku_condition = meta["condition"].as_str().unwrap_or("Near Mint");
+                (is_foil == want_foil && is_english && sku_condition.eq_ignore_ascii_case(&condition)).then_some(sku_id)
+            })
+            .min_by_key(|sku_id| (sku_id.parse::<u64>().unwrap_or(u64::MAX), sku_id.clone()));
+        
+        let history = match &sku_id {
+            Some(sku_id) => self.get_sku_price_history(sku_id, days).await?
+                .into_iter()
+                .map(|(price, timestamp)| PricePoint { price, timestamp })
+                .collect(),
+            None => Vec::new(),
+        };
+        
+        Ok(Some(CardPriceHistory {
+            uuid: uuid.to_string(),
+            finish: finish.to_lowercase(),
+            condition,
+            sku_id,
+            history,
+        }))
+    }
+
+    /// Latest retail and buylist prices of a card at TCGPlayer, Cardmarket and Card Kingdom,
+    /// from the `price:vendor:{vendor}:{uuid}` keys written by the indexer. Cardmarket
+    /// prices are in EUR. None when the card doesn't exist.
+    pub async fn get_cross_vendor_prices(&mut self, uuid: &str) -> Result<Option<VendorPrices>> {
+        if self.get_card_by_uuid(uuid).await?.is_none() {
+            return Ok(None);
+        }
+        
+        let mut con = self.connection().await?;
+        let keys: Vec<String> = ["tcgplayer", "cardmarket", "cardkingdom"].iter()
+            .map(|vendor| format!("price:vendor:{}:{}", vendor, uuid))
+            .collect();
+        let mut prices = Self::json_mget(&mut con, &keys).await?
+            .into_iter()
+            .map(|doc| doc.and_then(|doc| serde_json::from_value::<VendorPrice>(doc).ok()));
+        
+        Ok(Some(VendorPrices {
+            uuid: uuid.to_string(),
+            tcgplayer: prices.next().flatten(),
+            cardmarket: prices.next().flatten(),
+            cardkingdom: prices.next().flatten(),
+        }))
+    }
+
+    // SKU ids linked to a card directly or through its TCGPlayer product, sorted and
+    // de-duplicated, plus the product id
+    async fn card_sku_ids(con: &mut MultiplexedConnection, uuid: &str) -> Result<(Vec<String>, Option<String>)> {
+        let mut sku_ids: Vec<String> = con.smembers(format!("mtg:tcg:uuid_skus:{}", uuid)).await?;
+        let product_id: Option<String> = con.get(format!("mtg:tcg:uuid_to_product:{}", uuid)).await?;
+        if let Some(product_id) = &product_id {
+            let product_skus: Vec<String> = con.smembers(format!("mtg:tcg:product_skus:{}", product_id)).await?;
+            sku_ids.extend(product_skus);
+        }
+        sku_ids.sort();
+        sku_ids.dedup();
+        Ok((sku_ids, product_id))
+    }
+
+    // TCGPlayer condition name for the usual abbreviations; other input passes through
+    fn condition_name(condition: &str) -> String {
+        match condition.to_lowercase().as_str() {
+            "nm" => "Near Mint".to_string(),
+            "lp" => "Lightly Played".to_string(),
+            "mp" => "Moderately Played".to_string(),
+            "hp" => "Heavily Played".to_string(),
+            "dmg" => "Damaged".to_string(),
+            _ => condition.to_string(),
+        }
+    }
+
+    // Root documents for `keys` via JSON.MGET, None for missing keys
+    async fn json_mget(con: &mut MultiplexedConnection, keys: &[String]) -> Result<Vec<Option<serde_json::Value>>> {
+        Self::json_mget_path(con, keys, "$").await
+    }
+
+    // First match of a JSONPath in each of `keys`, None for missing keys or paths
+    async fn json_mget_path(con: &mut MultiplexedConnection, keys: &[String], path: &str) -> Result<Vec<Option<serde_json::Value>>> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        
+        let mut cmd = redis::cmd("JSON.MGET");
+        for key in keys {
+            cmd.arg(key);
+        }
+        cmd.arg(path);
+        
+        // One entry per key: a JSON array string for hits, nil for misses
+        let data: Vec<Option<String>> = cmd.query_async(con).await?;
+        let mut docs = Vec::with_capacity(data.len());
+        for json_str in data {
+            let doc = match json_str {
+                Some(json_str) => serde_json::from_str::<Vec<serde_json::Value>>(&json_str)?.into_iter().next(),
+                None => None,
+            };
+            docs.push(doc);
+        }
+        Ok(docs)
+    }
+
     pub async fn get_sku_price_latest(&mut self, sku_id: &str) -> Result<Option<TcgPrice>> {
-        let mut con = self.client.get_multiplexed_async_connection().await?;
+        let mut con = self.connection().await?;
         let key = format!("price:sku:{}:latest", sku_id);
         let data: Option<String> = con.get(&key).await?;
         
         match data {
             Some(json_str) => {
-                let price = serde_json::from_str(&json_str)?;
+                let mut price: TcgPrice = serde_json::from_str(&json_str)?;
+                // Records written before confidence was stored carry the raw quantities
+                price.price_confidence.get_or_insert_with(|| price_confidence(price.total_quantity, price.add_to_quantity));
                 Ok(Some(price))
             }
             None => Ok(None),
@@ -739,8 +2637,8 @@ impl MTGRedisClient {
     }
 
     pub async fn get_sku_price_history(&mut self, sku_id: &str, days: u32) -> Result<Vec<(f64, i64)>> {
-        let mut con = self.client.get_multiplexed_async_connection().await?;
-        let key = format!("price:sku:{}:history", sku_id);
+        let mut con = self.connection().await?;
+        let key = format!("mtg:tcg:price_history:{}", sku_id);
         let end_time = Utc::now().timestamp();
         let start_time = end_time - (days as i64 * 86400);
         
@@ -759,25 +2657,132 @@ impl MTGRedisClient {
         Ok(result)
     }
 
+    /// A deck's market total per index run over the last `days`, oldest first, as
+    /// (value, timestamp) pairs. Legacy deck ids are resolved first.
+    pub async fn get_deck_value_history(&mut self, uuid: &str, days: u32) -> Result<Vec<(f64, i64)>> {
+        let uuid = self.resolve_deck_uuid(uuid).await?;
+        let mut con = self.connection().await?;
+        let key = format!("mtgjson:decks:value_history:{}", uuid);
+        let end_time = Utc::now().timestamp();
+        let start_time = end_time - (days as i64 * 86400);
+        
+        let history: Vec<(String, f64)> = con
+            .zrangebyscore_withscores(&key, start_time, end_time)
+            .await?;
+        
+        // Members are "{timestamp}:{market_total}"
+        let result = history
+            .into_iter()
+            .filter_map(|(member, timestamp)| {
+                let (_, value) = member.split_once(':')?;
+                value.parse::<f64>().ok().map(|value| (value, timestamp as i64))
+            })
+            .collect();
+        
+        Ok(result)
+    }
+
+    /// Cards first indexed on or after `since` (YYYY-MM-DD), newest first. First-seen times
+    /// are kept across reindexes, so the initial run marks every card as new.
+    pub async fn get_new_cards_since(&mut self, since: &str, offset: usize, limit: usize) -> Result<(Vec<IndexedCard>, usize)> {
+        let mut con = self.connection().await?;
+        let since_date = chrono::NaiveDate::parse_from_str(since, "%Y-%m-%d")
+            .with_context(|| format!("Invalid date '{}', expected YYYY-MM-DD", since))?;
+        let min_score = since_date.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp();
+
+        let total: usize = con.zcount("mtgjson:cards:first_seen", min_score, "+inf").await?;
+        let uuids: Vec<String> = con
+            .zrevrangebyscore_limit("mtgjson:cards:first_seen", "+inf", min_score, offset as isize, limit as isize)
+            .await?;
+        let cards = self.get_cards_by_uuids(&uuids).await?;
+
+        Ok((cards, total))
+    }
+
+    /// Legality changes in a format, newest first, optionally limited to those detected on or
+    /// after `since` (YYYY-MM-DD). Changes come from diffing consecutive index runs, so
+    /// nothing is recorded until the second run.
+    pub async fn get_legality_changes(
+        &mut self,
+        format: &str,
+        since: Option<&str>,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(Vec<LegalityChange>, usize)> {
+        let mut con = self.connection().await?;
+        let min_score = match since {
+            Some(since) => {
+                let since_date = chrono::NaiveDate::parse_from_str(since, "%Y-%m-%d")
+                    .with_context(|| format!("Invalid date '{}', expected YYYY-MM-DD", since))?;
+                since_date.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp().to_string()
+            }
+            None => "-inf".to_string(),
+        };
+        let key = format!("mtgjson:legalities:changes:{}", format.to_lowercase());
+
+        let total: usize = con.zcount(&key, &min_score, "+inf").await?;
+        let entries: Vec<String> = con
+            .zrevrangebyscore_limit(&key, "+inf", &min_score, offset as isize, limit as isize)
+            .await?;
+        let changes = entries
+            .iter()
+            .filter_map(|entry| serde_json::from_str(entry).ok())
+            .collect();
+
+        Ok((changes, total))
+    }
+
+    /// Rulings issued on or after `since` (YYYY-MM-DD), oldest first, as (card name, ruling) pairs
+    pub async fn get_rulings_since(&mut self, since: &str) -> Result<Vec<(String, Ruling)>> {
+        let mut con = self.connection().await?;
+        let since_date = chrono::NaiveDate::parse_from_str(since, "%Y-%m-%d")
+            .with_context(|| format!("Invalid date '{}', expected YYYY-MM-DD", since))?;
+        let min_score = since_date.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp();
+        
+        let entries: Vec<String> = con
+            .zrangebyscore("mtg:rulings:by_date", min_score, "+inf")
+            .await?;
+        
+        let rulings = entries
+            .into_iter()
+            .filter_map(|entry| {
+                let value: serde_json::Value = serde_json::from_str(&entry).ok()?;
+                let card_name = value.get("card_name")?.as_str()?.to_string();
+                let ruling = Ruling {
+                    date: value.get("date")?.as_str()?.to_string(),
+                    text: value.get("text")?.as_str()?.to_string(),
+                };
+                Some((card_name, ruling))
+            })
+            .collect();
+        
+        Ok(rulings)
+    }
+
+    /// SKU IDs of a printing: its own SKUs plus those of its TCGPlayer product
     pub async fn get_card_skus(&mut self, uuid: &str) -> Result<HashSet<String>> {
-        let mut con = self.client.get_multiplexed_async_connection().await?;
-        let key = format!("card:{}:skus", uuid);
-        let sku_ids = con.smembers(&key).await?;
-        Ok(sku_ids)
+        let mut con = self.connection().await?;
+        let (sku_ids, _) = Self::card_sku_ids(&mut con, uuid).await?;
+        Ok(sku_ids.into_iter().collect())
     }
 
+    /// Card UUID a SKU was indexed for, from its `mtg:tcg:sku_meta:` document
     pub async fn get_card_by_sku_id(&mut self, sku_id: &str) -> Result<Option<String>> {
-        let mut con = self.client.get_multiplexed_async_connection().await?;
-        let key = format!("sku:{}", sku_id);
-        let card_uuid = con.get(&key).await?;
-        Ok(card_uuid)
+        let mut con = self.connection().await?;
+        let card_uuid = Self::json_mget_path(&mut con, &[format!("mtg:tcg:sku_meta:{}", sku_id)], "$.card_uuid").await?;
+        Ok(card_uuid.into_iter()
+            .flatten()
+            .next()
+            .and_then(|uuid| uuid.as_str().map(String::from)))
     }
 
+    /// Card UUID for a TCGPlayer product ID (regular or etched). Products shared by
+    /// several printings resolve to the lowest UUID so lookups are stable.
     pub async fn get_card_by_tcgplayer_id(&mut self, tcgplayer_id: &str) -> Result<Option<String>> {
-        let mut con = self.client.get_multiplexed_async_connection().await?;
-        let key = format!("tcgplayer:{}", tcgplayer_id);
-        let card_uuid = con.get(&key).await?;
-        Ok(card_uuid)
+        let mut con = self.connection().await?;
+        let key = format!("mtg:tcg:product_cards:{}", tcgplayer_id);
+        let card_uuids: Vec<String> = con.smembers(&key).await?;
+        Ok(card_uuids.into_iter().min())
     }
 
     // =============================================================================
@@ -785,7 +2790,7 @@ impl MTGRedisClient {
     // =============================================================================
 
     pub async fn get_set_by_code(&mut self, set_code: &str) -> Result<Option<SetInfo>> {
-        let mut con = self.client.get_multiplexed_async_connection().await?;
+        let mut con = self.connection().await?;
         let key = format!("set:{}", set_code);
         let data: Option<String> = con.get(&key).await?;
         
@@ -798,63 +2803,100 @@ impl MTGRedisClient {
         }
     }
 
+    /// Codes of every indexed set, sorted. Read from the per-set stats hash each index
+    /// run maintains; indexes written before it existed fall back to a SCAN of `set:*`.
     pub async fn get_all_sets(&mut self) -> Result<Vec<String>> {
-        let mut con = self.client.get_multiplexed_async_connection().await?;
-        let pattern = "set:*";
-        let keys: Vec<String> = con.keys(pattern).await?;
+        let mut con = self.connection().await?;
+        let mut set_codes: Vec<String> = con.hkeys(SET_STATS_KEY).await?;
+        
+        if set_codes.is_empty() {
+            set_codes = self.scan_keys("set:*").await?
+                .into_iter()
+                .filter_map(|key| {
+                    if !key.contains(":cards") && !key.contains(":decks") {
+                        key.strip_prefix("set:").map(String::from)
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+        }
+        set_codes.sort();
+        
+        Ok(set_codes)
+    }
+
+    /// Sets ordered by release date (then code), paged, with the total set count
+    pub async fn get_sets_page(&mut self, offset: usize, limit: usize) -> Result<(Vec<SetInfo>, usize)> {
+        let set_codes = self.get_all_sets().await?;
+        if set_codes.is_empty() {
+            return Ok((Vec::new(), 0));
+        }
         
-        let set_codes = keys
+        let mut con = self.connection().await?;
+        let keys: Vec<String> = set_codes.iter().map(|code| format!("set:{}", code)).collect();
+        let data: Vec<Option<String>> = redis::cmd("MGET").arg(&keys).query_async(&mut con).await?;
+        
+        let mut sets: Vec<SetInfo> = data
             .into_iter()
-            .filter_map(|key| {
-                if !key.contains(":cards") && !key.contains(":decks") {
-                    key.strip_prefix("set:").map(String::from)
-                } else {
-                    None
-                }
-            })
+            .flatten()
+            .filter_map(|json_str| serde_json::from_str(&json_str).ok())
             .collect();
+        sets.sort_by(|a, b| a.release_date.cmp(&b.release_date).then_with(|| a.code.cmp(&b.code)));
         
-        Ok(set_codes)
+        let total = sets.len();
+        let page = sets.into_iter().skip(offset).take(limit).collect();
+        
+        Ok((page, total))
     }
 
     // =============================================================================
     // PRICING OPERATIONS (Using Lua Scripts)
     // =============================================================================
 
+    /// Cards whose near-mint market price is at least `min_price`, most expensive first.
+    /// Read from the indexer's `price_range:*` buckets, so cards priced below its
+    /// `--price-floor` are never listed.
     pub async fn get_expensive_cards(&mut self, min_price: f64, max_results: usize) -> Result<Vec<serde_json::Value>> {
-        let args = vec![min_price.to_string(), max_results.to_string()];
-        let result: redis::Value = self.execute_lua_script_raw("find_expensive_cards", args).await?;
-        
-        match result {
-            redis::Value::Array(items) => {
-                let mut cards = Vec::new();
-                for item in items.iter() {
-                    match item {
-                        redis::Value::BulkString(json_bytes) => {
-                            if let Ok(json_str) = String::from_utf8(json_bytes.clone()) {
-                                if let Ok(card_json) = serde_json::from_str::<serde_json::Value>(&json_str) {
-                                    cards.push(card_json);
-                                }
-                            }
-                        }
-                        redis::Value::SimpleString(json_str) => {
-                            if let Ok(card_json) = serde_json::from_str::<serde_json::Value>(json_str) {
-                                cards.push(card_json);
-                            }
-                        }
-                        _ => {
-                            if let Ok(json_val) = redis_value_to_json(&item) {
-                                cards.push(json_val);
-                            }
-                        }
-                    }
-                }
-                Ok(cards)
-            }
-            _ => {
-                Err(anyhow::anyhow!("Unexpected return type from find_expensive_cards Lua script: {:?}", result))
-            }
+        let mut con = self.connection().await?;
+        
+        // The top `max_results` of each bucket that can hold such prices, plus its match count
+        let buckets = price_buckets_from(min_price);
+        let mut pipe = redis::pipe();
+        for bucket in &buckets {
+            let key = format!("price_range:{}", bucket);
+            pipe.cmd("ZREVRANGEBYSCORE").arg(&key).arg("+inf").arg(min_price)
+                .arg("WITHSCORES").arg("LIMIT").arg(0).arg(max_results);
+            pipe.cmd("ZCOUNT").arg(&key).arg(min_price).arg("+inf");
+        }
+        let replies: Vec<redis::Value> = pipe.query_async(&mut con).await?;
+        
+        let mut priced: Vec<(String, f64)> = Vec::new();
+        let mut total_found = 0;
+        for reply in replies.chunks(2) {
+            priced.extend(redis::from_redis_value::<Vec<(String, f64)>>(&reply[0])?);
+            total_found += redis::from_redis_value::<usize>(&reply[1])?;
         }
+        priced.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        priced.truncate(max_results);
+        
+        let keys: Vec<String> = priced.iter().map(|(uuid, _)| format!("mtg:cards:data:{}", uuid)).collect();
+        let docs = Self::json_mget(&mut con, &keys).await?;
+        
+        Ok(priced.into_iter()
+            .zip(docs)
+            .enumerate()
+            .map(|(i, ((uuid, price), doc))| serde_json::json!({
+                "uuid": uuid,
+                "name": doc.as_ref().and_then(|doc| doc.get("name")),
+                "price": price,
+                "condition": "Near Mint",
+                "set_code": doc.as_ref().and_then(|doc| doc.get("set_code")),
+                "rank": i + 1,
+                "min_price_threshold": min_price,
+                "total_found": total_found,
+            }))
+            .collect())
     }
 
     pub async fn get_trending_cards(&mut self, direction: &str, limit: usize) -> Result<Vec<serde_json::Value>> {
@@ -888,12 +2930,196 @@ impl MTGRedisClient {
         Ok(result)
     }
 
+    /// Image URLs for printing proxies of a deck: commanders then main board, each card
+    /// repeated by its quantity. The sideboard is left out. Cards without a Scryfall id
+    /// are skipped.
+    pub async fn export_deck_proxy(&mut self, deck_uuid: &str) -> Result<Option<Vec<String>>> {
+        let proxy_cards = match self.get_deck_proxy_cards(deck_uuid).await? {
+            Some(proxy_cards) => proxy_cards,
+            None => return Ok(None),
+        };
+
+        let urls = proxy_cards
+            .into_iter()
+            .filter_map(|card| card.image_url.map(|url| std::iter::repeat_n(url, card.count as usize)))
+            .flatten()
+            .collect();
+        Ok(Some(urls))
+    }
+
+    /// Distinct proxy cards for a deck in deck order, with quantities and basic-land flags
+    pub async fn get_deck_proxy_cards(&mut self, deck_uuid: &str) -> Result<Option<Vec<ProxyCard>>> {
+        let deck = match self.get_deck_by_uuid(deck_uuid).await? {
+            Some(deck) => deck,
+            None => return Ok(None),
+        };
+
+        let entries: Vec<&DeckCardInfo> = deck.commanders.iter().chain(deck.main_board.iter()).collect();
+        let uuids: Vec<String> = entries.iter().map(|entry| entry.uuid.clone()).collect();
+        let cards: HashMap<String, IndexedCard> = self
+            .get_cards_by_uuids(&uuids)
+            .await?
+            .into_iter()
+            .map(|card| (card.uuid.clone(), card))
+            .collect();
+
+        let proxy_cards = entries
+            .into_iter()
+            .map(|entry| {
+                let card = cards.get(&entry.uuid);
+                ProxyCard {
+                    name: entry.name.clone(),
+                    // Scryfall's image redirect, so no image URLs need to be indexed
+                    image_url: card.and_then(|card| card.scryfall_id.as_ref()).map(|id| {
+                        format!("https://api.scryfall.com/cards/{}?format=image&version=large", id)
+                    }),
+                    count: entry.count,
+                    is_basic: card.is_some_and(|card| {
+                        card.supertypes.iter().any(|t| t == "Basic") && card.types.iter().any(|t| t == "Land")
+                    }),
+                }
+            })
+            .collect();
+        Ok(Some(proxy_cards))
+    }
+
+    /// One page of a set's cards in natural collector-number order ("2" before "10"), the
+    /// faces of a multi-face card side by side, plus the set's total card count. None
+    /// when the set has no indexed cards.
+    pub async fn get_set_cards_page(&mut self, set_code: &str, offset: usize, limit: usize) -> Result<Option<(Vec<IndexedCard>, usize)>> {
+        let uuids: Vec<String> = self.get_cards_in_set(&set_code.to_uppercase()).await?
+            .into_iter()
+            .collect();
+        
+        let mut cards = Vec::new();
+        for chunk in uuids.chunks(CARD_FETCH_BATCH_SIZE) {
+            cards.extend(self.get_cards_by_uuids(chunk).await?);
+        }
+        if cards.is_empty() {
+            return Ok(None);
+        }
+        cards.sort_by(|a, b| collector_number_sort_key(&a.collector_number).cmp(&collector_number_sort_key(&b.collector_number))
+            .then_with(|| a.side.cmp(&b.side))
+            .then_with(|| a.uuid.cmp(&b.uuid)));
+        
+        let total = cards.len();
+        let page = cards.into_iter().skip(offset).take(limit).collect();
+        Ok(Some((page, total)))
+    }
+
+    /// Every card in a set as CSV, one row per card (the front face of multi-face cards),
+    /// in natural collector-number order. Empty when the set has no indexed cards.
+    pub async fn export_set_csv(&mut self, set_code: &str) -> Result<String> {
+        let uuids: Vec<String> = self.get_cards_in_set(&set_code.to_uppercase()).await?
+            .into_iter()
+            .collect();
+        
+        let mut cards = Vec::new();
+        for chunk in uuids.chunks(CARD_FETCH_BATCH_SIZE) {
+            cards.extend(self.get_cards_by_uuids(chunk).await?
+                .into_iter()
+                .filter(|card| card.side.as_deref().is_none_or(|side| side == "a")));
+        }
+        if cards.is_empty() {
+            return Ok(String::new());
+        }
+        cards.sort_by(|a, b| collector_number_sort_key(&a.collector_number).cmp(&collector_number_sort_key(&b.collector_number))
+            .then_with(|| a.name.cmp(&b.name)));
+        
+        let mut con = self.connection().await?;
+        let uuids: Vec<String> = cards.iter().map(|card| card.uuid.clone()).collect();
+        let priced_skus = Self::priced_skus_batch(&mut con, &uuids).await?;
+        
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer.write_record(["collector_number", "name", "rarity", "mana_cost", "type_line", "market_price", "foil_price"])?;
+        for (card, priced) in cards.iter().zip(priced_skus) {
+            let market_price = Self::preferred_sku_price(&priced, false);
+            let foil_price = Self::preferred_sku_price(&priced, true);
+            writer.write_record([
+                card.collector_number.as_str(),
+                card.name.as_str(),
+                card.rarity.as_str(),
+                card.mana_cost.as_deref().unwrap_or(""),
+                type_line(card).as_str(),
+                market_price.map(|p| format!("{:.2}", p)).unwrap_or_default().as_str(),
+                foil_price.map(|p| format!("{:.2}", p)).unwrap_or_default().as_str(),
+            ])?;
+        }
+        
+        let csv_data = String::from_utf8(writer.into_inner()?)?;
+        Ok(csv_data)
+    }
+
+    /// A deck as a Cockatrice `.cod` file: commanders and main board in the `main` zone,
+    /// sideboard in `side`, with foil and non-foil copies of a card merged into one entry.
+    /// Double-faced, flip and adventure cards are listed by their front face name. None
+    /// when the deck doesn't exist.
+    pub async fn export_deck_cockatrice(&mut self, deck_uuid: &str) -> Result<Option<String>> {
+        let deck = match self.get_deck_by_uuid(deck_uuid).await? {
+            Some(deck) => deck,
+            None => return Ok(None),
+        };
+        
+        let uuids: Vec<String> = deck.commanders.iter()
+            .chain(&deck.main_board)
+            .chain(&deck.side_board)
+            .map(|card| card.uuid.clone())
+            .collect();
+        let layouts: HashMap<String, String> = self.get_cards_by_uuids(&uuids).await?
+            .into_iter()
+            .map(|card| (card.uuid, card.layout))
+            .collect();
+        
+        Ok(Some(Self::cockatrice_deck_xml(&deck, &layouts)))
+    }
+
+    // The .cod document for `deck`, given the layout of its cards by uuid
+    fn cockatrice_deck_xml(deck: &IndexedDeck, layouts: &HashMap<String, String>) -> String {
+        let zones = [
+            ("main", deck.commanders.iter().chain(&deck.main_board).collect::<Vec<_>>()),
+            ("side", deck.side_board.iter().collect()),
+        ];
+        
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<cockatrice_deck version=\"1\">\n");
+        xml.push_str(&format!("    <deckname>{}</deckname>\n", xml_escape(&deck.name)));
+        xml.push_str(&format!("    <comments>{} ({})</comments>\n", xml_escape(&deck.deck_type), xml_escape(&deck.code)));
+        for (zone, cards) in zones {
+            // Quantities per name, in first-seen order
+            let mut counts: Vec<(&str, u32)> = Vec::new();
+            for card in cards {
+                let name = match layouts.get(&card.uuid) {
+                    Some(layout) if FRONT_FACE_NAME_LAYOUTS.contains(&layout.as_str()) => {
+                        card.name.split(" // ").next().unwrap_or(&card.name)
+                    }
+                    _ => card.name.as_str(),
+                };
+                match counts.iter_mut().find(|(counted, _)| *counted == name) {
+                    Some((_, count)) => *count += card.count,
+                    None => counts.push((name, card.count)),
+                }
+            }
+            if counts.is_empty() {
+                continue;
+            }
+            
+            xml.push_str(&format!("    <zone name=\"{}\">\n", zone));
+            for (name, count) in counts {
+                xml.push_str(&format!("        <card number=\"{}\" name=\"{}\"/>\n", count, xml_escape(name)));
+            }
+            xml.push_str("    </zone>\n");
+        }
+        xml.push_str("</cockatrice_deck>\n");
+        
+        xml
+    }
+
     pub async fn export_deck_to_tcg_csv(&mut self, deck_uuid: &str) -> Result<String> {
         let formatted_uuid = if deck_uuid.starts_with("deck_") {
             deck_uuid.to_string()
         } else {
             format!("deck_{}", deck_uuid)
         };
+        let formatted_uuid = self.resolve_deck_uuid(&formatted_uuid).await?;
         
         let args = vec![formatted_uuid, "single".to_string()];
         let result: String = self.execute_lua_script("export_tcg_csv", args).await?;
@@ -937,6 +3163,103 @@ impl MTGRedisClient {
         Ok(result)
     }
 
+    /// Indexed cards lacking one kind of data, for spotting coverage gaps after an index
+    /// run. `data_type` is one of `MISSING_DATA_TYPES`: `no_price` (no SKU with a stored
+    /// price), `no_image` (no Scryfall id to link an image from), `no_oracle_id` or
+    /// `no_tcgplayer_id`. Returns the count and up to `limit` cards, lowest uuid first.
+    pub async fn get_missing_data(&mut self, data_type: &str, limit: usize) -> Result<MissingDataReport> {
+        let field = match data_type {
+            "no_price" => None,
+            "no_image" => Some("$.scryfall_id"),
+            "no_oracle_id" => Some("$.scryfall_oracle_id"),
+            "no_tcgplayer_id" => Some("$.tcgplayer_product_id"),
+            _ => anyhow::bail!("Unknown missing data type '{}', expected one of {}", data_type, MISSING_DATA_TYPES.join(", ")),
+        };
+        
+        let mut uuids: Vec<String> = self.scan_keys("mtg:cards:data:*").await?
+            .into_iter()
+            .filter_map(|key| key.strip_prefix("mtg:cards:data:").map(str::to_string))
+            .collect();
+        uuids.sort();
+        let total_cards = uuids.len();
+        
+        let mut con = self.connection().await?;
+        let mut missing = Vec::new();
+        match field {
+            Some(path) => {
+                for batch in uuids.chunks(MISSING_DATA_BATCH_SIZE) {
+                    let keys: Vec<String> = batch.iter().map(|uuid| format!("mtg:cards:data:{}", uuid)).collect();
+                    let values = Self::json_mget_path(&mut con, &keys, path).await?;
+                    for (uuid, value) in batch.iter().zip(values) {
+                        let present = value.is_some_and(|value| !value.is_null() && value.as_str() != Some(""));
+                        if !present {
+                            missing.push(uuid.clone());
+                        }
+                    }
+                }
+            }
+            None => {
+                let priced = self.priced_card_uuids(&mut con).await?;
+                missing.extend(uuids.into_iter().filter(|uuid| !priced.contains(uuid)));
+            }
+        }
+        
+        let missing_count = missing.len();
+        missing.truncate(limit);
+        let sample = self.get_cards_by_uuids(&missing).await?
+            .into_iter()
+            .map(|card| CardSummary {
+                uuid: card.uuid,
+                name: card.name,
+                set_code: card.set_code,
+            })
+            .collect();
+        
+        Ok(MissingDataReport {
+            data_type: data_type.to_string(),
+            total_cards,
+            missing_count,
+            sample,
+        })
+    }
+
+    // Cards with at least one priced SKU. A product's SKUs price every printing on that
+    // product; SKUs without a product price the card named in their metadata.
+    async fn priced_card_uuids(&self, con: &mut MultiplexedConnection) -> Result<HashSet<String>> {
+        let sku_ids: Vec<String> = self.scan_keys("mtg:tcg:sku_price:*").await?
+            .into_iter()
+            .filter_map(|key| key.strip_prefix("mtg:tcg:sku_price:").map(str::to_string))
+            .collect();
+        
+        let mut priced = HashSet::new();
+        let mut product_ids = HashSet::new();
+        for batch in sku_ids.chunks(MISSING_DATA_BATCH_SIZE) {
+            let meta_keys: Vec<String> = batch.iter().map(|sku_id| format!("mtg:tcg:sku_meta:{}", sku_id)).collect();
+            for meta in Self::json_mget(con, &meta_keys).await?.into_iter().flatten() {
+                if let Some(uuid) = meta["card_uuid"].as_str() {
+                    priced.insert(uuid.to_string());
+                }
+                match &meta["product_id"] {
+                    serde_json::Value::String(product_id) => { product_ids.insert(product_id.clone()); }
+                    serde_json::Value::Number(product_id) => { product_ids.insert(product_id.to_string()); }
+                    _ => {}
+                }
+            }
+        }
+        
+        let product_ids: Vec<String> = product_ids.into_iter().collect();
+        for batch in product_ids.chunks(MISSING_DATA_BATCH_SIZE) {
+            let mut pipe = redis::pipe();
+            for product_id in batch {
+                pipe.cmd("SMEMBERS").arg(format!("mtg:tcg:product_cards:{}", product_id));
+            }
+            let members: Vec<Vec<String>> = pipe.query_async(con).await?;
+            priced.extend(members.into_iter().flatten());
+        }
+        
+        Ok(priced)
+    }
+
     pub async fn get_set_analysis(&mut self, set_code: &str) -> Result<Vec<serde_json::Value>> {
         let args = if set_code.is_empty() { 
             vec![] 
@@ -968,31 +3291,59 @@ impl MTGRedisClient {
     // ANALYTICS & STATISTICS
     // =============================================================================
 
-    pub async fn get_key_count(&mut self, pattern: &str) -> Result<usize> {
-        let mut con = self.client.get_multiplexed_async_connection().await?;
-        let keys: Vec<String> = con.keys(pattern).await?;
-        Ok(keys.len())
+    /// Number of keys in the database (DBSIZE, constant time)
+    pub async fn get_key_count(&mut self) -> Result<usize> {
+        let mut con = self.connection().await?;
+        let count: usize = redis::cmd("DBSIZE").query_async(&mut con).await?;
+        Ok(count)
+    }
+
+    // Incremental SCAN instead of KEYS, which blocks the server on large keyspaces
+    async fn scan_keys(&self, pattern: &str) -> Result<Vec<String>> {
+        let mut con = self.connection().await?;
+        let mut iter: redis::AsyncIter<String> = con.scan_match(pattern).await?;
+        
+        let mut keys = Vec::new();
+        while let Some(key) = iter.next_item().await {
+            keys.push(key);
+        }
+        
+        Ok(keys)
     }
 
+    /// Card, deck and set counts from the `mtgjson:stats` summary each index run writes
+    /// (and `--decks-only` refreshes), so the stats endpoints don't walk the keyspace.
+    /// All zero before the first index run.
     pub async fn get_database_stats(&mut self) -> Result<DatabaseStats> {
-        let card_count = self.get_key_count("card:*").await.unwrap_or(0);
-        let deck_count = self.get_key_count("deck:*").await.unwrap_or(0);
-        let set_count = self.get_all_sets().await.unwrap_or_default().len();
+        let index_stats = self.get_index_stats().await.unwrap_or(None);
+        let (total_cards, total_decks, total_sets) = index_stats.as_ref()
+            .map_or((0, 0, 0), |stats| (stats.total_cards, stats.deck_count, stats.total_sets));
         
         Ok(DatabaseStats {
-            total_cards: card_count,
-            total_decks: deck_count,
-            total_sets: set_count,
+            total_cards,
+            total_decks,
+            total_sets,
             last_update: Utc::now(),
+            index_stats,
         })
     }
 
+    pub async fn get_index_stats(&mut self) -> Result<Option<IndexStats>> {
+        let mut con = self.connection().await?;
+        let data: Option<String> = con.get("mtgjson:stats").await?;
+        
+        match data {
+            Some(json_str) => Ok(Some(serde_json::from_str(&json_str)?)),
+            None => Ok(None),
+        }
+    }
+
     // =============================================================================
     // UTILITY METHODS
     // =============================================================================
 
     pub async fn ping(&mut self) -> bool {
-        match self.client.get_multiplexed_async_connection().await {
+        match self.connection().await {
             Ok(mut con) => {
                 let result: Result<String, redis::RedisError> = redis::cmd("PING").query_async(&mut con).await;
                 match result {
@@ -1081,6 +3432,36 @@ fn redis_array_to_json_object(fields: &[redis::Value]) -> Result<serde_json::Val
     Ok(serde_json::Value::Object(object))
 }
 
+/// Sort key giving natural collector-number order: "2" < "10" < "10a" < "T1" < "T2" < "T10".
+/// Plain numbers sort by value then suffix ("5" < "5a" < "5★"); numbers with a letter prefix
+/// ("T3", "A01") and numbers without digits come after them, grouped by prefix.
+pub fn collector_number_sort_key(s: &str) -> (u64, String) {
+    let digits_start = s.find(|c: char| c.is_ascii_digit()).unwrap_or(s.len());
+    let digits_len = s[digits_start..]
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(s.len() - digits_start);
+    let prefix = &s[..digits_start];
+    let suffix = &s[digits_start + digits_len..];
+    let number = s[digits_start..digits_start + digits_len].parse::<u64>().ok();
+    
+    match number {
+        Some(number) if prefix.is_empty() => (number, suffix.to_string()),
+        // Zero-pad so the string comparison still orders prefixed numbers numerically
+        Some(number) => (u64::MAX, format!("{}{:020}{}", prefix, number, suffix)),
+        None => (u64::MAX, s.to_string()),
+    }
+}
+
+// "Legendary Creature — Elf Druid" from the card's supertypes, types and subtypes
+fn type_line(card: &IndexedCard) -> String {
+    let types = card.supertypes.iter().chain(&card.types).cloned().collect::<Vec<_>>().join(" ");
+    if card.subtypes.is_empty() {
+        types
+    } else {
+        format!("{} — {}", types, card.subtypes.join(" "))
+    }
+}
+
 // =============================================================================
 // RESPONSE TYPES
 // =============================================================================
@@ -1091,6 +3472,7 @@ pub struct DatabaseStats {
     pub total_decks: usize,
     pub total_sets: usize,
     pub last_update: DateTime<Utc>,
+    pub index_stats: Option<IndexStats>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -1124,4 +3506,162 @@ pub async fn create_mtg_client(redis_url: &str) -> Result<MTGRedisClient> {
 
 pub async fn create_mtg_client_from_env() -> Result<MTGRedisClient> {
     MTGRedisClient::from_env().await
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fallback_filters_map_to_membership_sets() {
+        let (_, query_filters) = MTGRedisClient::split_query_filters("goblin color:R");
+        let filters = HashMap::from([("format".to_string(), "Modern".to_string())]);
+        let mut keys = MTGRedisClient::fallback_filter_set_keys(&query_filters, &filters).unwrap();
+        keys.sort();
+        assert_eq!(keys, ["mtg:cards:color:r", "mtg:cards:legal:modern"]);
+    }
+
+    #[test]
+    fn fallback_rejects_filters_it_cannot_apply() {
+        let (_, negated) = MTGRedisClient::split_query_filters("goblin -color:R");
+        let (_, typed) = MTGRedisClient::split_query_filters("goblin type:Creature");
+        let mana_value = HashMap::from([("mana_value_max".to_string(), "3".to_string())]);
+
+        for (query_filters, filters) in [(negated, HashMap::new()), (typed, HashMap::new()), (Vec::new(), mana_value)] {
+            let error = MTGRedisClient::fallback_filter_set_keys(&query_filters, &filters).unwrap_err();
+            assert!(error.downcast_ref::<SearchIndexMissing>().is_some());
+        }
+    }
+
+    #[test]
+    fn tag_filter_values_are_escaped() {
+        let mut query = "*".to_string();
+        let (_, query_filters) = MTGRedisClient::split_query_filters("set:M}|-@x");
+        let filters = HashMap::from([("border_color".to_string(), "black} | @name:x".to_string())]);
+        MTGRedisClient::append_filter_clauses(&mut query, query_filters, filters).unwrap();
+        assert_eq!(query, r"* @set_code:{M\}\|\-\@x} @border_color:{black\}\ \|\ \@name\:x}");
+    }
+
+    #[test]
+    fn fuzzy_name_terms_drop_punctuation_and_query_syntax() {
+        assert_eq!(MTGRedisClient::fuzzy_name_terms("Will-o'-the-Wisp"), ["will", "o", "the", "%wisp%"]);
+        assert_eq!(MTGRedisClient::fuzzy_name_terms("Fire // Ice"), ["%fire%", "ice"]);
+        assert_eq!(MTGRedisClient::fuzzy_name_terms("Urza's Saga"), ["%urza%", "s", "%saga%"]);
+        assert_eq!(
+            MTGRedisClient::fuzzy_name_terms("bolt|@set_code:{LEA} -goblin"),
+            ["%bolt%", "%set%", "%code%", "lea", "%goblin%"],
+        );
+        assert!(MTGRedisClient::fuzzy_name_terms("// -- |").is_empty());
+    }
+
+    #[test]
+    fn color_count_and_mana_value_max_filter_numeric_ranges() {
+        let mut query = "@name|face_name:(%helix%)".to_string();
+        let filters = HashMap::from([
+            ("color_count".to_string(), "2".to_string()),
+            ("mana_value_max".to_string(), "3".to_string()),
+        ]);
+        MTGRedisClient::append_filter_clauses(&mut query, Vec::new(), filters).unwrap();
+        assert!(query.starts_with("@name|face_name:(%helix%) @"));
+        assert!(query.contains(" @color_count:[2 2]"), "{query}");
+        assert!(query.contains(" @mana_value:[-inf 3]"), "{query}");
+    }
+
+    #[test]
+    fn numeric_filters_must_be_numbers() {
+        let mut query = "*".to_string();
+        let filters = HashMap::from([("mana_value_max".to_string(), "2.5".to_string())]);
+        MTGRedisClient::append_filter_clauses(&mut query, Vec::new(), filters).unwrap();
+        assert_eq!(query, "* @mana_value:[-inf 2.5]");
+
+        for value in ["3]|@name:[0", "inf", "NaN", ""] {
+            let filters = HashMap::from([("color_count".to_string(), value.to_string())]);
+            let error = MTGRedisClient::append_filter_clauses(&mut "*".to_string(), Vec::new(), filters).unwrap_err();
+            assert!(error.downcast_ref::<InvalidSearchFilter>().is_some(), "{value}");
+        }
+    }
+
+    fn printing(uuid: &str, release_date: &str, is_promo: bool, availability: &[&str]) -> IndexedCard {
+        serde_json::from_value(serde_json::json!({
+            "uuid": uuid, "name": "Lightning Bolt", "set_code": "TST", "set_name": "Test",
+            "collector_number": "1", "rarity": "common", "mana_value": 1.0,
+            "colors": ["R"], "color_identity": ["R"], "types": ["Instant"], "subtypes": [],
+            "supertypes": [], "layout": "normal", "availability": availability,
+            "finishes": ["nonfoil"], "has_foil": false, "has_non_foil": true,
+            "is_reserved": false, "is_promo": is_promo, "release_date": release_date,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn primary_printing_is_the_latest_regular_paper_printing() {
+        let printings = vec![
+            printing("a-original", "1993-08-05", false, &["paper"]),
+            printing("b-latest", "2023-11-17", false, &["paper", "mtgo"]),
+            printing("c-promo", "2024-02-09", true, &["paper"]),
+            printing("d-digital", "2024-06-01", false, &["arena"]),
+        ];
+        assert_eq!(MTGRedisClient::primary_printing(printings).unwrap().uuid, "b-latest");
+
+        // Same-day reprints go to the lowest uuid, whatever order SMEMBERS returned
+        let tied = vec![printing("f", "2020-01-01", false, &["paper"]), printing("e", "2020-01-01", false, &["paper"])];
+        assert_eq!(MTGRedisClient::primary_printing(tied).unwrap().uuid, "e");
+
+        // Promos still stand in for cards that only have promo printings
+        let promos = vec![printing("g", "2019-01-01", true, &["paper"])];
+        assert_eq!(MTGRedisClient::primary_printing(promos).unwrap().uuid, "g");
+        assert!(MTGRedisClient::primary_printing(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn cockatrice_export_escapes_names_and_uses_front_faces() {
+        let deck: IndexedDeck = serde_json::from_value(serde_json::json!({
+            "uuid": "deck_1", "name": "Tom & Jerry's <\"Best\">", "code": "T&J",
+            "deck_type": "Commander Deck", "release_date": "2024-01-01", "is_commander": true,
+            "total_cards": 5, "unique_cards": 4,
+            "commanders": [
+                { "uuid": "c1", "name": "Delver of Secrets // Insectile Aberration", "count": 1, "is_foil": true, "set_code": "TST", "tcgplayer_product_id": null },
+            ],
+            "main_board": [
+                { "uuid": "m1", "name": "Fire // Ice", "count": 1, "is_foil": false, "set_code": "TST", "tcgplayer_product_id": null },
+                { "uuid": "m2", "name": "Delver of Secrets // Insectile Aberration", "count": 2, "is_foil": false, "set_code": "TST", "tcgplayer_product_id": null },
+            ],
+            "side_board": [
+                { "uuid": "s1", "name": "Ertai's Meddling", "count": 1, "is_foil": false, "set_code": "TST", "tcgplayer_product_id": null },
+            ],
+            "estimated_value": null,
+        }))
+        .unwrap();
+        let layouts = HashMap::from([
+            ("c1".to_string(), "transform".to_string()),
+            ("m1".to_string(), "split".to_string()),
+            ("m2".to_string(), "transform".to_string()),
+        ]);
+
+        assert_eq!(
+            MTGRedisClient::cockatrice_deck_xml(&deck, &layouts),
+            concat!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<cockatrice_deck version=\"1\">\n",
+                "    <deckname>Tom &amp; Jerry&apos;s &lt;&quot;Best&quot;&gt;</deckname>\n",
+                "    <comments>Commander Deck (T&amp;J)</comments>\n",
+                "    <zone name=\"main\">\n",
+                "        <card number=\"3\" name=\"Delver of Secrets\"/>\n",
+                "        <card number=\"1\" name=\"Fire // Ice\"/>\n",
+                "    </zone>\n",
+                "    <zone name=\"side\">\n",
+                "        <card number=\"1\" name=\"Ertai&apos;s Meddling\"/>\n",
+                "    </zone>\n",
+                "</cockatrice_deck>\n",
+            ),
+        );
+    }
+
+    #[test]
+    fn collector_numbers_sort_naturally() {
+        let ordered = ["2", "5", "5★", "10", "10a", "A01", "T1", "T10", "★5"];
+        for pair in ordered.windows(2) {
+            assert!(collector_number_sort_key(pair[0]) < collector_number_sort_key(pair[1]), "{} < {}", pair[0], pair[1]);
+        }
+        // Leading zeros don't change a prefixed number's place
+        assert_eq!(collector_number_sort_key("A01"), collector_number_sort_key("A1"));
+    }
+}
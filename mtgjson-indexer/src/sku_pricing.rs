@@ -1,4 +1,4 @@
-use crate::types::{TcgPrice, TcgplayerSku};
+use crate::types::{price_confidence, TcgPrice, TcgplayerSku};
 use anyhow::{Context, Result};
 use redis::{Client, Connection, Commands, Pipeline};
 use serde_json::json;
@@ -63,6 +63,7 @@ impl SkuPricingManager {
             "tcg_market_price": price.tcg_market_price,
             "tcg_direct_low": price.tcg_direct_low,
             "tcg_low_price": price.tcg_low_price,
+            "price_confidence": price_confidence(price.total_quantity, price.add_to_quantity),
             "timestamp": timestamp
         });
 
@@ -78,39 +79,40 @@ impl SkuPricingManager {
                 .arg(market_price);
         }
 
-        // Store SKU metadata (separate from pricing)
+        // Store SKU metadata (separate from pricing); card_uuid maps the SKU back to its card
         let sku_meta = json!({
             "condition": sku.condition.clone().unwrap_or_default(),
             "language": sku.language.clone().unwrap_or_else(|| "English".to_string()),
             "foil": sku.printing.as_deref() == Some("Foil"),
             "product_id": sku.product_id,
             "product_name": price.product_name,
-            "set_name": price.set_name
+            "set_name": price.set_name,
+            "card_uuid": card_uuid
         });
 
-        pipe.cmd("SET")
-            .arg(format!("sku:{}:meta", sku_id))
+        pipe.cmd("JSON.SET")
+            .arg(format!("mtg:tcg:sku_meta:{}", sku_id))
+            .arg("$")
             .arg(sku_meta.to_string());
 
-        // Create bidirectional mapping
-        pipe.cmd("SET")
-            .arg(format!("sku:{}:card", sku_id))
-            .arg(card_uuid);
-
         pipe.cmd("SADD")
-            .arg(format!("card:{}:skus", card_uuid))
+            .arg(format!("mtg:tcg:uuid_skus:{}", card_uuid))
             .arg(&sku_id);
 
         Ok(())
     }
 
-    /// Get card's TCGPlayer product ID
+    /// Get card's TCGPlayer product ID from the indexed card JSON document
     fn get_card_tcgplayer_product_id(&self, con: &mut Connection, card_uuid: &str) -> Result<Option<String>> {
-        let card_data: Option<String> = con.get(format!("card:{}", card_uuid))?;
+        let product_id_json: Option<String> = redis::cmd("JSON.GET")
+            .arg(format!("mtg:cards:data:{}", card_uuid))
+            .arg("$.tcgplayer_product_id")
+            .query(con)?;
         
-        if let Some(json_str) = card_data {
-            if let Ok(card_json) = serde_json::from_str::<serde_json::Value>(&json_str) {
-                return Ok(card_json["tcgplayer_product_id"].as_str().map(|s| s.to_string()));
+        if let Some(json_str) = product_id_json {
+            // JSONPath queries return an array of matches
+            if let Ok(matches) = serde_json::from_str::<Vec<serde_json::Value>>(&json_str) {
+                return Ok(matches.first().and_then(|v| v.as_str()).map(|s| s.to_string()));
             }
         }
         
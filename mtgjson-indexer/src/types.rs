@@ -1,5 +1,10 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+pub use double_metaphone::double_metaphone;
+pub use mtgjson_types::{
+    legacy_card_schema_version, DeckValue, IndexedCard, PurchaseUrls, RelatedCards, TcgPrice,
+    TcgplayerSku, CARD_SCHEMA_VERSION, MIN_READABLE_CARD_SCHEMA_VERSION,
+};
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -15,17 +20,99 @@ pub struct TcgplayerSkusFile {
     pub data: HashMap<String, Vec<TcgplayerSku>>,
 }
 
+/// One card's entry in AllPricesToday.json's `data` map, by game format; only `paper`
+/// has the marketplaces we store
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct CardPriceFormats {
+    #[serde(default)]
+    pub paper: HashMap<String, VendorPriceList>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct VendorPriceList {
+    pub currency: String,
+    pub retail: Option<PricePoints>,
+    pub buylist: Option<PricePoints>,
+}
+
+/// Date -> price series per finish
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PricePoints {
+    pub normal: Option<BTreeMap<String, f64>>,
+    pub foil: Option<BTreeMap<String, f64>>,
+    pub etched: Option<BTreeMap<String, f64>>,
+}
+
+/// Marketplaces whose prices are stored as `price:vendor:{vendor}:{uuid}`
+pub const PRICE_VENDORS: &[&str] = &["tcgplayer", "cardmarket", "cardkingdom"];
+
+/// Latest prices of one card at one marketplace, as stored in `price:vendor:{vendor}:{uuid}`
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct VendorPrice {
+    pub vendor: String,
+    pub currency: String,
+    pub date: String,
+    pub retail_normal: Option<f64>,
+    pub retail_foil: Option<f64>,
+    pub retail_etched: Option<f64>,
+    pub buylist_normal: Option<f64>,
+    pub buylist_foil: Option<f64>,
+    pub buylist_etched: Option<f64>,
+}
+
+impl VendorPrice {
+    /// The most recent price of each finish, None when the vendor has no price at all
+    pub fn from_price_list(vendor: &str, list: &VendorPriceList) -> Option<Self> {
+        let latest = |series: &Option<BTreeMap<String, f64>>| {
+            series.as_ref().and_then(|series| series.iter().next_back()).map(|(date, price)| (date.clone(), *price))
+        };
+        let retail = list.retail.as_ref();
+        let buylist = list.buylist.as_ref();
+        let points = [
+            retail.and_then(|r| latest(&r.normal)),
+            retail.and_then(|r| latest(&r.foil)),
+            retail.and_then(|r| latest(&r.etched)),
+            buylist.and_then(|b| latest(&b.normal)),
+            buylist.and_then(|b| latest(&b.foil)),
+            buylist.and_then(|b| latest(&b.etched)),
+        ];
+        let date = points.iter().flatten().map(|(date, _)| date.clone()).max()?;
+        let [retail_normal, retail_foil, retail_etched, buylist_normal, buylist_foil, buylist_etched] = points.map(|p| p.map(|(_, price)| price));
+
+        Some(Self {
+            vendor: vendor.to_string(),
+            currency: list.currency.clone(),
+            date,
+            retail_normal,
+            retail_foil,
+            retail_etched,
+            buylist_normal,
+            buylist_foil,
+            buylist_etched,
+        })
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Meta {
     pub date: String,
     pub version: String,
 }
 
+/// MTGJSON's Meta.json: just the current build's date and version, for update checks
+#[derive(Debug, Deserialize)]
+pub struct MetaFile {
+    pub data: Meta,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Set {
     pub base_set_size: u32,
     pub block: Option<String>,
+    // Booster configurations keyed by type ("draft", "collector", ...)
+    #[serde(default)]
+    pub booster: Option<BTreeMap<String, serde_json::Value>>,
     pub cards: Vec<CardSet>,
     #[serde(default)]
     pub cardsphere_set_id: Option<u32>,
@@ -62,11 +149,74 @@ pub struct Set {
     pub total_set_size: u32,
     #[serde(default)]
     pub token_set_code: Option<String>,
+    #[serde(default)]
+    pub tokens: Vec<CardToken>,
     pub translations: Translations,
     #[serde(rename = "type")]
     pub set_type: String,
 }
 
+/// A token or emblem from a set's `tokens` list. `related_cards.reverse_related` names
+/// the cards that create it.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CardToken {
+    pub uuid: String,
+    pub name: String,
+    #[serde(default)]
+    pub face_name: Option<String>,
+    pub number: String,
+    pub set_code: String,
+    #[serde(default)]
+    pub colors: Vec<String>,
+    #[serde(default)]
+    pub types: Vec<String>,
+    #[serde(default)]
+    pub subtypes: Vec<String>,
+    #[serde(default)]
+    pub supertypes: Vec<String>,
+    #[serde(default)]
+    pub power: Option<String>,
+    #[serde(default)]
+    pub toughness: Option<String>,
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub related_cards: Option<RelatedCards>,
+}
+
+/// Stored form of a token, as `mtg:tokens:data:{uuid}`
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TokenSummary {
+    pub uuid: String,
+    pub name: String,
+    pub set_code: String,
+    pub collector_number: String,
+    pub colors: Vec<String>,
+    pub types: Vec<String>,
+    pub subtypes: Vec<String>,
+    pub power: Option<String>,
+    pub toughness: Option<String>,
+    pub text: Option<String>,
+}
+
+impl From<&CardToken> for TokenSummary {
+    fn from(token: &CardToken) -> Self {
+        Self {
+            uuid: token.uuid.clone(),
+            name: token.name.clone(),
+            set_code: token.set_code.clone(),
+            collector_number: token.number.clone(),
+            colors: token.colors.clone(),
+            types: token.types.clone(),
+            subtypes: token.subtypes.clone(),
+            power: token.power.clone(),
+            toughness: token.toughness.clone(),
+            text: token.text.clone(),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct CardSet {
@@ -278,19 +428,6 @@ pub struct Identifiers {
     pub tnt_id: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct TcgplayerSku {
-    #[serde(default)]
-    pub condition: Option<String>,
-    #[serde(default)]
-    pub language: Option<String>,
-    #[serde(default)]
-    pub printing: Option<String>,
-    pub product_id: u64,
-    pub sku_id: u64,
-}
-
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ForeignData {
     #[serde(default)]
@@ -364,30 +501,155 @@ pub struct Legalities {
     pub vintage: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct PurchaseUrls {
-    #[serde(default)]
-    pub card_kingdom: Option<String>,
-    #[serde(default)]
-    pub card_kingdom_etched: Option<String>,
-    #[serde(default)]
-    pub card_kingdom_foil: Option<String>,
-    #[serde(default)]
-    pub cardmarket: Option<String>,
-    #[serde(default)]
-    pub tcgplayer: Option<String>,
-    #[serde(default)]
-    pub tcgplayer_etched: Option<String>,
+impl Legalities {
+    // Formats in which the card is currently "Legal" (restricted does not count)
+    pub fn legal_formats(&self) -> Vec<String> {
+        self.statuses()
+            .into_iter()
+            .filter(|(_, status)| *status == "Legal")
+            .map(|(format, _)| format.to_string())
+            .collect()
+    }
+
+    // (format, status) for every format the card is listed in: Legal, Banned, Restricted, ...
+    pub fn statuses(&self) -> Vec<(&'static str, &str)> {
+        let formats = [
+            ("alchemy", &self.alchemy),
+            ("brawl", &self.brawl),
+            ("commander", &self.commander),
+            ("duel", &self.duel),
+            ("explorer", &self.explorer),
+            ("future", &self.future),
+            ("gladiator", &self.gladiator),
+            ("historic", &self.historic),
+            ("historicbrawl", &self.historicbrawl),
+            ("legacy", &self.legacy),
+            ("modern", &self.modern),
+            ("oathbreaker", &self.oathbreaker),
+            ("oldschool", &self.oldschool),
+            ("pauper", &self.pauper),
+            ("paupercommander", &self.paupercommander),
+            ("penny", &self.penny),
+            ("pioneer", &self.pioneer),
+            ("predh", &self.predh),
+            ("premodern", &self.premodern),
+            ("standard", &self.standard),
+            ("standardbrawl", &self.standardbrawl),
+            ("timeless", &self.timeless),
+            ("vintage", &self.vintage),
+        ];
+
+        formats
+            .into_iter()
+            .filter_map(|(format, status)| status.as_deref().map(|status| (format, status)))
+            .collect()
+    }
 }
 
+/// A format status that differs from the previous index run. `previous` / `current`
+/// are None when the card was not listed in the format at that time.
 #[derive(Debug, Deserialize, Serialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct RelatedCards {
-    #[serde(default)]
-    pub reverse_related: Option<Vec<String>>,
-    #[serde(default)]
-    pub spellbook: Option<Vec<String>>,
+pub struct LegalityChange {
+    pub format: String,
+    pub oracle_id: String,
+    pub name: String,
+    pub uuid: String,
+    pub previous: Option<String>,
+    pub current: Option<String>,
+    pub detected_at: String,
+}
+
+// Query parameter TCGplayer reads its affiliate/partner code from, and the host its
+// product URLs are on
+const TCGPLAYER_AFFILIATE_PARAM: &str = "partner";
+const TCGPLAYER_HOST: &str = "tcgplayer.com";
+
+// Vendors MTGJSON only links to through mtgjson.com/links redirects. Those drop any
+// added parameter, and unlike TCGplayer's the vendor URL can't be rebuilt from
+// `identifiers`, so a code for them could never be applied.
+const UNTAGGABLE_AFFILIATE_VENDORS: &[&str] = &["cardkingdom", "cardmarket"];
+
+/// Affiliate codes added to purchase URLs (`--affiliate-tags vendor=code`), keyed by
+/// vendor. Only tcgplayer is supported.
+#[derive(Debug, Clone, Default)]
+pub struct AffiliateTags {
+    codes: HashMap<String, String>,
+}
+
+impl AffiliateTags {
+    /// Parse `vendor=code` pairs, rejecting unknown or untaggable vendors and empty codes
+    pub fn parse(specs: &[String]) -> Result<Self, String> {
+        let mut codes = HashMap::new();
+        for spec in specs {
+            let (vendor, code) = spec.split_once('=')
+                .ok_or_else(|| format!("Affiliate tag '{}' should be vendor=code", spec))?;
+            let vendor = vendor.trim().to_lowercase().replace(['_', '-'], "");
+            if UNTAGGABLE_AFFILIATE_VENDORS.contains(&vendor.as_str()) {
+                return Err(format!(
+                    "Affiliate tags for {} can't be applied: MTGJSON links to it only through mtgjson.com/links redirects, which drop the code. Only tcgplayer is supported",
+                    vendor
+                ));
+            }
+            if vendor != "tcgplayer" {
+                return Err(format!("Unknown affiliate vendor '{}', expected: tcgplayer", vendor));
+            }
+            if code.trim().is_empty() {
+                return Err(format!("Affiliate tag for {} has an empty code", vendor));
+            }
+            codes.insert(vendor, code.trim().to_string());
+        }
+        Ok(Self { codes })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.codes.is_empty()
+    }
+
+    /// Set the TCGplayer partner code on its URLs. MTGJSON's purchase URLs are
+    /// `mtgjson.com/links/...` redirects that would drop the parameter, so they are first
+    /// rebuilt as product URLs from `identifiers`. URLs that still aren't on tcgplayer.com
+    /// (or don't parse) are left untouched. An existing parameter is replaced, so
+    /// applying twice is harmless.
+    pub fn apply(&self, urls: &mut PurchaseUrls, identifiers: &Identifiers) {
+        let Some(code) = self.codes.get("tcgplayer") else {
+            return;
+        };
+        
+        let product_url = |product_id: &Option<String>| {
+            product_id.as_ref().map(|id| format!("https://www.tcgplayer.com/product/{}", id))
+        };
+        if urls.tcgplayer.is_some() {
+            urls.tcgplayer = product_url(&identifiers.tcgplayer_product_id).or(urls.tcgplayer.take());
+        }
+        if urls.tcgplayer_etched.is_some() {
+            urls.tcgplayer_etched = product_url(&identifiers.tcgplayer_etched_product_id).or(urls.tcgplayer_etched.take());
+        }
+        
+        for url in [&mut urls.tcgplayer, &mut urls.tcgplayer_etched].into_iter().flatten() {
+            if let Some(tagged) = with_query_param(url, TCGPLAYER_HOST, TCGPLAYER_AFFILIATE_PARAM, code) {
+                *url = tagged;
+            }
+        }
+    }
+}
+
+// `url` with `param` set to `value`, or None when it doesn't parse or isn't on `host`
+// (or a subdomain of it)
+fn with_query_param(url: &str, host: &str, param: &str, value: &str) -> Option<String> {
+    let mut parsed = reqwest::Url::parse(url).ok()?;
+    let url_host = parsed.host_str()?;
+    if url_host != host && !url_host.ends_with(&format!(".{}", host)) {
+        return None;
+    }
+    let pairs: Vec<(String, String)> = parsed.query_pairs()
+        .filter(|(key, _)| key != param)
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    parsed.query_pairs_mut()
+        .clear()
+        .extend_pairs(pairs)
+        .append_pair(param, value);
+    Some(parsed.into())
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -458,51 +720,45 @@ pub struct Translations {
     pub spanish: Option<String>,
 }
 
-// Simplified card structure optimized for Redis storage and fast querying
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct IndexedCard {
-    pub uuid: String,
-    pub name: String,
-    pub set_code: String,
-    pub set_name: String,
-    pub collector_number: String,
-    pub rarity: String,
-    pub mana_value: f32,
-    pub mana_cost: Option<String>,
-    pub colors: Vec<String>,
-    pub color_identity: Vec<String>,
-    pub types: Vec<String>,
-    pub subtypes: Vec<String>,
-    pub supertypes: Vec<String>,
-    pub power: Option<String>,
-    pub toughness: Option<String>,
-    pub loyalty: Option<String>,
-    pub defense: Option<String>,
-    pub text: Option<String>,
-    pub flavor_text: Option<String>,
-    pub layout: String,
-    pub availability: Vec<String>,
-    pub finishes: Vec<String>,
-    pub has_foil: bool,
-    pub has_non_foil: bool,
-    pub is_reserved: bool,
-    pub is_promo: bool,
-    pub release_date: String,
-    pub scryfall_oracle_id: Option<String>,
-    pub scryfall_id: Option<String>,
-    pub tcgplayer_product_id: Option<String>,
-    pub tcgplayer_skus: Vec<TcgplayerSku>,
-    pub purchase_urls: PurchaseUrls,
-}
-
-#[derive(Debug, Deserialize, Serialize, Clone)]
+// Summary written by the indexer at the end of each run (`mtgjson:stats`)
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct IndexStats {
     pub total_sets: usize,
     pub total_cards: usize,
     pub processed_cards: usize,
+    #[serde(default)]
+    pub deck_count: usize,
+    #[serde(default)]
+    pub cards_with_pricing: usize,
+    #[serde(default)]
+    pub total_skus: usize,
+    #[serde(default)]
+    pub price_coverage_pct: f64,
+    #[serde(default)]
+    pub cards_compressed: bool,
+    #[serde(default)]
+    pub lean_cards: bool,
+    #[serde(default)]
+    pub lean_saved_bytes: u64,    // Purchase URL and SKU JSON left out by --lean-cards
+    #[serde(default)]
+    pub price_floor: f64,
+    #[serde(default)]
+    pub cards_below_price_floor: u64,  // Priced cards kept out of price_range:* by the floor
+    #[serde(default)]
+    pub card_json_bytes: u64,     // Card JSON as stored, before any compression
+    #[serde(default)]
+    pub card_stored_bytes: u64,   // What was written: JSON doc, plus gzip blob when compressed
+    #[serde(default)]
+    pub compression_savings_pct: f64,
+    #[serde(default)]
+    pub avg_decompress_us: f64,   // Sampled read-side cost of --compress-cards
     pub last_update: String,
     pub source: String,
     pub version: String,
+    #[serde(default)]
+    pub mtgjson_date: Option<String>,  // meta.date of the indexed AllPrintings.json
+    #[serde(default = "legacy_card_schema_version")]
+    pub card_schema_version: u32,
 }
 
 #[derive(Debug, Deserialize)]
@@ -546,13 +802,390 @@ pub struct DeckCard {
     pub set_code: String,
     #[serde(default)]
     pub identifiers: Identifiers,
+    #[serde(default)]
+    pub supertypes: Vec<String>,
+    #[serde(default)]
+    pub types: Vec<String>,
 }
 
 fn default_count() -> u32 {
     1
 }
 
-#[derive(Debug, Serialize, Clone)]
+/// A card whose type line contains "Basic Land" (Basic supertype and Land type),
+/// including snow basics and Wastes.
+pub fn is_basic_land(supertypes: &[String], types: &[String]) -> bool {
+    supertypes.iter().any(|t| t == "Basic") && types.iter().any(|t| t == "Land")
+}
+
+/// Per-set shares of `mtgjson:stats`, one field per indexed set code, merged by `--sets` runs
+pub const SET_STATS_KEY: &str = "mtgjson:stats:sets";
+
+// Order-independent key for a Partner / Background commander pair
+pub fn commander_pair_key(first: &str, second: &str) -> String {
+    let mut names = [first.to_lowercase(), second.to_lowercase()];
+    names.sort();
+    format!("{}|{}", names[0], names[1])
+}
+
+/// Key suffix for `mtg:cards:name_to_oracle:`, e.g. "Ancestral Recall" -> "ancestral_recall"
+pub fn name_index_key(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Deck id from the set code and normalized name, so cosmetic MTGJSON renames
+/// (capitalization, punctuation, spacing) keep the same id across reindexes.
+pub fn deck_uuid(code: &str, name: &str) -> String {
+    let key = format!("{}_{}", code.to_lowercase(), name_index_key(name));
+    format!("deck_{}", uuid::Uuid::new_v5(&uuid::Uuid::NAMESPACE_DNS, key.as_bytes()))
+}
+
+/// Id scheme used before names were normalized; only needed to map old ids forward.
+pub fn legacy_deck_uuid(code: &str, name: &str) -> String {
+    let key = format!("{}_{}", code, name);
+    format!("deck_{}", uuid::Uuid::new_v5(&uuid::Uuid::NAMESPACE_DNS, key.as_bytes()))
+}
+
+/// Canonical color identity in WUBRG order, e.g. `["G", "W", "U"]` -> `"WUG"`.
+/// Colorless is the empty string; anything that isn't a color symbol is ignored.
+pub fn color_identity_key(colors: &[String]) -> String {
+    "WUBRG"
+        .chars()
+        .filter(|symbol| colors.iter().any(|color| color.eq_ignore_ascii_case(&symbol.to_string())))
+        .collect()
+}
+
+/// `price_range:{bucket}` sorted sets, scored by near-mint market price, with each
+/// bucket's lowest price. A bucket runs up to the next bucket's lowest price.
+pub const PRICE_BUCKETS: [(&str, f64); 8] = [
+    ("under_1", 0.0),
+    ("1_to_5", 1.0),
+    ("5_to_10", 5.0),
+    ("10_to_25", 10.0),
+    ("25_to_50", 25.0),
+    ("50_to_100", 50.0),
+    ("100_to_500", 100.0),
+    ("over_500", 500.0),
+];
+
+/// The `PRICE_BUCKETS` entry a price is stored under
+pub fn price_bucket(price: f64) -> &'static str {
+    PRICE_BUCKETS.iter()
+        .rev()
+        .find(|(_, lowest)| price >= *lowest)
+        .map_or(PRICE_BUCKETS[0].0, |(bucket, _)| *bucket)
+}
+
+/// Buckets that can hold a price of `min_price` or more
+pub fn price_buckets_from(min_price: f64) -> Vec<&'static str> {
+    PRICE_BUCKETS.iter()
+        .enumerate()
+        .filter(|(i, _)| PRICE_BUCKETS.get(i + 1).is_none_or(|(_, next_lowest)| *next_lowest > min_price))
+        .map(|(_, (bucket, _))| *bucket)
+        .collect()
+}
+
+// Listing depth at which a price is considered 50% reliable
+const PRICE_CONFIDENCE_HALF_DEPTH: f64 = 10.0;
+
+/// Reliability of a TCGPlayer price (0-1) from how many copies are listed.
+/// Uses `total_quantity`, falling back to `add_to_quantity`; a single listing scores ~0.09,
+/// 10 listings 0.5, 90 listings 0.9. No quantity data scores 0.
+pub fn price_confidence(total_quantity: Option<i32>, add_to_quantity: Option<i32>) -> f64 {
+    let depth = total_quantity.or(add_to_quantity).unwrap_or(0).max(0) as f64;
+    depth / (depth + PRICE_CONFIDENCE_HALF_DEPTH)
+}
+
+/// Excerpt of rules text around the first word matching one of `terms`, with every
+/// matching word in the excerpt wrapped in `**`. A word matches when it starts with a
+/// term (case-insensitive), so "fly" highlights "flying". Roughly `radius` bytes of
+/// context are kept on each side, cut at word boundaries. `None` when nothing matches.
+pub fn rules_text_snippet(text: &str, terms: &[String], radius: usize) -> Option<String> {
+    let terms: Vec<String> = terms.iter()
+        .map(|term| term.to_lowercase())
+        .filter(|term| !term.is_empty())
+        .collect();
+    let is_match = |&(start, end): &(usize, usize)| {
+        let word = text[start..end].to_lowercase();
+        terms.iter().any(|term| word.starts_with(term.as_str()))
+    };
+
+    let words = word_spans(text);
+    let (match_start, match_end) = *words.iter().find(|span| is_match(span))?;
+    let from = words.iter()
+        .map(|&(start, _)| start)
+        .find(|&start| start + radius >= match_start)
+        .unwrap_or(match_start);
+    let to = words.iter()
+        .rev()
+        .map(|&(_, end)| end)
+        .find(|&end| end <= match_end + radius)
+        .unwrap_or(match_end);
+    // Keep leading/trailing punctuation when the excerpt reaches either end
+    let from = if words.first().is_some_and(|&(start, _)| start >= from) { 0 } else { from };
+    let to = if words.last().is_some_and(|&(_, end)| end <= to) { text.len() } else { to };
+    let from = floor_char_boundary(text, from);
+    let to = ceil_char_boundary(text, to);
+
+    let mut snippet = String::new();
+    if from > 0 {
+        snippet.push('…');
+    }
+    let mut cursor = from;
+    for span in words.iter().filter(|&&(start, end)| start >= from && end <= to) {
+        if is_match(span) {
+            snippet.push_str(&text[cursor..span.0]);
+            snippet.push_str("**");
+            snippet.push_str(&text[span.0..span.1]);
+            snippet.push_str("**");
+            cursor = span.1;
+        }
+    }
+    snippet.push_str(&text[cursor..to]);
+    if to < text.len() {
+        snippet.push('…');
+    }
+
+    Some(snippet.replace('\n', " "))
+}
+
+// Largest char boundary at or before `index`
+fn floor_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+// Smallest char boundary at or after `index`
+fn ceil_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
+
+// Byte ranges of the words in `text`; apostrophes stay inside words ("opponent's")
+fn word_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        let in_word = c.is_alphanumeric() || c == '\'';
+        match (in_word, start) {
+            (true, None) => start = Some(i),
+            (false, Some(word_start)) => {
+                spans.push((word_start, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(word_start) = start {
+        spans.push((word_start, text.len()));
+    }
+    spans
+}
+
+// Archetype heuristic thresholds, counted by copies over the main board
+const AGGRO_MAX_AVG_MANA_VALUE: f32 = 2.5;
+const AGGRO_MIN_CREATURE_SHARE: f32 = 0.4;
+const CONTROL_MIN_INTERACTION: u32 = 8;
+const RAMP_MIN_SOURCES: u32 = 8;
+
+/// Infer archetype tags from a deck's main board. A deck may get several tags or none:
+/// - `aggro`: nonland average mana value <= 2.5 and at least 40% of nonland cards are creatures
+/// - `control`: at least 8 counterspells / targeted removal spells
+/// - `ramp`: at least 8 nonland cards that add mana or put lands from the library onto the battlefield
+pub fn infer_deck_archetype(deck: &DeckData) -> Vec<String> {
+    let mut nonland_cards = 0;
+    let mut creatures = 0;
+    let mut mana_value_total = 0.0;
+    let mut interaction = 0;
+    let mut ramp_sources = 0;
+
+    for card in &deck.main_board {
+        if card.types.iter().any(|t| t == "Land") {
+            continue;
+        }
+
+        let text = card.text.as_deref().unwrap_or("").to_lowercase();
+        nonland_cards += card.count;
+        mana_value_total += card.mana_value * card.count as f32;
+
+        if card.types.iter().any(|t| t == "Creature") {
+            creatures += card.count;
+        }
+        if text.contains("counter target")
+            || text.contains("destroy target")
+            || text.contains("exile target")
+            || text.contains("damage to target creature")
+            || text.contains("damage to any target")
+        {
+            interaction += card.count;
+        }
+        if text.contains("add {")
+            || (text.contains("search your library for") && text.contains("land") && text.contains("onto the battlefield"))
+        {
+            ramp_sources += card.count;
+        }
+    }
+
+    let mut tags = Vec::new();
+    if nonland_cards > 0 {
+        let avg_mana_value = mana_value_total / nonland_cards as f32;
+        let creature_share = creatures as f32 / nonland_cards as f32;
+        if avg_mana_value <= AGGRO_MAX_AVG_MANA_VALUE && creature_share >= AGGRO_MIN_CREATURE_SHARE {
+            tags.push("aggro".to_string());
+        }
+    }
+    if interaction >= CONTROL_MIN_INTERACTION {
+        tags.push("control".to_string());
+    }
+    if ramp_sources >= RAMP_MIN_SOURCES {
+        tags.push("ramp".to_string());
+    }
+
+    tags
+}
+
+/// Card names and rules-text phrases behind `score_deck_power`. The defaults live in
+/// `data/power_level.json`; edit that file to curate the lists.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PowerLevelLists {
+    pub fast_mana: Vec<String>,
+    pub tutors: Vec<String>,
+    pub tutor_phrases: Vec<String>,
+    pub combo_pieces: Vec<String>,
+    pub interaction_phrases: Vec<String>,
+}
+
+const POWER_LEVEL_LISTS_JSON: &str = include_str!("../data/power_level.json");
+
+impl PowerLevelLists {
+    /// The lists shipped in `data/power_level.json`
+    pub fn builtin() -> serde_json::Result<Self> {
+        serde_json::from_str(POWER_LEVEL_LISTS_JSON)
+    }
+}
+
+/// Estimated Commander bracket (1-5) of a deck and the signals that produced it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PowerEstimate {
+    pub bracket: u8,
+    pub signals: Vec<String>,
+}
+
+// Power rubric: points per signal, and the score each bracket starts at
+const POWER_MAX_FAST_MANA_POINTS: u32 = 3;
+const POWER_MAX_TUTOR_POINTS: u32 = 3;
+const POWER_LOW_CURVE_AVG_MANA_VALUE: f32 = 3.0;
+const POWER_VERY_LOW_CURVE_AVG_MANA_VALUE: f32 = 2.5;
+const POWER_MIN_INTERACTION_SHARE: f32 = 0.15;
+const POWER_BRACKET_THRESHOLDS: [(u32, u8); 4] = [(10, 5), (7, 4), (4, 3), (1, 2)];
+
+/// Estimate a deck's Commander bracket from its commander zone and main board, given
+/// as (card, copies). Points, counted by distinct card:
+/// - fast mana (`fast_mana`): 1 each, at most 3
+/// - tutors (`tutors`, or nonland text matching `tutor_phrases`): 1 each, at most 3
+/// - combo pieces (`combo_pieces`): 2 for one, 3 for two or more
+/// - nonland average mana value: 1 at <= 3.0, 2 at <= 2.5
+/// - interaction: 1 when at least 15% of nonland copies match `interaction_phrases`
+///
+/// A score of 0 is bracket 1, 1-3 bracket 2, 4-6 bracket 3, 7-9 bracket 4 and 10+
+/// bracket 5. Names match the card or either face name, case-insensitively.
+pub fn score_deck_power(cards: &[(&IndexedCard, u32)], lists: &PowerLevelLists) -> PowerEstimate {
+    let lower = |names: &[String]| -> Vec<String> { names.iter().map(|n| n.to_lowercase()).collect() };
+    let fast_mana = lower(&lists.fast_mana);
+    let tutors = lower(&lists.tutors);
+    let tutor_phrases = lower(&lists.tutor_phrases);
+    let combo_pieces = lower(&lists.combo_pieces);
+    let interaction_phrases = lower(&lists.interaction_phrases);
+
+    let mut fast_mana_found = Vec::new();
+    let mut tutors_found = Vec::new();
+    let mut combo_found = Vec::new();
+    let mut nonland_cards = 0;
+    let mut mana_value_total = 0.0;
+    let mut interaction = 0;
+
+    for &(card, count) in cards {
+        let names: Vec<String> = std::iter::once(card.name.as_str())
+            .chain(card.name.split(" // "))
+            .chain(card.face_name.as_deref())
+            .map(|n| n.to_lowercase())
+            .collect();
+        let listed = |list: &[String]| names.iter().any(|n| list.contains(n));
+        let text = card.text.as_deref().unwrap_or("").to_lowercase();
+        let is_land = card.types.iter().any(|t| t == "Land");
+
+        if listed(&fast_mana) && !fast_mana_found.contains(&card.name) {
+            fast_mana_found.push(card.name.clone());
+        }
+        if (listed(&tutors) || (!is_land && tutor_phrases.iter().any(|p| text.contains(p.as_str()))))
+            && !tutors_found.contains(&card.name)
+        {
+            tutors_found.push(card.name.clone());
+        }
+        if listed(&combo_pieces) && !combo_found.contains(&card.name) {
+            combo_found.push(card.name.clone());
+        }
+        if is_land {
+            continue;
+        }
+        nonland_cards += count;
+        mana_value_total += card.mana_value * count as f32;
+        if interaction_phrases.iter().any(|p| text.contains(p.as_str())) {
+            interaction += count;
+        }
+    }
+
+    let mut score = 0;
+    let mut signals = Vec::new();
+    if !fast_mana_found.is_empty() {
+        score += (fast_mana_found.len() as u32).min(POWER_MAX_FAST_MANA_POINTS);
+        signals.push(format!("fast mana: {}", fast_mana_found.join(", ")));
+    }
+    if !tutors_found.is_empty() {
+        score += (tutors_found.len() as u32).min(POWER_MAX_TUTOR_POINTS);
+        signals.push(format!("tutors: {}", tutors_found.join(", ")));
+    }
+    if !combo_found.is_empty() {
+        score += if combo_found.len() >= 2 { 3 } else { 2 };
+        signals.push(format!("combo pieces: {}", combo_found.join(", ")));
+    }
+    if nonland_cards > 0 {
+        let avg_mana_value = mana_value_total / nonland_cards as f32;
+        if avg_mana_value <= POWER_VERY_LOW_CURVE_AVG_MANA_VALUE {
+            score += 2;
+            signals.push(format!("very low curve: average mana value {:.2}", avg_mana_value));
+        } else if avg_mana_value <= POWER_LOW_CURVE_AVG_MANA_VALUE {
+            score += 1;
+            signals.push(format!("low curve: average mana value {:.2}", avg_mana_value));
+        }
+
+        let interaction_share = interaction as f32 / nonland_cards as f32;
+        if interaction_share >= POWER_MIN_INTERACTION_SHARE {
+            score += 1;
+            signals.push(format!(
+                "dense interaction: {} of {} nonland cards ({:.0}%)",
+                interaction, nonland_cards, interaction_share * 100.0
+            ));
+        }
+    }
+
+    let bracket = POWER_BRACKET_THRESHOLDS.iter()
+        .find(|(min_score, _)| score >= *min_score)
+        .map_or(1, |(_, bracket)| *bracket);
+    PowerEstimate { bracket, signals }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct IndexedDeck {
     pub uuid: String,
     pub name: String,
@@ -563,12 +1196,20 @@ pub struct IndexedDeck {
     pub total_cards: u32,
     pub unique_cards: u32,
     pub commanders: Vec<DeckCardInfo>,
+    #[serde(default)]
+    pub commander_pair: Option<(String, String)>,
+    #[serde(default)]
+    pub archetypes: Vec<String>,
+    #[serde(default)]
+    pub commander_color_identity: Vec<String>,  // Combined identity of all commanders, WUBRG order
     pub main_board: Vec<DeckCardInfo>,
     pub side_board: Vec<DeckCardInfo>,
+    #[serde(default)]
+    pub validation_issues: Vec<String>,  // e.g. cards outside the commanders' color identity
     pub estimated_value: Option<DeckValue>,
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DeckCardInfo {
     pub uuid: String,
     pub name: String,
@@ -576,18 +1217,11 @@ pub struct DeckCardInfo {
     pub is_foil: bool,
     pub set_code: String,
     pub tcgplayer_product_id: Option<String>,
+    #[serde(default)]
+    pub is_basic_land: bool,
 }
 
-#[derive(Debug, Serialize, Clone)]
-pub struct DeckValue {
-    pub market_total: f64,
-    pub direct_total: f64,
-    pub low_total: f64,
-    pub cards_with_pricing: u32,
-    pub cards_without_pricing: u32,
-}
-
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct SetInfo {
     pub code: String,
     pub name: String,
@@ -595,23 +1229,460 @@ pub struct SetInfo {
     pub set_type: String,
     pub total_cards: usize,
     pub base_set_size: u32,
+    #[serde(default)]
+    pub is_foil_only: bool,
+    #[serde(default)]
+    pub is_non_foil_only: bool,
+    #[serde(default)]
+    pub is_online_only: bool,
+    #[serde(default)]
+    pub is_foreign_only: bool,
+    #[serde(default)]
+    pub booster_types: Vec<String>,  // Booster configurations, e.g. "draft", "collector"
+}
+
+impl SetInfo {
+    /// Whether a copy from this set is priced from foil SKUs: foil-only sets have no
+    /// nonfoil printing and nonfoil-only sets no foil one, whatever the copy claims
+    pub fn prices_as_foil(&self, is_foil: bool) -> bool {
+        if self.is_foil_only {
+            true
+        } else if self.is_non_foil_only {
+            false
+        } else {
+            is_foil
+        }
+    }
 }
 
+// Scale factor turning a median absolute deviation into a standard deviation estimate
+const MAD_TO_STDDEV: f64 = 1.4826;
+// Spread floor as a fraction of the median, so identical prices (MAD = 0) don't make
+// every small difference an outlier
+const MIN_OUTLIER_SPREAD_FRACTION: f64 = 0.25;
+
+/// Outlier handling for TCGPlayer prices (`--filter-price-outliers`).
+///
+/// Prices are grouped by product (set, name, collector number) and finish, across
+/// conditions, so foil rows ("Near Mint Foil") are never measured against nonfoil ones.
+/// A price is an outlier if it lies more than `max_stddevs` from the group median or
+/// above `max_price`. The spread is a MAD-based standard deviation, because a plain
+/// standard deviation is inflated by the outlier it should detect. Groups with fewer
+/// than 3 prices only get the absolute cap.
 #[derive(Debug, Clone)]
-pub struct TcgPrice {
-    pub tcgplayer_id: String,
-    pub product_line: String,
-    pub set_name: String,
-    pub product_name: String,
-    pub title: String,
-    pub number: String,
-    pub rarity: String,
-    pub condition: String,
-    pub tcg_market_price: Option<f64>,
-    pub tcg_direct_low: Option<f64>,
-    pub tcg_low_price_with_shipping: Option<f64>,
-    pub tcg_low_price: Option<f64>,
-    pub total_quantity: Option<i32>,
-    pub add_to_quantity: Option<i32>,
-    pub tcg_marketplace_price: Option<f64>,
-}
\ No newline at end of file
+pub struct PriceOutlierFilter {
+    pub max_stddevs: f64,
+    pub max_price: Option<f64>,
+    /// Drop outlier prices instead of clamping them to the allowed range
+    pub drop: bool,
+}
+
+impl PriceOutlierFilter {
+    /// Filter every price column in place; returns how many prices were dropped or capped
+    pub fn apply(&self, pricing_data: &mut HashMap<String, Vec<TcgPrice>>) -> usize {
+        let mut groups: HashMap<(String, String, String, &'static str), Vec<&mut TcgPrice>> = HashMap::new();
+        for price in pricing_data.values_mut().flatten() {
+            let finish = price_finish(&price.condition);
+            let key = (price.set_name.clone(), price.product_name.clone(), price.number.clone(), finish);
+            groups.entry(key).or_default().push(price);
+        }
+
+        let columns: [fn(&mut TcgPrice) -> &mut Option<f64>; 5] = [
+            |p| &mut p.tcg_market_price,
+            |p| &mut p.tcg_direct_low,
+            |p| &mut p.tcg_low_price_with_shipping,
+            |p| &mut p.tcg_low_price,
+            |p| &mut p.tcg_marketplace_price,
+        ];
+
+        let mut flagged = 0;
+        for group in groups.values_mut() {
+            for column in columns {
+                let values: Vec<f64> = group.iter_mut().filter_map(|p| *column(p)).collect();
+                let (low, high) = self.allowed_range(&values);
+
+                for price in group.iter_mut() {
+                    let value = column(price);
+                    if let Some(v) = *value {
+                        if v < low || v > high {
+                            *value = if self.drop { None } else { Some(v.clamp(low, high)) };
+                            flagged += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        flagged
+    }
+
+    fn allowed_range(&self, values: &[f64]) -> (f64, f64) {
+        let cap = self.max_price.unwrap_or(f64::INFINITY);
+        if values.len() < 3 {
+            return (0.0, cap);
+        }
+
+        let center = median(values);
+        let deviations: Vec<f64> = values.iter().map(|v| (v - center).abs()).collect();
+        let spread = (median(&deviations) * MAD_TO_STDDEV).max(center * MIN_OUTLIER_SPREAD_FRACTION);
+
+        let low = (center - self.max_stddevs * spread).max(0.0);
+        let high = (center + self.max_stddevs * spread).min(cap);
+        (low.min(high), high)
+    }
+}
+
+// Finish of a TCGPlayer price row; the condition carries it as a suffix ("Near Mint Foil")
+fn price_finish(condition: &str) -> &'static str {
+    let condition = condition.to_ascii_lowercase();
+    if condition.ends_with("etched") {
+        "etched"
+    } else if condition.ends_with("foil") {
+        "foil"
+    } else {
+        "nonfoil"
+    }
+}
+
+fn median(values: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tcg_price(number: &str, condition: &str, market: f64) -> TcgPrice {
+        TcgPrice {
+            tcgplayer_id: "1".to_string(),
+            product_line: "Magic".to_string(),
+            set_name: "Alpha".to_string(),
+            product_name: "Card".to_string(),
+            title: String::new(),
+            number: number.to_string(),
+            rarity: "R".to_string(),
+            condition: condition.to_string(),
+            tcg_market_price: Some(market),
+            tcg_direct_low: None,
+            tcg_low_price_with_shipping: None,
+            tcg_low_price: None,
+            total_quantity: None,
+            add_to_quantity: None,
+            tcg_marketplace_price: None,
+            price_confidence: None,
+        }
+    }
+
+    fn market_prices(pricing_data: &HashMap<String, Vec<TcgPrice>>) -> Vec<Option<f64>> {
+        pricing_data["1"].iter().map(|price| price.tcg_market_price).collect()
+    }
+
+    const CONDITIONS: [&str; 4] = ["Near Mint", "Lightly Played", "Moderately Played", "Heavily Played"];
+
+    #[test]
+    fn prices_fall_in_the_bucket_starting_at_or_below_them() {
+        assert_eq!(price_bucket(0.25), "under_1");
+        assert_eq!(price_bucket(1.0), "1_to_5");
+        assert_eq!(price_bucket(4.99), "1_to_5");
+        assert_eq!(price_bucket(499.99), "100_to_500");
+        assert_eq!(price_bucket(500.0), "over_500");
+        assert_eq!(price_bucket(-1.0), "under_1");
+    }
+
+    #[test]
+    fn expensive_card_lookups_skip_buckets_below_the_minimum() {
+        assert_eq!(price_buckets_from(50.0), ["50_to_100", "100_to_500", "over_500"]);
+        assert_eq!(price_buckets_from(60.0), ["50_to_100", "100_to_500", "over_500"]);
+        assert_eq!(price_buckets_from(1000.0), ["over_500"]);
+        assert_eq!(price_buckets_from(0.0).len(), PRICE_BUCKETS.len());
+    }
+
+    #[test]
+    fn median_of_odd_and_even_counts() {
+        assert_eq!(median(&[3.0, 1.0, 2.0]), 2.0);
+        assert_eq!(median(&[4.0, 1.0, 3.0, 2.0]), 2.5);
+    }
+
+    #[test]
+    fn small_groups_only_get_the_absolute_cap() {
+        let filter = PriceOutlierFilter { max_stddevs: 3.0, max_price: Some(100.0), drop: false };
+        assert_eq!(filter.allowed_range(&[1.0, 500.0]), (0.0, 100.0));
+    }
+
+    #[test]
+    fn allowed_range_uses_median_absolute_deviation() {
+        let filter = PriceOutlierFilter { max_stddevs: 2.0, max_price: None, drop: false };
+        // Median 10, MAD 2 -> spread 2 * 1.4826, above the 25% floor of 2.5
+        let (low, high) = filter.allowed_range(&[6.0, 8.0, 10.0, 12.0, 100.0]);
+        assert!((low - (10.0 - 4.0 * MAD_TO_STDDEV)).abs() < 1e-9);
+        assert!((high - (10.0 + 4.0 * MAD_TO_STDDEV)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn identical_prices_fall_back_to_the_spread_floor() {
+        let filter = PriceOutlierFilter { max_stddevs: 1.0, max_price: None, drop: false };
+        assert_eq!(filter.allowed_range(&[4.0, 4.0, 4.0]), (3.0, 5.0));
+    }
+
+    #[test]
+    fn outliers_are_clamped_or_dropped() {
+        let prices: Vec<TcgPrice> = CONDITIONS.iter()
+            .zip([1.0, 1.1, 0.9, 50.0])
+            .map(|(condition, market)| tcg_price("1", condition, market))
+            .collect();
+
+        let mut clamped = HashMap::from([("1".to_string(), prices.clone())]);
+        let filter = PriceOutlierFilter { max_stddevs: 3.0, max_price: None, drop: false };
+        assert_eq!(filter.apply(&mut clamped), 1);
+        let capped = market_prices(&clamped)[3].unwrap();
+        assert!(capped < 50.0 && capped > 1.1);
+
+        let mut dropped = HashMap::from([("1".to_string(), prices)]);
+        let filter = PriceOutlierFilter { max_stddevs: 3.0, max_price: None, drop: true };
+        assert_eq!(filter.apply(&mut dropped), 1);
+        assert_eq!(market_prices(&dropped)[3], None);
+    }
+
+    #[test]
+    fn foil_prices_are_grouped_apart_from_nonfoil() {
+        let prices: Vec<TcgPrice> = CONDITIONS.iter()
+            .flat_map(|condition| [
+                tcg_price("1", condition, 1.0),
+                tcg_price("1", &format!("{} Foil", condition), 20.0),
+            ])
+            .collect();
+        let mut pricing_data = HashMap::from([("1".to_string(), prices)]);
+
+        let filter = PriceOutlierFilter { max_stddevs: 3.0, max_price: None, drop: false };
+        assert_eq!(filter.apply(&mut pricing_data), 0);
+        assert!(market_prices(&pricing_data).iter().all(|price| matches!(price, Some(p) if *p == 1.0 || *p == 20.0)));
+    }
+
+    fn affiliate_tags(specs: &[&str]) -> AffiliateTags {
+        AffiliateTags::parse(&specs.iter().map(|spec| spec.to_string()).collect::<Vec<_>>()).unwrap()
+    }
+
+    #[test]
+    fn tcgplayer_redirects_become_tagged_product_urls() {
+        let mut urls = PurchaseUrls {
+            tcgplayer: Some("https://mtgjson.com/links/aaa".to_string()),
+            tcgplayer_etched: Some("https://mtgjson.com/links/bbb".to_string()),
+            ..Default::default()
+        };
+        let identifiers = Identifiers {
+            tcgplayer_product_id: Some("12345".to_string()),
+            tcgplayer_etched_product_id: Some("67890".to_string()),
+            ..Default::default()
+        };
+        affiliate_tags(&["tcgplayer=shop"]).apply(&mut urls, &identifiers);
+        assert_eq!(urls.tcgplayer.as_deref(), Some("https://www.tcgplayer.com/product/12345?partner=shop"));
+        assert_eq!(urls.tcgplayer_etched.as_deref(), Some("https://www.tcgplayer.com/product/67890?partner=shop"));
+    }
+
+    #[test]
+    fn redirects_without_a_product_id_are_left_untagged() {
+        let mut urls = PurchaseUrls {
+            tcgplayer: Some("https://mtgjson.com/links/ddd".to_string()),
+            ..Default::default()
+        };
+        affiliate_tags(&["tcgplayer=shop"]).apply(&mut urls, &Identifiers::default());
+        assert_eq!(urls.tcgplayer.as_deref(), Some("https://mtgjson.com/links/ddd"));
+    }
+
+    #[test]
+    fn vendor_urls_keep_their_query_and_replace_an_existing_tag() {
+        let mut urls = PurchaseUrls {
+            tcgplayer: Some("https://www.tcgplayer.com/product/1?Language=English&partner=old".to_string()),
+            ..Default::default()
+        };
+        let tags = affiliate_tags(&["tcgplayer=shop"]);
+        tags.apply(&mut urls, &Identifiers::default());
+        tags.apply(&mut urls, &Identifiers::default());
+        assert_eq!(urls.tcgplayer.as_deref(), Some("https://www.tcgplayer.com/product/1?Language=English&partner=shop"));
+    }
+
+    #[test]
+    fn vendors_only_reachable_through_redirects_are_rejected() {
+        for spec in ["cardkingdom=shop", "card_kingdom=shop", "cardmarket=shop"] {
+            let error = AffiliateTags::parse(&[spec.to_string()]).unwrap_err();
+            assert!(error.contains("mtgjson.com/links"), "{error}");
+        }
+        assert!(AffiliateTags::parse(&["starcity=shop".to_string()]).unwrap_err().starts_with("Unknown affiliate vendor"));
+    }
+
+    #[test]
+    fn missing_urls_are_not_created() {
+        let mut urls = PurchaseUrls::default();
+        let identifiers = Identifiers { tcgplayer_product_id: Some("12345".to_string()), ..Default::default() };
+        affiliate_tags(&["tcgplayer=shop"]).apply(&mut urls, &identifiers);
+        assert_eq!(urls.tcgplayer, None);
+    }
+
+    fn power_card(name: &str, mana_value: f32, card_type: &str, text: &str) -> IndexedCard {
+        serde_json::from_value(serde_json::json!({
+            "uuid": name, "name": name, "set_code": "TST", "set_name": "Test",
+            "collector_number": "1", "rarity": "rare", "mana_value": mana_value,
+            "colors": [], "color_identity": [], "types": [card_type], "subtypes": [],
+            "supertypes": [], "text": text, "layout": "normal", "availability": ["paper"],
+            "finishes": ["nonfoil"], "has_foil": false, "has_non_foil": true,
+            "is_reserved": false, "is_promo": false, "release_date": "2020-01-01",
+        }))
+        .unwrap()
+    }
+
+    // Estimate for one copy of each of `cards`, padded with `filler` copies of a 6-drop
+    // vanilla creature that scores nothing and keeps the curve high
+    fn power_bracket(cards: &[IndexedCard], filler: u32) -> PowerEstimate {
+        let dreadmaw = power_card("Colossal Dreadmaw", 6.0, "Creature", "Trample");
+        let deck: Vec<(&IndexedCard, u32)> = cards.iter()
+            .map(|card| (card, 1))
+            .chain((filler > 0).then_some((&dreadmaw, filler)))
+            .collect();
+        score_deck_power(&deck, &PowerLevelLists::builtin().unwrap())
+    }
+
+    fn fast_mana() -> Vec<IndexedCard> {
+        vec![
+            power_card("Sol Ring", 1.0, "Artifact", "{T}: Add {C}{C}."),
+            power_card("Mana Crypt", 0.0, "Artifact", "{T}: Add {C}{C}."),
+            power_card("Mana Vault", 1.0, "Artifact", "{T}: Add {C}{C}{C}."),
+        ]
+    }
+
+    fn tutors() -> Vec<IndexedCard> {
+        vec![
+            power_card("Demonic Tutor", 2.0, "Sorcery", "Search your library for a card, put that card into your hand, then shuffle."),
+            power_card("Vampiric Tutor", 1.0, "Instant", "Search your library for a card, then shuffle and put that card on top."),
+            power_card("Imperial Seal", 1.0, "Sorcery", "Search your library for a card, then shuffle and put that card on top."),
+        ]
+    }
+
+    #[test]
+    fn builtin_power_level_lists_parse() {
+        let lists = PowerLevelLists::builtin().unwrap();
+        for list in [&lists.fast_mana, &lists.tutors, &lists.tutor_phrases, &lists.combo_pieces, &lists.interaction_phrases] {
+            assert!(!list.is_empty());
+        }
+        assert!(lists.fast_mana.iter().any(|name| name == "Sol Ring"));
+    }
+
+    #[test]
+    fn decks_without_signals_are_bracket_1() {
+        let estimate = power_bracket(&[], 40);
+        assert_eq!(estimate.bracket, 1);
+        assert!(estimate.signals.is_empty());
+    }
+
+    #[test]
+    fn a_single_fast_mana_piece_is_bracket_2() {
+        let estimate = power_bracket(&fast_mana()[..1], 40);
+        assert_eq!(estimate.bracket, 2);
+        assert_eq!(estimate.signals, ["fast mana: Sol Ring"]);
+    }
+
+    #[test]
+    fn fast_mana_and_a_tutor_are_bracket_3() {
+        let cards: Vec<IndexedCard> = fast_mana().into_iter().chain(tutors().into_iter().take(1)).collect();
+        assert_eq!(power_bracket(&cards, 40).bracket, 3);
+    }
+
+    #[test]
+    fn tutors_and_a_combo_piece_are_bracket_4() {
+        let cards: Vec<IndexedCard> = fast_mana().into_iter()
+            .chain(tutors())
+            .chain([power_card("Thassa's Oracle", 2.0, "Creature", "When Thassa's Oracle enters, look at the top X cards of your library.")])
+            .collect();
+        let estimate = power_bracket(&cards, 40);
+        assert_eq!(estimate.bracket, 4);
+        assert!(estimate.signals.contains(&"combo pieces: Thassa's Oracle".to_string()));
+    }
+
+    #[test]
+    fn a_cheap_combo_deck_is_bracket_5() {
+        let cards: Vec<IndexedCard> = fast_mana().into_iter()
+            .chain(tutors())
+            .chain([
+                power_card("Thassa's Oracle", 2.0, "Creature", "When Thassa's Oracle enters, look at the top X cards of your library."),
+                power_card("Underworld Breach", 2.0, "Enchantment", "Each nonland card in your graveyard has escape."),
+            ])
+            .collect();
+        let estimate = power_bracket(&cards, 0);
+        assert_eq!(estimate.bracket, 5);
+        assert!(estimate.signals.iter().any(|signal| signal.starts_with("very low curve")), "{:?}", estimate.signals);
+    }
+
+    fn price_series(points: &[(&str, f64)]) -> Option<BTreeMap<String, f64>> {
+        Some(points.iter().map(|(date, price)| (date.to_string(), *price)).collect())
+    }
+
+    #[test]
+    fn vendor_price_takes_each_finish_latest_point() {
+        let list = VendorPriceList {
+            currency: "USD".to_string(),
+            retail: Some(PricePoints {
+                normal: price_series(&[("2024-05-01", 1.0), ("2024-05-03", 1.5), ("2024-05-02", 9.0)]),
+                foil: price_series(&[("2024-05-02", 4.0)]),
+                etched: None,
+            }),
+            buylist: Some(PricePoints {
+                normal: None,
+                foil: price_series(&[("2024-04-30", 2.0)]),
+                etched: price_series(&[("2024-05-01", 3.0)]),
+            }),
+        };
+        let price = VendorPrice::from_price_list("cardkingdom", &list).unwrap();
+        assert_eq!(price.vendor, "cardkingdom");
+        assert_eq!(price.date, "2024-05-03");  // Newest date across every finish
+        assert_eq!(price.retail_normal, Some(1.5));
+        assert_eq!(price.retail_foil, Some(4.0));
+        assert_eq!(price.retail_etched, None);
+        assert_eq!(price.buylist_normal, None);
+        assert_eq!(price.buylist_foil, Some(2.0));
+        assert_eq!(price.buylist_etched, Some(3.0));
+    }
+
+    #[test]
+    fn vendor_price_is_none_without_any_price() {
+        let empty = VendorPriceList { currency: "EUR".to_string(), retail: None, buylist: None };
+        assert!(VendorPrice::from_price_list("cardmarket", &empty).is_none());
+
+        let empty_series = VendorPriceList {
+            currency: "EUR".to_string(),
+            retail: Some(PricePoints { normal: price_series(&[]), foil: None, etched: None }),
+            buylist: None,
+        };
+        assert!(VendorPrice::from_price_list("cardmarket", &empty_series).is_none());
+    }
+
+    #[test]
+    fn rules_text_snippet_handles_multibyte_text() {
+        let text = "Æther Vial — Whenever a créature enters, you may pay {E}{E}. ★ If you do, draw a card — then discard.";
+        let terms = vec!["draw".to_string()];
+        for radius in 0..text.len() {
+            let snippet = rules_text_snippet(text, &terms, radius).unwrap();
+            assert!(snippet.contains("**draw**"), "radius {radius}: {snippet}");
+        }
+        assert_eq!(
+            rules_text_snippet(text, &terms, 4).unwrap(),
+            "…do, **draw** a…"
+        );
+        assert_eq!(rules_text_snippet(text, &["ÆTHER".to_string()], 0).unwrap(), "**Æther**…");
+        assert!(rules_text_snippet(text, &["vials".to_string()], 10).is_none());
+    }
+
+    #[test]
+    fn char_boundaries_snap_out_of_multibyte_chars() {
+        let text = "a—b";
+        assert_eq!(floor_char_boundary(text, 2), 1);
+        assert_eq!(ceil_char_boundary(text, 2), 4);
+        assert_eq!(floor_char_boundary(text, 99), text.len());
+        assert_eq!(ceil_char_boundary(text, 0), 0);
+    }
+}
@@ -0,0 +1,202 @@
+//! Documents the MTGJSON indexer stores in Redis, shared with the Scryfall importer's API
+//! client so both sides (de)serialize `mtg:cards:data:{uuid}`, SKUs and prices the same way.
+
+use serde::{Deserialize, Serialize};
+
+// Simplified card structure optimized for Redis storage and fast querying.
+// Shared by the indexer (writes) and the API client (reads), hence the serde defaults.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct IndexedCard {
+    pub uuid: String,
+    pub name: String,
+    #[serde(default)]
+    pub face_name: Option<String>,
+    #[serde(default)]
+    pub side: Option<String>,          // "a", "b", ... for multi-face cards
+    #[serde(default)]
+    pub other_face_ids: Vec<String>,
+    pub set_code: String,
+    pub set_name: String,
+    pub collector_number: String,
+    pub rarity: String,
+    pub mana_value: f32,
+    pub mana_cost: Option<String>,
+    pub colors: Vec<String>,
+    pub color_identity: Vec<String>,
+    #[serde(default)]
+    pub color_count: u32,
+    pub types: Vec<String>,
+    pub subtypes: Vec<String>,
+    pub supertypes: Vec<String>,
+    pub power: Option<String>,
+    pub toughness: Option<String>,
+    pub loyalty: Option<String>,
+    pub defense: Option<String>,
+    #[serde(default)]
+    pub attraction_lights: Option<Vec<u32>>,  // Unfinity attractions
+    #[serde(default)]
+    pub hand: Option<String>,                 // Vanguard hand / life modifiers
+    #[serde(default)]
+    pub life: Option<String>,
+    pub text: Option<String>,
+    #[serde(default)]
+    pub original_text: Option<String>,
+    pub flavor_text: Option<String>,
+    #[serde(default)]
+    pub related_cards: RelatedCards,
+    pub layout: String,
+    pub availability: Vec<String>,
+    pub finishes: Vec<String>,
+    pub has_foil: bool,
+    pub has_non_foil: bool,
+    pub is_reserved: bool,
+    pub is_promo: bool,
+    #[serde(default)]
+    pub border_color: String,
+    #[serde(default)]
+    pub is_full_art: bool,
+    #[serde(default)]
+    pub is_textless: bool,
+    #[serde(default)]
+    pub security_stamp: Option<String>,  // oval, triangle, acorn, arena, heart, circle
+    #[serde(default)]
+    pub subsets: Vec<String>,  // e.g. "The List", "Special Guests"
+    #[serde(default)]
+    pub legal_formats: Vec<String>,
+    #[serde(default)]
+    pub edhrec_rank: Option<u32>,
+    pub release_date: String,
+    pub scryfall_oracle_id: Option<String>,
+    pub scryfall_id: Option<String>,
+    pub tcgplayer_product_id: Option<String>,
+    #[serde(default)]
+    pub tcgplayer_etched_product_id: Option<String>,
+    #[serde(default)]
+    pub tcgplayer_skus: Vec<TcgplayerSku>,  // Empty when indexed with --lean-cards
+    #[serde(default)]
+    pub purchase_urls: PurchaseUrls,
+    #[serde(default = "legacy_card_schema_version")]
+    pub schema_version: u32,
+}
+
+/// Layout of the card documents at `mtg:cards:data:{uuid}`. Bump when an `IndexedCard`
+/// field is removed, renamed or changes type; added fields with serde defaults don't
+/// need a bump. Cards written before versioning count as version 1.
+pub const CARD_SCHEMA_VERSION: u32 = 1;
+
+/// Oldest stored card layout the API client can still read
+pub const MIN_READABLE_CARD_SCHEMA_VERSION: u32 = 1;
+
+/// Schema version assumed for documents written before versioning
+pub fn legacy_card_schema_version() -> u32 {
+    1
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TcgplayerSku {
+    #[serde(default)]
+    pub condition: Option<String>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub printing: Option<String>,
+    pub product_id: u64,
+    pub sku_id: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PurchaseUrls {
+    #[serde(default)]
+    pub card_kingdom: Option<String>,
+    #[serde(default)]
+    pub card_kingdom_etched: Option<String>,
+    #[serde(default)]
+    pub card_kingdom_foil: Option<String>,
+    #[serde(default)]
+    pub cardmarket: Option<String>,
+    #[serde(default)]
+    pub tcgplayer: Option<String>,
+    #[serde(default)]
+    pub tcgplayer_etched: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RelatedCards {
+    #[serde(default)]
+    pub reverse_related: Option<Vec<String>>,
+    #[serde(default)]
+    pub spellbook: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeckValue {
+    pub market_total: f64,
+    pub direct_total: f64,
+    pub low_total: f64,
+    pub cards_with_pricing: u32,
+    pub cards_without_pricing: u32,
+    #[serde(default)]
+    pub price_confidence: f64,
+    #[serde(default)]
+    pub basics_excluded: bool,  // Whether the totals above leave out basic lands
+    #[serde(default)]
+    pub basic_land_count: u32,
+    #[serde(default)]
+    pub basic_lands_with_pricing: u32,
+    #[serde(default)]
+    pub basic_land_market_total: f64,
+    #[serde(default)]
+    pub basic_land_direct_total: f64,
+    #[serde(default)]
+    pub basic_land_low_total: f64,
+}
+
+impl DeckValue {
+    /// The same valuation with basic lands left out of (or added back into) the
+    /// totals and pricing counts. `price_confidence` is kept as indexed.
+    pub fn with_basics_excluded(&self, exclude: bool) -> DeckValue {
+        let mut value = self.clone();
+        if exclude == self.basics_excluded {
+            return value;
+        }
+        
+        let sign = if exclude { -1.0 } else { 1.0 };
+        let basics_without_pricing = self.basic_land_count.saturating_sub(self.basic_lands_with_pricing);
+        value.market_total += sign * self.basic_land_market_total;
+        value.direct_total += sign * self.basic_land_direct_total;
+        value.low_total += sign * self.basic_land_low_total;
+        if exclude {
+            value.cards_with_pricing = value.cards_with_pricing.saturating_sub(self.basic_lands_with_pricing);
+            value.cards_without_pricing = value.cards_without_pricing.saturating_sub(basics_without_pricing);
+        } else {
+            value.cards_with_pricing += self.basic_lands_with_pricing;
+            value.cards_without_pricing += basics_without_pricing;
+        }
+        value.basics_excluded = exclude;
+        value
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TcgPrice {
+    pub tcgplayer_id: String,
+    pub product_line: String,
+    pub set_name: String,
+    pub product_name: String,
+    pub title: String,
+    pub number: String,
+    pub rarity: String,
+    pub condition: String,
+    pub tcg_market_price: Option<f64>,
+    pub tcg_direct_low: Option<f64>,
+    pub tcg_low_price_with_shipping: Option<f64>,
+    pub tcg_low_price: Option<f64>,
+    pub total_quantity: Option<i32>,
+    pub add_to_quantity: Option<i32>,
+    pub tcg_marketplace_price: Option<f64>,
+    #[serde(default)]
+    pub price_confidence: Option<f64>,
+}
@@ -1,18 +1,66 @@
 use pyo3::prelude::*;
-use pyo3::types::PyDict;
+use pyo3::types::{PyDict, PyList};
+use std::collections::HashMap;
 
 mod main;
 use main::*;
 
+// In-memory matching needs no Redis, so it is usable from Rust as well as Python
+pub use main::{fuzzy_match, fuzzy_match_with_params, FuzzySearchParams};
+
 // API modules
 pub mod redis_client;
 
 /// Download Scryfall data and build indexes
+///
+/// Progress bars are drawn only when stderr is a terminal; pass
+/// `show_progress=False` to silence progress output entirely.
+///
+/// The download is retried up to `max_retries` times (default 3) with exponential
+/// backoff. On failure the error message names the phase that failed
+/// ("download", "parse" or "store").
+///
+/// `primary_printing` ("latest", the default, or "original") picks which printing
+/// supplies each card's id and image; regular paper printings are preferred either way.
+///
+/// `chunk_size` fixes how many cards each parallel indexing task takes; by default it
+/// is derived from the card count and thread count.
+///
+/// `lang` limits indexing to printings in one Scryfall language code (default "en");
+/// pass "all" to index every language.
+///
+/// `bulk_type` picks the Scryfall bulk file (default "all_cards"); "default_cards" or
+/// "oracle_cards" give a much smaller index. An unknown type fails listing the valid ones.
 #[pyfunction]
-fn download_and_index(redis_url: Option<String>) -> PyResult<String> {
+#[pyo3(signature = (redis_url=None, show_progress=None, max_retries=None, primary_printing=None, chunk_size=None, lang=None, bulk_type=None))]
+fn download_and_index(
+    redis_url: Option<String>,
+    show_progress: Option<bool>,
+    max_retries: Option<u32>,
+    primary_printing: Option<String>,
+    chunk_size: Option<usize>,
+    lang: Option<String>,
+    bulk_type: Option<String>,
+) -> PyResult<String> {
     let redis_url = redis_url.unwrap_or_else(|| "redis://127.0.0.1:9999".to_string());
+    if let Some(enabled) = show_progress {
+        set_progress_enabled(enabled);
+    }
+    let strategy = match primary_printing {
+        Some(strategy) => strategy.parse()
+            .map_err(pyo3::exceptions::PyValueError::new_err)?,
+        None => PrimaryPrintingStrategy::default(),
+    };
+    set_primary_printing_strategy(strategy);
+    set_chunk_size(chunk_size.filter(|&size| size > 0));
+    match lang.as_deref() {
+        Some(lang) if lang.eq_ignore_ascii_case("all") => set_index_language(None),
+        lang => set_index_language(Some(lang.unwrap_or("en"))),
+    }
+    
+    let bulk_type = bulk_type.as_deref().unwrap_or(DEFAULT_BULK_TYPE);
     
-    match run_indexer(&redis_url) {
+    match run_indexer_with_retries(&redis_url, max_retries.unwrap_or(DEFAULT_DOWNLOAD_RETRIES), bulk_type) {
         Ok(stats) => Ok(format!(
             "Successfully indexed {} cards with {} sets", 
             stats.card_count, 
@@ -24,6 +72,59 @@ fn download_and_index(redis_url: Option<String>) -> PyResult<String> {
     }
 }
 
+/// Update an existing index, rewriting only cards that changed since the last run
+///
+/// `previous_meta_date` is the bulk file's `updated_at` from the previous run (see
+/// `get_stats()["bulk_updated_at"]`); if Scryfall still reports the same date nothing is
+/// downloaded. Returns a dict of `added`, `updated`, `deleted` and `skipped` card counts.
+/// `bulk_type` should match the one the index was built from (default "all_cards").
+#[pyfunction]
+#[pyo3(signature = (redis_url=None, previous_meta_date=None, show_progress=None, bulk_type=None))]
+fn update_index(
+    redis_url: Option<String>,
+    previous_meta_date: Option<String>,
+    show_progress: Option<bool>,
+    bulk_type: Option<String>,
+) -> PyResult<PyObject> {
+    let redis_url = redis_url.unwrap_or_else(|| "redis://127.0.0.1:9999".to_string());
+    if let Some(enabled) = show_progress {
+        set_progress_enabled(enabled);
+    }
+    
+    Python::with_gil(|py| {
+        match run_indexer_incremental(&redis_url, previous_meta_date.as_deref(), bulk_type.as_deref().unwrap_or(DEFAULT_BULK_TYPE)) {
+            Ok(stats) => {
+                let changes = stats.changes.unwrap_or_default();
+                let dict = PyDict::new(py);
+                dict.set_item("card_count", stats.card_count)?;
+                dict.set_item("set_count", stats.set_count)?;
+                dict.set_item("bulk_updated_at", &stats.bulk_updated_at)?;
+                dict.set_item("added", changes.added)?;
+                dict.set_item("updated", changes.updated)?;
+                dict.set_item("deleted", changes.deleted)?;
+                dict.set_item("skipped", changes.skipped)?;
+                Ok(dict.into())
+            }
+            Err(e) => Err(pyo3::exceptions::PyRuntimeError::new_err(format!(
+                "Incremental indexing failed: {}", e
+            ))),
+        }
+    })
+}
+
+// The dict shape of a search result
+fn search_result_dict<'py>(py: Python<'py>, card: &IndexedCard) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("id", &card.id)?;
+    dict.set_item("oracle_id", &card.oracle_id)?;
+    dict.set_item("name", &card.name)?;
+    dict.set_item("sets", &card.sets)?;
+    dict.set_item("layout", &card.layout)?;
+    dict.set_item("tcgplayer_ids", &card.tcgplayer_ids)?;
+    dict.set_item("main_image", &card.main_image)?;
+    Ok(dict)
+}
+
 /// Search for cards using fuzzy matching
 #[pyfunction]
 fn search_cards(
@@ -36,23 +137,10 @@ fn search_cards(
     
     Python::with_gil(|py| {
         match search_cards_internal(&query, max_results, &redis_url) {
-            Ok(results) => {
-                let py_results: PyResult<Vec<PyObject>> = results
-                    .into_iter()
-                    .map(|card| {
-                        let dict = PyDict::new(py);
-                        dict.set_item("id", &card.id)?;
-                        dict.set_item("oracle_id", &card.oracle_id)?;
-                        dict.set_item("name", &card.name)?;
-                        dict.set_item("sets", &card.sets)?;
-                        dict.set_item("layout", &card.layout)?;
-                        dict.set_item("tcgplayer_ids", &card.tcgplayer_ids)?;
-                        dict.set_item("main_image", &card.main_image)?;
-                        Ok(dict.into())
-                    })
-                    .collect();
-                py_results
-            }
+            Ok(results) => results
+                .iter()
+                .map(|card| Ok(search_result_dict(py, card)?.into()))
+                .collect(),
             Err(e) => Err(pyo3::exceptions::PyRuntimeError::new_err(format!(
                 "Search failed: {}", e
             ))),
@@ -60,30 +148,91 @@ fn search_cards(
     })
 }
 
+/// Run many fuzzy searches over a single Redis connection, pipelining the script calls
+///
+/// Returns one list per query, in input order, holding the same dicts as
+/// `search_cards`. An empty list means that query had no matches, not an error;
+/// connection and script failures raise `RuntimeError` for the whole batch.
+#[pyfunction]
+fn search_cards_batch(
+    queries: Vec<String>,
+    max_results: Option<usize>,
+    redis_url: Option<String>,
+) -> PyResult<Vec<Vec<PyObject>>> {
+    let redis_url = redis_url.unwrap_or_else(|| "redis://127.0.0.1:9999".to_string());
+    let max_results = max_results.unwrap_or(20);
+    
+    Python::with_gil(|py| {
+        match search_cards_batch_internal(&queries, max_results, &redis_url) {
+            Ok(results) => results
+                .iter()
+                .map(|cards| cards.iter().map(|card| Ok(search_result_dict(py, card)?.into())).collect())
+                .collect(),
+            Err(e) => Err(pyo3::exceptions::PyRuntimeError::new_err(format!(
+                "Batch search failed: {}", e
+            ))),
+        }
+    })
+}
+
+/// Rank candidate names against a query in memory, without Redis
+///
+/// Returns up to `max_results` (default 20) `(name, score)` tuples, best first, using
+/// the same word / trigram / metaphone blend as `search_cards` plus edit distance.
+#[pyfunction]
+#[pyo3(name = "fuzzy_match", signature = (query, candidates, max_results=None))]
+fn fuzzy_match_py(query: String, candidates: Vec<String>, max_results: Option<usize>) -> Vec<(String, f64)> {
+    let candidates: Vec<&str> = candidates.iter().map(String::as_str).collect();
+    fuzzy_match(&query, &candidates, max_results.unwrap_or(20))
+}
+
+// The dict shape shared by the card lookups: a search result plus `prices`, a list of
+// per-printing dicts, or the JSON string older callers expect when `prices_as_json`
+fn card_to_dict<'py>(py: Python<'py>, card: &IndexedCard, prices_as_json: bool) -> PyResult<Bound<'py, PyDict>> {
+    let dict = search_result_dict(py, card)?;
+    if prices_as_json {
+        let prices_json = serde_json::to_string(&card.prices)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Failed to serialize prices: {}", e)))?;
+        dict.set_item("prices", prices_json)?;
+        return Ok(dict);
+    }
+    
+    let prices = PyList::empty(py);
+    for printing in &card.prices {
+        let entry = PyDict::new(py);
+        entry.set_item("set", &printing.set)?;
+        entry.set_item("set_name", &printing.set_name)?;
+        entry.set_item("set_type", &printing.set_type)?;
+        entry.set_item("collector_number", &printing.collector_number)?;
+        entry.set_item("tcgplayer_id", printing.tcgplayer_id)?;
+        entry.set_item("usd", &printing.prices.usd)?;
+        entry.set_item("usd_foil", &printing.prices.usd_foil)?;
+        entry.set_item("eur", &printing.prices.eur)?;
+        entry.set_item("released_at", &printing.released_at)?;
+        entry.set_item("rarity", &printing.rarity)?;
+        prices.append(entry)?;
+    }
+    dict.set_item("prices", prices)?;
+    Ok(dict)
+}
+
 /// Get card details by oracle ID
+///
+/// `prices` is a list of dicts, one per printing, with `set`, `set_name`, `set_type`,
+/// `collector_number`, `tcgplayer_id`, `usd`, `usd_foil`, `eur`, `released_at` and
+/// `rarity`. Pass `prices_as_json=True` to get the old JSON string instead.
 #[pyfunction]
+#[pyo3(signature = (oracle_id, redis_url=None, prices_as_json=None))]
 fn get_card_by_oracle_id(
     oracle_id: String,
     redis_url: Option<String>,
+    prices_as_json: Option<bool>,
 ) -> PyResult<PyObject> {
     let redis_url = redis_url.unwrap_or_else(|| "redis://127.0.0.1:9999".to_string());
     
     Python::with_gil(|py| {
         match get_card_by_oracle_id_internal(&oracle_id, &redis_url) {
-            Ok(Some(card)) => {
-                let dict = PyDict::new(py);
-                dict.set_item("id", &card.id)?;
-                dict.set_item("oracle_id", &card.oracle_id)?;
-                dict.set_item("name", &card.name)?;
-                dict.set_item("sets", &card.sets)?;
-                dict.set_item("layout", &card.layout)?;
-                dict.set_item("tcgplayer_ids", &card.tcgplayer_ids)?;
-                dict.set_item("main_image", &card.main_image)?;
-                let prices_json = serde_json::to_string(&card.prices)
-                    .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Failed to serialize prices: {}", e)))?;
-                dict.set_item("prices", prices_json)?;
-                Ok(dict.into())
-            }
+            Ok(Some(card)) => Ok(card_to_dict(py, &card, prices_as_json.unwrap_or(false))?.into()),
             Ok(None) => Err(pyo3::exceptions::PyKeyError::new_err(format!(
                 "Card with oracle_id '{}' not found", oracle_id
             ))),
@@ -94,6 +243,31 @@ fn get_card_by_oracle_id(
     })
 }
 
+/// Get card details by TCGPlayer product ID, resolved through the `tcg:{id}` mapping.
+/// Returns the same dict as `get_card_by_oracle_id`; raises `KeyError` when the ID
+/// isn't mapped to an indexed card.
+#[pyfunction]
+#[pyo3(signature = (tcgplayer_id, redis_url=None, prices_as_json=None))]
+fn get_card_by_tcgplayer_id(
+    tcgplayer_id: i64,
+    redis_url: Option<String>,
+    prices_as_json: Option<bool>,
+) -> PyResult<PyObject> {
+    let redis_url = redis_url.unwrap_or_else(|| "redis://127.0.0.1:9999".to_string());
+    
+    Python::with_gil(|py| {
+        match get_card_by_tcgplayer_id_internal(tcgplayer_id, &redis_url) {
+            Ok(Some(card)) => Ok(card_to_dict(py, &card, prices_as_json.unwrap_or(false))?.into()),
+            Ok(None) => Err(pyo3::exceptions::PyKeyError::new_err(format!(
+                "No card mapped to TCGPlayer id {}", tcgplayer_id
+            ))),
+            Err(e) => Err(pyo3::exceptions::PyRuntimeError::new_err(format!(
+                "Failed to get card: {}", e
+            ))),
+        }
+    })
+}
+
 /// Get autocomplete suggestions
 #[pyfunction]
 fn get_autocomplete(
@@ -112,6 +286,24 @@ fn get_autocomplete(
     }
 }
 
+/// Get autocomplete suggestions for several prefixes in one round trip
+#[pyfunction]
+fn get_autocomplete_batch(
+    prefixes: Vec<String>,
+    max_per: Option<usize>,
+    redis_url: Option<String>,
+) -> PyResult<HashMap<String, Vec<String>>> {
+    let redis_url = redis_url.unwrap_or_else(|| "redis://127.0.0.1:9999".to_string());
+    let max_per = max_per.unwrap_or(10);
+    
+    match get_autocomplete_batch_internal(&prefixes, max_per, &redis_url) {
+        Ok(suggestions) => Ok(suggestions),
+        Err(e) => Err(pyo3::exceptions::PyRuntimeError::new_err(format!(
+            "Batch autocomplete failed: {}", e
+        ))),
+    }
+}
+
 /// Get statistics about the indexed data
 #[pyfunction]
 fn get_stats(redis_url: Option<String>) -> PyResult<PyObject> {
@@ -124,6 +316,7 @@ fn get_stats(redis_url: Option<String>) -> PyResult<PyObject> {
                 dict.set_item("card_count", stats.card_count)?;
                 dict.set_item("set_count", stats.set_count)?;
                 dict.set_item("last_update", &stats.last_update)?;
+                dict.set_item("bulk_updated_at", &stats.bulk_updated_at)?;
                 Ok(dict.into())
             }
             Err(e) => Err(pyo3::exceptions::PyRuntimeError::new_err(format!(
@@ -133,13 +326,32 @@ fn get_stats(redis_url: Option<String>) -> PyResult<PyObject> {
     })
 }
 
+/// Reload the fuzzy search script if Redis no longer has it cached
+///
+/// Call once at startup: after a `SCRIPT FLUSH` the stored SHA is stale, and this
+/// re-loads the script without a reindex. Returns the script's SHA.
+#[pyfunction]
+fn ensure_search_script(redis_url: Option<String>) -> PyResult<String> {
+    let redis_url = redis_url.unwrap_or_else(|| "redis://127.0.0.1:9999".to_string());
+    
+    main::ensure_search_script(&redis_url).map_err(|e| {
+        pyo3::exceptions::PyRuntimeError::new_err(format!("Failed to load search script: {}", e))
+    })
+}
+
 /// A Python module implemented in Rust.
 #[pymodule]
 fn scryfall_indexer(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(download_and_index, m)?)?;
+    m.add_function(wrap_pyfunction!(update_index, m)?)?;
     m.add_function(wrap_pyfunction!(search_cards, m)?)?;
+    m.add_function(wrap_pyfunction!(search_cards_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(fuzzy_match_py, m)?)?;
     m.add_function(wrap_pyfunction!(get_card_by_oracle_id, m)?)?;
+    m.add_function(wrap_pyfunction!(get_card_by_tcgplayer_id, m)?)?;
     m.add_function(wrap_pyfunction!(get_autocomplete, m)?)?;
+    m.add_function(wrap_pyfunction!(get_autocomplete_batch, m)?)?;
     m.add_function(wrap_pyfunction!(get_stats, m)?)?;
+    m.add_function(wrap_pyfunction!(ensure_search_script, m)?)?;
     Ok(())
 } 
\ No newline at end of file
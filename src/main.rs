@@ -1,16 +1,49 @@
-use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
 use rayon::prelude::*;
 use redis::{Client, Commands, Connection};
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
-use std::sync::{Arc, Mutex};
-use std::time::Instant;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, AtomicUsize};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 use chrono::Utc;
+use double_metaphone::double_metaphone;
 
 const BATCH_SIZE: usize = 2000;     
-const CHUNK_SIZE: usize = 8000;  // Reduced for larger all_cards dataset
+const CHUNK_SIZE: usize = 8000;  // Upper bound on adaptive chunks; caps per-chunk map memory
+const MIN_CHUNK_SIZE: usize = 500;
+const CHUNKS_PER_THREAD: usize = 4;  // Enough slack for rayon to balance uneven chunks
+const SINGLE_CHUNK_MAX_CARDS: usize = 2000;  // Below this, merge overhead outweighs parallelism
 const MAX_PREFIX_LENGTH: usize = 30;
 const NGRAM_SIZE: usize = 3; 
+const TYPO_MIN_PREFIX_LENGTH: usize = 4;  // Shorter prefixes match too broadly to correct
+const MAX_TYPO_VARIANTS: usize = 256;
+const BENCHMARK_MAX_RESULTS: usize = 20;
+const VALIDATION_SAMPLE_SIZE: usize = 200;
+const OFFLINE_INDEX_MAGIC: &[u8; 8] = b"MTGOFFIX";
+pub const OFFLINE_INDEX_VERSION: u32 = 3;  // Bump whenever OfflineIndex or IndexedCard changes shape
+pub const DEFAULT_DOWNLOAD_RETRIES: u32 = 3;
+pub const DEFAULT_BULK_TYPE: &str = "all_cards";  // Every printing; default_cards / oracle_cards are smaller
+const DOWNLOAD_RETRY_BASE_DELAY_SECS: u64 = 2;  // Doubles after each failed attempt
+const ERROR_BODY_SNIPPET_BYTES: u64 = 300;  // Body excerpt quoted when a response isn't JSON
+const DEFAULT_USER_AGENT: &str = "MTGPriceAnalyzer/2.0";  // Overridden by SCRYFALL_USER_AGENT
+const SCRYFALL_REQUEST_SPACING: Duration = Duration::from_millis(100);  // Scryfall asks for at most ~10 requests/sec
+const DEFAULT_INDEX_LANGUAGE: &str = "en";
+const PROGRESS_LOG_INTERVAL: Duration = Duration::from_secs(10);  // Plain-text update cadence off-TTY
+
+static PROGRESS_DISABLED: AtomicBool = AtomicBool::new(false);
+static CHUNK_SIZE_OVERRIDE: AtomicUsize = AtomicUsize::new(0);  // 0 = size chunks from the card count
+static STOPWORDS: RwLock<Option<HashSet<String>>> = RwLock::new(None);  // None = index every word
+static PRIMARY_PRINTING: RwLock<PrimaryPrintingStrategy> = RwLock::new(PrimaryPrintingStrategy::Latest);
+static INDEX_LANGUAGE: RwLock<Option<Cow<'static, str>>> = RwLock::new(Some(Cow::Borrowed(DEFAULT_INDEX_LANGUAGE)));  // None = every language
+static SCRYFALL_NEXT_REQUEST: Mutex<Option<Instant>> = Mutex::new(None);  // Earliest time the next request may go out
+
+// Common name words that match thousands of cards; used by --stopwords
+const DEFAULT_STOPWORDS: &[&str] = &[
+    "the", "of", "and", "to", "in", "on", "at", "by", "for", "from", "into", "with", "an",
+];
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct ScryfallCard {
@@ -22,6 +55,8 @@ pub struct ScryfallCard {
     pub layout: String,
     pub set: String,
     pub set_name: String,
+    #[serde(default)]
+    pub set_type: Option<String>,
     pub collector_number: String,
     #[serde(default)]
     pub tcgplayer_id: Option<i64>,
@@ -35,6 +70,12 @@ pub struct ScryfallCard {
     pub released_at: Option<String>,
     #[serde(default)]
     pub rarity: Option<String>,
+    #[serde(default)]
+    pub promo: bool,
+    #[serde(default)]
+    pub digital: bool,
+    #[serde(default)]
+    pub lang: Option<String>,  // Scryfall language code ("en", "ja", ...)
 }
 
 #[derive(Deserialize, Debug, Clone, Serialize, Default)]
@@ -64,7 +105,7 @@ pub struct Prices {
     pub eur: Option<String>,
 }
 
-#[derive(Default)]
+#[derive(Default, Serialize, Deserialize)]
 pub struct SearchIndexes {
     pub ngrams: HashMap<String, HashSet<String>>,
     pub metaphones: HashMap<String, HashSet<String>>,
@@ -87,6 +128,8 @@ pub struct IndexedCard {
 pub struct PrintingPrice {
     pub set: String, 
     pub set_name: Option<String>,
+    #[serde(default)]
+    pub set_type: Option<String>,
     pub collector_number: String,
     pub tcgplayer_id: Option<i64>,
     pub prices: Prices,
@@ -107,52 +150,369 @@ pub struct PrintingInfo {
     pub rarity: Option<String>,
 }
 
+/// Which printing supplies an `IndexedCard`'s `id`, `layout` and `main_image`.
+/// Both strategies prefer regular paper printings (not promo, not digital-only)
+/// with an image, falling back to the rest only when a card has none.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PrimaryPrintingStrategy {
+    /// Most recently released printing
+    #[default]
+    Latest,
+    /// First released printing
+    Original,
+}
+
+impl std::str::FromStr for PrimaryPrintingStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "latest" => Ok(Self::Latest),
+            "original" => Ok(Self::Original),
+            other => Err(format!("unknown primary printing strategy '{}' (expected latest or original)", other)),
+        }
+    }
+}
+
+// The fields of an IndexedCard that come from one representative printing
+#[derive(Debug, Clone)]
+struct PrimaryPrinting {
+    id: String,
+    layout: String,
+    main_image: Option<String>,
+    released_at: Option<String>,
+    regular: bool,
+}
+
+impl PrimaryPrinting {
+    fn from_card(card: &ScryfallCard) -> Self {
+        let main_image = card
+            .image_uris
+            .as_ref()
+            .map(|uris| uris.normal.clone())
+            .or_else(|| {
+                card.card_faces.as_ref().and_then(|faces| {
+                    faces.get(0).and_then(|face| {
+                        face.image_uris.as_ref().map(|uris| uris.normal.clone())
+                    })
+                })
+            });
+        
+        Self {
+            id: card.id.clone(),
+            layout: card.layout.clone(),
+            main_image,
+            released_at: card.released_at.clone(),
+            regular: !card.promo && !card.digital,
+        }
+    }
+    
+    /// Whether `self` should replace `other`; ties go to the lower Scryfall id so
+    /// the choice doesn't depend on the order printings were merged in
+    fn beats(&self, other: &Self, strategy: PrimaryPrintingStrategy) -> bool {
+        let by_date = match strategy {
+            PrimaryPrintingStrategy::Latest => self.released_at.cmp(&other.released_at),
+            PrimaryPrintingStrategy::Original => other.released_at.cmp(&self.released_at),
+        };
+        
+        self.regular.cmp(&other.regular)
+            .then_with(|| self.main_image.is_some().cmp(&other.main_image.is_some()))
+            .then_with(|| self.released_at.is_some().cmp(&other.released_at.is_some()))
+            .then(by_date)
+            .then_with(|| other.id.cmp(&self.id))
+            .is_gt()
+    }
+    
+    fn apply(self, card: &mut IndexedCard) {
+        card.id = self.id;
+        card.layout = self.layout;
+        card.main_image = self.main_image;
+    }
+}
+
+fn offer_primary(
+    primaries: &mut HashMap<String, PrimaryPrinting>,
+    oracle_id: String,
+    candidate: PrimaryPrinting,
+    strategy: PrimaryPrintingStrategy,
+) {
+    match primaries.get_mut(&oracle_id) {
+        Some(current) if candidate.beats(current, strategy) => *current = candidate,
+        Some(_) => {}
+        None => {
+            primaries.insert(oracle_id, candidate);
+        }
+    }
+}
+
+/// Tunable knobs passed to the fuzzy search Lua script
+#[derive(Debug, Clone)]
+pub struct FuzzySearchParams {
+    pub max_distance: usize,
+    pub word_weight: u32,
+    pub ngram_weight: u32,
+    pub metaphone_weight: u32,
+}
+
+impl Default for FuzzySearchParams {
+    fn default() -> Self {
+        Self {
+            max_distance: 2,
+            word_weight: 1,
+            ngram_weight: 1,
+            metaphone_weight: 2,
+        }
+    }
+}
+
+impl FuzzySearchParams {
+    fn from_args(args: &[String]) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut params = Self::default();
+        let mut iter = args.iter();
+        
+        while let Some(arg) = iter.next() {
+            let target = match arg.as_str() {
+                "--word-weight" => &mut params.word_weight,
+                "--ngram-weight" => &mut params.ngram_weight,
+                "--metaphone-weight" => &mut params.metaphone_weight,
+                "--max-distance" => {
+                    params.max_distance = iter.next().ok_or("--max-distance requires a value")?.parse()?;
+                    continue;
+                }
+                _ => continue,
+            };
+            *target = iter.next().ok_or_else(|| format!("{} requires a value", arg))?.parse()?;
+        }
+        
+        Ok(params)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SearchBenchmarkReport {
+    pub queries: usize,
+    pub precision_at_1: f64,
+    pub precision_at_5: f64,
+    pub mean_reciprocal_rank: f64,
+    pub misses: Vec<String>,
+}
+
+/// Outcome of each integrity check run by `validate_index`
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    pub checks: Vec<(String, bool, String)>,
+}
+
+impl ValidationReport {
+    fn record(&mut self, name: &str, passed: bool, detail: String) {
+        self.checks.push((name.to_string(), passed, detail));
+    }
+
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|(_, passed, _)| *passed)
+    }
+}
+
+/// Stage of `run_indexer` that produced an error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexPhase {
+    Download,
+    Parse,
+    Store,
+}
+
+impl std::fmt::Display for IndexPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IndexPhase::Download => write!(f, "download"),
+            IndexPhase::Parse => write!(f, "parse"),
+            IndexPhase::Store => write!(f, "store"),
+        }
+    }
+}
+
+/// Indexing failure tagged with the phase it happened in, so callers can tell a
+/// retryable network problem from bad data or a Redis failure
+#[derive(Debug)]
+pub struct IndexerError {
+    pub phase: IndexPhase,
+    pub attempts: u32,
+    pub source: Box<dyn std::error::Error>,
+}
+
+impl IndexerError {
+    fn new(phase: IndexPhase, source: Box<dyn std::error::Error>) -> Self {
+        IndexerError { phase, attempts: 1, source }
+    }
+}
+
+impl std::fmt::Display for IndexerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.attempts > 1 {
+            write!(f, "{} phase failed after {} attempts: {}", self.phase, self.attempts, self.source)
+        } else {
+            write!(f, "{} phase failed: {}", self.phase, self.source)
+        }
+    }
+}
+
+impl std::error::Error for IndexerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct IndexStats {
     pub card_count: usize,
     pub set_count: usize,
     pub last_update: String,
+    /// `updated_at` of the Scryfall bulk file behind an incremental run
+    #[serde(default)]
+    pub bulk_updated_at: Option<String>,
+    /// Per-card outcome of an incremental run; `None` for full runs
+    #[serde(default)]
+    pub changes: Option<IndexChanges>,
+}
+
+/// How `run_indexer_incremental` handled each oracle card
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct IndexChanges {
+    pub added: usize,
+    pub updated: usize,
+    pub deleted: usize,
+    pub skipped: usize,
 }
 
 // Public API functions for Python bindings
 
-pub fn run_indexer(redis_url: &String) -> Result<IndexStats, Box<dyn std::error::Error>> {
+/// `bulk_type` is the Scryfall bulk file to index ("all_cards", "default_cards",
+/// "oracle_cards", ...)
+pub fn run_indexer(redis_url: &String, bulk_type: &str) -> Result<IndexStats, Box<dyn std::error::Error>> {
+    run_indexer_with_retries(redis_url, DEFAULT_DOWNLOAD_RETRIES, bulk_type)
+}
+
+/// Run the full pipeline, retrying the Scryfall download up to `max_retries` times
+/// with exponential backoff. Errors are returned as an `IndexerError` naming the phase.
+pub fn run_indexer_with_retries(redis_url: &str, max_retries: u32, bulk_type: &str) -> Result<IndexStats, Box<dyn std::error::Error>> {
     let start_time = Instant::now();
     
     println!("=== Starting Enhanced Scryfall Indexer ===");
     println!("System configuration:");
     println!("- Batch size: {}", BATCH_SIZE);
-    println!("- Chunk size: {}", CHUNK_SIZE);
+    match CHUNK_SIZE_OVERRIDE.load(std::sync::atomic::Ordering::Relaxed) {
+        0 => println!("- Chunk size: adaptive ({}-{})", MIN_CHUNK_SIZE, CHUNK_SIZE),
+        size => println!("- Chunk size: {}", size),
+    }
     println!("- Max prefix length: {}", MAX_PREFIX_LENGTH);
     println!("- N-gram size: {}", NGRAM_SIZE);
     
-    let cards = download_scryfall_data()?;
-    let (oracle_id_map, all_set_codes, search_indexes) = build_card_index(&cards)?;
+    let (cards, bulk_updated_at) = download_with_retries(max_retries, bulk_type)?;
+    let (oracle_id_map, all_set_codes, search_indexes) = build_card_index(&cards)
+        .map_err(|e| IndexerError::new(IndexPhase::Parse, e))?;
     
-    println!("Connecting to Redis...");
-    let client = Client::open(redis_url.to_string())?;
-    let mut con = client.get_connection()?;
+    let card_count = oracle_id_map.len();
+    let set_count = all_set_codes.len();
     
-    let ping: String = redis::cmd("PING").query(&mut con)?;
-    if ping != "PONG" {
-        return Err("Redis connection failed".into());
+    store_in_redis(redis_url, oracle_id_map, all_set_codes, search_indexes, &cards, bulk_updated_at.as_deref())
+        .map_err(|e| IndexerError::new(IndexPhase::Store, e))?;
+    
+    let total_time = start_time.elapsed();
+    println!(
+        "=== Total execution time: {:.2} seconds ===",
+        total_time.as_secs_f32()
+    );
+    
+    Ok(IndexStats {
+        card_count,
+        set_count,
+        last_update: Utc::now().to_rfc3339(),
+        bulk_updated_at,
+        changes: None,
+    })
+}
+
+/// Update an existing index in place. Nothing is downloaded when the bulk file's
+/// `updated_at` still equals `previous_meta_date`. Otherwise each card's hash is compared
+/// with `card:hash:{oracle_id}`: unchanged cards are skipped, changed cards have their old
+/// keys removed and are rewritten, and cards gone from the bulk file are deleted along
+/// with their n-gram, metaphone and word index entries.
+pub fn run_indexer_incremental(redis_url: &str, previous_meta_date: Option<&str>, bulk_type: &str) -> Result<IndexStats, Box<dyn std::error::Error>> {
+    let start_time = Instant::now();
+    
+    println!("=== Starting incremental Scryfall index update ===");
+    
+    let bulk_info = scryfall_client()
+        .and_then(|client| fetch_bulk_file_info(&client, bulk_type))
+        .map_err(|e| match e.downcast::<IndexerError>() {
+            Ok(indexer_error) => *indexer_error,
+            Err(e) => IndexerError::new(IndexPhase::Download, e),
+        })?;
+    
+    if bulk_info.updated_at.is_some() && bulk_info.updated_at.as_deref() == previous_meta_date {
+        println!("Bulk file unchanged since {}, nothing to update", previous_meta_date.unwrap_or_default());
+        // Nothing is rewritten, but the script cache may have been flushed since the last run
+        ensure_search_script(redis_url)
+            .map_err(|e| IndexerError::new(IndexPhase::Store, e))?;
+        let mut stats = get_stats_internal(redis_url)
+            .map_err(|e| IndexerError::new(IndexPhase::Store, e))?;
+        stats.changes = Some(IndexChanges { skipped: stats.card_count, ..Default::default() });
+        stats.bulk_updated_at = bulk_info.updated_at;
+        return Ok(stats);
     }
     
+    let (cards, _) = download_with_retries(DEFAULT_DOWNLOAD_RETRIES, bulk_type)?;
+    let (oracle_id_map, all_set_codes, _) = build_card_index(&cards)
+        .map_err(|e| IndexerError::new(IndexPhase::Parse, e))?;
+    
     let card_count = oracle_id_map.len();
     let set_count = all_set_codes.len();
     
-    store_card_index(&mut con, oracle_id_map, all_set_codes, search_indexes, &cards)?;
+    let changes = store_card_changes(redis_url, oracle_id_map, all_set_codes, &cards, bulk_info.updated_at.as_deref())
+        .map_err(|e| IndexerError::new(IndexPhase::Store, e))?;
     
-    let total_time = start_time.elapsed();
+    println!(
+        "Incremental update: {} added, {} updated, {} deleted, {} unchanged",
+        changes.added, changes.updated, changes.deleted, changes.skipped
+    );
     println!(
         "=== Total execution time: {:.2} seconds ===",
-        total_time.as_secs_f32()
+        start_time.elapsed().as_secs_f32()
+    );
+    
+    Ok(IndexStats {
+        card_count,
+        set_count,
+        last_update: Utc::now().to_rfc3339(),
+        bulk_updated_at: bulk_info.updated_at,
+        changes: Some(changes),
+    })
+}
+
+/// Download and index the `bulk_type` Scryfall file, then write it to `path` as an
+/// `OfflineIndex` instead of loading it into Redis
+pub fn export_offline_index(path: &str, bulk_type: &str) -> Result<IndexStats, Box<dyn std::error::Error>> {
+    let (cards, bulk_updated_at) = download_scryfall_data(bulk_type)?;
+    let (oracle_id_map, all_set_codes, search_indexes) = build_card_index(&cards)?;
+    
+    let card_count = oracle_id_map.len();
+    let set_count = all_set_codes.len();
+    
+    println!("Writing offline index to {}...", path);
+    let index = OfflineIndex::new(oracle_id_map, search_indexes);
+    let bytes_written = index.save(path)?;
+    println!(
+        "Offline index v{} written ({:.1} MB)",
+        OFFLINE_INDEX_VERSION,
+        bytes_written as f64 / 1024.0 / 1024.0
     );
     
     Ok(IndexStats {
         card_count,
         set_count,
         last_update: Utc::now().to_rfc3339(),
+        bulk_updated_at,
+        changes: None,
     })
 }
 
@@ -160,20 +520,20 @@ pub fn search_cards_internal(
     query: &str,
     max_results: usize,
     redis_url: &str,
+) -> Result<Vec<IndexedCard>, Box<dyn std::error::Error>> {
+    search_cards_with_params(query, max_results, redis_url, &FuzzySearchParams::default())
+}
+
+pub fn search_cards_with_params(
+    query: &str,
+    max_results: usize,
+    redis_url: &str,
+    params: &FuzzySearchParams,
 ) -> Result<Vec<IndexedCard>, Box<dyn std::error::Error>> {
     let client = Client::open(redis_url.to_string())?;
     let mut con = client.get_connection()?;
     
-    // Use the fuzzy search Lua script
-    let script_sha: String = con.get("mtg:script:fuzzy_search")?;
-    
-    let oracle_ids: Vec<String> = redis::cmd("EVALSHA")
-        .arg(&script_sha)
-        .arg(0)
-        .arg(query)
-        .arg(2) // max_distance
-        .arg(max_results)
-        .query(&mut con)?;
+    let oracle_ids = fuzzy_search_oracle_ids(&mut con, query, max_results, params)?;
     
     let mut results = Vec::new();
     for oracle_id in oracle_ids {
@@ -187,6 +547,363 @@ pub fn search_cards_internal(
     Ok(results)
 }
 
+fn fuzzy_search_oracle_ids(
+    con: &mut Connection,
+    query: &str,
+    max_results: usize,
+    params: &FuzzySearchParams,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    // Use the fuzzy search Lua script, loading it if the key was never written
+    let script_sha: Option<String> = con.get("mtg:script:fuzzy_search")?;
+    let script_sha = match script_sha {
+        Some(sha) => sha,
+        None => load_fuzzy_search_script(con)?,
+    };
+    
+    let evalsha = |con: &mut Connection, sha: &str| -> redis::RedisResult<Vec<String>> {
+        fuzzy_search_cmd(sha, query, max_results, params).query(con)
+    };
+    
+    // A SCRIPT FLUSH (e.g. managed Redis maintenance) drops the script but not the stored
+    // SHA; reload it once instead of failing every search until the next reindex
+    let oracle_ids = match evalsha(con, &script_sha) {
+        Err(e) if e.kind() == redis::ErrorKind::NoScriptError => {
+            let script_sha = load_fuzzy_search_script(con)?;
+            evalsha(con, &script_sha)?
+        }
+        result => result?,
+    };
+    
+    Ok(oracle_ids)
+}
+
+// EVALSHA of the fuzzy search script for one query
+fn fuzzy_search_cmd(sha: &str, query: &str, max_results: usize, params: &FuzzySearchParams) -> redis::Cmd {
+    // The script looks up the query's metaphone codes but can't compute them itself
+    let (metaphone, alternate_metaphone) = double_metaphone(query);
+    let mut cmd = redis::cmd("EVALSHA");
+    cmd.arg(sha)
+        .arg(0)
+        .arg(query)
+        .arg(params.max_distance)
+        .arg(max_results)
+        .arg(params.word_weight)
+        .arg(params.ngram_weight)
+        .arg(params.metaphone_weight)
+        .arg(&metaphone)
+        .arg(&alternate_metaphone);
+    cmd
+}
+
+/// Run many searches over one connection: the script calls for every query go out in
+/// one pipeline and the matched cards are fetched with a single MGET. Results are in
+/// input order; an empty list means that query matched nothing.
+pub fn search_cards_batch_internal(
+    queries: &[String],
+    max_results: usize,
+    redis_url: &str,
+) -> Result<Vec<Vec<IndexedCard>>, Box<dyn std::error::Error>> {
+    if queries.is_empty() {
+        return Ok(Vec::new());
+    }
+    
+    let client = Client::open(redis_url.to_string())?;
+    let mut con = client.get_connection()?;
+    let params = FuzzySearchParams::default();
+    
+    let run_searches = |con: &mut Connection, sha: &str| -> redis::RedisResult<Vec<Vec<String>>> {
+        let mut pipe = redis::pipe();
+        for query in queries {
+            pipe.add_command(fuzzy_search_cmd(sha, query, max_results, &params));
+        }
+        pipe.query(con)
+    };
+    
+    // Same one-time reload as fuzzy_search_oracle_ids if the script cache was flushed
+    let script_sha: Option<String> = con.get("mtg:script:fuzzy_search")?;
+    let script_sha = match script_sha {
+        Some(sha) => sha,
+        None => load_fuzzy_search_script(&mut con)?,
+    };
+    let oracle_ids_per_query = match run_searches(&mut con, &script_sha) {
+        Err(e) if e.kind() == redis::ErrorKind::NoScriptError => {
+            let script_sha = load_fuzzy_search_script(&mut con)?;
+            run_searches(&mut con, &script_sha)?
+        }
+        result => result?,
+    };
+    
+    let mut unique_ids: Vec<&String> = oracle_ids_per_query.iter().flatten().collect();
+    unique_ids.sort();
+    unique_ids.dedup();
+    let mut cards: HashMap<&str, IndexedCard> = HashMap::new();
+    if !unique_ids.is_empty() {
+        let keys: Vec<String> = unique_ids.iter().map(|id| format!("card:oracle:{}", id)).collect();
+        let docs: Vec<Option<String>> = con.mget(&keys)?;
+        for (oracle_id, doc) in unique_ids.iter().zip(docs) {
+            if let Some(card) = doc.and_then(|doc| serde_json::from_str::<IndexedCard>(&doc).ok()) {
+                cards.insert(oracle_id.as_str(), card);
+            }
+        }
+    }
+    
+    Ok(oracle_ids_per_query.iter()
+        .map(|oracle_ids| oracle_ids.iter().filter_map(|id| cards.get(id.as_str()).cloned()).collect())
+        .collect())
+}
+
+/// Score `candidates` against `query` entirely in memory, without Redis.
+///
+/// Uses the same signals as the Redis fuzzy search (shared words, shared trigrams and
+/// matching metaphone codes, blended by the default `FuzzySearchParams` weights) plus the
+/// Levenshtein similarity of the whole name. Names starting with the query always rank
+/// above the rest, as prefix matches do in Redis. Returns up to `max_results`
+/// `(candidate, score)` pairs, best first, with scores in `0.0..=1.0`.
+pub fn fuzzy_match(query: &str, candidates: &[&str], max_results: usize) -> Vec<(String, f64)> {
+    fuzzy_match_with_params(query, candidates, max_results, &FuzzySearchParams::default())
+}
+
+pub fn fuzzy_match_with_params(
+    query: &str,
+    candidates: &[&str],
+    max_results: usize,
+    params: &FuzzySearchParams,
+) -> Vec<(String, f64)> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    
+    // Words shorter than 3 characters are ignored by the word index lookup too
+    let query_words: HashSet<String> = tokenize_words(&query).into_iter().filter(|w| w.len() >= 3).collect();
+    let query_ngrams: HashSet<String> = generate_ngrams(&query, NGRAM_SIZE).into_iter().collect();
+    let query_metaphones = generate_metaphones(&query);
+    let weight_total = (params.word_weight + params.ngram_weight + params.metaphone_weight) as f64 + 1.0;
+    
+    let mut scored: Vec<(String, f64)> = candidates
+        .iter()
+        .filter_map(|&candidate| {
+            let name = candidate.to_lowercase();
+            let is_prefix = name.starts_with(&query);
+            let distance = levenshtein_distance(&query, &name);
+            
+            let word_overlap = if query_words.is_empty() {
+                0.0
+            } else {
+                let words: HashSet<String> = tokenize_words(&name).into_iter().collect();
+                query_words.iter().filter(|w| words.contains(*w)).count() as f64 / query_words.len() as f64
+            };
+            let ngrams: HashSet<String> = generate_ngrams(&name, NGRAM_SIZE).into_iter().collect();
+            let ngram_overlap = query_ngrams.intersection(&ngrams).count() as f64 / query_ngrams.len() as f64;
+            let metaphone_match = generate_metaphones(&name).iter().any(|code| query_metaphones.contains(code));
+            
+            if !is_prefix && word_overlap == 0.0 && ngram_overlap == 0.0 && !metaphone_match && distance > params.max_distance {
+                return None;
+            }
+            
+            let max_len = query.chars().count().max(name.chars().count()) as f64;
+            let edit_similarity = 1.0 - distance as f64 / max_len;
+            let blended = (params.word_weight as f64 * word_overlap
+                + params.ngram_weight as f64 * ngram_overlap
+                + if metaphone_match { params.metaphone_weight as f64 } else { 0.0 }
+                + edit_similarity) / weight_total;
+            
+            let score = if is_prefix { 0.5 + blended / 2.0 } else { blended / 2.0 };
+            Some((candidate.to_string(), score))
+        })
+        .collect();
+    
+    scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    scored.truncate(max_results);
+    scored
+}
+
+/// Run every `query,expected` pair in a CSV through the fuzzy search and score the ranking.
+/// `expected` may be an oracle ID or an exact card name.
+pub fn run_search_benchmark(
+    queries_path: &str,
+    redis_url: &str,
+    params: &FuzzySearchParams,
+) -> Result<SearchBenchmarkReport, Box<dyn std::error::Error>> {
+    let client = Client::open(redis_url.to_string())?;
+    let mut con = client.get_connection()?;
+    
+    let contents = std::fs::read_to_string(queries_path)?;
+    let mut report = SearchBenchmarkReport::default();
+    let mut reciprocal_rank_sum = 0.0;
+    let mut hits_at_1 = 0;
+    let mut hits_at_5 = 0;
+    
+    for line in contents.lines().skip(1) {
+        let fields = parse_csv_line(line);
+        if fields.len() < 2 || fields[0].is_empty() {
+            continue;
+        }
+        let (query, expected) = (&fields[0], &fields[1]);
+        
+        // Resolve card names through the name index so seed files stay readable
+        let expected_oracle_id = match con.get::<_, Option<String>>(format!("card:name:{}", expected.to_lowercase()))? {
+            Some(oracle_id) => oracle_id,
+            None => expected.clone(),
+        };
+        
+        let results = fuzzy_search_oracle_ids(&mut con, &query.to_lowercase(), BENCHMARK_MAX_RESULTS, params)?;
+        report.queries += 1;
+        
+        match results.iter().position(|id| *id == expected_oracle_id) {
+            Some(rank) => {
+                if rank == 0 {
+                    hits_at_1 += 1;
+                }
+                if rank < 5 {
+                    hits_at_5 += 1;
+                }
+                reciprocal_rank_sum += 1.0 / (rank + 1) as f64;
+            }
+            None => report.misses.push(query.clone()),
+        }
+    }
+    
+    if report.queries > 0 {
+        let total = report.queries as f64;
+        report.precision_at_1 = hits_at_1 as f64 / total;
+        report.precision_at_5 = hits_at_5 as f64 / total;
+        report.mean_reciprocal_rank = reciprocal_rank_sum / total;
+    }
+    
+    Ok(report)
+}
+
+/// Audit an existing index without re-indexing: stats, the fuzzy script, a sample of
+/// card documents, set membership and price keys.
+pub fn validate_index(redis_url: &str) -> Result<ValidationReport, Box<dyn std::error::Error>> {
+    let client = Client::open(redis_url.to_string())?;
+    let mut con = client.get_connection()?;
+    let mut report = ValidationReport::default();
+    
+    // Stats written at the end of every run
+    let card_count: Option<String> = con.get("mtg:stats:card_count")?;
+    let last_update: Option<String> = con.get("mtg:stats:last_update")?;
+    match (card_count.as_deref().map(str::parse::<usize>), last_update.as_deref().map(chrono::DateTime::parse_from_rfc3339)) {
+        (Some(Ok(count)), Some(Ok(updated))) => report.record("stats", true, format!("{} cards, updated {}", count, updated)),
+        (None, _) | (_, None) => report.record("stats", false, "mtg:stats:card_count or mtg:stats:last_update missing".to_string()),
+        _ => report.record("stats", false, "stats keys present but unparseable".to_string()),
+    }
+    
+    // Fuzzy search script registered and still cached by the server
+    let script_sha: Option<String> = con.get("mtg:script:fuzzy_search")?;
+    match script_sha {
+        Some(sha) => {
+            let loaded: Vec<i32> = redis::cmd("SCRIPT").arg("EXISTS").arg(&sha).query(&mut con)?;
+            let ok = loaded.first() == Some(&1);
+            report.record("fuzzy_script", ok, if ok { format!("loaded ({})", sha) } else { format!("{} not in script cache", sha) });
+        }
+        None => report.record("fuzzy_script", false, "mtg:script:fuzzy_search missing".to_string()),
+    }
+    
+    // Sampled card documents deserialize and appear in their sets
+    let card_keys: Vec<String> = con.scan_match::<_, String>("card:oracle:*")?
+        .take(VALIDATION_SAMPLE_SIZE)
+        .collect();
+    let mut bad_cards = Vec::new();
+    let mut missing_memberships = Vec::new();
+    for key in &card_keys {
+        let oracle_id = key.trim_start_matches("card:oracle:");
+        let data: Option<String> = con.get(key)?;
+        match data.as_deref().map(serde_json::from_str::<IndexedCard>) {
+            Some(Ok(card)) => {
+                for set_code in &card.sets {
+                    let member: bool = con.sismember(format!("set:{}", set_code), oracle_id)?;
+                    if !member {
+                        missing_memberships.push(format!("{} in set:{}", oracle_id, set_code));
+                    }
+                }
+            }
+            _ => bad_cards.push(key.clone()),
+        }
+    }
+    report.record(
+        "card_documents",
+        !card_keys.is_empty() && bad_cards.is_empty(),
+        if card_keys.is_empty() {
+            "no card:oracle:* keys found".to_string()
+        } else {
+            format!("{}/{} sampled cards deserialize{}", card_keys.len() - bad_cards.len(), card_keys.len(),
+                if bad_cards.is_empty() { String::new() } else { format!("; bad: {}", bad_cards.join(", ")) })
+        },
+    );
+    report.record(
+        "set_membership",
+        missing_memberships.is_empty(),
+        if missing_memberships.is_empty() {
+            "sampled cards present in all their set:{code} sets".to_string()
+        } else {
+            format!("{} missing: {}", missing_memberships.len(), missing_memberships.iter().take(10).cloned().collect::<Vec<_>>().join(", "))
+        },
+    );
+    
+    // Every set listed in mtg:sets has members
+    let sets_data: String = con.get("mtg:sets").unwrap_or_else(|_| "[]".to_string());
+    let set_codes: Vec<String> = serde_json::from_str(&sets_data).unwrap_or_default();
+    let mut empty_sets = Vec::new();
+    for set_code in &set_codes {
+        let count: usize = con.scard(format!("set:{}", set_code))?;
+        if count == 0 {
+            empty_sets.push(set_code.clone());
+        }
+    }
+    report.record(
+        "set_counts",
+        !set_codes.is_empty() && empty_sets.is_empty(),
+        if set_codes.is_empty() {
+            "mtg:sets missing or empty".to_string()
+        } else {
+            format!("{} sets, {} empty{}", set_codes.len(), empty_sets.len(),
+                if empty_sets.is_empty() { String::new() } else { format!(": {}", empty_sets.join(", ")) })
+        },
+    );
+    
+    // Price keys point at cards that exist
+    let mut price_oracle_ids: Vec<String> = con.zrange("prices:usd", 0, VALIDATION_SAMPLE_SIZE as isize - 1)?;
+    price_oracle_ids.extend(
+        con.scan_match::<_, String>("price:latest:*")?
+            .take(VALIDATION_SAMPLE_SIZE)
+            .map(|key| key.trim_start_matches("price:latest:").to_string())
+            .collect::<Vec<_>>(),
+    );
+    let mut dangling_prices = Vec::new();
+    for oracle_id in &price_oracle_ids {
+        let exists: bool = con.exists(format!("card:oracle:{}", oracle_id))?;
+        if !exists {
+            dangling_prices.push(oracle_id.clone());
+        }
+    }
+    report.record(
+        "price_references",
+        dangling_prices.is_empty(),
+        format!("{} sampled price entries, {} reference missing cards", price_oracle_ids.len(), dangling_prices.len()),
+    );
+    
+    Ok(report)
+}
+
+fn parse_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    
+    for c in line.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut current).trim().to_string()),
+            _ => current.push(c),
+        }
+    }
+    fields.push(current.trim().to_string());
+    
+    fields
+}
+
 pub fn get_card_by_oracle_id_internal(
     oracle_id: &str,
     redis_url: &str,
@@ -194,13 +911,110 @@ pub fn get_card_by_oracle_id_internal(
     let client = Client::open(redis_url.to_string())?;
     let mut con = client.get_connection()?;
     
-    match con.get::<_, String>(format!("card:oracle:{}", oracle_id)) {
-        Ok(card_data) => {
-            let card = serde_json::from_str::<IndexedCard>(&card_data)?;
-            Ok(Some(card))
+    // A missing key is None; connection and command failures are errors
+    let card_data: Option<String> = con.get(format!("card:oracle:{}", oracle_id))?;
+    match card_data {
+        Some(card_data) => Ok(Some(serde_json::from_str::<IndexedCard>(&card_data)?)),
+        None => Ok(None),
+    }
+}
+
+/// Resolve a TCGPlayer product id through the `tcg:{id}` mapping; unmapped ids give `None`
+pub fn get_card_by_tcgplayer_id_internal(
+    tcgplayer_id: i64,
+    redis_url: &str,
+) -> Result<Option<IndexedCard>, Box<dyn std::error::Error>> {
+    let client = Client::open(redis_url.to_string())?;
+    let mut con = client.get_connection()?;
+    
+    let oracle_id: Option<String> = con.get(format!("tcg:{}", tcgplayer_id))?;
+    match oracle_id {
+        Some(oracle_id) => get_card_by_oracle_id_internal(&oracle_id, redis_url),
+        None => Ok(None),
+    }
+}
+
+/// Autocomplete several prefixes at once: one pipelined round trip for the
+/// `auto:prefix:` sets and one for the card names. Results are keyed by the
+/// prefix as given; unlike `get_autocomplete_internal` there is no typo fallback.
+pub fn get_autocomplete_batch_internal(
+    prefixes: &[String],
+    max_per: usize,
+    redis_url: &str,
+) -> Result<HashMap<String, Vec<String>>, Box<dyn std::error::Error>> {
+    let mut results = HashMap::new();
+    if prefixes.is_empty() {
+        return Ok(results);
+    }
+    
+    let client = Client::open(redis_url.to_string())?;
+    let mut con = client.get_connection()?;
+    
+    let mut pipe = redis::pipe();
+    for prefix in prefixes {
+        pipe.cmd("SMEMBERS").arg(format!("auto:prefix:{}", prefix.to_lowercase()));
+    }
+    let matches: Vec<Vec<String>> = pipe.query(&mut con)?;
+    
+    let mut wanted: Vec<Vec<String>> = Vec::with_capacity(matches.len());
+    let mut unique_ids: Vec<String> = Vec::new();
+    let mut seen_ids = HashSet::new();
+    for oracle_ids in matches {
+        let ids: Vec<String> = oracle_ids.into_iter().take(max_per).collect();
+        for id in &ids {
+            if seen_ids.insert(id.clone()) {
+                unique_ids.push(id.clone());
+            }
         }
-        Err(_) => Ok(None),
+        wanted.push(ids);
     }
+    
+    let mut names: HashMap<String, String> = HashMap::new();
+    if !unique_ids.is_empty() {
+        let mut pipe = redis::pipe();
+        for id in &unique_ids {
+            pipe.cmd("GET").arg(format!("card:oracle:{}", id));
+        }
+        let cards: Vec<Option<String>> = pipe.query(&mut con)?;
+        for (id, card_data) in unique_ids.into_iter().zip(cards) {
+            if let Some(card) = card_data.and_then(|d| serde_json::from_str::<IndexedCard>(&d).ok()) {
+                names.insert(id, card.name);
+            }
+        }
+    }
+    
+    for (prefix, ids) in prefixes.iter().zip(wanted) {
+        let mut seen_names = HashSet::new();
+        let suggestions: Vec<String> = ids.iter()
+            .filter_map(|id| names.get(id))
+            .filter(|name| seen_names.insert(name.as_str()))
+            .cloned()
+            .collect();
+        results.insert(prefix.clone(), suggestions);
+    }
+    
+    Ok(results)
+}
+
+pub fn get_cards_by_set_type_internal(
+    set_type: &str,
+    redis_url: &str,
+) -> Result<Vec<IndexedCard>, Box<dyn std::error::Error>> {
+    let client = Client::open(redis_url.to_string())?;
+    let mut con = client.get_connection()?;
+    
+    let oracle_ids: Vec<String> = con.smembers(format!("set_type:{}", set_type.to_lowercase()))?;
+    
+    let mut cards = Vec::new();
+    for oracle_id in oracle_ids {
+        if let Ok(card_data) = con.get::<_, String>(format!("card:oracle:{}", oracle_id)) {
+            if let Ok(card) = serde_json::from_str::<IndexedCard>(&card_data) {
+                cards.push(card);
+            }
+        }
+    }
+    
+    Ok(cards)
 }
 
 pub fn get_autocomplete_internal(
@@ -212,7 +1026,19 @@ pub fn get_autocomplete_internal(
     let mut con = client.get_connection()?;
     
     let prefix_lower = prefix.to_lowercase();
-    let oracle_ids: Vec<String> = con.smembers(format!("auto:prefix:{}", prefix_lower))?;
+    let mut oracle_ids: Vec<String> = con.smembers(format!("auto:prefix:{}", prefix_lower))?;
+    
+    // Fall back to prefixes within edit distance 1 when the typed prefix matches nothing
+    if oracle_ids.is_empty() && prefix_lower.chars().count() >= TYPO_MIN_PREFIX_LENGTH {
+        let variants = generate_typo_variants(&prefix_lower);
+        
+        let mut pipe = redis::pipe();
+        for variant in &variants {
+            pipe.cmd("SMEMBERS").arg(format!("auto:prefix:{}", variant));
+        }
+        let variant_matches: Vec<Vec<String>> = pipe.query(&mut con)?;
+        oracle_ids = merge_variant_matches(variant_matches);
+    }
     
     let mut card_names = Vec::new();
     for oracle_id in oracle_ids.into_iter().take(max_results) {
@@ -232,6 +1058,7 @@ pub fn get_stats_internal(redis_url: &str) -> Result<IndexStats, Box<dyn std::er
     
     let card_count: usize = con.get("mtg:stats:card_count").unwrap_or(0);
     let last_update: String = con.get("mtg:stats:last_update").unwrap_or_else(|_| "Never".to_string());
+    let bulk_updated_at: Option<String> = con.get("mtg:stats:bulk_updated_at").unwrap_or(None);
     
     // Count unique sets
     let sets_data: String = con.get("mtg:sets").unwrap_or_else(|_| "[]".to_string());
@@ -241,30 +1068,249 @@ pub fn get_stats_internal(redis_url: &str) -> Result<IndexStats, Box<dyn std::er
         card_count,
         set_count: sets.len(),
         last_update,
+        bulk_updated_at,
+        changes: None,
     })
 }
 
-fn download_scryfall_data() -> Result<Vec<ScryfallCard>, Box<dyn std::error::Error>> {
-    println!("Downloading Scryfall all_cards.json (this may take a while)...");
+/// Redis-free copy of the card map and search indexes for standalone/embedded use.
+/// Stored as an 8-byte magic, a little-endian `OFFLINE_INDEX_VERSION`, then a bincode body.
+#[derive(Serialize, Deserialize)]
+pub struct OfflineIndex {
+    pub cards: HashMap<String, IndexedCard>,
+    pub indexes: SearchIndexes,
+    names: Vec<(String, String)>,  // (lowercase name, oracle_id), sorted for prefix lookups
+}
+
+impl OfflineIndex {
+    pub fn new(cards: HashMap<String, IndexedCard>, indexes: SearchIndexes) -> Self {
+        let mut names: Vec<(String, String)> = cards
+            .values()
+            .map(|card| (card.name.to_lowercase(), card.oracle_id.clone()))
+            .collect();
+        names.sort();
+        
+        OfflineIndex { cards, indexes, names }
+    }
+    
+    pub fn save(&self, path: &str) -> Result<usize, Box<dyn std::error::Error>> {
+        let body = bincode::serde::encode_to_vec(self, bincode::config::standard())?;
+        
+        let mut bytes = Vec::with_capacity(OFFLINE_INDEX_MAGIC.len() + 4 + body.len());
+        bytes.extend_from_slice(OFFLINE_INDEX_MAGIC);
+        bytes.extend_from_slice(&OFFLINE_INDEX_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&body);
+        std::fs::write(path, &bytes)?;
+        
+        Ok(bytes.len())
+    }
+    
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let bytes = std::fs::read(path)?;
+        let header_len = OFFLINE_INDEX_MAGIC.len() + 4;
+        
+        if bytes.len() < header_len || &bytes[..OFFLINE_INDEX_MAGIC.len()] != OFFLINE_INDEX_MAGIC {
+            return Err(format!("{} is not an offline search index", path).into());
+        }
+        
+        let version = u32::from_le_bytes(bytes[OFFLINE_INDEX_MAGIC.len()..header_len].try_into()?);
+        if version != OFFLINE_INDEX_VERSION {
+            return Err(format!(
+                "{} is offline index v{}, this build reads v{}; re-export it",
+                path, version, OFFLINE_INDEX_VERSION
+            ).into());
+        }
+        
+        let (index, _) = bincode::serde::decode_from_slice(&bytes[header_len..], bincode::config::standard())?;
+        Ok(index)
+    }
+    
+    /// Same candidate scoring as the Redis fuzzy search script: name prefix matches first,
+    /// then word hits, falling back to n-grams and finally metaphones
+    pub fn search(&self, query: &str, max_results: usize, params: &FuzzySearchParams) -> Vec<&IndexedCard> {
+        let query = query.to_lowercase();
+        let mut results: Vec<&str> = self.prefix_matches(&query).take(max_results).collect();
+        
+        if results.len() < max_results {
+            let mut candidates: HashMap<&str, u32> = HashMap::new();
+            
+            for word in query.split_whitespace().filter(|word| word.len() >= 3) {
+                for id in self.indexes.words.get(word).into_iter().flatten() {
+                    *candidates.entry(id.as_str()).or_insert(0) += params.word_weight;
+                }
+            }
+            
+            if candidates.is_empty() && query.len() >= 3 {
+                for ngram in generate_ngrams(&query, NGRAM_SIZE) {
+                    for id in self.indexes.ngrams.get(&ngram).into_iter().flatten() {
+                        *candidates.entry(id.as_str()).or_insert(0) += params.ngram_weight;
+                    }
+                }
+            }
+            
+            if candidates.is_empty() {
+                for metaphone in generate_metaphones(&query) {
+                    for id in self.indexes.metaphones.get(&metaphone).into_iter().flatten() {
+                        candidates.insert(id.as_str(), params.metaphone_weight);
+                    }
+                }
+            }
+            
+            let mut ranked: Vec<(&str, u32)> = candidates.into_iter().collect();
+            ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+            
+            for (id, _) in ranked {
+                if results.len() >= max_results {
+                    break;
+                }
+                if !results.contains(&id) {
+                    results.push(id);
+                }
+            }
+        }
+        
+        results.into_iter().filter_map(|id| self.cards.get(id)).collect()
+    }
+    
+    pub fn autocomplete(&self, prefix: &str, max_results: usize) -> Vec<String> {
+        let prefix = prefix.to_lowercase();
+        self.prefix_matches(&prefix)
+            .take(max_results)
+            .filter_map(|id| self.cards.get(id).map(|card| card.name.clone()))
+            .collect()
+    }
+    
+    fn prefix_matches<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        let start = self.names.partition_point(|(name, _)| name.as_str() < prefix);
+        self.names[start..]
+            .iter()
+            .take_while(move |(name, _)| name.starts_with(prefix))
+            .map(|(_, id)| id.as_str())
+    }
+}
+
+fn store_in_redis(
+    redis_url: &str,
+    oracle_id_map: HashMap<String, IndexedCard>,
+    all_set_codes: HashSet<String>,
+    search_indexes: SearchIndexes,
+    cards: &[ScryfallCard],
+    bulk_updated_at: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Connecting to Redis...");
+    let client = Client::open(redis_url.to_string())?;
+    let mut con = client.get_connection()?;
+    
+    let ping: String = redis::cmd("PING").query(&mut con)?;
+    if ping != "PONG" {
+        return Err("Redis connection failed".into());
+    }
+    
+    store_card_index(&mut con, oracle_id_map, all_set_codes, search_indexes, cards, bulk_updated_at)
+}
 
-    let client = reqwest::blocking::Client::builder()
-        .user_agent("MTGPriceAnalyzer/2.0")
+/// Download with exponential backoff. Parse errors are not retried since a
+/// malformed bulk file won't fix itself; each retry restarts the download.
+fn download_with_retries(max_retries: u32, bulk_type: &str) -> Result<(Vec<ScryfallCard>, Option<String>), IndexerError> {
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        match download_scryfall_data(bulk_type) {
+            Ok(download) => return Ok(download),
+            Err(e) => {
+                let e = match e.downcast::<IndexerError>() {
+                    Ok(indexer_error) => return Err(*indexer_error),
+                    Err(e) => e,
+                };
+                if attempt > max_retries {
+                    return Err(IndexerError { phase: IndexPhase::Download, attempts: attempt, source: e });
+                }
+                
+                let delay = DOWNLOAD_RETRY_BASE_DELAY_SECS << (attempt - 1).min(6);
+                println!("Download attempt {} failed: {}. Retrying in {}s...", attempt, e, delay);
+                std::thread::sleep(Duration::from_secs(delay));
+            }
+        }
+    }
+}
+
+/// One entry (e.g. `all_cards`) of Scryfall's bulk-data listing
+struct BulkFileInfo {
+    download_uri: String,
+    size: u64,
+    updated_at: Option<String>,
+}
+
+/// HTTP client for Scryfall requests. Scryfall's API guidelines ask for a descriptive
+/// User-Agent, so set `SCRYFALL_USER_AGENT` (e.g. "MyApp/1.0 (me@example.com)") to
+/// identify your deployment.
+fn scryfall_client() -> Result<reqwest::blocking::Client, Box<dyn std::error::Error>> {
+    let user_agent = std::env::var("SCRYFALL_USER_AGENT")
+        .ok()
+        .filter(|agent| !agent.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_USER_AGENT.to_string());
+    
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert(reqwest::header::ACCEPT, reqwest::header::HeaderValue::from_static("application/json"));
+    
+    Ok(reqwest::blocking::Client::builder()
+        .user_agent(user_agent)
+        .default_headers(headers)
         .timeout(std::time::Duration::from_secs(300))
-        .build()?;
+        .build()?)
+}
 
+/// Block until at least SCRYFALL_REQUEST_SPACING has passed since the previous Scryfall
+/// request from this process, then claim the slot
+fn wait_for_scryfall_slot() {
+    let mut next_request = SCRYFALL_NEXT_REQUEST.lock().unwrap_or_else(|e| e.into_inner());
+    let now = Instant::now();
+    if let Some(next) = *next_request {
+        if next > now {
+            std::thread::sleep(next - now);
+        }
+    }
+    *next_request = Some(Instant::now() + SCRYFALL_REQUEST_SPACING);
+}
+
+/// Rate-limited GET against Scryfall. A 429 fails with the Retry-After delay and is not
+/// retried by download_with_retries, since retrying early is what gets an IP blocked.
+fn scryfall_get(
+    client: &reqwest::blocking::Client,
+    url: &str,
+) -> Result<reqwest::blocking::Response, Box<dyn std::error::Error>> {
+    wait_for_scryfall_slot();
+    let response = client.get(url).send()?;
+    
+    if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<u64>().ok());
+        let message = match retry_after {
+            Some(seconds) => format!("Scryfall rate limit hit for {} (HTTP 429); retry after {} seconds", url, seconds),
+            None => format!("Scryfall rate limit hit for {} (HTTP 429); no Retry-After given", url),
+        };
+        return Err(Box::new(IndexerError::new(IndexPhase::Download, message.into())));
+    }
+    
+    Ok(response)
+}
+
+/// Look up `bulk_type` in Scryfall's bulk-data listing. An unknown type fails with the
+/// valid ones, as a download-phase IndexerError so it isn't retried.
+fn fetch_bulk_file_info(client: &reqwest::blocking::Client, bulk_type: &str) -> Result<BulkFileInfo, Box<dyn std::error::Error>> {
     let bulk_data_url = "https://api.scryfall.com/bulk-data";
     println!("Fetching metadata from: {}", bulk_data_url);
 
-    let response = client
-        .get(bulk_data_url)
-        .header("Accept", "application/json")
-        .send()?;
+    let response = scryfall_get(client, bulk_data_url)?;
 
     if !response.status().is_success() {
         return Err(format!("Failed to get bulk data: HTTP {}", response.status()).into());
     }
 
-    let bulk_data: serde_json::Value = response.json()?;
+    let bulk_data: serde_json::Value = require_json(response, bulk_data_url)?.json()?;
 
     if !bulk_data.is_object() {
         return Err("API response is not a JSON object".into());
@@ -277,36 +1323,72 @@ fn download_scryfall_data() -> Result<Vec<ScryfallCard>, Box<dyn std::error::Err
 
     println!("Found {} bulk data entries", data_array.len());
 
-    let default_cards_entry = data_array
+    let Some(bulk_entry) = data_array
         .iter()
-        .find(|item| item.get("type").and_then(|t| t.as_str()) == Some("all_cards"))
-        .ok_or("all_cards entry not found")?;
+        .find(|item| item.get("type").and_then(|t| t.as_str()) == Some(bulk_type))
+    else {
+        let valid_types: Vec<&str> = data_array
+            .iter()
+            .filter_map(|item| item.get("type").and_then(|t| t.as_str()))
+            .collect();
+        let message = format!("Unknown bulk type '{}'; Scryfall offers: {}", bulk_type, valid_types.join(", "));
+        return Err(Box::new(IndexerError::new(IndexPhase::Download, message.into())));
+    };
 
-    let download_uri = default_cards_entry
+    let download_uri = bulk_entry
         .get("download_uri")
         .and_then(|u| u.as_str())
         .ok_or("download_uri field not found or not a string")?;
 
-    let compressed_size = default_cards_entry.get("size").and_then(|s| s.as_u64()).unwrap_or(0);
+    Ok(BulkFileInfo {
+        download_uri: download_uri.to_string(),
+        size: bulk_entry.get("size").and_then(|s| s.as_u64()).unwrap_or(0),
+        updated_at: bulk_entry.get("updated_at").and_then(|u| u.as_str()).map(str::to_string),
+    })
+}
+
+/// Cards of the `bulk_type` file, with the file's `updated_at`
+fn download_scryfall_data(bulk_type: &str) -> Result<(Vec<ScryfallCard>, Option<String>), Box<dyn std::error::Error>> {
+    println!("Downloading Scryfall {}.json (this may take a while)...", bulk_type);
+
+    let client = scryfall_client()?;
+    let bulk_info = fetch_bulk_file_info(&client, bulk_type)?;
+    let download_uri = bulk_info.download_uri.as_str();
+
     println!("Found download URI: {}", download_uri);
-    println!("Downloading ALL card data (~{}MB compressed, includes ALL printings)", compressed_size / 1024 / 1024);
-    println!("This is significantly larger than default_cards and will take longer to process...");
+    println!("Downloading {} (~{}MB compressed)", bulk_type, bulk_info.size / 1024 / 1024);
+    if bulk_type == "all_cards" {
+        println!("all_cards includes ALL printings; it is significantly larger than default_cards and will take longer to process...");
+    }
 
     let download_start = Instant::now();
     
-    let cards_response = client
-        .get(download_uri)
-        .header("Accept", "application/json")
-        .send()?;
+    let cards_response = scryfall_get(&client, download_uri)?;
 
+    // Bulk files are swapped out daily, so a 404 or 5xx here is often transient;
+    // download_with_retries retries it with backoff
     if !cards_response.status().is_success() {
-        return Err(format!("Failed to download cards: HTTP {}", cards_response.status()).into());
+        return Err(format!(
+            "Bulk file {} is unavailable (HTTP {})",
+            download_uri,
+            cards_response.status()
+        ).into());
     }
+    let cards_response = require_json(cards_response, download_uri)?;
 
-    println!("Download complete, parsing JSON");
-    
-    let body = cards_response.text()?;
-    let cards: Vec<ScryfallCard> = serde_json::from_str(&body)?;
+    println!("Streaming and parsing card JSON");
+    
+    // Deserialize straight off the response body; buffering it into a String first
+    // doubles peak memory on the multi-hundred-MB bulk file
+    let reader = std::io::BufReader::with_capacity(1024 * 1024, cards_response);
+    let cards: Vec<ScryfallCard> = serde_json::from_reader(reader).map_err(|e| -> Box<dyn std::error::Error> {
+        // An I/O error here is the connection dropping mid-stream, which is worth retrying
+        if e.is_io() {
+            e.into()
+        } else {
+            Box::new(IndexerError::new(IndexPhase::Parse, e.into()))
+        }
+    })?;
 
     let elapsed = download_start.elapsed();
     println!(
@@ -315,7 +1397,53 @@ fn download_scryfall_data() -> Result<Vec<ScryfallCard>, Box<dyn std::error::Err
     );
     println!("Downloaded {} cards", cards.len());
 
-    Ok(cards)
+    Ok((retain_index_language(cards), bulk_info.updated_at))
+}
+
+/// all_cards lists every language's printing under the same set and collector number;
+/// keep only the configured language so foreign printings don't inflate the printings
+/// list, the price aggregation, or win the primary image. Cards without a `lang` are
+/// treated as English.
+fn retain_index_language(mut cards: Vec<ScryfallCard>) -> Vec<ScryfallCard> {
+    let Some(lang) = index_language() else {
+        return cards;
+    };
+    
+    let before = cards.len();
+    cards.retain(|card| card.lang.as_deref().unwrap_or(DEFAULT_INDEX_LANGUAGE).eq_ignore_ascii_case(&lang));
+    println!("Keeping {} '{}' printings ({} in other languages skipped)", cards.len(), lang, before - cards.len());
+    cards
+}
+
+/// Scryfall, a CDN or a captive portal can answer 200 with an HTML page. Fail with the
+/// content type and the start of the body instead of an opaque serde error. Responses
+/// without a Content-Type are let through.
+fn require_json(
+    response: reqwest::blocking::Response,
+    url: &str,
+) -> Result<reqwest::blocking::Response, Box<dyn std::error::Error>> {
+    use std::io::Read;
+    
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("")
+        .to_lowercase();
+    if content_type.is_empty() || content_type.contains("json") {
+        return Ok(response);
+    }
+    
+    let mut body = Vec::new();
+    let _ = response.take(ERROR_BODY_SNIPPET_BYTES).read_to_end(&mut body);
+    let snippet = String::from_utf8_lossy(&body)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    Err(format!(
+        "Expected JSON from {} but got '{}' (proxy or captive portal?): {}",
+        url, content_type, snippet
+    ).into())
 }
 
 fn levenshtein_distance(s1: &str, s2: &str) -> usize {
@@ -354,45 +1482,65 @@ fn levenshtein_distance(s1: &str, s2: &str) -> usize {
     matrix[len1][len2]
 }
 
-fn generate_metaphone(text: &str) -> String {
-    let text = text.to_lowercase();
-    let mut result = String::new();
-    let mut prev_char: Option<char> = None;
-    
-    for c in text.chars() {
-        let code = match c {
-            'b' | 'p' | 'f' | 'v' => "B",
-            'c' | 'k' | 'q' => "K",
-            'd' | 't' => "T",
-            'g' | 'j' => "J",
-            'l' => "L",
-            'm' | 'n' => "M",
-            'r' => "R",
-            's' | 'z' => "S",
-            'x' => "KS",
-            'a' | 'e' | 'i' | 'o' | 'u' | 'y' | 'w' | 'h' => "",
-            _ => "",
-        };
-        
-        if !code.is_empty() {
-            let code_chars: Vec<char> = code.chars().collect();
-            
-            if code_chars.len() == 1 {
-                let code_char = code_chars[0];
-                if prev_char != Some(code_char) {
-                    result.push(code_char);
-                    prev_char = Some(code_char);
-                }
-            } else {
-                result.push_str(code);
-                if let Some(first_char) = code_chars.first() {
-                    prev_char = Some(*first_char);
-                }
+// Single transposition, deletion and substitution variants of a prefix, cheapest edits first
+fn generate_typo_variants(prefix: &str) -> Vec<String> {
+    let chars: Vec<char> = prefix.chars().collect();
+    let mut seen = HashSet::new();
+    let mut variants = Vec::new();
+    seen.insert(prefix.to_string());
+    
+    let mut push_variant = |candidate: Vec<char>| {
+        let variant: String = candidate.into_iter().collect();
+        if seen.insert(variant.clone()) {
+            variants.push(variant);
+        }
+    };
+    
+    for i in 0..chars.len().saturating_sub(1) {
+        let mut candidate = chars.clone();
+        candidate.swap(i, i + 1);
+        push_variant(candidate);
+    }
+    
+    for i in 0..chars.len() {
+        let mut candidate = chars.clone();
+        candidate.remove(i);
+        push_variant(candidate);
+    }
+    
+    for i in 0..chars.len() {
+        for replacement in 'a'..='z' {
+            if replacement != chars[i] {
+                let mut candidate = chars.clone();
+                candidate[i] = replacement;
+                push_variant(candidate);
             }
         }
     }
     
-    result
+    variants.truncate(MAX_TYPO_VARIANTS);
+    variants
+}
+
+// Oracle ids matched by each typo variant, in variant order so transpositions rank first
+fn merge_variant_matches(variant_matches: Vec<Vec<String>>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    variant_matches.into_iter()
+        .flatten()
+        .filter(|id| seen.insert(id.clone()))
+        .collect()
+}
+
+/// Distinct non-empty Double Metaphone codes of `text` (primary, then alternate); a name
+/// is indexed under `metaphone:{code}` for each
+fn generate_metaphones(text: &str) -> Vec<String> {
+    let (primary, alternate) = double_metaphone(text);
+    let mut codes = vec![primary];
+    if alternate != codes[0] {
+        codes.push(alternate);
+    }
+    codes.retain(|code| !code.is_empty());
+    codes
 }
 
 fn generate_ngrams(text: &str, n: usize) -> Vec<String> {
@@ -414,11 +1562,17 @@ fn generate_ngrams(text: &str, n: usize) -> Vec<String> {
 }
 
 fn tokenize_words(text: &str) -> Vec<String> {
+    partition_stopwords(text).0
+}
+
+// Splits a name into (words to index, words dropped as stopwords)
+fn partition_stopwords(text: &str) -> (Vec<String>, Vec<String>) {
+    let stopwords = STOPWORDS.read().unwrap_or_else(|e| e.into_inner());
     text.to_lowercase()
         .split(|c: char| !c.is_alphanumeric())
         .filter(|s| !s.is_empty() && s.len() >= 2)
         .map(|s| s.to_string())
-        .collect()
+        .partition(|word| !stopwords.as_ref().is_some_and(|stopwords| stopwords.contains(word)))
 }
 
 fn build_card_index(
@@ -427,7 +1581,7 @@ fn build_card_index(
     println!("Building card index in parallel...");
     let start_time = Instant::now();
     
-    let pb = ProgressBar::new(cards.len() as u64);
+    let pb = new_progress_bar(cards.len() as u64, "Building card index");
     pb.set_style(ProgressStyle::default_bar()
         .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} cards ({eta})")?
         .progress_chars("#>-"));
@@ -437,14 +1591,27 @@ fn build_card_index(
     let oracle_map = Arc::new(Mutex::new(HashMap::new()));
     let set_codes = Arc::new(Mutex::new(HashSet::new()));
     let search_indexes = Arc::new(Mutex::new(SearchIndexes::default()));
+    let skipped_stopwords = Arc::new(Mutex::new(HashSet::new()));
+    let primaries = Arc::new(Mutex::new(HashMap::new()));
+    let strategy = primary_printing_strategy();
+    
+    let chunk_size = index_chunk_size(cards.len());
+    println!(
+        "Indexing in chunks of {} cards ({} chunks, {} threads)",
+        chunk_size,
+        cards.len().div_ceil(chunk_size),
+        rayon::current_num_threads()
+    );
     
-    cards.par_chunks(CHUNK_SIZE)
+    cards.par_chunks(chunk_size)
         .for_each(|chunk| {
             let mut local_oracle_map: HashMap<String, IndexedCard> = HashMap::new();
             let mut local_set_codes = HashSet::new();
             let mut local_ngrams = HashMap::new();
             let mut local_metaphones = HashMap::new();
             let mut local_words = HashMap::new();
+            let mut local_stopwords = HashSet::new();
+            let mut local_primaries: HashMap<String, PrimaryPrinting> = HashMap::new();
             let mut local_skipped = 0;
             
             for card in chunk {
@@ -460,6 +1627,7 @@ fn build_card_index(
                 let printing_price = PrintingPrice {
                     set: card.set.clone(),
                     set_name: Some(card.set_name.clone()),
+                    set_type: card.set_type.clone(),
                     collector_number: card.collector_number.clone(),
                     tcgplayer_id: card.tcgplayer_id,
                     prices: card.prices.clone().unwrap_or_default(),
@@ -467,19 +1635,10 @@ fn build_card_index(
                     rarity: card.rarity.clone(),
                 };
                 
+                offer_primary(&mut local_primaries, oracle_id.clone(), PrimaryPrinting::from_card(card), strategy);
+                
+                // id, layout and main_image are placeholders until the primary printing is applied
                 let indexed_card = local_oracle_map.entry(oracle_id.clone()).or_insert_with(|| {
-                    let main_image = card
-                        .image_uris
-                        .as_ref()
-                        .map(|uris| uris.normal.clone())
-                        .or_else(|| {
-                            card.card_faces.as_ref().and_then(|faces| {
-                                faces.get(0).and_then(|face| {
-                                    face.image_uris.as_ref().map(|uris| uris.normal.clone())
-                                })
-                            })
-                        });
-                    
                     IndexedCard {
                         id: card.id.clone(),
                         oracle_id: oracle_id.clone(),
@@ -487,7 +1646,7 @@ fn build_card_index(
                         sets: Vec::new(),
                         layout: card.layout.clone(),
                         tcgplayer_ids: Vec::new(),
-                        main_image,
+                        main_image: None,
                         prices: Vec::new(),
                     }
                 });
@@ -512,18 +1671,21 @@ fn build_card_index(
                         .insert(oracle_id.clone());
                 }
                 
-                // Build metaphone indexes for phonetic matching
-                let metaphone = generate_metaphone(&name_lower);
-                local_metaphones.entry(metaphone)
-                    .or_insert_with(HashSet::new)
-                    .insert(oracle_id.clone());
+                // Build metaphone indexes for phonetic matching, under both codes
+                for metaphone in generate_metaphones(&name_lower) {
+                    local_metaphones.entry(metaphone)
+                        .or_insert_with(HashSet::new)
+                        .insert(oracle_id.clone());
+                }
                 
-                // Build word indexes
-                for word in tokenize_words(&name_lower) {
+                // Build word indexes; stopwords stay reachable through prefixes and n-grams
+                let (words, dropped) = partition_stopwords(&name_lower);
+                for word in words {
                     local_words.entry(word)
                         .or_insert_with(HashSet::new)
                         .insert(oracle_id.clone());
                 }
+                local_stopwords.extend(dropped);
             }
             
             let mut oracle_map_lock = oracle_map.lock().unwrap();
@@ -548,6 +1710,12 @@ fn build_card_index(
                 }).or_insert(new_card);
             }
             
+            let mut primaries_lock = primaries.lock().unwrap();
+            for (oracle_id, candidate) in local_primaries {
+                offer_primary(&mut primaries_lock, oracle_id, candidate, strategy);
+            }
+            drop(primaries_lock);
+            
             let mut set_codes_lock = set_codes.lock().unwrap();
             for set_code in local_set_codes {
                 set_codes_lock.insert(set_code);
@@ -570,6 +1738,8 @@ fn build_card_index(
                 entry.extend(ids);
             }
             
+            skipped_stopwords.lock().unwrap().extend(local_stopwords);
+            
             skipped_count.fetch_add(local_skipped, std::sync::atomic::Ordering::Relaxed);
             
             pb.inc(chunk.len() as u64);
@@ -587,11 +1757,28 @@ fn build_card_index(
         elapsed.as_secs_f32()
     );
     
-    let oracle_map_result = Arc::try_unwrap(oracle_map)
+    let mut oracle_map_result = Arc::try_unwrap(oracle_map)
         .map_err(|_| "Failed to unwrap oracle_map")?
         .into_inner()
         .map_err(|e| format!("Failed to unwrap oracle_map mutex: {:?}", e))?;
     
+    let primaries_result = Arc::try_unwrap(primaries)
+        .map_err(|_| "Failed to unwrap primaries")?
+        .into_inner()
+        .map_err(|e| format!("Failed to unwrap primaries mutex: {:?}", e))?;
+    for (oracle_id, primary) in primaries_result {
+        if let Some(card) = oracle_map_result.get_mut(&oracle_id) {
+            primary.apply(card);
+        }
+    }
+    
+    // Chunks merge in a different order every run; sort so unchanged cards hash the same
+    for card in oracle_map_result.values_mut() {
+        card.sets.sort();
+        card.tcgplayer_ids.sort();
+        card.prices.sort_by(|a, b| (&a.set, &a.collector_number).cmp(&(&b.set, &b.collector_number)));
+    }
+    
     let set_codes_result = Arc::try_unwrap(set_codes)
         .map_err(|_| "Failed to unwrap set_codes")?
         .into_inner()
@@ -607,6 +1794,15 @@ fn build_card_index(
     println!("- N-gram indexes: {}", search_indexes_result.ngrams.len());
     println!("- Metaphone indexes: {}", search_indexes_result.metaphones.len());
     println!("- Word indexes: {}", search_indexes_result.words.len());
+    let skipped_stopwords = skipped_stopwords.lock().unwrap().len();
+    if skipped_stopwords > 0 {
+        let unfiltered = search_indexes_result.words.len() + skipped_stopwords;
+        println!(
+            "- Stopwords skipped: {} word:* keys ({:.2}% fewer)",
+            skipped_stopwords,
+            skipped_stopwords as f64 / unfiltered as f64 * 100.0
+        );
+    }
     
     pb.finish_with_message(format!("Card indexing completed: {} unique cards", oracle_map_result.len()));
     
@@ -619,28 +1815,26 @@ fn store_card_index(
     all_set_codes: HashSet<String>,
     search_indexes: SearchIndexes,
     cards: &[ScryfallCard],
+    bulk_updated_at: Option<&str>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     println!("Storing {} unique cards in Redis", oracle_id_map.len());
     
-    let mp = MultiProgress::new();
-    let overall_pb = mp.add(ProgressBar::new(oracle_id_map.len() as u64));
+    let mp = MultiProgress::with_draw_target(progress_draw_target());
+    let overall_pb = mp.add(new_progress_bar(oracle_id_map.len() as u64, "Storing cards"));
     overall_pb.set_style(ProgressStyle::default_bar()
         .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} cards ({eta})")?
         .progress_chars("#>-"));
     
-    let mut card_id_map: HashMap<&str, &ScryfallCard> = HashMap::new();
-    for card in cards {
-        if card.oracle_id.is_some() {
-            card_id_map.insert(&card.id, card);
-        }
-    }
+    // One O(n) pass over every printing, so each oracle card's printings are a map lookup
+    // instead of a scan of the whole corpus (which made this loop O(cards × printings))
+    let printings_by_oracle = group_printings(cards);
 
     let oracle_map_len = oracle_id_map.len();
     
     let entries: Vec<(String, IndexedCard)> = oracle_id_map.into_iter().collect();
     
     for (i, batch) in entries.chunks(BATCH_SIZE).enumerate() {
-        let batch_pb = mp.add(ProgressBar::new(batch.len() as u64));
+        let batch_pb = mp.add(new_progress_bar(batch.len() as u64, &format!("Batch #{}", i + 1)));
         batch_pb.set_style(ProgressStyle::default_bar()
             .template(&format!("Batch #{} {{bar:30.blue}} {{pos}}/{{len}}", i + 1))?
             .progress_chars("=> "));
@@ -649,121 +1843,8 @@ fn store_card_index(
         pipe.atomic();
         
         for (oracle_id, card) in batch {
-            let card_json = serde_json::to_string(&card)?;
-            pipe.cmd("SET").arg(format!("card:oracle:{}", oracle_id)).arg(&card_json);
-            
-            pipe.cmd("SET").arg(format!("card:name:{}", card.name.to_lowercase())).arg(oracle_id);
-            
-            let name_lower = card.name.to_lowercase();
-            let chars: Vec<char> = name_lower.chars().collect();
-            let prefix_len = std::cmp::min(chars.len(), MAX_PREFIX_LENGTH);
-            
-            for i in 1..=prefix_len {
-                let prefix: String = chars[0..i].iter().collect();
-                pipe.cmd("SADD")
-                    .arg(format!("auto:prefix:{}", prefix))
-                    .arg(oracle_id);
-            }
-            
-            for word in tokenize_words(&name_lower) {
-                let word_chars: Vec<char> = word.chars().collect();
-                let word_len = word_chars.len();
-
-                let prefix_limit = std::cmp::min(word_len, MAX_PREFIX_LENGTH);
-                
-                // Add word-level prefixes for each word in the name
-                for i in 1..=prefix_limit {
-                    let word_prefix: String = word_chars[0..i].iter().collect();
-                    pipe.cmd("SADD")
-                        .arg(format!("auto:word:{}", word_prefix))
-                        .arg(oracle_id);
-                }
-            }
-            
-            for set_code in &card.sets {
-                pipe.cmd("SADD")
-                    .arg(format!("set:{}", set_code))
-                    .arg(oracle_id);
-            }
-            
-            for tcgplayer_id in &card.tcgplayer_ids {
-                pipe.cmd("SET").arg(format!("tcg:{}", tcgplayer_id)).arg(oracle_id);
-            }
-            
-            for price_data in &card.prices {
-                if let Some(usd_price) = &price_data.prices.usd {
-                    if let Ok(price_value) = usd_price.parse::<f32>() {
-                        if price_value > 0.0 {
-                            let price_bucket = (price_value * 100.0).round() as i32;
-                            pipe.cmd("ZADD")
-                                .arg("prices:usd")
-                                .arg(price_bucket)
-                                .arg(oracle_id);
-                        }
-                    }
-                }
-            }
-            
-            // With all_cards, we get multiple printings per card
-            // Using max price to represent the highest-value printing for this card
-            let latest_price = card.prices.iter()
-                .filter_map(|p| p.prices.usd.as_ref().and_then(|price| price.parse::<f32>().ok()))
-                .fold(0.0f32, |a, b| a.max(b));
-                
-            if latest_price > 0.0 {
-                pipe.cmd("SET").arg(format!("price:latest:{}", oracle_id)).arg(latest_price.to_string());
-            }
-            
-            let card_id = &card.id;
-            if let Some(source_card) = card_id_map.get(card_id.as_str()) {
-                pipe.cmd("SADD")
-                    .arg(format!("printings:{}", oracle_id))
-                    .arg(&source_card.id);
-                
-                pipe.cmd("SET").arg(format!("printing:{}", source_card.id)).arg(oracle_id);
-                
-                let printing_info = PrintingInfo {
-                    id: source_card.id.clone(),
-                    set: source_card.set.clone(),
-                    set_name: source_card.set_name.clone(),
-                    collector_number: source_card.collector_number.clone(),
-                    tcgplayer_id: source_card.tcgplayer_id,
-                    prices: source_card.prices.clone(),
-                    image_uris: source_card.image_uris.clone(),
-                    released_at: source_card.released_at.clone(),
-                    rarity: source_card.rarity.clone(),
-                };
-                
-                pipe.cmd("SET")
-                    .arg(format!("printing:info:{}", source_card.id))
-                    .arg(serde_json::to_string(&printing_info)?);
-            }
-            
-            for other_card in cards {
-                if other_card.oracle_id.as_ref() == Some(oracle_id) && &other_card.id != card_id {
-                    pipe.cmd("SADD")
-                        .arg(format!("printings:{}", oracle_id))
-                        .arg(&other_card.id);
-                    
-                    pipe.cmd("SET").arg(format!("printing:{}", other_card.id)).arg(oracle_id);
-                    
-                    let printing_info = PrintingInfo {
-                        id: other_card.id.clone(),
-                        set: other_card.set.clone(),
-                        set_name: other_card.set_name.clone(),
-                        collector_number: other_card.collector_number.clone(),
-                        tcgplayer_id: other_card.tcgplayer_id,
-                        prices: other_card.prices.clone(),
-                        image_uris: other_card.image_uris.clone(),
-                        released_at: other_card.released_at.clone(),
-                        rarity: other_card.rarity.clone(),
-                    };
-                    
-                    pipe.cmd("SET")
-                        .arg(format!("printing:info:{}", other_card.id))
-                        .arg(serde_json::to_string(&printing_info)?);
-                }
-            }
+            let printings = printings_by_oracle.get(oracle_id.as_str()).map(Vec::as_slice).unwrap_or(&[]);
+            queue_card_writes(&mut pipe, oracle_id, card, printings)?;
         }
         
         let _: () = pipe.query(con)?;
@@ -774,7 +1855,7 @@ fn store_card_index(
     
     // Store search indexes
     println!("Storing n-gram indexes...");
-    let ngram_pb = mp.add(ProgressBar::new(search_indexes.ngrams.len() as u64));
+    let ngram_pb = mp.add(new_progress_bar(search_indexes.ngrams.len() as u64, "Storing n-grams"));
     ngram_pb.set_style(ProgressStyle::default_bar()
         .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} n-grams")?
         .progress_chars("#>-"));
@@ -792,7 +1873,7 @@ fn store_card_index(
     ngram_pb.finish_with_message("N-gram indexes stored");
     
     println!("Storing metaphone indexes...");
-    let mp_pb = mp.add(ProgressBar::new(search_indexes.metaphones.len() as u64));
+    let mp_pb = mp.add(new_progress_bar(search_indexes.metaphones.len() as u64, "Storing metaphones"));
     mp_pb.set_style(ProgressStyle::default_bar()
         .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} metaphones")?
         .progress_chars("#>-"));
@@ -810,7 +1891,7 @@ fn store_card_index(
     mp_pb.finish_with_message("Metaphone indexes stored");
     
     println!("Storing word indexes...");
-    let word_pb = mp.add(ProgressBar::new(search_indexes.words.len() as u64));
+    let word_pb = mp.add(new_progress_bar(search_indexes.words.len() as u64, "Storing words"));
     word_pb.set_style(ProgressStyle::default_bar()
         .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} words")?
         .progress_chars("#>-"));
@@ -831,15 +1912,351 @@ fn store_card_index(
     let _: () = con.set("mtg:sets", serde_json::to_string(&set_codes)?)?;
     let _: () = con.set("mtg:stats:card_count", oracle_map_len)?;
     let _: () = con.set("mtg:stats:last_update", Utc::now().to_rfc3339())?;
+    // Lets a later --incremental run skip an unchanged bulk file
+    if let Some(updated_at) = bulk_updated_at {
+        let _: () = con.set("mtg:stats:bulk_updated_at", updated_at)?;
+    }
+    
+    load_fuzzy_search_script(con)?;
+    
+    overall_pb.finish_with_message("All cards and indexes stored in Redis");
+    
+    Ok(())
+}
+
+/// Every printing of each oracle card, sorted by printing id. O(n) to build plus the
+/// per-card sorts, i.e. O(n log k) for n printings with at most k per card.
+fn group_printings(cards: &[ScryfallCard]) -> HashMap<&str, Vec<&ScryfallCard>> {
+    let mut printings_by_oracle: HashMap<&str, Vec<&ScryfallCard>> = HashMap::new();
+    for card in cards {
+        if let Some(oracle_id) = &card.oracle_id {
+            printings_by_oracle.entry(oracle_id.as_str()).or_default().push(card);
+        }
+    }
+    for printings in printings_by_oracle.values_mut() {
+        printings.sort_by(|a, b| a.id.cmp(&b.id));
+    }
+    printings_by_oracle
+}
+
+fn printing_info(card: &ScryfallCard) -> PrintingInfo {
+    PrintingInfo {
+        id: card.id.clone(),
+        set: card.set.clone(),
+        set_name: card.set_name.clone(),
+        collector_number: card.collector_number.clone(),
+        tcgplayer_id: card.tcgplayer_id,
+        prices: card.prices.clone(),
+        image_uris: card.image_uris.clone(),
+        released_at: card.released_at.clone(),
+        rarity: card.rarity.clone(),
+    }
+}
+
+/// FNV-1a over a card's JSON and its printings' JSON, stored as `card:hash:{oracle_id}`.
+/// Hand-rolled because std's hasher isn't guaranteed stable between Rust releases.
+fn card_hash(card: &IndexedCard, printings: &[&ScryfallCard]) -> Result<String, Box<dyn std::error::Error>> {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    let mut feed = |json: String| {
+        for byte in json.bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    };
+    
+    feed(serde_json::to_string(card)?);
+    for printing in printings {
+        feed(serde_json::to_string(&printing_info(printing))?);
+    }
+    Ok(format!("{:016x}", hash))
+}
+
+/// Autocomplete, set and set type sets an oracle id is added to by `queue_card_writes`
+fn card_set_keys(card: &IndexedCard) -> HashSet<String> {
+    let mut keys = HashSet::new();
+    let name_lower = card.name.to_lowercase();
+    
+    let chars: Vec<char> = name_lower.chars().collect();
+    for i in 1..=std::cmp::min(chars.len(), MAX_PREFIX_LENGTH) {
+        keys.insert(format!("auto:prefix:{}", chars[0..i].iter().collect::<String>()));
+    }
+    
+    // Add word-level prefixes for each word in the name
+    for word in tokenize_words(&name_lower) {
+        let word_chars: Vec<char> = word.chars().collect();
+        for i in 1..=std::cmp::min(word_chars.len(), MAX_PREFIX_LENGTH) {
+            keys.insert(format!("auto:word:{}", word_chars[0..i].iter().collect::<String>()));
+        }
+    }
+    
+    for set_code in &card.sets {
+        keys.insert(format!("set:{}", set_code));
+    }
+    
+    // Expansion vs promo/token/etc. filtering, matching the MTGJSON side
+    for set_type in card.prices.iter().filter_map(|p| p.set_type.as_ref()) {
+        keys.insert(format!("set_type:{}", set_type));
+    }
+    
+    keys
+}
+
+/// N-gram, metaphone and word sets for a card name, matching `build_card_index`
+fn search_index_keys(name: &str) -> HashSet<String> {
+    let name_lower = name.to_lowercase();
+    let mut keys: HashSet<String> = generate_ngrams(&name_lower, NGRAM_SIZE)
+        .into_iter()
+        .map(|ngram| format!("ngram:{}", ngram))
+        .collect();
+    for metaphone in generate_metaphones(&name_lower) {
+        keys.insert(format!("metaphone:{}", metaphone));
+    }
+    for word in tokenize_words(&name_lower) {
+        keys.insert(format!("word:{}", word));
+    }
+    keys
+}
+
+/// Queue every card-level key for one oracle card. N-gram, metaphone and word sets are
+/// written separately: in bulk by `store_card_index`, per card by `store_card_changes`.
+fn queue_card_writes(
+    pipe: &mut redis::Pipeline,
+    oracle_id: &str,
+    card: &IndexedCard,
+    printings: &[&ScryfallCard],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let card_json = serde_json::to_string(card)?;
+    pipe.cmd("SET").arg(format!("card:oracle:{}", oracle_id)).arg(&card_json);
+    pipe.cmd("SET").arg(format!("card:hash:{}", oracle_id)).arg(card_hash(card, printings)?);
+    
+    pipe.cmd("SET").arg(format!("card:name:{}", card.name.to_lowercase())).arg(oracle_id);
+    
+    for key in card_set_keys(card) {
+        pipe.cmd("SADD").arg(key).arg(oracle_id);
+    }
+    
+    for tcgplayer_id in &card.tcgplayer_ids {
+        pipe.cmd("SET").arg(format!("tcg:{}", tcgplayer_id)).arg(oracle_id);
+    }
+    
+    for price_data in &card.prices {
+        if let Some(usd_price) = &price_data.prices.usd {
+            if let Ok(price_value) = usd_price.parse::<f32>() {
+                if price_value > 0.0 {
+                    let price_bucket = (price_value * 100.0).round() as i32;
+                    pipe.cmd("ZADD")
+                        .arg("prices:usd")
+                        .arg(price_bucket)
+                        .arg(oracle_id);
+                }
+            }
+        }
+    }
+    
+    // With all_cards, we get multiple printings per card
+    // Using max price to represent the highest-value printing for this card
+    let latest_price = card.prices.iter()
+        .filter_map(|p| p.prices.usd.as_ref().and_then(|price| price.parse::<f32>().ok()))
+        .fold(0.0f32, |a, b| a.max(b));
+        
+    if latest_price > 0.0 {
+        pipe.cmd("SET").arg(format!("price:latest:{}", oracle_id)).arg(latest_price.to_string());
+    }
+    
+    for printing in printings {
+        pipe.cmd("SADD")
+            .arg(format!("printings:{}", oracle_id))
+            .arg(&printing.id);
+        
+        pipe.cmd("SET").arg(format!("printing:{}", printing.id)).arg(oracle_id);
+        
+        pipe.cmd("SET")
+            .arg(format!("printing:info:{}", printing.id))
+            .arg(serde_json::to_string(&printing_info(printing))?);
+    }
+    
+    Ok(())
+}
+
+/// Queue removal of everything stored for `oracle_id`, including its search index
+/// entries. Name and TCGplayer keys another card in the new data still uses are kept.
+fn queue_card_removal(
+    con: &mut Connection,
+    pipe: &mut redis::Pipeline,
+    oracle_id: &str,
+    live_names: &HashSet<String>,
+    live_tcg_ids: &HashSet<i64>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let old_json: Option<String> = con.get(format!("card:oracle:{}", oracle_id))?;
+    let old_printings: Vec<String> = con.smembers(format!("printings:{}", oracle_id))?;
+    
+    if let Some(old_card) = old_json.as_deref().and_then(|json| serde_json::from_str::<IndexedCard>(json).ok()) {
+        for key in card_set_keys(&old_card).into_iter().chain(search_index_keys(&old_card.name)) {
+            pipe.cmd("SREM").arg(key).arg(oracle_id);
+        }
+        
+        let name_lower = old_card.name.to_lowercase();
+        if !live_names.contains(&name_lower) {
+            pipe.cmd("DEL").arg(format!("card:name:{}", name_lower));
+        }
+        for tcgplayer_id in old_card.tcgplayer_ids.iter().filter(|id| !live_tcg_ids.contains(*id)) {
+            pipe.cmd("DEL").arg(format!("tcg:{}", tcgplayer_id));
+        }
+    }
+    
+    for printing_id in &old_printings {
+        pipe.cmd("DEL").arg(format!("printing:{}", printing_id));
+        pipe.cmd("DEL").arg(format!("printing:info:{}", printing_id));
+    }
+    
+    pipe.cmd("ZREM").arg("prices:usd").arg(oracle_id);
+    pipe.cmd("DEL")
+        .arg(format!("card:oracle:{}", oracle_id))
+        .arg(format!("card:hash:{}", oracle_id))
+        .arg(format!("price:latest:{}", oracle_id))
+        .arg(format!("printings:{}", oracle_id));
+    
+    Ok(())
+}
+
+/// Incremental counterpart of `store_card_index`: writes only cards whose `card_hash`
+/// differs from the stored one and deletes cards no longer in `oracle_id_map`
+fn store_card_changes(
+    redis_url: &str,
+    oracle_id_map: HashMap<String, IndexedCard>,
+    all_set_codes: HashSet<String>,
+    cards: &[ScryfallCard],
+    bulk_updated_at: Option<&str>,
+) -> Result<IndexChanges, Box<dyn std::error::Error>> {
+    println!("Connecting to Redis...");
+    let client = Client::open(redis_url.to_string())?;
+    let mut con = client.get_connection()?;
+    
+    let existing: HashSet<String> = con.scan_match::<_, String>("card:oracle:*")?
+        .map(|key| key.trim_start_matches("card:oracle:").to_string())
+        .collect();
+    let deleted: Vec<String> = existing.iter()
+        .filter(|oracle_id| !oracle_id_map.contains_key(*oracle_id))
+        .cloned()
+        .collect();
+    
+    let live_names: HashSet<String> = oracle_id_map.values().map(|card| card.name.to_lowercase()).collect();
+    let live_tcg_ids: HashSet<i64> = oracle_id_map.values().flat_map(|card| card.tcgplayer_ids.iter().copied()).collect();
+    let printings_by_oracle = group_printings(cards);
+    let card_count = oracle_id_map.len();
+    let mut changes = IndexChanges::default();
+    
+    let pb = new_progress_bar(card_count as u64, "Comparing cards");
+    pb.set_style(ProgressStyle::default_bar()
+        .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} cards ({eta})")?
+        .progress_chars("#>-"));
+    
+    let entries: Vec<(String, IndexedCard)> = oracle_id_map.into_iter().collect();
+    for batch in entries.chunks(BATCH_SIZE) {
+        let hash_keys: Vec<String> = batch.iter().map(|(oracle_id, _)| format!("card:hash:{}", oracle_id)).collect();
+        let stored_hashes: Vec<Option<String>> = redis::cmd("MGET").arg(&hash_keys).query(&mut con)?;
+        
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        
+        for ((oracle_id, card), stored_hash) in batch.iter().zip(stored_hashes) {
+            let printings = printings_by_oracle.get(oracle_id.as_str()).map(Vec::as_slice).unwrap_or(&[]);
+            if stored_hash.as_deref() == Some(card_hash(card, printings)?.as_str()) {
+                changes.skipped += 1;
+                continue;
+            }
+            
+            if existing.contains(oracle_id) {
+                queue_card_removal(&mut con, &mut pipe, oracle_id, &live_names, &live_tcg_ids)?;
+                changes.updated += 1;
+            } else {
+                changes.added += 1;
+            }
+            
+            queue_card_writes(&mut pipe, oracle_id, card, printings)?;
+            for key in search_index_keys(&card.name) {
+                pipe.cmd("SADD").arg(key).arg(oracle_id);
+            }
+        }
+        
+        let _: () = pipe.query(&mut con)?;
+        pb.inc(batch.len() as u64);
+    }
+    pb.finish_with_message(format!(
+        "{} added, {} updated, {} unchanged",
+        changes.added, changes.updated, changes.skipped
+    ));
+    
+    if !deleted.is_empty() {
+        println!("Removing {} cards no longer in the bulk file...", deleted.len());
+    }
+    for batch in deleted.chunks(BATCH_SIZE) {
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        for oracle_id in batch {
+            queue_card_removal(&mut con, &mut pipe, oracle_id, &live_names, &live_tcg_ids)?;
+        }
+        let _: () = pipe.query(&mut con)?;
+        changes.deleted += batch.len();
+    }
+    
+    let set_codes: Vec<String> = all_set_codes.into_iter().collect();
+    let _: () = con.set("mtg:sets", serde_json::to_string(&set_codes)?)?;
+    let _: () = con.set("mtg:stats:card_count", card_count)?;
+    let _: () = con.set("mtg:stats:last_update", Utc::now().to_rfc3339())?;
+    if let Some(updated_at) = bulk_updated_at {
+        let _: () = con.set("mtg:stats:bulk_updated_at", updated_at)?;
+    }
     
-    // Store fuzzy search scripts in Redis
-    println!("Loading fuzzy search Lua scripts...");
+    load_fuzzy_search_script(&mut con)?;
     
-    // Script for fuzzy searching by Levenshtein distance
-    let fuzzy_search_script = r#"
+    Ok(changes)
+}
+
+/// Connect to `redis_url` and reload the fuzzy search script if the server lost it,
+/// returning its SHA
+pub fn ensure_search_script(redis_url: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let client = Client::open(redis_url.to_string())?;
+    let mut con = client.get_connection()?;
+    load_fuzzy_search_script(&mut con)
+}
+
+/// Make sure the fuzzy search Lua script is in the server's script cache and its SHA is
+/// recorded under `mtg:script:fuzzy_search`, returning the SHA. Skips `SCRIPT LOAD` when
+/// the stored SHA matches the current script and `SCRIPT EXISTS` still finds it.
+pub fn load_fuzzy_search_script(con: &mut Connection) -> Result<String, Box<dyn std::error::Error>> {
+    let fuzzy_search_sha = redis::Script::new(FUZZY_SEARCH_SCRIPT).get_hash().to_string();
+    let stored_sha: Option<String> = con.get("mtg:script:fuzzy_search")?;
+    
+    if stored_sha.as_deref() == Some(fuzzy_search_sha.as_str()) {
+        let loaded: Vec<i32> = redis::cmd("SCRIPT").arg("EXISTS").arg(&fuzzy_search_sha).query(con)?;
+        if loaded.first() == Some(&1) {
+            println!("Fuzzy search Lua script already loaded ({})", fuzzy_search_sha);
+            return Ok(fuzzy_search_sha);
+        }
+        println!("Fuzzy search Lua script missing from the script cache, reloading...");
+    } else {
+        println!("Loading fuzzy search Lua scripts...");
+    }
+    
+    let loaded_sha: String = redis::cmd("SCRIPT")
+        .arg("LOAD")
+        .arg(FUZZY_SEARCH_SCRIPT)
+        .query(con)?;
+    
+    let _: () = con.set("mtg:script:fuzzy_search", &loaded_sha)?;
+    
+    Ok(loaded_sha)
+}
+
+// Script for fuzzy searching by Levenshtein distance
+const FUZZY_SEARCH_SCRIPT: &str = r#"
     local query = ARGV[1]
     local max_distance = tonumber(ARGV[2]) or 2
     local max_results = tonumber(ARGV[3]) or 20
+    local word_weight = tonumber(ARGV[4]) or 1
+    local ngram_weight = tonumber(ARGV[5]) or 1
+    local metaphone_weight = tonumber(ARGV[6]) or 2
     
     local candidates = {}
     local results = {}
@@ -869,7 +2286,7 @@ fn store_card_index(
                 if not candidates[id] then
                     candidates[id] = 0
                 end
-                candidates[id] = candidates[id] + 1
+                candidates[id] = candidates[id] + word_weight
             end
         end
     end
@@ -886,45 +2303,23 @@ fn store_card_index(
                 if not candidates[id] then
                     candidates[id] = 0
                 end
-                candidates[id] = candidates[id] + 1
+                candidates[id] = candidates[id] + ngram_weight
             end
         end
     end
     
-    -- If we still don't have candidates, try metaphone match
+    -- If we still don't have candidates, try the query's Double Metaphone codes. They
+    -- come from the caller (ARGV[7] primary, ARGV[8] alternate), so they always match
+    -- the encoder that built the metaphone:{code} index.
     if next(candidates) == nil then
-        -- Simple metaphone implementation directly in Lua
-        local function simplify_metaphone(text)
-            local result = ""
-            local map = {
-                ['b'] = 'B', ['p'] = 'B', ['f'] = 'B', ['v'] = 'B',
-                ['c'] = 'K', ['k'] = 'K', ['q'] = 'K',
-                ['d'] = 'T', ['t'] = 'T',
-                ['g'] = 'J', ['j'] = 'J',
-                ['l'] = 'L',
-                ['m'] = 'M', ['n'] = 'M',
-                ['r'] = 'R',
-                ['s'] = 'S', ['z'] = 'S',
-                ['x'] = 'KS'
-            }
-            
-            text = string.lower(text)
-            for i = 1, #text do
-                local char = text:sub(i, i)
-                local code = map[char] or ""
-                result = result .. code
-            end
-            
-            return result
-        end
-        
-        local metaphone = simplify_metaphone(query)
-        if #metaphone > 0 then
-            local metaphone_key = 'metaphone:' .. metaphone
-            local metaphone_matches = redis.call('SMEMBERS', metaphone_key)
-            
-            for _, id in ipairs(metaphone_matches) do
-                candidates[id] = 2  -- Give metaphone matches a good score
+        for i = 7, 8 do
+            local metaphone = ARGV[i]
+            if metaphone and #metaphone > 0 then
+                local metaphone_matches = redis.call('SMEMBERS', 'metaphone:' .. metaphone)
+                
+                for _, id in ipairs(metaphone_matches) do
+                    candidates[id] = metaphone_weight
+                end
             end
         end
     end
@@ -945,17 +2340,102 @@ fn store_card_index(
     
     return results
     "#;
+
+/// Excludes `stopwords` from the word index (and word autocomplete) for the rest of the
+/// process; `None` indexes every word again. Prefix and n-gram matching are unaffected,
+/// so "the" still autocompletes "The Abyss".
+pub fn set_stopwords(stopwords: Option<HashSet<String>>) {
+    *STOPWORDS.write().unwrap_or_else(|e| e.into_inner()) = stopwords;
+}
+
+/// Fixes the `build_card_index` chunk size for the rest of the process; `None` sizes
+/// chunks from the card count and thread count again.
+pub fn set_chunk_size(chunk_size: Option<usize>) {
+    CHUNK_SIZE_OVERRIDE.store(chunk_size.unwrap_or(0), std::sync::atomic::Ordering::Relaxed);
+}
+
+/// Roughly `CHUNKS_PER_THREAD` chunks per rayon thread, clamped to
+/// `MIN_CHUNK_SIZE..=CHUNK_SIZE`. Small runs get a single chunk so the shared maps
+/// are locked once.
+fn index_chunk_size(card_count: usize) -> usize {
+    let override_size = CHUNK_SIZE_OVERRIDE.load(std::sync::atomic::Ordering::Relaxed);
+    if override_size > 0 {
+        return override_size;
+    }
+    if card_count <= SINGLE_CHUNK_MAX_CARDS {
+        return card_count.max(1);
+    }
     
-    let fuzzy_search_sha: String = redis::cmd("SCRIPT")
-        .arg("LOAD")
-        .arg(fuzzy_search_script)
-        .query(con)?;
-    
-    let _: () = con.set("mtg:script:fuzzy_search", fuzzy_search_sha)?;
-    
-    overall_pb.finish_with_message("All cards and indexes stored in Redis");
+    let target_chunks = rayon::current_num_threads() * CHUNKS_PER_THREAD;
+    card_count.div_ceil(target_chunks).clamp(MIN_CHUNK_SIZE, CHUNK_SIZE)
+}
+
+/// Chooses which printing represents each card in subsequent index builds.
+pub fn set_primary_printing_strategy(strategy: PrimaryPrintingStrategy) {
+    *PRIMARY_PRINTING.write().unwrap_or_else(|e| e.into_inner()) = strategy;
+}
+
+fn primary_printing_strategy() -> PrimaryPrintingStrategy {
+    *PRIMARY_PRINTING.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Index only printings in `lang` (a Scryfall language code, "en" by default), or every
+/// language with `None`
+pub fn set_index_language(lang: Option<&str>) {
+    *INDEX_LANGUAGE.write().unwrap_or_else(|e| e.into_inner()) = lang.map(|lang| Cow::Owned(lang.to_lowercase()));
+}
+
+fn index_language() -> Option<Cow<'static, str>> {
+    INDEX_LANGUAGE.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+pub fn default_stopwords() -> HashSet<String> {
+    DEFAULT_STOPWORDS.iter().map(|word| word.to_string()).collect()
+}
+
+/// One stopword per line; blank lines and `#` comments are ignored.
+pub fn load_stopwords(path: &str) -> Result<HashSet<String>, Box<dyn std::error::Error>> {
+    let contents = std::fs::read_to_string(path)?;
+    Ok(contents
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim().to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect())
+}
+
+/// Turns progress output on or off for the rest of the process.
+pub fn set_progress_enabled(enabled: bool) {
+    PROGRESS_DISABLED.store(!enabled, std::sync::atomic::Ordering::Relaxed);
+}
+
+fn progress_draw_target() -> ProgressDrawTarget {
+    if PROGRESS_DISABLED.load(std::sync::atomic::Ordering::Relaxed) || !std::io::stderr().is_terminal() {
+        ProgressDrawTarget::hidden()
+    } else {
+        ProgressDrawTarget::stderr()
+    }
+}
+
+/// Live bar on a terminal. Off-TTY the bar is hidden and a plain-text line is
+/// printed every PROGRESS_LOG_INTERVAL instead, so log files stay readable.
+fn new_progress_bar(len: u64, label: &str) -> ProgressBar {
+    let pb = ProgressBar::with_draw_target(Some(len), progress_draw_target());
+    if PROGRESS_DISABLED.load(std::sync::atomic::Ordering::Relaxed) || std::io::stderr().is_terminal() {
+        return pb;
+    }
     
-    Ok(())
+    let weak = pb.downgrade();
+    let label = label.to_string();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(PROGRESS_LOG_INTERVAL);
+        let Some(pb) = weak.upgrade() else { break };
+        if pb.is_finished() {
+            break;
+        }
+        let pct = if len > 0 { pb.position() as f64 / len as f64 * 100.0 } else { 100.0 };
+        println!("{}: {}/{} ({:.1}%)", label, pb.position(), len, pct);
+    });
+    pb
 }
 
 fn get_redis_url() -> String {
@@ -968,19 +2448,120 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let redis_url = get_redis_url();
     println!("Using Redis URL: {}", redis_url);
     
-    let stats = run_indexer(&redis_url)?;
+    let args: Vec<String> = std::env::args().collect();
+    if args.iter().any(|arg| arg == "--no-progress" || arg == "--quiet") {
+        set_progress_enabled(false);
+    }
+    
+    if let Some(pos) = args.iter().position(|arg| arg == "--stopwords-file") {
+        let stopwords_path = args.get(pos + 1).ok_or("--stopwords-file requires a path")?;
+        let stopwords = load_stopwords(stopwords_path)?;
+        println!("Excluding {} stopwords from {} from the word index", stopwords.len(), stopwords_path);
+        set_stopwords(Some(stopwords));
+    } else if args.iter().any(|arg| arg == "--stopwords") {
+        println!("Excluding {} default stopwords from the word index", DEFAULT_STOPWORDS.len());
+        set_stopwords(Some(default_stopwords()));
+    }
+    
+    if let Some(pos) = args.iter().position(|arg| arg == "--chunk-size") {
+        let chunk_size: usize = args.get(pos + 1)
+            .ok_or("--chunk-size requires a number of cards")?
+            .parse()?;
+        if chunk_size == 0 {
+            return Err("--chunk-size must be at least 1".into());
+        }
+        println!("Indexing in fixed chunks of {} cards", chunk_size);
+        set_chunk_size(Some(chunk_size));
+    }
+    
+    if let Some(pos) = args.iter().position(|arg| arg == "--primary-printing") {
+        let strategy: PrimaryPrintingStrategy = args.get(pos + 1)
+            .ok_or("--primary-printing requires latest or original")?
+            .parse()?;
+        println!("Using {:?} printing as each card's primary printing", strategy);
+        set_primary_printing_strategy(strategy);
+    }
+    
+    if let Some(pos) = args.iter().position(|arg| arg == "--lang") {
+        let lang = args.get(pos + 1).ok_or("--lang requires a language code or 'all'")?;
+        if lang.eq_ignore_ascii_case("all") {
+            println!("Indexing printings in every language");
+            set_index_language(None);
+        } else {
+            println!("Indexing only '{}' printings", lang);
+            set_index_language(Some(lang.as_str()));
+        }
+    }
+    
+    if let Some(pos) = args.iter().position(|arg| arg == "--benchmark-search") {
+        let queries_path = args.get(pos + 1).ok_or("--benchmark-search requires a CSV path")?;
+        let params = FuzzySearchParams::from_args(&args)?;
+        println!("Benchmarking fuzzy search with {:?}", params);
+        
+        let report = run_search_benchmark(queries_path, &redis_url, &params)?;
+        println!("\n=== Search Quality ===");
+        println!("  Queries: {}", report.queries);
+        println!("  Precision@1: {:.3}", report.precision_at_1);
+        println!("  Precision@5: {:.3}", report.precision_at_5);
+        println!("  Mean reciprocal rank: {:.3}", report.mean_reciprocal_rank);
+        if !report.misses.is_empty() {
+            println!("  Not found in top {}: {}", BENCHMARK_MAX_RESULTS, report.misses.join(", "));
+        }
+        return Ok(());
+    }
+    
+    let bulk_type = match args.iter().position(|arg| arg == "--bulk-type") {
+        Some(pos) => args.get(pos + 1).ok_or("--bulk-type requires a Scryfall bulk type, e.g. default_cards")?.clone(),
+        None => DEFAULT_BULK_TYPE.to_string(),
+    };
+    
+    if let Some(pos) = args.iter().position(|arg| arg == "--export-index") {
+        let export_path = args.get(pos + 1).ok_or("--export-index requires an output path")?;
+        let stats = export_offline_index(export_path, &bulk_type)?;
+        println!("Exported {} unique cards across {} sets to {}", stats.card_count, stats.set_count, export_path);
+        return Ok(());
+    }
+    
+    if args.iter().any(|arg| arg == "--validate") {
+        let report = validate_index(&redis_url)?;
+        println!("\n=== Index Validation ===");
+        for (name, passed, detail) in &report.checks {
+            println!("  [{}] {}: {}", if *passed { "PASS" } else { "FAIL" }, name, detail);
+        }
+        if !report.passed() {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    
+    if args.iter().any(|arg| arg == "--incremental") {
+        let client = Client::open(redis_url.clone())?;
+        let mut con = client.get_connection()?;
+        let previous_meta_date: Option<String> = con.get("mtg:stats:bulk_updated_at")?;
+        
+        let stats = run_indexer_incremental(&redis_url, previous_meta_date.as_deref(), &bulk_type)?;
+        if let Some(changes) = &stats.changes {
+            println!(
+                "Stats: {} unique cards, {} sets ({} added, {} updated, {} deleted, {} unchanged)",
+                stats.card_count, stats.set_count, changes.added, changes.updated, changes.deleted, changes.skipped
+            );
+        }
+        return Ok(());
+    }
+    
+    let stats = run_indexer(&redis_url, &bulk_type)?;
     
-    println!("Scryfall ALL CARDS data successfully downloaded and indexed with enhanced autocomplete and fuzzy search");
-    println!("Stats: {} unique cards (ALL printings included), {} sets", stats.card_count, stats.set_count);
+    println!("Scryfall {} data successfully downloaded and indexed with enhanced autocomplete and fuzzy search", bulk_type);
+    println!("Stats: {} unique cards, {} sets", stats.card_count, stats.set_count);
     
     // Display key usage statistics
     let client = Client::open(redis_url.clone())?;
     let mut con = client.get_connection()?;
     
     let key_types = [
-        "card:oracle:*", "card:name:*", "auto:prefix:*", "auto:word:*",
+        "card:oracle:*", "card:hash:*", "card:name:*", "auto:prefix:*", "auto:word:*",
         "ngram:*", "metaphone:*", "word:*",
-        "set:*", "tcg:*", "prices:*", "printings:*", "printing:*"
+        "set:*", "set_type:*", "tcg:*", "prices:*", "printings:*", "printing:*"
     ];
     
     println!("\nRedis Memory Usage:");
@@ -996,3 +2577,35 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // auto:prefix:{prefix} sets for the given (oracle_id, name) pairs
+    fn prefix_index(cards: &[(&str, &str)]) -> HashMap<String, Vec<String>> {
+        let mut index: HashMap<String, Vec<String>> = HashMap::new();
+        for (oracle_id, name) in cards {
+            let name = name.to_lowercase();
+            for end in name.char_indices().map(|(i, c)| i + c.len_utf8()) {
+                index.entry(name[..end].to_string()).or_default().push(oracle_id.to_string());
+            }
+        }
+        index
+    }
+
+    #[test]
+    fn transposed_prefix_ranks_intended_card_first() {
+        // "mihgtn" is one substitution away from "lihgtn", so the decoy is also a candidate
+        let index = prefix_index(&[("decoy", "Mihgtn Decoy"), ("bolt", "Lightning Bolt")]);
+        assert!(!index.contains_key("lihgtn"));
+
+        let variant_matches: Vec<Vec<String>> = generate_typo_variants("lihgtn")
+            .iter()
+            .map(|variant| index.get(variant).cloned().unwrap_or_default())
+            .collect();
+        let ranked = merge_variant_matches(variant_matches);
+
+        assert_eq!(ranked, vec!["bolt".to_string(), "decoy".to_string()]);
+    }
+}
@@ -5,42 +5,8 @@ use anyhow::{Result, Context};
 use chrono::{DateTime, Utc};
 use std::env;
 
-// API-specific type definitions (simplified from mtgjson-indexer/types.rs)
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct IndexedCard {
-    pub uuid: String,
-    pub name: String,
-    pub set_code: String,
-    pub set_name: String,
-    pub collector_number: String,
-    pub rarity: String,
-    pub mana_value: f32,
-    pub mana_cost: Option<String>,
-    pub colors: Vec<String>,
-    pub color_identity: Vec<String>,
-    pub types: Vec<String>,
-    pub subtypes: Vec<String>,
-    pub supertypes: Vec<String>,
-    pub power: Option<String>,
-    pub toughness: Option<String>,
-    pub loyalty: Option<String>,
-    pub defense: Option<String>,
-    pub text: Option<String>,
-    pub flavor_text: Option<String>,
-    pub layout: String,
-    pub availability: Vec<String>,
-    pub finishes: Vec<String>,
-    pub has_foil: bool,
-    pub has_non_foil: bool,
-    pub is_reserved: bool,
-    pub is_promo: bool,
-    pub release_date: String,
-    pub scryfall_oracle_id: Option<String>,
-    pub scryfall_id: Option<String>,
-    pub tcgplayer_product_id: Option<String>,
-    pub tcgplayer_skus: Vec<TcgplayerSku>,
-    pub purchase_urls: PurchaseUrls,
-}
+// Card, SKU and price documents as the MTGJSON indexer stores them
+pub use mtgjson_types::{DeckValue, IndexedCard, PurchaseUrls, TcgPrice, TcgplayerSku};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct IndexedDeck {
@@ -68,45 +34,6 @@ pub struct DeckCardInfo {
     pub tcgplayer_product_id: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct DeckValue {
-    pub market_total: f64,
-    pub direct_total: f64,
-    pub low_total: f64,
-    pub cards_with_pricing: u32,
-    pub cards_without_pricing: u32,
-}
-
-#[derive(Debug, Deserialize, Serialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct TcgplayerSku {
-    #[serde(default)]
-    pub condition: Option<String>,
-    #[serde(default)]
-    pub language: Option<String>,
-    #[serde(default)]
-    pub printing: Option<String>,
-    pub product_id: u64,
-    pub sku_id: u64,
-}
-
-#[derive(Debug, Deserialize, Serialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct PurchaseUrls {
-    #[serde(default)]
-    pub card_kingdom: Option<String>,
-    #[serde(default)]
-    pub card_kingdom_etched: Option<String>,
-    #[serde(default)]
-    pub card_kingdom_foil: Option<String>,
-    #[serde(default)]
-    pub cardmarket: Option<String>,
-    #[serde(default)]
-    pub tcgplayer: Option<String>,
-    #[serde(default)]
-    pub tcgplayer_etched: Option<String>,
-}
-
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SetInfo {
     pub code: String,
@@ -117,25 +44,6 @@ pub struct SetInfo {
     pub base_set_size: u32,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct TcgPrice {
-    pub tcgplayer_id: String,
-    pub product_line: String,
-    pub set_name: String,
-    pub product_name: String,
-    pub title: String,
-    pub number: String,
-    pub rarity: String,
-    pub condition: String,
-    pub tcg_market_price: Option<f64>,
-    pub tcg_direct_low: Option<f64>,
-    pub tcg_low_price_with_shipping: Option<f64>,
-    pub tcg_low_price: Option<f64>,
-    pub total_quantity: Option<i32>,
-    pub add_to_quantity: Option<i32>,
-    pub tcg_marketplace_price: Option<f64>,
-}
-
 pub struct MTGRedisClient {
     client: Client,
 }